@@ -16,6 +16,7 @@ pub struct Keypair {
     pub secret_key: Option<SecretKey>,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct KeypairUnowned<'a> {
     pub pubkey: &'a Pubkey,
     pub secret_key: Option<&'a SecretKey>,
@@ -119,6 +120,10 @@ impl FullKeypair {
             secret_key: Some(self.secret_key),
         }
     }
+
+    pub fn nsec(&self) -> Option<String> {
+        self.secret_key.to_bech32().ok()
+    }
 }
 
 impl std::fmt::Display for Keypair {
@@ -2,6 +2,7 @@ mod client;
 mod error;
 mod filter;
 mod keypair;
+pub mod nip46;
 mod note;
 mod profile;
 mod pubkey;
@@ -16,9 +17,9 @@ pub use nostr::SecretKey;
 pub use note::{Note, NoteId};
 pub use profile::ProfileState;
 pub use pubkey::{Pubkey, PubkeyRef};
-pub use relay::message::{RelayEvent, RelayMessage};
-pub use relay::pool::{PoolEvent, PoolRelay, RelayPool};
+pub use relay::message::{CommandResult, RelayEvent, RelayMessage};
+pub use relay::pool::{PoolEvent, PoolRelay, PoolSummary, RelayPool, RelayUsage};
 pub use relay::subs_debug::{OwnedRelayEvent, RelayLogEvent, SubsDebug, TransferStats};
-pub use relay::{Relay, RelayStatus};
+pub use relay::{is_rate_limited, Relay, RelayStats, RelayStatus, SendQueue};
 
 pub type Result<T> = std::result::Result<T, error::Error>;
@@ -0,0 +1,271 @@
+//! Nostr Connect (NIP-46) messages, for signing with a remote signer
+//! (a "bunker") instead of a local secret key.
+
+use nostr::nips::nip44;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{Error, Pubkey, SecretKey};
+
+/// Kind of the events that carry NIP-46 requests and responses
+pub const NIP46_KIND: u64 = 24133;
+
+/// A `bunker://<remote-signer-pubkey>?relay=...&secret=...` URI handed out
+/// by a remote signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BunkerUri {
+    pub remote_signer: Pubkey,
+    pub relays: Vec<String>,
+    pub secret: Option<String>,
+}
+
+impl BunkerUri {
+    pub fn parse(uri: &str) -> Result<Self, Error> {
+        let url = Url::parse(uri.trim()).map_err(|e| Error::Generic(e.to_string()))?;
+        if url.scheme() != "bunker" {
+            return Err(Error::Generic(format!(
+                "expected a bunker:// uri, got {}://",
+                url.scheme()
+            )));
+        }
+
+        let host = url.host_str().ok_or(Error::InvalidPublicKey)?;
+        let remote_signer = Pubkey::from_hex(host)?;
+
+        let mut relays = Vec::new();
+        let mut secret = None;
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "relay" => match Url::parse(&value) {
+                    Ok(relay) => relays.push(relay.to_string()),
+                    Err(_) => return Err(Error::InvalidRelayUrl),
+                },
+                "secret" => secret = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        if relays.is_empty() {
+            return Err(Error::Generic("bunker uri has no relays".to_owned()));
+        }
+
+        Ok(Self {
+            remote_signer,
+            relays,
+            secret,
+        })
+    }
+}
+
+impl std::fmt::Display for BunkerUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut url = Url::parse(&format!("bunker://{}", self.remote_signer.hex()))
+            .map_err(|_| std::fmt::Error)?;
+        {
+            let mut query = url.query_pairs_mut();
+            for relay in &self.relays {
+                query.append_pair("relay", relay);
+            }
+            if let Some(secret) = &self.secret {
+                query.append_pair("secret", secret);
+            }
+        }
+        write!(f, "{url}")
+    }
+}
+
+/// A `nostrconnect://<client-pubkey>?relay=...&secret=...` URI we show the
+/// user, for signers that connect to us instead of handing out a bunker://
+/// address. The signer answers `connect` with the secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NostrConnectUri {
+    pub client: Pubkey,
+    pub relays: Vec<String>,
+    pub secret: String,
+    /// Our app name, shown by the signer
+    pub name: Option<String>,
+}
+
+impl NostrConnectUri {
+    pub fn new(client: Pubkey, relays: Vec<String>, name: Option<String>) -> Self {
+        Self {
+            client,
+            relays,
+            secret: random_id(),
+            name,
+        }
+    }
+}
+
+impl std::fmt::Display for NostrConnectUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut url = Url::parse(&format!("nostrconnect://{}", self.client.hex()))
+            .map_err(|_| std::fmt::Error)?;
+        {
+            let mut query = url.query_pairs_mut();
+            for relay in &self.relays {
+                query.append_pair("relay", relay);
+            }
+            query.append_pair("secret", &self.secret);
+            if let Some(name) = &self.name {
+                query.append_pair("name", name);
+            }
+        }
+        write!(f, "{url}")
+    }
+}
+
+/// A request to the remote signer. The signer answers with a
+/// [`Nip46Response`] carrying the same id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Nip46Request {
+    pub id: String,
+    pub method: String,
+    pub params: Vec<String>,
+}
+
+impl Nip46Request {
+    fn new(method: &str, params: Vec<String>) -> Self {
+        Self {
+            id: random_id(),
+            method: method.to_owned(),
+            params,
+        }
+    }
+
+    pub fn connect(remote_signer: &Pubkey, secret: Option<&str>) -> Self {
+        let mut params = vec![remote_signer.hex()];
+        if let Some(secret) = secret {
+            params.push(secret.to_owned());
+        }
+        Self::new("connect", params)
+    }
+
+    pub fn get_public_key() -> Self {
+        Self::new("get_public_key", vec![])
+    }
+
+    /// Ask the signer to sign an unsigned event, as json with `kind`,
+    /// `content`, `tags` and `created_at`
+    pub fn sign_event(unsigned_event: String) -> Self {
+        Self::new("sign_event", vec![unsigned_event])
+    }
+
+    pub fn ping() -> Self {
+        Self::new("ping", vec![])
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Nip46Response {
+    pub id: String,
+    #[serde(default)]
+    pub result: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl Nip46Response {
+    /// Signers answer with `auth_url` and a url in `error` when the user
+    /// needs to approve the request on a web page first
+    pub fn auth_url(&self) -> Option<&str> {
+        if self.result.as_deref() == Some("auth_url") {
+            self.error.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
+/// Encrypt a NIP-46 message for `to`
+pub fn encrypt(secret_key: &SecretKey, to: &Pubkey, message: &str) -> Result<String, Error> {
+    let to = nostr::PublicKey::from_slice(to.bytes()).map_err(|_| Error::InvalidPublicKey)?;
+    nip44::encrypt(secret_key, &to, message, nip44::Version::V2)
+        .map_err(|e| Error::Generic(e.to_string()))
+}
+
+/// Decrypt a NIP-46 message sent to us by `from`
+pub fn decrypt(secret_key: &SecretKey, from: &Pubkey, payload: &str) -> Result<String, Error> {
+    let from = nostr::PublicKey::from_slice(from.bytes()).map_err(|_| Error::InvalidPublicKey)?;
+    nip44::decrypt(secret_key, &from, payload).map_err(|e| Error::Generic(e.to_string()))
+}
+
+fn random_id() -> String {
+    use nostr::secp256k1::rand::{rngs::OsRng, RngCore};
+
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FullKeypair;
+
+    const SIGNER: &str = "fa984bd7dbb282f07e16e7ae87b26a2a7b9b90b7246a44771f0cf5ae58018f52";
+
+    #[test]
+    fn parse_bunker_uri() {
+        let uri = format!(
+            "bunker://{SIGNER}?relay=wss%3A%2F%2Frelay.nsec.app&relay=wss://relay.damus.io&secret=abc"
+        );
+        let parsed = BunkerUri::parse(&uri).unwrap();
+
+        assert_eq!(parsed.remote_signer.hex(), SIGNER);
+        assert_eq!(
+            parsed.relays,
+            vec!["wss://relay.nsec.app/", "wss://relay.damus.io/"]
+        );
+        assert_eq!(parsed.secret.as_deref(), Some("abc"));
+        assert_eq!(BunkerUri::parse(&parsed.to_string()).unwrap(), parsed);
+    }
+
+    #[test]
+    fn reject_bad_bunker_uris() {
+        assert!(BunkerUri::parse(&format!("nostrconnect://{SIGNER}?relay=wss://r.io")).is_err());
+        assert!(BunkerUri::parse(&format!("bunker://{SIGNER}")).is_err());
+        assert!(BunkerUri::parse("bunker://notakey?relay=wss://r.io").is_err());
+    }
+
+    #[test]
+    fn nostrconnect_uri() {
+        let client = Pubkey::from_hex(SIGNER).unwrap();
+        let uri = NostrConnectUri::new(
+            client,
+            vec!["wss://relay.nsec.app/".to_owned()],
+            Some("Notedeck".to_owned()),
+        );
+
+        assert_eq!(
+            uri.to_string(),
+            format!(
+                "nostrconnect://{SIGNER}?relay=wss%3A%2F%2Frelay.nsec.app%2F&secret={}&name=Notedeck",
+                uri.secret
+            )
+        );
+    }
+
+    #[test]
+    fn encrypt_roundtrip() {
+        let client = FullKeypair::generate();
+        let signer = FullKeypair::generate();
+
+        let request = Nip46Request::get_public_key();
+        let json = serde_json::to_string(&request).unwrap();
+        let payload = encrypt(&client.secret_key, &signer.pubkey, &json).unwrap();
+        let decrypted = decrypt(&signer.secret_key, &client.pubkey, &payload).unwrap();
+
+        let parsed: Nip46Request = serde_json::from_str(&decrypted).unwrap();
+        assert_eq!(parsed.id, request.id);
+        assert_eq!(parsed.method, "get_public_key");
+    }
+
+    #[test]
+    fn auth_url_response() {
+        let response: Nip46Response =
+            serde_json::from_str(r#"{"id":"1","result":"auth_url","error":"https://a.io"}"#)
+                .unwrap();
+        assert_eq!(response.auth_url(), Some("https://a.io"));
+    }
+}
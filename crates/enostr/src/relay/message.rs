@@ -8,6 +8,23 @@ pub struct CommandResult<'a> {
     message: &'a str,
 }
 
+impl<'a> CommandResult<'a> {
+    /// Hex id of the event this answers
+    pub fn event_id(&self) -> &'a str {
+        self.event_id
+    }
+
+    /// Did the relay store the event?
+    pub fn accepted(&self) -> bool {
+        self.status
+    }
+
+    /// Why the relay refused the event, may be empty
+    pub fn message(&self) -> &'a str {
+        self.message
+    }
+}
+
 pub fn calculate_command_result_size(result: &CommandResult) -> usize {
     std::mem::size_of_val(result) + result.event_id.len() + result.message.len()
 }
@@ -13,8 +13,13 @@ use tracing::{debug, error};
 
 pub mod message;
 pub mod pool;
+mod send_queue;
+mod stats;
 pub mod subs_debug;
 
+pub use send_queue::{is_rate_limited, SendQueue};
+pub use stats::RelayStats;
+
 #[derive(Debug, Copy, Clone)]
 pub enum RelayStatus {
     Connected,
@@ -1,8 +1,8 @@
-use crate::relay::{setup_multicast_relay, MulticastRelay, Relay, RelayStatus};
-use crate::{ClientMessage, Error, Result};
+use crate::relay::{setup_multicast_relay, MulticastRelay, Relay, RelayStats, RelayStatus};
+use crate::{ClientMessage, Error, RelayMessage, Result};
 use nostrdb::Filter;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::time::{Duration, Instant};
 
 use url::Url;
@@ -13,6 +13,7 @@ use ewebsock::{WsEvent, WsMessage};
 #[cfg(not(target_arch = "wasm32"))]
 use tracing::{debug, error};
 
+use super::send_queue::SendQueue;
 use super::subs_debug::SubsDebug;
 
 #[derive(Debug)]
@@ -40,11 +41,71 @@ pub enum PoolRelay {
     Multicast(MulticastRelay),
 }
 
+/// What a relay is used for, from the NIP-65 read and write markers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayUsage {
+    /// Subscriptions are sent to this relay
+    pub read: bool,
+    /// Our events are published to this relay
+    pub write: bool,
+}
+
+impl Default for RelayUsage {
+    fn default() -> Self {
+        Self {
+            read: true,
+            write: true,
+        }
+    }
+}
+
+impl RelayUsage {
+    /// Should this message go to a relay used this way?
+    pub fn accepts(&self, msg: &ClientMessage) -> bool {
+        match msg {
+            ClientMessage::Event(_) => self.write,
+            ClientMessage::Req { .. } => self.read,
+            _ => true,
+        }
+    }
+}
+
+/// How the pool's websocket relays are doing, for status indicators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolSummary {
+    pub connected: usize,
+    pub connecting: usize,
+    pub total: usize,
+}
+
+impl PoolSummary {
+    /// Connected while any relay is, otherwise connecting while any
+    /// relay is trying to
+    pub fn status(&self) -> RelayStatus {
+        if self.connected > 0 {
+            RelayStatus::Connected
+        } else if self.connecting > 0 {
+            RelayStatus::Connecting
+        } else {
+            RelayStatus::Disconnected
+        }
+    }
+
+    /// Relays we aren't connected to and are reconnecting
+    pub fn reconnecting(&self) -> usize {
+        self.total - self.connected
+    }
+}
+
 pub struct WebsocketRelay {
     pub relay: Relay,
+    pub usage: RelayUsage,
+    pub stats: RelayStats,
     pub last_ping: Instant,
     pub last_connect_attempt: Instant,
     pub retry_connect_after: Duration,
+    /// Events waiting to go out, paced to the relay's rate limits
+    pub send_queue: SendQueue,
 }
 
 impl PoolRelay {
@@ -78,10 +139,49 @@ impl PoolRelay {
         }
     }
 
+    pub fn usage(&self) -> RelayUsage {
+        match self {
+            Self::Websocket(wsr) => wsr.usage,
+            Self::Multicast(_mcr) => RelayUsage::default(),
+        }
+    }
+
+    pub fn stats(&self) -> Option<&RelayStats> {
+        match self {
+            Self::Websocket(wsr) => Some(&wsr.stats),
+            Self::Multicast(_mcr) => None,
+        }
+    }
+
+    /// Events waiting in the send queue. Callers can hold back on bulk
+    /// sends while this grows.
+    pub fn queued(&self) -> usize {
+        match self {
+            Self::Websocket(wsr) => wsr.send_queue.len(),
+            Self::Multicast(_mcr) => 0,
+        }
+    }
+
+    /// Should this relay get subscriptions? Relays that keep failing are
+    /// skipped until they connect again or are pinned.
+    fn is_routable(&self) -> bool {
+        self.usage().read && !self.stats().is_some_and(|s| s.is_failing())
+    }
+
     pub fn send(&mut self, msg: &ClientMessage) -> Result<()> {
         match self {
             Self::Websocket(wsr) => {
-                wsr.relay.send(msg);
+                if let ClientMessage::Req { sub_id, .. } = msg {
+                    wsr.stats.req_sent(sub_id);
+                }
+
+                if let ClientMessage::Event(_) = msg {
+                    wsr.send_queue.push(msg.clone());
+                    wsr.flush_send_queue(Instant::now());
+                } else {
+                    wsr.stats.message_sent();
+                    wsr.relay.send(msg);
+                }
                 Ok(())
             }
 
@@ -112,12 +212,31 @@ impl WebsocketRelay {
     pub fn new(relay: Relay) -> Self {
         Self {
             relay,
+            usage: RelayUsage::default(),
+            stats: RelayStats::default(),
             last_ping: Instant::now(),
             last_connect_attempt: Instant::now(),
             retry_connect_after: Self::initial_reconnect_duration(),
+            send_queue: SendQueue::default(),
         }
     }
 
+    /// Send the queued events whose turn it is. Returns when the next
+    /// one can go.
+    pub fn flush_send_queue(&mut self, now: Instant) -> Option<Instant> {
+        for msg in self.send_queue.ready(now) {
+            self.stats.message_sent();
+            self.relay.send(&msg);
+        }
+        self.send_queue.next_ready()
+    }
+
+    pub fn ping(&mut self) {
+        self.relay.ping();
+        self.last_ping = Instant::now();
+        self.stats.ping_sent();
+    }
+
     pub fn initial_reconnect_duration() -> Duration {
         Duration::from_secs(5)
     }
@@ -127,6 +246,9 @@ pub struct RelayPool {
     pub relays: Vec<PoolRelay>,
     pub ping_rate: Duration,
     pub debug: Option<SubsDebug>,
+    /// Open subscriptions by id, so relays that were skipped while
+    /// failing get them once they recover
+    subs: BTreeMap<String, Vec<Filter>>,
 }
 
 impl Default for RelayPool {
@@ -142,6 +264,7 @@ impl RelayPool {
             relays: vec![],
             ping_rate: Duration::from_secs(45),
             debug: None,
+            subs: BTreeMap::new(),
         }
     }
 
@@ -180,8 +303,13 @@ impl RelayPool {
             .collect()
     }
 
+    /// Send a message to every relay whose read/write usage accepts it
     pub fn send(&mut self, cmd: &ClientMessage) {
         for relay in &mut self.relays {
+            if !relay.usage().accepts(cmd) {
+                continue;
+            }
+
             if let Some(debug) = &mut self.debug {
                 debug.send_cmd(relay.url().to_owned(), cmd);
             }
@@ -192,6 +320,7 @@ impl RelayPool {
     }
 
     pub fn unsubscribe(&mut self, subid: String) {
+        self.subs.remove(&subid);
         for relay in &mut self.relays {
             let cmd = ClientMessage::close(subid.clone());
             if let Some(debug) = &mut self.debug {
@@ -207,8 +336,22 @@ impl RelayPool {
         }
     }
 
+    /// Subscribe on every read relay, skipping relays that keep failing
+    /// unless that would leave us with none
     pub fn subscribe(&mut self, subid: String, filter: Vec<Filter>) {
+        self.subs.insert(subid.clone(), filter.clone());
+        let any_routable = self.relays.iter().any(|r| r.is_routable());
+
         for relay in &mut self.relays {
+            let skip = if any_routable {
+                !relay.is_routable()
+            } else {
+                !relay.usage().read
+            };
+            if skip {
+                continue;
+            }
+
             if let Some(debug) = &mut self.debug {
                 debug.send_cmd(
                     relay.url().to_owned(),
@@ -237,7 +380,8 @@ impl RelayPool {
                                 relay.last_connect_attempt + relay.retry_connect_after;
                             if now > reconnect_at {
                                 relay.last_connect_attempt = now;
-                                let next_duration = Duration::from_millis(3000);
+                                let next_duration =
+                                    relay.stats.reconnect_delay(Duration::from_millis(3000));
                                 debug!(
                                     "bumping reconnect duration from {:?} to {:?} and retrying connect",
                                     relay.retry_connect_after, next_duration
@@ -258,8 +402,7 @@ impl RelayPool {
                             let should_ping = now - relay.last_ping > self.ping_rate;
                             if should_ping {
                                 debug!("pinging {}", relay.relay.url);
-                                relay.relay.ping();
-                                relay.last_ping = Instant::now();
+                                relay.ping();
                             }
                         }
 
@@ -272,6 +415,41 @@ impl RelayPool {
         }
     }
 
+    /// Connection counts over the websocket relays. Multicast is left out
+    /// since it's always "connected".
+    pub fn summary(&self) -> PoolSummary {
+        let mut summary = PoolSummary::default();
+        for relay in &self.relays {
+            let PoolRelay::Websocket(wsr) = relay else {
+                continue;
+            };
+            summary.total += 1;
+            match wsr.relay.status {
+                RelayStatus::Connected => summary.connected += 1,
+                RelayStatus::Connecting => summary.connecting += 1,
+                RelayStatus::Disconnected => {}
+            }
+        }
+        summary
+    }
+
+    /// When the next queued event of any relay can go out. The queues are
+    /// flushed in [`Self::try_recv`], so an idle app should wake up then.
+    pub fn next_send_at(&self) -> Option<Instant> {
+        self.relays
+            .iter()
+            .filter_map(|relay| match relay {
+                PoolRelay::Websocket(wsr) => wsr.send_queue.next_ready(),
+                PoolRelay::Multicast(_) => None,
+            })
+            .min()
+    }
+
+    /// Events waiting in the send queues of all relays
+    pub fn queued_events(&self) -> usize {
+        self.relays.iter().map(PoolRelay::queued).sum()
+    }
+
     pub fn send_to(&mut self, cmd: &ClientMessage, relay_url: &str) {
         for relay in &mut self.relays {
             if relay.url() == relay_url {
@@ -286,6 +464,60 @@ impl RelayPool {
         }
     }
 
+    /// Ping a relay now to measure its latency
+    pub fn ping(&mut self, relay_url: &str) {
+        for relay in &mut self.relays {
+            if let PoolRelay::Websocket(wsr) = relay {
+                if wsr.relay.url.as_str() == relay_url {
+                    wsr.ping();
+                }
+            }
+        }
+    }
+
+    /// Pinned relays keep getting subscriptions even while failing
+    pub fn set_pinned(&mut self, relay_url: &str, pinned: bool) {
+        for relay in &mut self.relays {
+            if let PoolRelay::Websocket(wsr) = relay {
+                if wsr.relay.url.as_str() == relay_url {
+                    wsr.stats.pinned = pinned;
+                }
+            }
+        }
+    }
+
+    /// Drop and reopen the connection to a relay, keeping its stats
+    pub fn reconnect(
+        &mut self,
+        relay_url: &str,
+        wakeup: impl Fn() + Send + Sync + Clone + 'static,
+    ) {
+        for relay in &mut self.relays {
+            if let PoolRelay::Websocket(wsr) = relay {
+                if wsr.relay.url.as_str() == relay_url {
+                    wsr.last_connect_attempt = Instant::now();
+                    if let Err(err) = wsr.relay.connect(wakeup.clone()) {
+                        error!("error reconnecting to {relay_url}: {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Set what a relay is used for. Returns true if it changed.
+    pub fn set_usage(&mut self, relay_url: &str, usage: RelayUsage) -> bool {
+        for relay in &mut self.relays {
+            if let PoolRelay::Websocket(wsr) = relay {
+                if wsr.relay.url.as_str() == relay_url && wsr.usage != usage {
+                    wsr.usage = usage;
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
     /// check whether a relay url is valid to add
     pub fn is_valid_url(&self, url: &str) -> bool {
         if url.is_empty() {
@@ -365,17 +597,50 @@ impl RelayPool {
                 }
             }
 
+            if let PoolRelay::Websocket(wsr) = relay {
+                wsr.flush_send_queue(Instant::now());
+            }
+
             if let Some(event) = relay.try_recv() {
                 match &event {
                     WsEvent::Opened => {
                         relay.set_status(RelayStatus::Connected);
+                        let mut recovered = false;
+                        if let PoolRelay::Websocket(wsr) = relay {
+                            recovered = wsr.stats.is_failing();
+                            wsr.stats.connected();
+                        }
+
+                        // subscriptions made while it was failing skipped it
+                        if recovered && relay.usage().read {
+                            debug!(
+                                "{} recovered, resending {} subs",
+                                relay.url(),
+                                self.subs.len()
+                            );
+                            for (subid, filters) in &self.subs {
+                                let cmd = ClientMessage::req(subid.clone(), filters.clone());
+                                if let Some(debug) = &mut self.debug {
+                                    debug.send_cmd(relay.url().to_owned(), &cmd);
+                                }
+                                if let Err(err) = relay.send(&cmd) {
+                                    error!("error resubscribing to {}: {err}", relay.url());
+                                }
+                            }
+                        }
                     }
                     WsEvent::Closed => {
                         relay.set_status(RelayStatus::Disconnected);
+                        if let PoolRelay::Websocket(wsr) = relay {
+                            wsr.stats.failed();
+                        }
                     }
                     WsEvent::Error(err) => {
                         error!("{:?}", err);
                         relay.set_status(RelayStatus::Disconnected);
+                        if let PoolRelay::Websocket(wsr) = relay {
+                            wsr.stats.failed();
+                        }
                     }
                     WsEvent::Message(ev) => {
                         // let's just handle pongs here.
@@ -390,6 +655,36 @@ impl RelayPool {
                                 PoolRelay::Multicast(_mcr) => {}
                             }
                         }
+
+                        if let PoolRelay::Websocket(wsr) = relay {
+                            if let WsMessage::Text(text) = ev {
+                                wsr.stats.message_received(text.len());
+                            }
+
+                            match ev {
+                                WsMessage::Pong(_) => wsr.stats.pong_received(),
+                                WsMessage::Text(text) if text.starts_with("[\"EOSE\"") => {
+                                    if let Ok(RelayMessage::Eose(subid)) =
+                                        RelayMessage::from_json(text)
+                                    {
+                                        wsr.stats.eose_received(subid);
+                                    }
+                                }
+                                WsMessage::Text(text) if text.starts_with("[\"OK\"") => {
+                                    if let Ok(RelayMessage::OK(result)) =
+                                        RelayMessage::from_json(text)
+                                    {
+                                        wsr.send_queue.handle_ok(
+                                            result.event_id(),
+                                            result.accepted(),
+                                            result.message(),
+                                            Instant::now(),
+                                        );
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
                     }
                 }
 
@@ -409,3 +704,61 @@ impl RelayPool {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_routes_messages() {
+        let read_only = RelayUsage {
+            read: true,
+            write: false,
+        };
+        let write_only = RelayUsage {
+            read: false,
+            write: true,
+        };
+        let event = ClientMessage::event_json("{}".to_owned()).unwrap();
+        let req = ClientMessage::req("sub".to_owned(), vec![]);
+        let close = ClientMessage::close("sub".to_owned());
+
+        assert!(!read_only.accepts(&event));
+        assert!(read_only.accepts(&req));
+        assert!(write_only.accepts(&event));
+        assert!(!write_only.accepts(&req));
+
+        // closes always go out so nothing is left subscribed
+        assert!(read_only.accepts(&close));
+        assert!(write_only.accepts(&close));
+    }
+
+    #[test]
+    fn remembers_open_subscriptions() {
+        let mut pool = RelayPool::new();
+        pool.subscribe("home".to_owned(), vec![Filter::new().kinds([1]).build()]);
+        pool.subscribe("dms".to_owned(), vec![]);
+        pool.unsubscribe("dms".to_owned());
+
+        assert_eq!(pool.subs.keys().collect::<Vec<_>>(), ["home"]);
+    }
+
+    #[test]
+    fn summary_status_prefers_connected() {
+        let mut summary = PoolSummary {
+            connected: 0,
+            connecting: 2,
+            total: 3,
+        };
+        assert!(matches!(summary.status(), RelayStatus::Connecting));
+        assert_eq!(summary.reconnecting(), 3);
+
+        summary.connected = 1;
+        assert!(matches!(summary.status(), RelayStatus::Connected));
+
+        assert!(matches!(
+            PoolSummary::default().status(),
+            RelayStatus::Disconnected
+        ));
+    }
+}
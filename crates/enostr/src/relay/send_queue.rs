@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::ClientMessage;
+
+/// Events a relay takes back to back before we start pacing them
+const BURST: f32 = 10.0;
+
+/// Sustained events per second to a single relay
+const PER_SECOND: f32 = 4.0;
+
+/// First pause after a relay says we're rate limited, doubled each time
+const BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Resends of an event a relay rate limited before we give up on it
+const MAX_RETRIES: u32 = 3;
+
+/// Sent events we remember until their OK, for resending
+const MAX_AWAITING_OK: usize = 500;
+
+/// The NIP-01 prefix of an OK message refusing an event for going too fast
+const RATE_LIMITED: &str = "rate-limited";
+
+struct SentEvent {
+    msg: ClientMessage,
+    retries: u32,
+}
+
+/// Paces the EVENTs we send to one relay, so bursts like a bulk import
+/// don't trip its rate limits. Events the relay rate limits anyway are
+/// sent again after backing off.
+pub struct SendQueue {
+    tokens: f32,
+    last_refill: Instant,
+    /// Nothing is sent until then, after the relay rate limited us
+    paused_until: Option<Instant>,
+    backoff: Duration,
+    /// Events waiting their turn, with how often they were resent
+    queued: VecDeque<SentEvent>,
+    /// Sent events by id until the relay answers, oldest first
+    awaiting_ok: VecDeque<(String, SentEvent)>,
+}
+
+impl Default for SendQueue {
+    fn default() -> Self {
+        Self {
+            tokens: BURST,
+            last_refill: Instant::now(),
+            paused_until: None,
+            backoff: BACKOFF,
+            queued: VecDeque::new(),
+            awaiting_ok: VecDeque::new(),
+        }
+    }
+}
+
+impl SendQueue {
+    pub fn push(&mut self, msg: ClientMessage) {
+        self.queued.push_back(SentEvent { msg, retries: 0 });
+    }
+
+    /// Events waiting to be sent
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// The events that can go out now
+    pub fn ready(&mut self, now: Instant) -> Vec<ClientMessage> {
+        if self.queued.is_empty() {
+            return Vec::new();
+        }
+
+        if self.paused_until.is_some_and(|until| now < until) {
+            return Vec::new();
+        }
+        self.paused_until = None;
+
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f32() * PER_SECOND).min(BURST);
+        self.last_refill = now;
+
+        let mut ready = Vec::new();
+        while self.tokens >= 1.0 {
+            let Some(sent) = self.queued.pop_front() else {
+                break;
+            };
+            self.tokens -= 1.0;
+            ready.push(sent.msg.clone());
+            self.await_ok(sent);
+        }
+
+        ready
+    }
+
+    /// When the next queued event can go out, none if nothing is queued
+    pub fn next_ready(&self) -> Option<Instant> {
+        if self.queued.is_empty() {
+            return None;
+        }
+
+        if let Some(until) = self.paused_until {
+            return Some(until);
+        }
+
+        let missing = (1.0 - self.tokens).max(0.0);
+        Some(self.last_refill + Duration::from_secs_f32(missing / PER_SECOND))
+    }
+
+    fn await_ok(&mut self, sent: SentEvent) {
+        let ClientMessage::Event(event) = &sent.msg else {
+            return;
+        };
+        let Some(id) = event_id(&event.note_json) else {
+            return;
+        };
+
+        self.awaiting_ok.push_back((id, sent));
+        while self.awaiting_ok.len() > MAX_AWAITING_OK {
+            self.awaiting_ok.pop_front();
+        }
+    }
+
+    /// The relay answered an event. Rate limited events are queued again
+    /// and sending pauses for a while.
+    pub fn handle_ok(&mut self, event_id: &str, accepted: bool, message: &str, now: Instant) {
+        let Some(index) = self.awaiting_ok.iter().position(|(id, _)| id == event_id) else {
+            return;
+        };
+        let Some((_, mut sent)) = self.awaiting_ok.remove(index) else {
+            return;
+        };
+
+        if accepted {
+            self.backoff = BACKOFF;
+            return;
+        }
+
+        if !is_rate_limited(message) || sent.retries >= MAX_RETRIES {
+            return;
+        }
+
+        tracing::debug!("rate limited, resending {event_id} in {:?}", self.backoff);
+        sent.retries += 1;
+        self.queued.push_front(sent);
+        self.paused_until = Some(now + self.backoff);
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Did a relay refuse an event only because we sent too fast?
+pub fn is_rate_limited(ok_message: &str) -> bool {
+    ok_message.starts_with(RATE_LIMITED)
+}
+
+fn event_id(note_json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(note_json).ok()?;
+    Some(value.get("id")?.as_str()?.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str) -> ClientMessage {
+        ClientMessage::event_json(format!(r#"{{"id":"{id}","kind":1}}"#)).unwrap()
+    }
+
+    #[test]
+    fn paces_bursts_and_resends_rate_limited() {
+        let start = Instant::now();
+        let mut queue = SendQueue::default();
+        for i in 0..15 {
+            queue.push(event(&i.to_string()));
+        }
+
+        assert_eq!(queue.ready(start).len(), BURST as usize);
+        assert_eq!(queue.len(), 5);
+        // the next one has to wait for a token
+        let next = queue.next_ready().unwrap();
+        assert!(next > start && next <= start + Duration::from_secs(1));
+
+        // one second later a few more may go
+        let later = start + Duration::from_secs(1);
+        assert_eq!(queue.ready(later).len(), PER_SECOND as usize);
+
+        queue.handle_ok("0", false, "rate-limited: slow down", later);
+        assert_eq!(queue.len(), 2);
+        // paused while backing off
+        assert!(queue.ready(later + Duration::from_secs(1)).is_empty());
+        assert_eq!(queue.next_ready(), Some(later + BACKOFF));
+
+        let resumed = queue.ready(later + BACKOFF);
+        assert!(matches!(&resumed[0], ClientMessage::Event(e) if e.note_json.contains("\"0\"")));
+
+        // other refusals aren't retried
+        queue.handle_ok("1", false, "blocked: spam", later);
+        assert!(queue.is_empty());
+    }
+}
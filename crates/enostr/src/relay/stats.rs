@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How many connection failures in a row before a relay stops getting
+/// subscriptions
+const FAILING_AFTER: u32 = 3;
+
+/// Longest we wait between reconnect attempts to a failing relay
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Connection and responsiveness numbers for a relay, used to show relay
+/// health and to route subscriptions away from relays that keep failing
+#[derive(Debug, Default, Clone)]
+pub struct RelayStats {
+    /// Round trip time of the last answered ping
+    pub latency: Option<Duration>,
+    /// Moving average of how long the relay takes to send EOSE after a REQ
+    pub eose_time: Option<Duration>,
+    pub connects: u32,
+    pub failures: u32,
+    /// Failures since the last successful connection
+    pub consecutive_failures: u32,
+    /// Pinned relays always get subscriptions, even while failing
+    pub pinned: bool,
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_received: u64,
+
+    /// When our last ping was sent, cleared when the pong comes back
+    ping_sent: Option<Instant>,
+    /// When each open REQ was sent, cleared on EOSE
+    reqs_sent: HashMap<String, Instant>,
+}
+
+impl RelayStats {
+    /// Fraction of connection attempts that succeeded
+    pub fn success_rate(&self) -> Option<f32> {
+        let attempts = self.connects + self.failures;
+        if attempts == 0 {
+            return None;
+        }
+
+        Some(self.connects as f32 / attempts as f32)
+    }
+
+    /// Has this relay failed enough that we should stop routing
+    /// subscriptions to it?
+    pub fn is_failing(&self) -> bool {
+        !self.pinned && self.consecutive_failures >= FAILING_AFTER
+    }
+
+    /// How long to wait before trying to connect again, backing off while
+    /// the relay keeps failing
+    pub fn reconnect_delay(&self, base: Duration) -> Duration {
+        let exp = self.consecutive_failures.saturating_sub(1).min(8);
+        (base * 2u32.pow(exp)).min(MAX_RECONNECT_DELAY)
+    }
+
+    pub fn connected(&mut self) {
+        self.connects += 1;
+        self.consecutive_failures = 0;
+    }
+
+    pub fn failed(&mut self) {
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        // anything in flight was lost with the connection
+        self.ping_sent = None;
+        self.reqs_sent.clear();
+    }
+
+    pub fn message_sent(&mut self) {
+        self.messages_sent += 1;
+    }
+
+    pub fn message_received(&mut self, bytes: usize) {
+        self.messages_received += 1;
+        self.bytes_received += bytes as u64;
+    }
+
+    pub fn ping_sent(&mut self) {
+        self.ping_sent = Some(Instant::now());
+    }
+
+    pub fn pong_received(&mut self) {
+        if let Some(sent) = self.ping_sent.take() {
+            self.latency = Some(sent.elapsed());
+        }
+    }
+
+    pub fn req_sent(&mut self, subid: &str) {
+        self.reqs_sent.insert(subid.to_owned(), Instant::now());
+    }
+
+    pub fn eose_received(&mut self, subid: &str) {
+        let Some(sent) = self.reqs_sent.remove(subid) else {
+            return;
+        };
+
+        let took = sent.elapsed();
+        self.eose_time = Some(match self.eose_time {
+            // weigh recent subscriptions more
+            Some(avg) => avg.mul_f32(0.75) + took.mul_f32(0.25),
+            None => took,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_after_consecutive_failures() {
+        let mut stats = RelayStats::default();
+        stats.connected();
+        for _ in 0..FAILING_AFTER {
+            assert!(!stats.is_failing());
+            stats.failed();
+        }
+        assert!(stats.is_failing());
+        assert_eq!(stats.success_rate(), Some(1.0 / (1 + FAILING_AFTER) as f32));
+
+        stats.pinned = true;
+        assert!(!stats.is_failing());
+        stats.pinned = false;
+
+        stats.connected();
+        assert!(!stats.is_failing());
+    }
+
+    #[test]
+    fn reconnect_backs_off() {
+        let base = Duration::from_secs(3);
+        let mut stats = RelayStats::default();
+        assert_eq!(stats.reconnect_delay(base), base);
+
+        stats.failed();
+        assert_eq!(stats.reconnect_delay(base), base);
+        stats.failed();
+        assert_eq!(stats.reconnect_delay(base), base * 2);
+
+        for _ in 0..20 {
+            stats.failed();
+        }
+        assert_eq!(stats.reconnect_delay(base), MAX_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn eose_time_is_tracked_per_subscription() {
+        let mut stats = RelayStats::default();
+        stats.eose_received("unknown");
+        assert!(stats.eose_time.is_none());
+
+        stats.req_sent("sub");
+        stats.eose_received("sub");
+        assert!(stats.eose_time.is_some());
+    }
+}
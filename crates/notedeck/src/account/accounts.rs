@@ -1,21 +1,25 @@
 use uuid::Uuid;
 
+use crate::account::app_data::AccountAppData;
 use crate::account::cache::AccountCache;
 use crate::account::contacts::Contacts;
-use crate::account::mute::AccountMutedData;
+use crate::account::mute::{AccountMutedData, MuteAction};
 use crate::account::relay::{
     modify_advertised_relays, update_relay_configuration, AccountRelayData, RelayAction,
     RelayDefaults,
 };
+use crate::account::signer::{RemoteSigner, SignerEvent};
 use crate::storage::AccountStorageWriter;
 use crate::user_account::UserAccountSerializable;
 use crate::{
-    AccountStorage, MuteFun, SingleUnkIdAction, UnifiedSubscription, UnknownIds, UserAccount,
-    ZapWallet,
+    AccountStorage, MuteFun, PublishTracker, RelaySpec, SettingsHandler, SingleUnkIdAction,
+    UnifiedSubscription, UnknownIds, UserAccount, ZapWallet,
 };
-use enostr::{ClientMessage, FilledKeypair, Keypair, Pubkey, RelayPool};
+use enostr::nip46::BunkerUri;
+use enostr::{ClientMessage, FilledKeypair, Keypair, KeypairUnowned, Pubkey, RelayPool};
 use nostrdb::{Ndb, Note, Transaction};
 
+use std::collections::BTreeSet;
 // TODO: remove this
 use std::sync::Arc;
 
@@ -26,6 +30,9 @@ pub struct Accounts {
     storage_writer: Option<AccountStorageWriter>,
     relay_defaults: RelayDefaults,
     subs: AccountSubs,
+    /// A remote signer we are logging in with, until it tells us the
+    /// user's pubkey
+    pending_signer: Option<RemoteSigner>,
 }
 
 impl Accounts {
@@ -94,6 +101,7 @@ impl Accounts {
             storage_writer,
             relay_defaults,
             subs,
+            pending_signer: None,
         }
     }
 
@@ -160,6 +168,59 @@ impl Accounts {
         })
     }
 
+    /// Start logging in with a remote signer. Once it has told us the
+    /// user's pubkey, finish with [`Accounts::add_remote_signer_account`].
+    pub fn connect_remote_signer(&mut self, uri: BunkerUri) {
+        self.pending_signer = Some(RemoteSigner::new(uri));
+    }
+
+    /// Start logging in with a remote signer that connects to our
+    /// nostrconnect:// uri, see [`RemoteSigner::nostrconnect_uri`]
+    pub fn start_nostrconnect(&mut self) {
+        self.pending_signer = Some(RemoteSigner::nostrconnect());
+    }
+
+    pub fn pending_signer(&self) -> Option<&RemoteSigner> {
+        self.pending_signer.as_ref()
+    }
+
+    pub fn cancel_remote_signer(&mut self) {
+        self.pending_signer = None;
+    }
+
+    /// Add the account the pending remote signer signs for
+    #[must_use = "UnknownIdAction's must be handled. Use .process_unknown_id_action()"]
+    pub fn add_remote_signer_account(&mut self) -> Option<AddAccountResponse> {
+        let pk = *self.pending_signer.as_ref()?.user()?;
+        let signer = self.pending_signer.take()?;
+        self.add_signer_account(pk, signer)
+    }
+
+    /// Add an account that signs with `signer`, eg. one restored from a
+    /// backup
+    #[must_use = "UnknownIdAction's must be handled. Use .process_unknown_id_action()"]
+    pub fn add_signer_account(
+        &mut self,
+        pk: Pubkey,
+        signer: RemoteSigner,
+    ) -> Option<AddAccountResponse> {
+        let resp = self.add_account(Keypair::only_pubkey(pk));
+
+        let acc = self.cache.get_mut(&pk)?;
+        acc.signer = Some(signer);
+
+        if let Some(key_store) = &self.storage_writer {
+            if let Err(e) = key_store.write_account(&(&*acc).into()) {
+                tracing::error!("Could not save remote signer for {pk:?}: {e}");
+            }
+        }
+
+        Some(resp.unwrap_or(AddAccountResponse {
+            switch_to: pk,
+            unk_id_action: SingleUnkIdAction::pubkey(pk),
+        }))
+    }
+
     /// Update the `UserAccount` via callback and save the result to disk.
     /// return true if the update was successful
     pub fn update_current_account(&mut self, update: impl FnOnce(&mut UserAccount)) -> bool {
@@ -185,6 +246,22 @@ impl Accounts {
         self.get_selected_account().key.to_full()
     }
 
+    /// The key of an account that can post. Remote signer accounts have
+    /// no secret key here, their notes go through
+    /// [`RemoteSigner::sign_note`] before they are published.
+    pub fn poster(&self, pubkey: &Pubkey) -> Option<KeypairUnowned<'_>> {
+        let acc = self.cache.get(pubkey)?;
+        (acc.key.secret_key.is_some() || acc.signer.is_some()).then(|| acc.keypair())
+    }
+
+    pub fn selected_poster(&self) -> Option<KeypairUnowned<'_>> {
+        self.poster(self.selected_account_pubkey())
+    }
+
+    pub fn selected_signer_mut(&mut self) -> Option<&mut RemoteSigner> {
+        self.cache.selected_mut().signer.as_mut()
+    }
+
     /// Get the selected account's pubkey as bytes. Common operation so
     /// we make it a helper here.
     pub fn selected_account_pubkey_bytes(&self) -> &[u8; 32] {
@@ -297,12 +374,27 @@ impl Accounts {
             ),
             relay_url,
         );
+        pool.send_to(
+            &ClientMessage::req(
+                self.subs.app_data.remote.clone(),
+                vec![data.app_data.filter.clone()],
+            ),
+            relay_url,
+        );
     }
 
-    pub fn update(&mut self, ndb: &mut Ndb, pool: &mut RelayPool, ctx: &egui::Context) {
+    pub fn update(
+        &mut self,
+        ndb: &mut Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+        ctx: &egui::Context,
+    ) {
         // IMPORTANT - This function is called in the UI update loop,
         // make sure it is fast when idle
 
+        self.update_signers(ndb, pool, publish_tracker, ctx);
+
         let Some(update) = self
             .cache
             .selected_mut()
@@ -327,18 +419,80 @@ impl Accounts {
         }
     }
 
+    fn update_signers(
+        &mut self,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+        ctx: &egui::Context,
+    ) {
+        if let Some(signer) = &mut self.pending_signer {
+            // ask again once the user dismissed a failed attempt
+            if signer.user().is_none() && signer.requests().is_empty() {
+                signer.connect(pool);
+            }
+
+            // we only need the pubkey here, which the signer keeps
+            signer.update(ndb, pool, create_wakeup(ctx));
+        }
+
+        let Some(signer) = &mut self.cache.selected_mut().signer else {
+            return;
+        };
+
+        for event in signer.update(ndb, pool, create_wakeup(ctx)) {
+            let SignerEvent::Signed(json) = event else {
+                continue;
+            };
+
+            if let Err(err) = publish_tracker.publish_json(pool, json) {
+                tracing::error!("could not publish remotely signed note: {err}");
+            }
+        }
+    }
+
     pub fn get_full<'a>(&'a self, pubkey: &Pubkey) -> Option<FilledKeypair<'a>> {
         self.cache.get(pubkey).and_then(|r| r.key.to_full())
     }
 
+    /// Advertise the bootstrap relays as the relay list of a freshly made
+    /// account, so other clients know where to find it
+    pub fn publish_starter_relays(
+        &mut self,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+        pubkey: &Pubkey,
+    ) {
+        let Some(acc) = self.cache.get_mut(pubkey) else {
+            return;
+        };
+
+        let Some(secret_key) = &acc.key.secret_key else {
+            return;
+        };
+
+        acc.data.relay.advertised = self.relay_defaults.bootstrap_relays.clone();
+        acc.data
+            .relay
+            .publish_nip65_relays(&secret_key.to_secret_bytes(), pool, publish_tracker);
+    }
+
     pub fn process_relay_action(
         &mut self,
         ctx: &egui::Context,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         action: RelayAction,
     ) {
         let acc = self.cache.selected_mut();
-        modify_advertised_relays(&acc.key, action, pool, &self.relay_defaults, &mut acc.data);
+        modify_advertised_relays(
+            &acc.key,
+            action,
+            pool,
+            publish_tracker,
+            &self.relay_defaults,
+            &mut acc.data,
+        );
 
         update_relay_configuration(
             pool,
@@ -349,6 +503,77 @@ impl Accounts {
         );
     }
 
+    /// Mute or unmute something for the selected account and publish the
+    /// new mute list. Needs the account's secret key.
+    pub fn process_mute_action(
+        &mut self,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+        action: MuteAction,
+    ) {
+        let acc = self.cache.selected_mut();
+        let Some(seckey) = acc.key.secret_key.as_ref().map(|sk| sk.to_secret_bytes()) else {
+            tracing::warn!("can't change the mute list of a read-only account");
+            return;
+        };
+
+        acc.data
+            .muted
+            .apply(action, &seckey, ndb, pool, publish_tracker);
+    }
+
+    /// Relays send EOSE for the mute list even when the account has none,
+    /// which is when changing it becomes safe
+    pub fn handle_eose(&mut self, subid: &str) {
+        if subid == self.subs.mute.remote {
+            self.cache.selected_mut().data.muted.handle_eose();
+        }
+    }
+
+    /// Keep settings in step with the selected account's other devices:
+    /// take newer settings they published and publish ours when they
+    /// changed here. Returns true when settings from another device were
+    /// applied. Needs the account's secret key to encrypt them.
+    pub fn sync_settings(
+        &mut self,
+        settings: &mut SettingsHandler,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+    ) -> bool {
+        let acc = self.cache.selected_mut();
+        let Some(seckey) = &acc.key.secret_key else {
+            return false;
+        };
+        let pubkey = acc.key.pubkey;
+        let app_data = &mut acc.data.app_data;
+
+        let mut applied = false;
+        if let Some((created_at, synced)) = app_data.take_settings(seckey, &pubkey) {
+            applied = settings.apply_synced(synced, &pubkey, created_at);
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let updated_at = settings.updated_at();
+        if app_data.needs_publish(updated_at, now) {
+            app_data.publish(
+                &settings.synced(&pubkey),
+                updated_at,
+                seckey,
+                &pubkey,
+                ndb,
+                pool,
+                publish_tracker,
+            );
+        }
+
+        applied
+    }
+
     pub fn get_subs(&self) -> &AccountSubs {
         &self.subs
     }
@@ -393,6 +618,10 @@ fn get_acc_from_storage(user_account_serializable: UserAccountSerializable) -> O
     let keypair = user_account_serializable.key;
     let new_account_data = AccountData::new(keypair.pubkey.bytes());
 
+    let signer = user_account_serializable
+        .signer
+        .and_then(|s| s.into_signer(keypair.pubkey));
+
     let mut wallet = None;
     if let Some(wallet_s) = user_account_serializable.wallet {
         let m_wallet: Result<crate::ZapWallet, crate::Error> = wallet_s.into();
@@ -407,6 +636,7 @@ fn get_acc_from_storage(user_account_serializable: UserAccountSerializable) -> O
     Some(UserAccount {
         key: keypair,
         wallet,
+        signer,
         data: new_account_data,
     })
 }
@@ -416,6 +646,7 @@ pub struct AccountData {
     pub(crate) relay: AccountRelayData,
     pub(crate) muted: AccountMutedData,
     pub contacts: Contacts,
+    pub(crate) app_data: AccountAppData,
 }
 
 impl AccountData {
@@ -424,9 +655,15 @@ impl AccountData {
             relay: AccountRelayData::new(pubkey),
             muted: AccountMutedData::new(pubkey),
             contacts: Contacts::new(pubkey),
+            app_data: AccountAppData::new(pubkey),
         }
     }
 
+    /// The account's NIP-65 relay list
+    pub fn advertised_relays(&self) -> &BTreeSet<RelaySpec> {
+        &self.relay.advertised
+    }
+
     pub(super) fn poll_for_updates(
         &mut self,
         ndb: &Ndb,
@@ -441,6 +678,8 @@ impl AccountData {
         self.muted.poll_for_updates(ndb, &txn, subs.mute.local);
         self.contacts
             .poll_for_updates(ndb, &txn, subs.contacts.local);
+        self.app_data
+            .poll_for_updates(ndb, &txn, subs.app_data.local);
 
         resp
     }
@@ -450,6 +689,7 @@ impl AccountData {
         self.relay.query(ndb, txn);
         self.muted.query(ndb, txn);
         self.contacts.query(ndb, txn);
+        self.app_data.query(ndb, txn);
     }
 }
 
@@ -466,6 +706,7 @@ pub struct AccountSubs {
     relay: UnifiedSubscription,
     mute: UnifiedSubscription,
     pub contacts: UnifiedSubscription,
+    app_data: UnifiedSubscription,
 }
 
 impl AccountSubs {
@@ -480,12 +721,14 @@ impl AccountSubs {
         let relay = subscribe(ndb, pool, &data.relay.filter);
         let mute = subscribe(ndb, pool, &data.muted.filter);
         let contacts = subscribe(ndb, pool, &data.contacts.filter);
+        let app_data = subscribe(ndb, pool, &data.app_data.filter);
         update_relay_configuration(pool, relay_defaults, pk, &data.relay, wakeup);
 
         Self {
             relay,
             mute,
             contacts,
+            app_data,
         }
     }
 
@@ -501,6 +744,7 @@ impl AccountSubs {
         unsubscribe(ndb, pool, &self.relay);
         unsubscribe(ndb, pool, &self.mute);
         unsubscribe(ndb, pool, &self.contacts);
+        unsubscribe(ndb, pool, &self.app_data);
 
         *self = AccountSubs::new(ndb, pool, relay_defaults, pk, new_selection_data, wakeup);
     }
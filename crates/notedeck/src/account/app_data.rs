@@ -0,0 +1,210 @@
+use enostr::{Pubkey, RelayPool, SecretKey};
+use nostr::nips::nip44;
+use nostrdb::{Filter, IngestMetadata, Ndb, Note, NoteBuilder, NoteKey, Subscription, Transaction};
+use tracing::{debug, error, info};
+
+use crate::{PublishTracker, SyncedSettings};
+
+/// NIP-78 arbitrary custom app data
+pub const APP_DATA_KIND: u64 = 30078;
+
+/// The `d` tag of our settings event
+const SETTINGS_D_TAG: &str = "notedeck/settings";
+
+/// How long to wait after the last change before publishing, so dragging
+/// a slider doesn't publish an event per frame
+pub const SETTINGS_PUBLISH_DELAY: u64 = 5;
+
+/// The account's notedeck settings, kept as a NIP-78 app data event
+/// encrypted to itself so they follow the account to other devices
+#[derive(Clone)]
+pub(crate) struct AccountAppData {
+    pub filter: Filter,
+    /// created_at of the newest settings event we saw or published
+    synced_at: u64,
+    /// The newest settings event we haven't looked at yet, still encrypted
+    pending: Option<(u64, String)>,
+}
+
+impl AccountAppData {
+    pub fn new(pubkey: &[u8; 32]) -> Self {
+        let filter = Filter::new()
+            .authors([pubkey])
+            .kinds([APP_DATA_KIND])
+            .tags([SETTINGS_D_TAG], 'd')
+            .limit(1)
+            .build();
+
+        AccountAppData {
+            filter,
+            synced_at: 0,
+            pending: None,
+        }
+    }
+
+    pub(super) fn query(&mut self, ndb: &Ndb, txn: &Transaction) {
+        let lim = self
+            .filter
+            .limit()
+            .unwrap_or(crate::filter::default_limit()) as i32;
+        let nks = ndb
+            .query(txn, std::slice::from_ref(&self.filter), lim)
+            .expect("query user app data results")
+            .iter()
+            .map(|qr| qr.note_key)
+            .collect::<Vec<NoteKey>>();
+
+        self.harvest(ndb, txn, &nks);
+    }
+
+    pub(super) fn poll_for_updates(&mut self, ndb: &Ndb, txn: &Transaction, sub: Subscription) {
+        let nks = ndb.poll_for_notes(sub, 1);
+        if nks.is_empty() {
+            return;
+        }
+
+        self.harvest(ndb, txn, &nks);
+    }
+
+    fn harvest(&mut self, ndb: &Ndb, txn: &Transaction, nks: &[NoteKey]) {
+        for nk in nks {
+            let Ok(note) = ndb.get_note_by_key(txn, *nk) else {
+                continue;
+            };
+
+            if !is_settings_note(&note) || note.created_at() <= self.synced_at {
+                continue;
+            }
+
+            debug!("found synced settings from {}", note.created_at());
+            self.synced_at = note.created_at();
+            self.pending = Some((note.created_at(), note.content().to_owned()));
+        }
+    }
+
+    /// Decrypt settings another device published, if there are new ones
+    pub(super) fn take_settings(
+        &mut self,
+        seckey: &SecretKey,
+        pubkey: &Pubkey,
+    ) -> Option<(u64, SyncedSettings)> {
+        let (created_at, content) = self.pending.take()?;
+
+        let json = match own_public_key(pubkey)
+            .and_then(|pubkey| nip44::decrypt(seckey, &pubkey, &content).map_err(|e| e.to_string()))
+        {
+            Ok(json) => json,
+            Err(err) => {
+                error!("could not decrypt synced settings: {err}");
+                return None;
+            }
+        };
+
+        match serde_json::from_str(&json) {
+            Ok(settings) => Some((created_at, settings)),
+            Err(err) => {
+                error!("invalid synced settings: {err}");
+                None
+            }
+        }
+    }
+
+    /// Whether local settings changed at `updated_at` still need publishing
+    pub(super) fn needs_publish(&self, updated_at: u64, now: u64) -> bool {
+        updated_at > self.synced_at && now >= updated_at + SETTINGS_PUBLISH_DELAY
+    }
+
+    /// Publish our settings, dated when they last changed so relays and
+    /// other devices keep whichever side changed last
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn publish(
+        &mut self,
+        settings: &SyncedSettings,
+        updated_at: u64,
+        seckey: &SecretKey,
+        pubkey: &Pubkey,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+    ) {
+        // don't retry every frame if something below fails
+        self.synced_at = updated_at;
+
+        let json = match serde_json::to_string(settings) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("could not serialize settings: {err}");
+                return;
+            }
+        };
+
+        let content = match own_public_key(pubkey).and_then(|pubkey| {
+            nip44::encrypt(seckey, &pubkey, &json, nip44::Version::V2).map_err(|e| e.to_string())
+        }) {
+            Ok(content) => content,
+            Err(err) => {
+                error!("could not encrypt settings: {err}");
+                return;
+            }
+        };
+
+        let note = NoteBuilder::new()
+            .kind(APP_DATA_KIND as u32)
+            .content(&content)
+            .created_at(updated_at)
+            .start_tag()
+            .tag_str("d")
+            .tag_str(SETTINGS_D_TAG)
+            .sign(&seckey.to_secret_bytes())
+            .build()
+            .expect("note build");
+
+        let Ok(event) = enostr::ClientMessage::event(&note) else {
+            error!("could not serialize settings event");
+            return;
+        };
+
+        if let Ok(json) = event.to_json() {
+            let _ = ndb.process_event_with(&json, IngestMetadata::new().client(true));
+        }
+
+        info!("publishing synced settings from {updated_at}");
+        if let Err(err) = publish_tracker.publish(pool, &note) {
+            error!("could not publish synced settings: {err}");
+        }
+    }
+}
+
+/// Settings are NIP-44 encrypted to the account itself
+fn own_public_key(pubkey: &Pubkey) -> Result<nostr::PublicKey, String> {
+    nostr::PublicKey::from_slice(pubkey.bytes()).map_err(|e| e.to_string())
+}
+
+fn is_settings_note(note: &Note) -> bool {
+    note.kind() as u64 == APP_DATA_KIND
+        && note.tags().iter().any(|tag| {
+            tag.count() >= 2
+                && tag.get_str(0) == Some("d")
+                && tag.get_str(1) == Some(SETTINGS_D_TAG)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn publish_waits_for_changes_to_settle() {
+        let data = AccountAppData {
+            filter: Filter::new().build(),
+            synced_at: 100,
+            pending: None,
+        };
+
+        // not newer than what we already have
+        assert!(!data.needs_publish(100, 1000));
+        // changed, but maybe still changing
+        assert!(!data.needs_publish(200, 202));
+        assert!(data.needs_publish(200, 200 + SETTINGS_PUBLISH_DELAY));
+    }
+}
@@ -0,0 +1,366 @@
+use enostr::nip46::BunkerUri;
+use enostr::{ClientMessage, Keypair, Pubkey, SecretKey};
+use nostr::nips::nip49::{EncryptedSecretKey, KeySecurity};
+use nostrdb::{Filter, IngestMetadata, Ndb, Transaction};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::account::signer::RemoteSignerSerializable;
+use crate::{Accounts, Settings, FALLBACK_PUBKEY};
+
+const BACKUP_VERSION: u32 = 1;
+
+/// scrypt cost for the exported keys, NIP-49 recommends at least 16
+const BACKUP_LOG_N: u8 = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("wrong passphrase")]
+    WrongPassphrase,
+    #[error("not a notedeck backup")]
+    Invalid,
+    #[error("made by a newer notedeck (version {0})")]
+    UnsupportedVersion(u32),
+    #[error("could not encrypt key")]
+    Encrypt,
+    #[error("a key doesn't belong to its account")]
+    KeyMismatch,
+}
+
+/// Everything needed to set notedeck up again on a fresh install: keys
+/// encrypted with a passphrase (NIP-49), each account's relay list and
+/// follow list, and the local settings. Nothing secret is stored in the
+/// clear, so the translation api key and bunker secrets are left out.
+#[derive(Serialize, Deserialize)]
+pub struct AccountBackup {
+    pub version: u32,
+    pub created_at: u64,
+    pub accounts: Vec<BackedUpAccount>,
+    pub settings: Settings,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackedUpAccount {
+    pub pubkey: Pubkey,
+    /// The secret key as an ncryptsec, missing for accounts we can't
+    /// sign for
+    pub ncryptsec: Option<String>,
+    /// The latest relay list and contact list, as event json
+    pub events: Vec<String>,
+    /// How to reach the account's remote signer again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<BackedUpSigner>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackedUpSigner {
+    /// The signer's bunker:// uri, without its one-time secret
+    pub uri: String,
+    /// Our session key the signer knows us by, as an ncryptsec
+    pub client_ncryptsec: String,
+}
+
+/// An account from a backup whose keys opened
+pub struct RestoredAccount {
+    pub keypair: Keypair,
+    pub signer: Option<RemoteSignerSerializable>,
+}
+
+/// What goes into a backup, gathered from the accounts and database.
+/// Encrypting the keys is slow on purpose, so [`BackupContents::encrypt`]
+/// should run off the UI thread.
+pub struct BackupContents {
+    accounts: Vec<PlainAccount>,
+    settings: Settings,
+}
+
+struct PlainAccount {
+    pubkey: Pubkey,
+    secret_key: Option<SecretKey>,
+    signer: Option<RemoteSignerSerializable>,
+    events: Vec<String>,
+}
+
+impl BackupContents {
+    pub fn new(accounts: &Accounts, ndb: &Ndb, mut settings: Settings) -> Self {
+        let txn = Transaction::new(ndb).expect("txn");
+        let fallback = FALLBACK_PUBKEY();
+
+        let accounts = (&accounts.cache)
+            .into_iter()
+            .filter(|(pubkey, _)| **pubkey != fallback)
+            .map(|(pubkey, account)| {
+                let data = &account.data;
+                PlainAccount {
+                    pubkey: *pubkey,
+                    secret_key: account.key.secret_key.clone(),
+                    signer: account
+                        .signer
+                        .as_ref()
+                        .and_then(RemoteSignerSerializable::new),
+                    events: [&data.relay.filter, &data.contacts.filter]
+                        .into_iter()
+                        .filter_map(|filter| latest_event_json(ndb, &txn, filter))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        settings.translation_api_key = None;
+
+        Self { accounts, settings }
+    }
+
+    pub fn encrypt(self, passphrase: &str) -> Result<AccountBackup, BackupError> {
+        self.encrypt_with(passphrase, BACKUP_LOG_N)
+    }
+
+    fn encrypt_with(self, passphrase: &str, log_n: u8) -> Result<AccountBackup, BackupError> {
+        let accounts = self
+            .accounts
+            .into_iter()
+            .map(|account| {
+                let signer = match account.signer {
+                    Some(signer) => {
+                        let client = signer
+                            .client
+                            .secret_key
+                            .as_ref()
+                            .ok_or(BackupError::Encrypt)?;
+                        Some(BackedUpSigner {
+                            uri: without_secret(&signer.uri)?,
+                            client_ncryptsec: encrypt_key(client, passphrase, log_n)?,
+                        })
+                    }
+                    None => None,
+                };
+
+                Ok(BackedUpAccount {
+                    pubkey: account.pubkey,
+                    ncryptsec: account
+                        .secret_key
+                        .as_ref()
+                        .map(|secret| encrypt_key(secret, passphrase, log_n))
+                        .transpose()?,
+                    events: account.events,
+                    signer,
+                })
+            })
+            .collect::<Result<Vec<_>, BackupError>>()?;
+
+        Ok(AccountBackup {
+            version: BACKUP_VERSION,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            accounts,
+            settings: self.settings,
+        })
+    }
+}
+
+impl AccountBackup {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, BackupError> {
+        let backup: Self = serde_json::from_str(json).map_err(|_| BackupError::Invalid)?;
+        if backup.version > BACKUP_VERSION {
+            return Err(BackupError::UnsupportedVersion(backup.version));
+        }
+        Ok(backup)
+    }
+
+    /// Decrypt every key. Fails if any of them doesn't open with the
+    /// passphrase or isn't the account's key, so nothing is restored
+    /// halfway.
+    pub fn accounts(&self, passphrase: &str) -> Result<Vec<RestoredAccount>, BackupError> {
+        self.accounts
+            .iter()
+            .map(|account| {
+                let keypair = match account.ncryptsec.as_deref() {
+                    Some(ncryptsec) => {
+                        let keypair = Keypair::from_secret(decrypt_key(ncryptsec, passphrase)?);
+                        if keypair.pubkey != account.pubkey {
+                            return Err(BackupError::KeyMismatch);
+                        }
+                        keypair
+                    }
+                    None => Keypair::only_pubkey(account.pubkey),
+                };
+
+                let signer = match &account.signer {
+                    Some(signer) => {
+                        let client = decrypt_key(&signer.client_ncryptsec, passphrase)?;
+                        Some(RemoteSignerSerializable {
+                            uri: signer.uri.clone(),
+                            client: Keypair::from_secret(client),
+                        })
+                    }
+                    None => None,
+                };
+
+                Ok(RestoredAccount { keypair, signer })
+            })
+            .collect()
+    }
+
+    /// Put the relay and follow lists in the database, so the restored
+    /// accounts have them before any relay answers
+    pub fn ingest_events(&self, ndb: &Ndb) {
+        for json in self.accounts.iter().flat_map(|a| a.events.iter()) {
+            let Ok(msg) = ClientMessage::event_json(json.clone()).and_then(|m| m.to_json()) else {
+                continue;
+            };
+
+            if let Err(err) = ndb.process_event_with(&msg, IngestMetadata::new().client(true)) {
+                error!("could not ingest backed up event: {err}");
+            }
+        }
+    }
+}
+
+fn encrypt_key(secret: &SecretKey, passphrase: &str, log_n: u8) -> Result<String, BackupError> {
+    EncryptedSecretKey::new(secret, passphrase, log_n, KeySecurity::Unknown)
+        .and_then(|e| e.to_bech32())
+        .map_err(|_| BackupError::Encrypt)
+}
+
+/// The bunker secret is only good for the first `connect`, the signer
+/// knows our session key after that
+fn without_secret(uri: &str) -> Result<String, BackupError> {
+    let mut uri = BunkerUri::parse(uri).map_err(|_| BackupError::Invalid)?;
+    uri.secret = None;
+    Ok(uri.to_string())
+}
+
+fn decrypt_key(ncryptsec: &str, passphrase: &str) -> Result<SecretKey, BackupError> {
+    EncryptedSecretKey::from_bech32(ncryptsec)
+        .map_err(|_| BackupError::Invalid)?
+        .to_secret_key(passphrase)
+        .map_err(|_| BackupError::WrongPassphrase)
+}
+
+fn latest_event_json(ndb: &Ndb, txn: &Transaction, filter: &Filter) -> Option<String> {
+    let results = ndb.query(txn, std::slice::from_ref(filter), 1).ok()?;
+    results.first()?.note.json().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::FullKeypair;
+
+    fn backup_of(kp: &Keypair) -> AccountBackup {
+        let secret = kp.secret_key.as_ref().unwrap();
+        let ncryptsec = EncryptedSecretKey::new(secret, "hunter2", 1, KeySecurity::Unknown)
+            .unwrap()
+            .to_bech32()
+            .unwrap();
+
+        AccountBackup {
+            version: BACKUP_VERSION,
+            created_at: 0,
+            accounts: vec![BackedUpAccount {
+                pubkey: kp.pubkey,
+                ncryptsec: Some(ncryptsec),
+                events: vec![],
+                signer: None,
+            }],
+            settings: Settings::default(),
+        }
+    }
+
+    #[test]
+    fn keys_need_the_passphrase() {
+        let kp = FullKeypair::generate().to_keypair();
+        let json = backup_of(&kp).to_json().unwrap();
+        let backup = AccountBackup::from_json(&json).unwrap();
+
+        assert!(matches!(
+            backup.accounts("wrong"),
+            Err(BackupError::WrongPassphrase)
+        ));
+
+        let restored = backup.accounts("hunter2").unwrap();
+        assert_eq!(restored[0].keypair.pubkey, kp.pubkey);
+        assert_eq!(restored[0].keypair.secret_key, kp.secret_key);
+    }
+
+    #[test]
+    fn keeps_remote_signers() {
+        let user = FullKeypair::generate().pubkey;
+        let client = FullKeypair::generate().to_keypair();
+        let signer_pk = FullKeypair::generate().pubkey.hex();
+        let uri = format!("bunker://{signer_pk}?relay=wss://relay.nsec.app&secret=letmein");
+
+        let contents = BackupContents {
+            accounts: vec![PlainAccount {
+                pubkey: user,
+                secret_key: None,
+                signer: Some(RemoteSignerSerializable {
+                    uri: uri.clone(),
+                    client: client.clone(),
+                }),
+                events: vec![],
+            }],
+            settings: Settings {
+                translation_api_key: Some("sk-12345".to_owned()),
+                ..Default::default()
+            },
+        };
+        let json = contents
+            .encrypt_with("hunter2", 1)
+            .unwrap()
+            .to_json()
+            .unwrap();
+        assert!(!json.contains(&client.secret_key.as_ref().unwrap().to_secret_hex()));
+        assert!(!json.contains("letmein"));
+        assert!(!json.contains("sk-12345"));
+
+        let restored = AccountBackup::from_json(&json)
+            .unwrap()
+            .accounts("hunter2")
+            .unwrap();
+        assert_eq!(restored[0].keypair.pubkey, user);
+        assert!(restored[0].keypair.secret_key.is_none());
+
+        let signer = restored[0].signer.as_ref().unwrap();
+        let restored_uri = BunkerUri::parse(&signer.uri).unwrap();
+        assert_eq!(restored_uri.remote_signer.hex(), signer_pk);
+        assert_eq!(restored_uri.secret, None);
+        assert_eq!(signer.client.pubkey, client.pubkey);
+        assert_eq!(signer.client.secret_key, client.secret_key);
+    }
+
+    #[test]
+    fn rejects_keys_of_other_accounts() {
+        let kp = FullKeypair::generate().to_keypair();
+        let mut backup = backup_of(&kp);
+        backup.accounts[0].pubkey = FullKeypair::generate().pubkey;
+
+        assert!(matches!(
+            backup.accounts("hunter2"),
+            Err(BackupError::KeyMismatch)
+        ));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let kp = FullKeypair::generate().to_keypair();
+        let mut backup = backup_of(&kp);
+        backup.version = BACKUP_VERSION + 1;
+        let json = backup.to_json().unwrap();
+
+        assert!(matches!(
+            AccountBackup::from_json(&json),
+            Err(BackupError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            AccountBackup::from_json("{}"),
+            Err(BackupError::Invalid)
+        ));
+    }
+}
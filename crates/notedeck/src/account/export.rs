@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use chrono::{Days, Local, NaiveDate, NaiveTime};
+use enostr::Pubkey;
+use nostrdb::{Filter, Ndb, NoteKey, Transaction};
+
+/// Calendar events (NIP-52): date based, time based and RSVPs
+const CALENDAR_KINDS: [u64; 3] = [31922, 31923, 31925];
+
+/// How many notes we pull from the db at a time
+const QUERY_BATCH: i32 = 5000;
+
+/// Events to write out as JSON lines, one event per line, for archiving
+/// or moving to another client
+#[derive(Debug, Clone)]
+pub struct EventExport {
+    /// First day to export, in local time
+    pub from: NaiveDate,
+    /// Last day to export, included
+    pub to: NaiveDate,
+    /// Also export calendar events others published that tag us
+    pub calendar_invites: bool,
+}
+
+impl Default for EventExport {
+    /// The past year
+    fn default() -> Self {
+        let to = Local::now().date_naive();
+        Self {
+            from: to.checked_sub_days(Days::new(365)).unwrap_or(to),
+            to,
+            calendar_invites: false,
+        }
+    }
+}
+
+impl EventExport {
+    pub fn since(&self) -> u64 {
+        local_timestamp(self.from)
+    }
+
+    pub fn until(&self) -> u64 {
+        let next_day = self.to.checked_add_days(Days::new(1)).unwrap_or(self.to);
+        local_timestamp(next_day).saturating_sub(1)
+    }
+
+    /// Our notes up to `until`, or the calendar events tagging us
+    fn filter(&self, author: &Pubkey, until: u64, invites: bool) -> Filter {
+        let filter = if invites {
+            Filter::new()
+                .kinds(CALENDAR_KINDS)
+                .pubkeys([author.bytes()])
+        } else {
+            Filter::new().authors([author.bytes()])
+        };
+
+        filter.since(self.since()).until(until).build()
+    }
+
+    /// Write the events of `author` to `out`, newest first. Returns how
+    /// many were written.
+    pub fn write(
+        &self,
+        ndb: &Ndb,
+        author: &Pubkey,
+        out: &mut impl Write,
+    ) -> std::io::Result<usize> {
+        let txn = Transaction::new(ndb).map_err(std::io::Error::other)?;
+        let mut written: HashSet<NoteKey> = HashSet::new();
+
+        let sources: &[bool] = if self.calendar_invites {
+            &[false, true]
+        } else {
+            &[false]
+        };
+
+        for &invites in sources {
+            let mut until = self.until();
+            loop {
+                let page = self.filter(author, until, invites);
+                let results = ndb
+                    .query(&txn, std::slice::from_ref(&page), QUERY_BATCH)
+                    .map_err(std::io::Error::other)?;
+
+                let mut oldest = until;
+                let mut added = 0;
+                for result in &results {
+                    oldest = oldest.min(result.note.created_at());
+                    if !written.insert(result.note_key) {
+                        continue;
+                    }
+                    let json = result.note.json().map_err(std::io::Error::other)?;
+                    writeln!(out, "{json}")?;
+                    added += 1;
+                }
+
+                // the next page starts at the oldest timestamp we saw, since
+                // more notes can share it. Stop once a page brings nothing new.
+                if results.len() < QUERY_BATCH as usize || added == 0 {
+                    break;
+                }
+                until = oldest;
+            }
+        }
+
+        out.flush()?;
+        Ok(written.len())
+    }
+}
+
+fn local_timestamp(date: NaiveDate) -> u64 {
+    date.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_covers_whole_days() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let export = EventExport {
+            from: day,
+            to: day,
+            calendar_invites: false,
+        };
+
+        assert_eq!(export.until() - export.since(), 24 * 60 * 60 - 1);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod accounts;
+pub mod app_data;
+pub mod backup;
 pub mod cache;
 pub mod contacts;
+pub mod export;
 pub mod mute;
 pub mod relay;
+pub mod signer;
 
 pub const FALLBACK_PUBKEY: fn() -> enostr::Pubkey = || {
     enostr::Pubkey::new([
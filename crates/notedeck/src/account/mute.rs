@@ -1,14 +1,28 @@
 use std::sync::Arc;
 
-use nostrdb::{Filter, Ndb, NoteKey, Subscription, Transaction};
-use tracing::{debug, error};
+use enostr::RelayPool;
+use nostrdb::{Filter, IngestMetadata, Ndb, NoteBuilder, NoteKey, Subscription, Transaction};
+use tracing::{debug, error, info, warn};
 
-use crate::Muted;
+use crate::{MuteItem, Muted, PublishTracker};
+
+#[derive(Debug, Clone)]
+pub enum MuteAction {
+    Add(MuteItem),
+    Remove(MuteItem),
+}
 
 #[derive(Clone)]
 pub(crate) struct AccountMutedData {
     pub filter: Filter,
     pub muted: Arc<Muted>,
+    /// Content of the last mute list we saw. Private mutes are encrypted
+    /// in there, we keep it as is when publishing so they aren't lost.
+    private: String,
+    /// Whether relays sent us their mute list, or told us they have
+    /// none. Until then our copy may be stale, and publishing it would
+    /// overwrite mutes made on other devices.
+    seen_remote: bool,
 }
 
 impl AccountMutedData {
@@ -23,6 +37,8 @@ impl AccountMutedData {
         AccountMutedData {
             filter,
             muted: Arc::new(Muted::default()),
+            private: String::new(),
+            seen_remote: false,
         }
     }
 
@@ -42,6 +58,7 @@ impl AccountMutedData {
         debug!("initial muted {:?}", muted);
 
         self.muted = Arc::new(muted);
+        self.private = Self::harvest_private(ndb, txn, &nks);
     }
 
     pub(crate) fn harvest_nip51_muted(ndb: &Ndb, txn: &Transaction, nks: &[NoteKey]) -> Muted {
@@ -57,12 +74,12 @@ impl AccountMutedData {
                         }
                         Some("t") => {
                             if let Some(str) = tag.get(1).and_then(|f| f.variant().str()) {
-                                muted.hashtags.insert(str.to_string());
+                                muted.add(MuteItem::Hashtag(str.to_string()));
                             }
                         }
                         Some("word") => {
                             if let Some(str) = tag.get(1).and_then(|f| f.variant().str()) {
-                                muted.words.insert(str.to_string());
+                                muted.add(MuteItem::Word(str.to_string()));
                             }
                         }
                         Some("e") => {
@@ -95,5 +112,96 @@ impl AccountMutedData {
         let muted = AccountMutedData::harvest_nip51_muted(ndb, txn, &nks);
         debug!("updated muted {:?}", muted);
         self.muted = Arc::new(muted);
+        self.private = Self::harvest_private(ndb, txn, &nks);
+        self.seen_remote = true;
+    }
+
+    /// The relays finished sending the mute list, if they had one
+    pub(super) fn handle_eose(&mut self) {
+        self.seen_remote = true;
+    }
+
+    fn harvest_private(ndb: &Ndb, txn: &Transaction, nks: &[NoteKey]) -> String {
+        nks.iter()
+            .find_map(|nk| ndb.get_note_by_key(txn, *nk).ok())
+            .map(|note| note.content().to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Change the mute list and publish it as a new NIP-51 mute list.
+    /// The change goes on top of the latest list we have stored, and
+    /// nothing is published before the relays' list was seen.
+    pub(super) fn apply(
+        &mut self,
+        action: MuteAction,
+        seckey: &[u8; 32],
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+    ) {
+        if !self.seen_remote {
+            warn!("not changing the mute list before the relays sent theirs: {action:?}");
+            return;
+        }
+
+        match Transaction::new(ndb) {
+            Ok(txn) => self.query(ndb, &txn),
+            Err(err) => {
+                error!("could not load the latest mute list: {err}");
+                return;
+            }
+        }
+
+        let mut muted = (*self.muted).clone();
+        let changed = match &action {
+            MuteAction::Add(item) => muted.add(item.clone()),
+            MuteAction::Remove(item) => muted.remove(item),
+        };
+
+        if !changed {
+            return;
+        }
+
+        info!("{action:?}");
+        self.muted = Arc::new(muted);
+        self.publish_nip51_muted(seckey, ndb, pool, publish_tracker);
+    }
+
+    fn publish_nip51_muted(
+        &self,
+        seckey: &[u8; 32],
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+    ) {
+        let mut builder = NoteBuilder::new().kind(10000).content(&self.private);
+        for pk in &self.muted.pubkeys {
+            builder = builder.start_tag().tag_str("p").tag_id(pk);
+        }
+        for hashtag in &self.muted.hashtags {
+            builder = builder.start_tag().tag_str("t").tag_str(hashtag);
+        }
+        for word in &self.muted.words {
+            builder = builder.start_tag().tag_str("word").tag_str(word);
+        }
+        for thread in &self.muted.threads {
+            builder = builder.start_tag().tag_str("e").tag_id(thread);
+        }
+
+        let note = builder.sign(seckey).build().expect("note build");
+        let Ok(event) = enostr::ClientMessage::event(&note) else {
+            error!("could not serialize mute list");
+            return;
+        };
+
+        // ingest it ourselves too, so the list is right after a restart
+        // even if no relay echoes it back
+        if let Ok(json) = event.to_json() {
+            let _ = ndb.process_event_with(&json, IngestMetadata::new().client(true));
+        }
+
+        if let Err(err) = publish_tracker.publish(pool, &note) {
+            error!("could not publish mute list: {err}");
+        }
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::BTreeSet;
 
-use crate::{AccountData, RelaySpec};
+use crate::{AccountData, PublishTracker, RelaySpec};
 use enostr::{Keypair, Pubkey, RelayPool};
 use nostrdb::{Filter, Ndb, NoteBuilder, NoteKey, Subscription, Transaction};
 use tracing::{debug, error, info};
@@ -96,7 +96,12 @@ impl AccountRelayData {
         relays
     }
 
-    pub fn publish_nip65_relays(&self, seckey: &[u8; 32], pool: &mut RelayPool) {
+    pub fn publish_nip65_relays(
+        &self,
+        seckey: &[u8; 32],
+        pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
+    ) {
         let mut builder = NoteBuilder::new().kind(10002).content("");
         for rs in &self.advertised {
             builder = builder.start_tag().tag_str("r").tag_str(&rs.url);
@@ -107,7 +112,9 @@ impl AccountRelayData {
             }
         }
         let note = builder.sign(seckey).build().expect("note build");
-        pool.send(&enostr::ClientMessage::event(&note).expect("note client message"));
+        if let Err(err) = publish_tracker.publish(pool, &note) {
+            error!("could not publish relay list: {err}");
+        }
     }
 
     pub fn poll_for_updates(&mut self, ndb: &Ndb, txn: &Transaction, sub: Subscription) -> bool {
@@ -193,7 +200,19 @@ pub(super) fn update_relay_configuration(
     let mut sub: BTreeSet<RelaySpec> = pool_specs.difference(&desired_relays).cloned().collect();
     if !add.is_empty() {
         debug!("configuring added relays: {:?}", add);
-        let _ = pool.add_urls(add.iter().map(|r| r.url.clone()).collect(), wakeup);
+        let _ = pool.add_urls(add.iter().map(|r| r.url.clone()).collect(), wakeup.clone());
+    }
+
+    // Apply the read/write markers. Relays we were already connected to
+    // are reconnected so their subscriptions match their new usage.
+    let reconnect: BTreeSet<String> = desired_relays
+        .iter()
+        .filter(|r| pool.set_usage(&r.url, r.usage()) && !add.contains(*r))
+        .map(|r| r.url.clone())
+        .collect();
+    for url in &reconnect {
+        debug!("reconnecting {url} with new markers");
+        pool.reconnect(url, wakeup.clone());
     }
     if !sub.is_empty() {
         // certain relays are persistent like the multicast relay,
@@ -211,6 +230,12 @@ pub(super) fn update_relay_configuration(
 pub enum RelayAction {
     Add(String),
     Remove(String),
+    /// Change the NIP-65 read/write markers of an advertised relay
+    SetUsage {
+        url: String,
+        read: bool,
+        write: bool,
+    },
 }
 
 impl RelayAction {
@@ -218,6 +243,7 @@ impl RelayAction {
         match self {
             RelayAction::Add(url) => url,
             RelayAction::Remove(url) => url,
+            RelayAction::SetUsage { url, .. } => url,
         }
     }
 }
@@ -226,6 +252,7 @@ pub(super) fn modify_advertised_relays(
     kp: &Keypair,
     action: RelayAction,
     pool: &mut RelayPool,
+    publish_tracker: &mut PublishTracker,
     relay_defaults: &RelayDefaults,
     account_data: &mut AccountData,
 ) {
@@ -233,6 +260,10 @@ pub(super) fn modify_advertised_relays(
     match action {
         RelayAction::Add(_) => info!("add advertised relay \"{}\"", relay_url),
         RelayAction::Remove(_) => info!("remove advertised relay \"{}\"", relay_url),
+        RelayAction::SetUsage { read, write, .. } => info!(
+            "set advertised relay \"{}\" read: {read} write: {write}",
+            relay_url
+        ),
     }
 
     // let selected = self.cache.selected_mut();
@@ -250,12 +281,23 @@ pub(super) fn modify_advertised_relays(
         RelayAction::Remove(_) => {
             advertised.remove(&RelaySpec::new(relay_url, false, false));
         }
+        RelayAction::SetUsage { read, write, .. } => {
+            if !read && !write {
+                // a relay used for neither should be removed instead
+                return;
+            }
+            // no marker means both
+            let spec = RelaySpec::new(relay_url, read && !write, write && !read);
+            advertised.replace(spec);
+        }
     }
 
     // If we have the secret key publish the NIP-65 relay list
     if let Some(secretkey) = &kp.secret_key {
-        account_data
-            .relay
-            .publish_nip65_relays(&secretkey.to_secret_bytes(), pool);
+        account_data.relay.publish_nip65_relays(
+            &secretkey.to_secret_bytes(),
+            pool,
+            publish_tracker,
+        );
     }
 }
@@ -0,0 +1,670 @@
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+
+use enostr::nip46::{self, BunkerUri, Nip46Request, Nip46Response, NostrConnectUri, NIP46_KIND};
+use enostr::{ClientMessage, FullKeypair, Keypair, Pubkey, RelayPool};
+use nostrdb::{Filter, Ndb, Note, NoteBuilder, Subscription, Transaction};
+use tokenator::{ParseError, TokenParser, TokenSerializable};
+use tracing::{debug, error};
+use uuid::Uuid;
+
+/// What we asked the remote signer to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerMethod {
+    Connect,
+    GetPublicKey,
+    /// Sign an event of this kind
+    SignEvent(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerStatus {
+    /// Sent, waiting for the signer to answer
+    Waiting,
+    /// The signer wants the user to approve the request at this url
+    NeedsAuth(String),
+    Failed(String),
+}
+
+/// A request the remote signer hasn't finished yet
+#[derive(Debug, Clone)]
+pub struct SignerRequest {
+    pub id: String,
+    pub method: SignerMethod,
+    pub status: SignerStatus,
+    /// The unsigned event of a sign request, to check what comes back
+    unsigned: Option<String>,
+}
+
+pub enum SignerEvent {
+    /// The signer told us which account it signs for
+    Connected(Pubkey),
+    /// A signed event, ready to publish
+    Signed(String),
+}
+
+/// Relays we ask signers to answer our nostrconnect:// uri on
+pub const NOSTRCONNECT_RELAYS: &[&str] = &["wss://relay.nsec.app/"];
+
+/// An account's remote signer (NIP-46 bunker). Requests go out as
+/// encrypted kind 24133 events on the bunker's relays and are answered
+/// asynchronously, so they wait in a queue until the signer responds.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    /// The signer's pubkey. For nostrconnect:// logins it's only known
+    /// once the signer answered our uri.
+    remote_signer: Option<Pubkey>,
+    /// Relays the signer talks to us on
+    relays: Vec<String>,
+    /// Secret from the bunker:// uri, sent along with `connect`
+    secret: Option<String>,
+    /// Our nostrconnect:// uri while we wait for a signer to answer it
+    nostrconnect: Option<NostrConnectUri>,
+    /// Our session key, the signer knows us by this
+    client: FullKeypair,
+    /// The user's pubkey, once the signer told us
+    user: Option<Pubkey>,
+    requests: Vec<SignerRequest>,
+    /// Sign requests whose result goes back to whoever asked, by request
+    /// id, instead of being published
+    replies: HashMap<String, Sender<Result<String, String>>>,
+    /// Local subscription for responses from the signer
+    sub: Option<Subscription>,
+    remote_subid: String,
+}
+
+impl RemoteSigner {
+    pub fn new(uri: BunkerUri) -> Self {
+        Self::with_client(uri, FullKeypair::generate(), None)
+    }
+
+    /// Wait for a signer to connect to us. Show the user
+    /// [`RemoteSigner::nostrconnect_uri`] to paste into their signer.
+    pub fn nostrconnect() -> Self {
+        let relays: Vec<String> = NOSTRCONNECT_RELAYS.iter().map(|r| r.to_string()).collect();
+        let client = FullKeypair::generate();
+        let uri = NostrConnectUri::new(client.pubkey, relays.clone(), Some("Notedeck".to_owned()));
+
+        Self {
+            nostrconnect: Some(uri),
+            ..Self::with_relays(None, relays, None, client, None)
+        }
+    }
+
+    fn with_client(uri: BunkerUri, client: FullKeypair, user: Option<Pubkey>) -> Self {
+        Self::with_relays(
+            Some(uri.remote_signer),
+            uri.relays,
+            uri.secret,
+            client,
+            user,
+        )
+    }
+
+    fn with_relays(
+        remote_signer: Option<Pubkey>,
+        relays: Vec<String>,
+        secret: Option<String>,
+        client: FullKeypair,
+        user: Option<Pubkey>,
+    ) -> Self {
+        Self {
+            remote_signer,
+            relays,
+            secret,
+            nostrconnect: None,
+            client,
+            user,
+            requests: Vec::new(),
+            replies: HashMap::new(),
+            sub: None,
+            remote_subid: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// How to reach the signer again, once we know who it is
+    pub fn bunker_uri(&self) -> Option<BunkerUri> {
+        self.remote_signer.map(|remote_signer| BunkerUri {
+            remote_signer,
+            relays: self.relays.clone(),
+            secret: self.secret.clone(),
+        })
+    }
+
+    /// The uri to hand to the user's signer, until it connected
+    pub fn nostrconnect_uri(&self) -> Option<&NostrConnectUri> {
+        self.nostrconnect.as_ref()
+    }
+
+    pub fn user(&self) -> Option<&Pubkey> {
+        self.user.as_ref()
+    }
+
+    /// Requests the signer hasn't finished yet, including ones that need
+    /// approval or failed
+    pub fn requests(&self) -> &[SignerRequest] {
+        &self.requests
+    }
+
+    pub fn dismiss(&mut self, id: &str) {
+        self.requests.retain(|r| r.id != id);
+        if let Some(reply) = self.replies.remove(id) {
+            let _ = reply.send(Err("dismissed".to_owned()));
+        }
+    }
+
+    /// Start a session with the signer and ask for the user's pubkey. For
+    /// nostrconnect:// this waits for the signer to connect instead.
+    pub fn connect(&mut self, pool: &mut RelayPool) {
+        let Some(remote_signer) = self.remote_signer else {
+            return;
+        };
+
+        let connect = Nip46Request::connect(&remote_signer, self.secret.as_deref());
+        self.send(pool, connect, SignerMethod::Connect);
+        self.send(
+            pool,
+            Nip46Request::get_public_key(),
+            SignerMethod::GetPublicKey,
+        );
+    }
+
+    /// Ask the signer to sign `note`. Only the kind, content, tags and
+    /// timestamp are sent, the signer fills in the rest.
+    pub fn sign_note(&mut self, note: &Note, pool: &mut RelayPool) -> Result<(), enostr::Error> {
+        self.send(
+            pool,
+            Nip46Request::sign_event(unsigned_json(note)?),
+            SignerMethod::SignEvent(note.kind()),
+        );
+        Ok(())
+    }
+
+    /// Ask the signer to sign an event from [`unsigned_json`] and hand the
+    /// signed json to `reply` instead of publishing it. Used for auth
+    /// events that only go to a server.
+    pub fn sign_for(
+        &mut self,
+        unsigned: String,
+        kind: u32,
+        pool: &mut RelayPool,
+        reply: Sender<Result<String, String>>,
+    ) {
+        let request = Nip46Request::sign_event(unsigned);
+        let id = request.id.clone();
+
+        if self.send(pool, request, SignerMethod::SignEvent(kind)) {
+            self.replies.insert(id, reply);
+        } else {
+            let _ = reply.send(Err("could not send the request to the signer".to_owned()));
+        }
+    }
+
+    /// Returns false if the request couldn't be sent
+    fn send(&mut self, pool: &mut RelayPool, request: Nip46Request, method: SignerMethod) -> bool {
+        let Some(remote_signer) = self.remote_signer else {
+            error!("no remote signer to send {} to yet", request.method);
+            return false;
+        };
+
+        let json = match serde_json::to_string(&request) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("could not serialize nip46 request: {err}");
+                return false;
+            }
+        };
+
+        let content = match nip46::encrypt(&self.client.secret_key, &remote_signer, &json) {
+            Ok(content) => content,
+            Err(err) => {
+                error!("could not encrypt nip46 request: {err}");
+                return false;
+            }
+        };
+
+        let note = NoteBuilder::new()
+            .kind(NIP46_KIND as u32)
+            .content(&content)
+            .start_tag()
+            .tag_str("p")
+            .tag_str(&remote_signer.hex())
+            .sign(&self.client.secret_key.to_secret_bytes())
+            .build()
+            .expect("nip46 note");
+
+        let msg = match ClientMessage::event(&note) {
+            Ok(msg) => msg,
+            Err(err) => {
+                error!("could not create nip46 message: {err}");
+                return false;
+            }
+        };
+
+        for relay in &self.relays {
+            pool.send_to(&msg, relay);
+        }
+
+        let unsigned = match method {
+            SignerMethod::SignEvent(_) => request.params.into_iter().next(),
+            _ => None,
+        };
+        self.requests.push(SignerRequest {
+            id: request.id,
+            method,
+            status: SignerStatus::Waiting,
+            unsigned,
+        });
+        true
+    }
+
+    fn filter(&self) -> Filter {
+        Filter::new()
+            .kinds([NIP46_KIND])
+            .pubkeys([self.client.pubkey.bytes()])
+            .build()
+    }
+
+    /// Make sure we are listening on the signer's relays. They aren't
+    /// part of the account's relay list, so they may have been dropped
+    /// when it changed.
+    fn ensure_relays(
+        &mut self,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        wakeup: impl Fn() + Send + Sync + Clone + 'static,
+    ) {
+        if self.sub.is_none() {
+            match ndb.subscribe(&[self.filter()]) {
+                Ok(sub) => self.sub = Some(sub),
+                Err(err) => error!("could not subscribe to remote signer: {err}"),
+            }
+        }
+
+        for relay in &self.relays {
+            if pool.has(relay) {
+                continue;
+            }
+
+            debug!("connecting to remote signer relay {relay}");
+            if let Err(err) = pool.add_url(relay.clone(), wakeup.clone()) {
+                error!("could not add remote signer relay {relay}: {err}");
+                continue;
+            }
+
+            pool.send_to(
+                &ClientMessage::req(self.remote_subid.clone(), vec![self.filter()]),
+                relay,
+            );
+        }
+    }
+
+    /// Handle responses from the signer
+    pub fn update(
+        &mut self,
+        ndb: &Ndb,
+        pool: &mut RelayPool,
+        wakeup: impl Fn() + Send + Sync + Clone + 'static,
+    ) -> Vec<SignerEvent> {
+        self.ensure_relays(ndb, pool, wakeup);
+
+        let Some(sub) = self.sub else {
+            return Vec::new();
+        };
+
+        let keys = ndb.poll_for_notes(sub, 16);
+        if keys.is_empty() {
+            return Vec::new();
+        }
+
+        let Ok(txn) = Transaction::new(ndb) else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        for key in keys {
+            let Ok(note) = ndb.get_note_by_key(&txn, key) else {
+                continue;
+            };
+
+            // anyone can send us kind 24133 events, only our signer's count
+            let from = Pubkey::new(*note.pubkey());
+            if self.remote_signer.is_some_and(|signer| signer != from) {
+                debug!("ignoring nip46 event from {from}, not our signer");
+                continue;
+            }
+
+            let response = nip46::decrypt(&self.client.secret_key, &from, note.content())
+                .map_err(|e| e.to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<Nip46Response>(&json).map_err(|e| e.to_string())
+                });
+
+            match response {
+                Ok(response) if self.remote_signer.is_none() => {
+                    self.handle_nostrconnect(from, response, pool)
+                }
+                Ok(response) => events.extend(self.handle_response(response)),
+                Err(err) => error!("invalid response from remote signer: {err}"),
+            }
+        }
+
+        events
+    }
+
+    /// A signer answering our nostrconnect:// uri echoes its secret. From
+    /// then on it's our signer, and we ask it for the user's pubkey.
+    fn handle_nostrconnect(&mut self, from: Pubkey, response: Nip46Response, pool: &mut RelayPool) {
+        let Some(uri) = &self.nostrconnect else {
+            return;
+        };
+
+        if response.result.as_deref() != Some(uri.secret.as_str()) {
+            debug!("ignoring nostrconnect answer from {from} without our secret");
+            return;
+        }
+
+        self.remote_signer = Some(from);
+        self.nostrconnect = None;
+        self.send(
+            pool,
+            Nip46Request::get_public_key(),
+            SignerMethod::GetPublicKey,
+        );
+    }
+
+    fn handle_response(&mut self, response: Nip46Response) -> Option<SignerEvent> {
+        let idx = self.requests.iter().position(|r| r.id == response.id)?;
+
+        if let Some(url) = response.auth_url() {
+            self.requests[idx].status = SignerStatus::NeedsAuth(url.to_owned());
+            return None;
+        }
+
+        if let Some(err) = response.error.filter(|e| !e.is_empty()) {
+            let request = self.requests.remove(idx);
+            self.fail(request, err);
+            return None;
+        }
+
+        let request = self.requests.remove(idx);
+        let result = response.result.unwrap_or_default();
+
+        match request.method {
+            SignerMethod::Connect => None,
+
+            SignerMethod::GetPublicKey => match Pubkey::from_hex(&result) {
+                Ok(user) => {
+                    self.user = Some(user);
+                    Some(SignerEvent::Connected(user))
+                }
+                Err(err) => {
+                    self.fail(request, format!("invalid pubkey from signer: {err}"));
+                    None
+                }
+            },
+
+            SignerMethod::SignEvent(_) => {
+                match check_signed(&result, request.unsigned.as_deref(), self.user) {
+                    Ok(()) => match self.replies.remove(&request.id) {
+                        Some(reply) => {
+                            let _ = reply.send(Ok(result));
+                            None
+                        }
+                        None => Some(SignerEvent::Signed(result)),
+                    },
+                    Err(reason) => {
+                        self.fail(request, reason.to_owned());
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whoever asked for the request hears about the failure, otherwise
+    /// it stays in the queue for the user to see
+    fn fail(&mut self, mut request: SignerRequest, reason: String) {
+        if let Some(reply) = self.replies.remove(&request.id) {
+            let _ = reply.send(Err(reason));
+            return;
+        }
+
+        request.status = SignerStatus::Failed(reason);
+        self.requests.push(request);
+    }
+}
+
+/// Is `signed` the event we asked to have signed, validly signed by the
+/// user? A signer could otherwise get us to publish something else.
+fn check_signed(
+    signed: &str,
+    unsigned: Option<&str>,
+    user: Option<Pubkey>,
+) -> Result<(), &'static str> {
+    let note = enostr::Note::from_json(signed).map_err(|_| "signer returned an invalid event")?;
+
+    if Some(note.pubkey) != user {
+        return Err("signer returned an event for another key");
+    }
+
+    if !crate::zaps::valid_note_signature(&note) {
+        return Err("signer returned an event with a bad signature");
+    }
+
+    let unsigned: serde_json::Value = unsigned
+        .and_then(|json| serde_json::from_str(json).ok())
+        .ok_or("we don't know what we asked the signer to sign")?;
+    if unsigned["kind"] != note.kind
+        || unsigned["content"] != note.content.as_str()
+        || unsigned["tags"] != serde_json::json!(note.tags)
+        || unsigned["created_at"] != note.created_at
+    {
+        return Err("signer signed a different event than we asked for");
+    }
+
+    Ok(())
+}
+
+/// The parts of `note` a remote signer needs. It fills in the pubkey,
+/// id and signature itself.
+pub fn unsigned_json(note: &Note) -> Result<String, enostr::Error> {
+    let json: serde_json::Value = serde_json::from_str(&note.json()?)?;
+    let unsigned = serde_json::json!({
+        "kind": json["kind"],
+        "content": json["content"],
+        "tags": json["tags"],
+        "created_at": json["created_at"],
+    });
+    Ok(unsigned.to_string())
+}
+
+/// What we store on disk for a remote signer account
+pub struct RemoteSignerSerializable {
+    pub uri: String,
+    pub client: Keypair,
+}
+
+impl RemoteSignerSerializable {
+    /// Nothing to store until we know which signer we're talking to
+    pub fn new(signer: &RemoteSigner) -> Option<Self> {
+        Some(Self {
+            uri: signer.bunker_uri()?.to_string(),
+            client: signer.client.clone().to_keypair(),
+        })
+    }
+
+    pub fn into_signer(self, user: Pubkey) -> Option<RemoteSigner> {
+        let uri = match BunkerUri::parse(&self.uri) {
+            Ok(uri) => uri,
+            Err(err) => {
+                error!("invalid stored bunker uri: {err}");
+                return None;
+            }
+        };
+
+        let client = self.client.to_full()?.to_full();
+        Some(RemoteSigner::with_client(uri, client, Some(user)))
+    }
+}
+
+impl TokenSerializable for RemoteSignerSerializable {
+    fn parse_from_tokens<'a>(parser: &mut TokenParser<'a>) -> Result<Self, ParseError<'a>> {
+        parser.parse_token("bunker_uri")?;
+        let uri = parser.pull_token()?.to_owned();
+        parser.parse_token("bunker_client")?;
+        let client = Keypair::parse_from_tokens(parser)?;
+
+        if client.secret_key.is_none() {
+            return Err(ParseError::DecodeFailed);
+        }
+
+        Ok(Self { uri, client })
+    }
+
+    fn serialize_tokens(&self, writer: &mut tokenator::TokenWriter) {
+        writer.write_token("bunker_uri");
+        writer.write_token(&self.uri);
+        writer.write_token("bunker_client");
+        self.client.serialize_tokens(writer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note_by(user: &FullKeypair, content: &str) -> String {
+        NoteBuilder::new()
+            .kind(1)
+            .content(content)
+            .created_at(1000)
+            .sign(&user.secret_key.secret_bytes())
+            .build()
+            .unwrap()
+            .json()
+            .unwrap()
+    }
+
+    /// A signer waiting on a pubkey request and on signing a kind 1 "hi"
+    fn signer() -> RemoteSigner {
+        let uri = BunkerUri {
+            remote_signer: FullKeypair::generate().pubkey,
+            relays: vec!["wss://relay.example.com/".to_owned()],
+            secret: None,
+        };
+        let mut signer = RemoteSigner::new(uri);
+
+        let builder = NoteBuilder::new().kind(1).content("hi").created_at(1000);
+        let template = builder
+            .sign(&FullKeypair::generate().secret_key.secret_bytes())
+            .build()
+            .unwrap();
+        let unsigned = unsigned_json(&template).unwrap();
+
+        for (id, method, unsigned) in [
+            ("pk", SignerMethod::GetPublicKey, None),
+            ("sign", SignerMethod::SignEvent(1), Some(unsigned)),
+        ] {
+            signer.requests.push(SignerRequest {
+                id: id.to_owned(),
+                method,
+                status: SignerStatus::Waiting,
+                unsigned,
+            });
+        }
+        signer
+    }
+
+    fn response(id: &str, result: &str, error: Option<&str>) -> Nip46Response {
+        Nip46Response {
+            id: id.to_owned(),
+            result: Some(result.to_owned()),
+            error: error.map(str::to_owned),
+        }
+    }
+
+    fn assert_sign_failed(signer: &RemoteSigner) {
+        let sign = signer.requests().iter().find(|r| r.id == "sign").unwrap();
+        assert!(matches!(sign.status, SignerStatus::Failed(_)));
+    }
+
+    #[test]
+    fn responses_update_the_queue() {
+        let mut signer = signer();
+        let user = FullKeypair::generate();
+
+        let ev = signer.handle_response(response("pk", &user.pubkey.hex(), None));
+        assert!(matches!(ev, Some(SignerEvent::Connected(pk)) if pk == user.pubkey));
+        assert_eq!(signer.user(), Some(&user.pubkey));
+
+        let ev = signer.handle_response(response("sign", "auth_url", Some("https://a.io")));
+        assert!(ev.is_none());
+        assert_eq!(
+            signer.requests()[0].status,
+            SignerStatus::NeedsAuth("https://a.io".to_owned())
+        );
+
+        let ev = signer.handle_response(response("sign", &note_by(&user, "hi"), None));
+        assert!(matches!(ev, Some(SignerEvent::Signed(_))));
+        assert!(signer.requests().is_empty());
+    }
+
+    #[test]
+    fn rejects_events_signed_by_another_key() {
+        let mut signer = signer();
+        signer.user = Some(FullKeypair::generate().pubkey);
+
+        let other = note_by(&FullKeypair::generate(), "hi");
+        let ev = signer.handle_response(response("sign", &other, None));
+        assert!(ev.is_none());
+        assert_sign_failed(&signer);
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        let mut signer = signer();
+        let user = FullKeypair::generate();
+        signer.user = Some(user.pubkey);
+
+        // a real signature, but of another note
+        let mut signed: serde_json::Value = serde_json::from_str(&note_by(&user, "hi")).unwrap();
+        let other: serde_json::Value = serde_json::from_str(&note_by(&user, "bye")).unwrap();
+        signed["sig"] = other["sig"].clone();
+
+        let ev = signer.handle_response(response("sign", &signed.to_string(), None));
+        assert!(ev.is_none());
+        assert_sign_failed(&signer);
+    }
+
+    #[test]
+    fn rejects_swapped_content() {
+        let mut signer = signer();
+        let user = FullKeypair::generate();
+        signer.user = Some(user.pubkey);
+
+        let swapped = note_by(&user, "send me your sats");
+        let ev = signer.handle_response(response("sign", &swapped, None));
+        assert!(ev.is_none());
+        assert_sign_failed(&signer);
+    }
+
+    #[test]
+    fn nostrconnect_needs_our_secret() {
+        let mut signer = RemoteSigner::nostrconnect();
+        let mut pool = RelayPool::new();
+        let remote = FullKeypair::generate().pubkey;
+
+        signer.handle_nostrconnect(remote, response("1", "wrong", None), &mut pool);
+        assert!(signer.bunker_uri().is_none());
+
+        let secret = signer.nostrconnect_uri().unwrap().secret.clone();
+        signer.handle_nostrconnect(remote, response("1", &secret, None), &mut pool);
+        assert_eq!(
+            signer.bunker_uri().map(|uri| uri.remote_signer),
+            Some(remote)
+        );
+        assert!(signer.nostrconnect_uri().is_none());
+        assert_eq!(signer.requests()[0].method, SignerMethod::GetPublicKey);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::account::FALLBACK_PUBKEY;
 use crate::i18n::Localization;
+use crate::media::upload::MediaUploader;
 use crate::persist::{AppSizeHandler, SettingsHandler};
 use crate::wallet::GlobalWallet;
 use crate::zaps::Zaps;
@@ -7,8 +8,9 @@ use crate::Error;
 use crate::JobPool;
 use crate::NotedeckOptions;
 use crate::{
-    frame_history::FrameHistory, AccountStorage, Accounts, AppContext, Args, DataPath,
-    DataPathType, Directory, Images, NoteAction, NoteCache, RelayDebugView, UnknownIds,
+    frame_history::FrameHistory, AccountStorage, Accounts, AppContext, AppSubscriptions, Args,
+    DataPath, DataPathType, Directory, Images, NoteAction, NoteCache, Notifications, Outbox,
+    PublishTracker, RelayDebugView, Translations, UnknownIds,
 };
 use egui::Margin;
 use egui::ThemePreference;
@@ -19,6 +21,7 @@ use std::cell::RefCell;
 use std::collections::BTreeSet;
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Instant;
 use tracing::{error, info};
 use unic_langid::{LanguageIdentifier, LanguageIdentifierError};
 
@@ -28,6 +31,8 @@ use android_activity::AndroidApp;
 pub enum AppAction {
     Note(NoteAction),
     ToggleChrome,
+    /// Switch to another app by its catalog id, enabling it if needed
+    OpenApp(&'static str),
 }
 
 pub trait App {
@@ -78,6 +83,11 @@ pub struct Notedeck {
     frame_history: FrameHistory,
     job_pool: JobPool,
     i18n: Localization,
+    media_uploader: MediaUploader,
+    notifications: Notifications,
+    subs: AppSubscriptions,
+    publish_tracker: PublishTracker,
+    translations: Translations,
 
     #[cfg(target_os = "android")]
     android_app: Option<AndroidApp>,
@@ -123,13 +133,56 @@ impl eframe::App for Notedeck {
             .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
 
         // handle account updates
-        self.accounts.update(&mut self.ndb, &mut self.pool, ctx);
+        self.accounts.update(
+            &mut self.ndb,
+            &mut self.pool,
+            &mut self.publish_tracker,
+            ctx,
+        );
+        if self.accounts.sync_settings(
+            &mut self.settings,
+            &self.ndb,
+            &mut self.pool,
+            &mut self.publish_tracker,
+        ) {
+            // the theme is read back from egui below, so set it there too
+            ctx.set_theme(self.settings.theme());
+            crate::theme::set_theme_options(ctx, self.settings.theme_options());
+            crate::motion::apply(ctx, self.settings.reduce_motion());
+        }
+        self.notifications
+            .update(&mut self.ndb, &self.accounts, self.settings.all_accounts());
 
         self.zaps
             .process(&mut self.accounts, &mut self.global_wallet, &self.ndb);
+        self.publish_tracker.update(&mut self.pool);
 
+        crate::zoom::handle_shortcuts(ctx);
         render_notedeck(self, ctx);
 
+        self.media_uploader
+            .update(ctx, &self.settings, &mut self.accounts, &mut self.pool);
+
+        // every app adds what it's missing during the frame, so they're all
+        // fetched together
+        if self.unknown_ids.ready_to_send() {
+            self.unknown_ids.send(&mut self.pool);
+        }
+
+        // paced events only go out while we're updating, so wake up for them
+        if let Some(next_send) = self.pool.next_send_at() {
+            ctx.request_repaint_after(next_send.saturating_duration_since(Instant::now()));
+        }
+
+        let focused = ctx.input(|i| i.viewport().focused).unwrap_or(true);
+        self.notifications.announce(
+            &self.ndb,
+            &mut self.i18n,
+            focused || !self.settings.os_notifications(),
+        );
+
+        self.img_cache.enforce_texture_budget();
+
         self.settings.update_batch(|settings| {
             settings.zoom_factor = ctx.zoom_factor();
             settings.locale = self.i18n.get_current_locale().to_string();
@@ -185,27 +238,18 @@ impl Notedeck {
             .clone()
             .unwrap_or(data_path.as_ref().to_str().expect("db path ok").to_string());
         let path = DataPath::new(&data_path);
-        let dbpath_str = parsed_args
-            .dbpath
-            .clone()
-            .unwrap_or_else(|| path.path(DataPathType::Db).to_str().unwrap().to_string());
+        let db_dir = crate::db_maintenance::db_dir(&path, &parsed_args);
+        crate::db_maintenance::swap_in_compacted(&db_dir);
+        let dbpath_str = db_dir.to_str().unwrap().to_string();
 
         let _ = std::fs::create_dir_all(&dbpath_str);
 
         let img_cache_dir = path.path(DataPathType::Cache);
         let _ = std::fs::create_dir_all(img_cache_dir.clone());
 
-        let map_size = if cfg!(target_os = "windows") {
-            // 16 Gib on windows because it actually creates the file
-            1024usize * 1024usize * 1024usize * 16usize
-        } else {
-            // 1 TiB for everything else since its just virtually mapped
-            1024usize * 1024usize * 1024usize * 1024usize
-        };
-
         let settings = SettingsHandler::new(&path).load();
 
-        let config = Config::new().set_ingester_threads(2).set_mapsize(map_size);
+        let config = ndb_config();
 
         let keystore = if parsed_args.options.contains(NotedeckOptions::UseKeystore) {
             let keys_path = path.path(DataPathType::Keys);
@@ -256,7 +300,17 @@ impl Notedeck {
             accounts.select_account(&first.pubkey, &mut ndb, &txn, &mut pool, ctx);
         }
 
-        let img_cache = Images::new(img_cache_dir);
+        let mut img_cache = Images::new(img_cache_dir);
+        img_cache.set_cache_limit(settings.media_cache_limit_mb() * 1024 * 1024);
+        img_cache.set_texture_budget(settings.texture_budget_mb() as usize * 1024 * 1024);
+        crate::media::images::set_max_image_length(settings.max_image_length());
+        crate::set_image_proxy(
+            settings
+                .image_proxy()
+                .as_deref()
+                .and_then(crate::ImageProxy::new),
+        );
+        let translations = Translations::new(settings.translator());
         let note_cache = NoteCache::default();
 
         let app_size = AppSizeHandler::new(&path);
@@ -288,6 +342,14 @@ impl Notedeck {
             }
         }
 
+        let media_uploader = MediaUploader::default();
+
+        let notifications = Notifications::new(settings.disabled_notifications());
+
+        if let Some(uri) = &parsed_args.uri {
+            crate::nostr_uri::emit_uri_opened(uri.clone());
+        }
+
         Self {
             ndb,
             img_cache,
@@ -307,6 +369,11 @@ impl Notedeck {
             zaps,
             job_pool,
             i18n,
+            media_uploader,
+            notifications,
+            subs: AppSubscriptions::default(),
+            publish_tracker: PublishTracker::new(Outbox::new(&path)),
+            translations,
             #[cfg(target_os = "android")]
             android_app: None,
         }
@@ -320,9 +387,12 @@ impl Notedeck {
             ctx,
             self.args.options,
             self.theme(),
+            self.settings.theme_options(),
             self.note_body_font_size(),
             self.zoom_factor(),
         );
+        crate::shortcuts::set_bindings(ctx, self.settings.shortcuts());
+        crate::motion::apply(ctx, self.settings.reduce_motion());
     }
 
     /// ensure we recognized all the arguments
@@ -372,6 +442,11 @@ impl Notedeck {
             frame_history: &mut self.frame_history,
             job_pool: &mut self.job_pool,
             i18n: &mut self.i18n,
+            media_uploader: &mut self.media_uploader,
+            notifications: &mut self.notifications,
+            subs: &mut self.subs,
+            publish_tracker: &mut self.publish_tracker,
+            translations: &mut self.translations,
             #[cfg(target_os = "android")]
             android: self.android_app.as_ref().unwrap().clone(),
         }
@@ -401,3 +476,15 @@ impl Notedeck {
         &self.unrecognized_args
     }
 }
+
+pub(crate) fn ndb_config() -> Config {
+    let map_size = if cfg!(target_os = "windows") {
+        // 16 Gib on windows because it actually creates the file
+        1024usize * 1024usize * 1024usize * 16usize
+    } else {
+        // 1 TiB for everything else since its just virtually mapped
+        1024usize * 1024usize * 1024usize * 1024usize
+    };
+
+    Config::new().set_ingester_threads(2).set_mapsize(map_size)
+}
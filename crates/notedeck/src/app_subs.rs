@@ -0,0 +1,156 @@
+//! Remote subscriptions owned by apps.
+//!
+//! Apps open relay subscriptions through [`AppSubscriptions`] with their
+//! catalog id as the owner. Chrome closes an app's subscriptions when it
+//! goes to the background and reopens them when it comes back, unless
+//! they are marked keep-alive. When an app is turned off all of its
+//! subscriptions are closed.
+
+use enostr::{ClientMessage, RelayPool};
+use nostrdb::Filter;
+use uuid::Uuid;
+
+struct AppSub {
+    owner: &'static str,
+    subid: String,
+    filters: Vec<Filter>,
+    /// Stay open while the owner is in the background
+    keep_alive: bool,
+    /// Whether the REQ is open on the relays right now
+    open: bool,
+}
+
+#[derive(Default)]
+pub struct AppSubscriptions {
+    subs: Vec<AppSub>,
+}
+
+impl AppSubscriptions {
+    /// Open a remote subscription owned by `owner`. Returns the
+    /// subscription id.
+    pub fn subscribe(
+        &mut self,
+        pool: &mut RelayPool,
+        owner: &'static str,
+        filters: Vec<Filter>,
+    ) -> String {
+        let subid = Uuid::new_v4().to_string();
+        pool.subscribe(subid.clone(), filters.clone());
+
+        self.subs.push(AppSub {
+            owner,
+            subid: subid.clone(),
+            filters,
+            keep_alive: false,
+            open: true,
+        });
+
+        subid
+    }
+
+    /// Keep a subscription open while its app is in the background
+    pub fn keep_alive(&mut self, subid: &str, keep_alive: bool) {
+        if let Some(sub) = self.subs.iter_mut().find(|s| s.subid == subid) {
+            sub.keep_alive = keep_alive;
+        }
+    }
+
+    pub fn unsubscribe(&mut self, pool: &mut RelayPool, subid: &str) {
+        let Some(index) = self.subs.iter().position(|s| s.subid == subid) else {
+            return;
+        };
+
+        let sub = self.subs.remove(index);
+        if sub.open {
+            pool.unsubscribe(sub.subid);
+        }
+    }
+
+    /// Every subscription as its owner, id and whether it's open, for
+    /// diagnostics
+    pub fn list(&self) -> impl Iterator<Item = (&'static str, &str, bool)> {
+        self.subs
+            .iter()
+            .map(|sub| (sub.owner, sub.subid.as_str(), sub.open))
+    }
+
+    /// Whether the subscription is open on the relays right now
+    pub fn is_open(&self, subid: &str) -> bool {
+        self.subs.iter().any(|s| s.subid == subid && s.open)
+    }
+
+    /// Close the owner's subscriptions that aren't keep-alive. They are
+    /// remembered so [`Self::foreground`] can reopen them.
+    pub fn background(&mut self, pool: &mut RelayPool, owner: &str) {
+        for sub in self.subs.iter_mut() {
+            if sub.owner == owner && sub.open && !sub.keep_alive {
+                pool.unsubscribe(sub.subid.clone());
+                sub.open = false;
+            }
+        }
+    }
+
+    /// Reopen the owner's subscriptions closed by [`Self::background`]
+    pub fn foreground(&mut self, pool: &mut RelayPool, owner: &str) {
+        for sub in self.subs.iter_mut() {
+            if sub.owner == owner && !sub.open {
+                pool.subscribe(sub.subid.clone(), sub.filters.clone());
+                sub.open = true;
+            }
+        }
+    }
+
+    /// Close and forget every subscription the owner has, eg. when the
+    /// app is turned off
+    pub fn close_owner(&mut self, pool: &mut RelayPool, owner: &str) {
+        self.subs.retain(|sub| {
+            if sub.owner != owner {
+                return true;
+            }
+
+            if sub.open {
+                pool.unsubscribe(sub.subid.clone());
+            }
+            false
+        });
+    }
+
+    /// Resend the open subscriptions to a relay that just connected
+    pub fn send_initial_filters(&self, pool: &mut RelayPool, relay_url: &str) {
+        for sub in self.subs.iter().filter(|s| s.open) {
+            pool.send_to(
+                &ClientMessage::req(sub.subid.clone(), sub.filters.clone()),
+                relay_url,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_closes_all_but_keep_alive() {
+        let mut pool = RelayPool::new();
+        let mut subs = AppSubscriptions::default();
+
+        let a = subs.subscribe(&mut pool, "a", vec![Filter::new().kinds([1]).build()]);
+        let kept = subs.subscribe(&mut pool, "a", vec![Filter::new().kinds([7]).build()]);
+        let b = subs.subscribe(&mut pool, "b", vec![Filter::new().kinds([1]).build()]);
+        subs.keep_alive(&kept, true);
+
+        subs.background(&mut pool, "a");
+        assert!(!subs.is_open(&a));
+        assert!(subs.is_open(&kept));
+        assert!(subs.is_open(&b));
+
+        subs.foreground(&mut pool, "a");
+        assert!(subs.is_open(&a));
+
+        subs.close_owner(&mut pool, "a");
+        assert!(!subs.is_open(&a));
+        assert!(!subs.is_open(&kept));
+        assert!(subs.is_open(&b));
+    }
+}
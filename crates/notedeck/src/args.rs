@@ -1,5 +1,6 @@
 use std::collections::BTreeSet;
 
+use crate::nostr_uri::NostrUri;
 use crate::NotedeckOptions;
 use enostr::{Keypair, Pubkey, SecretKey};
 use tracing::error;
@@ -12,6 +13,8 @@ pub struct Args {
     pub options: NotedeckOptions,
     pub dbpath: Option<String>,
     pub datapath: Option<String>,
+    /// A `nostr:` link we were launched to open
+    pub uri: Option<NostrUri>,
 }
 
 impl Args {
@@ -25,6 +28,7 @@ impl Args {
             dbpath: None,
             datapath: None,
             locale: None,
+            uri: None,
         };
 
         let mut i = 0;
@@ -128,6 +132,11 @@ impl Args {
                 res.options.set(NotedeckOptions::FeatureNotebook, true);
             } else if arg == "--clndash" {
                 res.options.set(NotedeckOptions::FeatureClnDash, true);
+            } else if arg.starts_with("nostr:") {
+                res.uri = NostrUri::parse(arg);
+                if res.uri.is_none() {
+                    error!("failed to parse nostr link {}", arg);
+                }
             } else {
                 unrecognized_args.insert(arg.clone());
             }
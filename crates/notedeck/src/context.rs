@@ -1,12 +1,17 @@
 use crate::{
-    account::accounts::Accounts, frame_history::FrameHistory, i18n::Localization,
-    wallet::GlobalWallet, zaps::Zaps, Args, DataPath, Images, JobPool, NoteCache, SettingsHandler,
-    UnknownIds,
+    account::accounts::Accounts,
+    frame_history::FrameHistory,
+    i18n::Localization,
+    media::upload::MediaUploader,
+    wallet::{get_wallet_for, GlobalWallet},
+    zaps::Zaps,
+    AppSubscriptions, Args, DataPath, Images, JobPool, NoteCache, Notification, Notifications,
+    PublishTracker, SettingsHandler, Translations, UnknownIds, ZapTargetOwned, ZappingError,
 };
 use egui_winit::clipboard::Clipboard;
 
-use enostr::RelayPool;
-use nostrdb::Ndb;
+use enostr::{PoolSummary, RelayPool};
+use nostrdb::{Ndb, Note, NoteReply};
 
 #[cfg(target_os = "android")]
 use android_activity::AndroidApp;
@@ -29,6 +34,11 @@ pub struct AppContext<'a> {
     pub frame_history: &'a mut FrameHistory,
     pub job_pool: &'a mut JobPool,
     pub i18n: &'a mut Localization,
+    pub media_uploader: &'a mut MediaUploader,
+    pub notifications: &'a mut Notifications,
+    pub subs: &'a mut AppSubscriptions,
+    pub publish_tracker: &'a mut PublishTracker,
+    pub translations: &'a mut Translations,
 
     #[cfg(target_os = "android")]
     pub android: AndroidApp,
@@ -49,6 +59,61 @@ impl SoftKeyboardContext {
 }
 
 impl<'a> AppContext<'a> {
+    /// How many relays we're connected to. Apps can show this so users
+    /// know why nothing is arriving.
+    pub fn relay_summary(&self) -> PoolSummary {
+        self.pool.summary()
+    }
+
+    /// Push a notification into the shared inbox. Returns false if the user
+    /// turned this kind of notification off.
+    pub fn notify(&mut self, notification: Notification) -> bool {
+        self.notifications.push(notification)
+    }
+
+    /// Is `note` hidden by the selected account's mute list? Every app
+    /// should check this before showing notes.
+    pub fn is_muted(&self, note: &Note) -> bool {
+        let thread = NoteReply::new(note.tags())
+            .root()
+            .map(|root| *root.id)
+            .unwrap_or(*note.id());
+
+        self.accounts.mute().is_muted(note, &thread)
+    }
+
+    /// Zap a note or profile from the selected account using its wallet.
+    /// Without `msats` the wallet's default amount is sent. Progress and
+    /// errors show up in [`Zaps`] like zaps sent from a note's action bar.
+    pub fn zap(&mut self, target: &ZapTargetOwned, msats: Option<u64>) -> Result<(), ZappingError> {
+        let sender = *self.accounts.selected_account_pubkey();
+        // a new zap replaces one that failed
+        self.zaps.clear_error_for(sender.bytes(), target.into());
+
+        let error = if self.accounts.selected_filled().is_none() {
+            ZappingError::InvalidAccount
+        } else if let Some(wallet) =
+            get_wallet_for(self.accounts, self.global_wallet, sender.bytes())
+        {
+            let msats = msats.unwrap_or_else(|| wallet.default_zap.get_default_zap_msats());
+            let relays = self
+                .pool
+                .relays
+                .iter()
+                .map(|r| r.url().to_string())
+                .collect();
+            self.zaps
+                .send_zap(sender.bytes(), relays, target.into(), msats);
+            return Ok(());
+        } else {
+            ZappingError::SenderNoWallet
+        };
+
+        self.zaps
+            .send_error(sender.bytes(), target.into(), error.clone());
+        Err(error)
+    }
+
     pub fn soft_keyboard_rect(&self, screen_rect: Rect, ctx: SoftKeyboardContext) -> Option<Rect> {
         match ctx {
             SoftKeyboardContext::Virtual => {
@@ -0,0 +1,413 @@
+//! Storage stats and pruning for the note database.
+//!
+//! nostrdb can't delete notes in place, so pruning copies the notes worth
+//! keeping into a fresh database next to the current one. The copy
+//! replaces the current database the next time notedeck starts.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use nostrdb::{Filter, Ndb, Transaction};
+use tracing::{error, info};
+
+use crate::{Args, DataPath, DataPathType};
+
+/// How many notes to read per query while scanning the database
+const SCAN_BATCH: u64 = 1000;
+
+/// Written into the compacted database once every note was copied
+const READY_MARKER: &str = "compacted";
+
+/// Where the note database lives
+pub fn db_dir(path: &DataPath, args: &Args) -> PathBuf {
+    args.dbpath
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.path(DataPathType::Db))
+}
+
+fn compacted_dir(db_dir: &Path) -> PathBuf {
+    let mut name = db_dir.as_os_str().to_owned();
+    name.push("-compacted");
+    PathBuf::from(name)
+}
+
+/// Swap in a database that was compacted during the last run. Must be
+/// called before the database is opened.
+pub fn swap_in_compacted(db_dir: &Path) {
+    let compacted = compacted_dir(db_dir);
+    if !compacted.join(READY_MARKER).exists() {
+        // an unfinished compaction, start over next time
+        let _ = std::fs::remove_dir_all(&compacted);
+        return;
+    }
+
+    let mut old = db_dir.as_os_str().to_owned();
+    old.push("-old");
+    let old = PathBuf::from(old);
+
+    let swapped = std::fs::rename(db_dir, &old)
+        .and_then(|_| std::fs::rename(&compacted, db_dir))
+        .and_then(|_| std::fs::remove_file(db_dir.join(READY_MARKER)));
+
+    match swapped {
+        Ok(()) => {
+            info!("using compacted database");
+            let _ = std::fs::remove_dir_all(&old);
+        }
+        Err(err) => {
+            error!("could not swap in compacted database: {err}");
+            if !db_dir.exists() {
+                let _ = std::fs::rename(&old, db_dir);
+            }
+        }
+    }
+}
+
+/// Kinds where only the latest note per author counts, which we never
+/// prune
+pub fn is_replaceable(kind: u32) -> bool {
+    kind == 0 || kind == 3 || (10000..20000).contains(&kind) || (30000..40000).contains(&kind)
+}
+
+/// Which notes a compaction drops
+#[derive(Debug, Clone, Default)]
+pub struct PruneOptions {
+    pub kinds: BTreeSet<u32>,
+    /// Only notes created before this unix time are dropped
+    pub older_than: u64,
+    /// Our accounts, whose notes are always kept
+    pub keep_authors: Vec<[u8; 32]>,
+}
+
+impl PruneOptions {
+    fn keeps(&self, kind: u32, created_at: u64, author: &[u8; 32]) -> bool {
+        !self.kinds.contains(&kind)
+            || created_at >= self.older_than
+            || is_replaceable(kind)
+            || self.keep_authors.contains(author)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DbStats {
+    pub disk_bytes: u64,
+    pub total: u64,
+    /// Note count by kind
+    pub kinds: BTreeMap<u32, u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum MaintenanceStatus {
+    #[default]
+    Idle,
+    Counting {
+        scanned: u64,
+    },
+    Counted(DbStats),
+    Compacting {
+        scanned: u64,
+        kept: u64,
+        /// From the last count, if there was one
+        total: Option<u64>,
+    },
+    /// Done, the copy is used after a restart
+    Compacted {
+        kept: u64,
+        dropped: u64,
+    },
+    Failed(String),
+}
+
+impl MaintenanceStatus {
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self,
+            MaintenanceStatus::Counting { .. } | MaintenanceStatus::Compacting { .. }
+        )
+    }
+}
+
+/// Runs counts and compactions in the background
+#[derive(Default)]
+pub struct DbMaintenance {
+    status: Arc<Mutex<MaintenanceStatus>>,
+}
+
+impl DbMaintenance {
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Count notes by kind and measure the database on disk
+    pub fn count(&self, ndb: &Ndb, db_dir: &Path) {
+        if self.status().is_running() {
+            return;
+        }
+
+        let ndb = ndb.clone();
+        let db_dir = db_dir.to_owned();
+        let status = Arc::clone(&self.status);
+        *status.lock().unwrap() = MaintenanceStatus::Counting { scanned: 0 };
+
+        thread::spawn(move || {
+            let mut stats = DbStats {
+                disk_bytes: crate::imgcache::compute_folder_size(&db_dir),
+                ..Default::default()
+            };
+
+            let scanned = scan(&ndb, |kind, _, _, _| {
+                *stats.kinds.entry(kind).or_default() += 1;
+                stats.total += 1;
+                if stats.total % SCAN_BATCH == 0 {
+                    *status.lock().unwrap() = MaintenanceStatus::Counting {
+                        scanned: stats.total,
+                    };
+                }
+            });
+
+            *status.lock().unwrap() = match scanned {
+                Ok(()) => MaintenanceStatus::Counted(stats),
+                Err(err) => MaintenanceStatus::Failed(err.to_string()),
+            };
+        });
+    }
+
+    /// Copy the notes `options` keeps into a new database that replaces
+    /// this one on the next start
+    pub fn compact(&self, ndb: &Ndb, db_dir: &Path, options: PruneOptions) {
+        let total = match self.status() {
+            MaintenanceStatus::Counted(stats) => Some(stats.total),
+            status if status.is_running() => return,
+            _ => None,
+        };
+
+        let ndb = ndb.clone();
+        let target = compacted_dir(db_dir);
+        let status = Arc::clone(&self.status);
+        *status.lock().unwrap() = MaintenanceStatus::Compacting {
+            scanned: 0,
+            kept: 0,
+            total,
+        };
+
+        thread::spawn(move || {
+            let result = copy_kept(&ndb, &target, &options, |scanned, kept| {
+                *status.lock().unwrap() = MaintenanceStatus::Compacting {
+                    scanned,
+                    kept,
+                    total,
+                };
+            });
+
+            *status.lock().unwrap() = match result {
+                Ok((kept, dropped)) => {
+                    info!("compacted database: kept {kept}, dropped {dropped}");
+                    MaintenanceStatus::Compacted { kept, dropped }
+                }
+                Err(err) => {
+                    error!("database compaction failed: {err}");
+                    let _ = std::fs::remove_dir_all(&target);
+                    MaintenanceStatus::Failed(err.to_string())
+                }
+            };
+        });
+    }
+}
+
+fn copy_kept(
+    ndb: &Ndb,
+    target: &Path,
+    options: &PruneOptions,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<(u64, u64), crate::Error> {
+    let _ = std::fs::remove_dir_all(target);
+    std::fs::create_dir_all(target)?;
+
+    let target_str = target.to_string_lossy();
+    let copy = Ndb::new(&target_str, &crate::app::ndb_config())?;
+
+    let mut scanned = 0;
+    let mut wanted = 0;
+    let mut kept = 0;
+    scan(ndb, |kind, created_at, author, json| {
+        scanned += 1;
+        if options.keeps(kind, created_at, author) {
+            wanted += 1;
+            if let Some(json) = json() {
+                if copy
+                    .process_event(&format!("[\"EVENT\",\"compact\",{json}]"))
+                    .is_ok()
+                {
+                    kept += 1;
+                }
+            }
+        }
+
+        if scanned % SCAN_BATCH == 0 {
+            progress(scanned, kept);
+        }
+    })?;
+
+    // dropping waits for the writer to store everything we queued
+    drop(copy);
+
+    // only mark the copy ready, and so give up the current database,
+    // once every note we meant to keep made it in
+    let copy = Ndb::new(&target_str, &crate::app::ndb_config())?;
+    let mut stored = 0;
+    scan(&copy, |_, _, _, _| stored += 1)?;
+    drop(copy);
+
+    if stored != wanted {
+        return Err(crate::Error::Generic(format!(
+            "compacted database has {stored} of the {wanted} notes we wanted to keep"
+        )));
+    }
+
+    std::fs::write(target.join(READY_MARKER), b"")?;
+
+    Ok((stored, scanned - wanted))
+}
+
+/// Visit every note, newest first, with its kind, created_at, author and
+/// a way to get its json
+fn scan(
+    ndb: &Ndb,
+    mut visit: impl FnMut(u32, u64, &[u8; 32], &dyn Fn() -> Option<String>),
+) -> Result<(), nostrdb::Error> {
+    let mut until = u64::MAX;
+
+    loop {
+        let txn = Transaction::new(ndb)?;
+        let filter = Filter::new().until(until).limit(SCAN_BATCH).build();
+        let results = ndb.query(&txn, &[filter], SCAN_BATCH as i32)?;
+        let Some(oldest) = results.iter().map(|r| r.note.created_at()).min() else {
+            return Ok(());
+        };
+
+        // the batch can end in the middle of its oldest second, that
+        // second is visited on its own
+        for result in results.iter().filter(|r| r.note.created_at() > oldest) {
+            let note = &result.note;
+            visit(note.kind(), note.created_at(), note.pubkey(), &|| {
+                note.json().ok()
+            });
+        }
+        scan_second(ndb, &txn, oldest, &mut visit)?;
+
+        if oldest == 0 {
+            return Ok(());
+        }
+        until = oldest - 1;
+    }
+}
+
+/// Visit every note created in `second`, however many there are
+fn scan_second(
+    ndb: &Ndb,
+    txn: &Transaction,
+    second: u64,
+    visit: &mut impl FnMut(u32, u64, &[u8; 32], &dyn Fn() -> Option<String>),
+) -> Result<(), nostrdb::Error> {
+    let mut limit = SCAN_BATCH;
+
+    loop {
+        let filter = Filter::new()
+            .since(second)
+            .until(second)
+            .limit(limit)
+            .build();
+        let results = ndb.query(txn, &[filter], limit as i32)?;
+        if (results.len() as u64) < limit {
+            for result in &results {
+                let note = &result.note;
+                visit(note.kind(), note.created_at(), note.pubkey(), &|| {
+                    note.json().ok()
+                });
+            }
+            return Ok(());
+        }
+
+        // a full page might have cut the second short, ask for more
+        limit *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_keeps_replaceables_own_and_recent() {
+        let me = [1; 32];
+        let stranger = [2; 32];
+        let options = PruneOptions {
+            kinds: [1, 7, 30023].into(),
+            older_than: 1000,
+            keep_authors: vec![me],
+        };
+
+        assert!(!options.keeps(1, 999, &stranger));
+        assert!(!options.keeps(7, 10, &stranger));
+        assert!(options.keeps(1, 1000, &stranger));
+        assert!(options.keeps(1, 10, &me));
+        assert!(options.keeps(6, 10, &stranger));
+        assert!(options.keeps(30023, 10, &stranger));
+        assert!(options.keeps(0, 10, &stranger));
+    }
+
+    #[tokio::test]
+    async fn scan_visits_a_crowded_second_once() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let ndb = Ndb::new(tmp_dir.path().to_str().unwrap(), &nostrdb::Config::new()).unwrap();
+        let sub = ndb.subscribe(&[Filter::new().kinds([1]).build()]).unwrap();
+
+        // more notes in one second than fit in a batch, and one on either
+        // side of it
+        let total = SCAN_BATCH + 500 + 2;
+        for i in 0..total {
+            let created_at = match i {
+                0 => 999,
+                1 => 1001,
+                _ => 1000,
+            };
+            let note = nostrdb::NoteBuilder::new()
+                .kind(1)
+                .content(&i.to_string())
+                .created_at(created_at)
+                .sign(&[1; 32])
+                .build()
+                .unwrap();
+            let json = note.json().unwrap();
+            ndb.process_event(&format!("[\"EVENT\",\"test\",{json}]"))
+                .unwrap();
+        }
+
+        let mut stored = 0;
+        while stored < total {
+            stored += ndb.wait_for_notes(sub, total as u32).await.unwrap().len() as u64;
+        }
+
+        let mut visited = 0;
+        let mut seen = BTreeSet::new();
+        scan(&ndb, |_, _, _, json| {
+            visited += 1;
+            seen.insert(json());
+        })
+        .unwrap();
+
+        assert_eq!(visited, total);
+        assert_eq!(seen.len() as u64, total);
+    }
+
+    #[test]
+    fn compacted_dir_is_a_sibling() {
+        assert_eq!(
+            compacted_dir(Path::new("/data/db")),
+            PathBuf::from("/data/db-compacted")
+        );
+    }
+}
@@ -3,7 +3,7 @@ use fluent::{FluentArgs, FluentBundle, FluentResource};
 use fluent_langneg::negotiate_languages;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use unic_langid::{langid, LanguageIdentifier};
+use unic_langid::{langid, CharacterDirection, LanguageIdentifier};
 
 const EN_US: LanguageIdentifier = langid!("en-US");
 const EN_XA: LanguageIdentifier = langid!("en-XA");
@@ -430,6 +430,12 @@ impl Localization {
         &self.current_locale
     }
 
+    /// Is the current locale written right-to-left? Widgets mirror their
+    /// layout when it is.
+    pub fn is_rtl(&self) -> bool {
+        is_rtl_locale(&self.current_locale)
+    }
+
     /// Gets all available locales
     pub fn get_available_locales(&self) -> &[LanguageIdentifier] {
         &self.available_locales
@@ -490,6 +496,11 @@ impl Localization {
     }
 }
 
+/// Is `locale` written right-to-left, like arabic or hebrew?
+pub fn is_rtl_locale(locale: &LanguageIdentifier) -> bool {
+    matches!(locale.character_direction(), CharacterDirection::RTL)
+}
+
 /// Statistics about cache usage
 #[derive(Debug, Clone)]
 pub struct CacheStats {
@@ -687,6 +698,17 @@ mod tests {
         assert_eq!(stats2.string_cache_size, 0);
     }
     */
+
+    use super::*;
+
+    #[test]
+    fn rtl_locales() {
+        assert!(is_rtl_locale(&langid!("ar")));
+        assert!(is_rtl_locale(&langid!("he-IL")));
+        assert!(!is_rtl_locale(&EN_US));
+        assert!(!is_rtl_locale(&JA));
+        assert!(!Localization::default().is_rtl());
+    }
 }
 
 /// Replace each invalid character with exactly one underscore
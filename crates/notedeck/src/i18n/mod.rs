@@ -12,7 +12,7 @@ pub use error::IntlError;
 pub use key::{IntlKey, IntlKeyBuf};
 
 pub use manager::CacheStats;
-pub use manager::Localization;
+pub use manager::{is_rtl_locale, Localization};
 
 /// Re-export commonly used types for convenience
 pub use fluent::FluentArgs;
@@ -101,7 +101,7 @@ macro_rules! tr_plural {
         }
     }};
     // Without named parameters
-    ($one:expr, $other:expr, $comment:expr, $count:expr) => {{
-        $crate::tr_plural!($one, $other, $comment, $count, )
+    ($i18n:expr, $one:expr, $other:expr, $comment:expr, $count:expr) => {{
+        $crate::tr_plural!($i18n, $one, $other, $comment, $count, )
     }};
 }
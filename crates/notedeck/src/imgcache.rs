@@ -3,6 +3,7 @@ use crate::media::images::ImageType;
 use crate::media::AnimationMode;
 use crate::urls::{UrlCache, UrlMimes};
 use crate::ImageMetadata;
+use crate::JobPool;
 use crate::ObfuscationType;
 use crate::RenderableMedia;
 use crate::Result;
@@ -12,8 +13,9 @@ use poll_promise::Promise;
 
 use egui::ColorImage;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, create_dir_all, File};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
@@ -25,9 +27,29 @@ use std::path::PathBuf;
 use std::path::{self, Path};
 use tracing::warn;
 
+/// How many times a failed fetch is retried automatically
+const MAX_FETCH_RETRIES: u32 = 4;
+
+/// Delay before the first automatic retry, doubled on every attempt
+const FETCH_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+struct FetchRetry {
+    attempts: u32,
+    next_attempt: Instant,
+}
+
 #[derive(Default)]
 pub struct TexturesCache {
     pub cache: hashbrown::HashMap<String, TextureStateInternal>,
+    /// Backoff state for fetches that ended in an error, until they
+    /// succeed or the retries are used up
+    retries: HashMap<String, FetchRetry>,
+    /// When each texture was last asked for, used for eviction
+    last_used: HashMap<String, Instant>,
+    /// Lookups that found the url already in the cache
+    hits: u64,
+    /// Lookups that had to start a fetch
+    misses: u64,
 }
 
 impl TexturesCache {
@@ -57,14 +79,37 @@ impl TexturesCache {
         use_loading: bool,
         closure: impl FnOnce() -> Promise<Option<Result<TexturedImage>>>,
     ) -> &mut TextureStateInternal {
+        self.mark_used(url);
+
         let state = match self.cache.raw_entry_mut().from_key(url) {
             hashbrown::hash_map::RawEntryMut::Occupied(entry) => {
+                self.hits += 1;
                 let state = entry.into_mut();
+                let was_pending = matches!(state, TextureStateInternal::Pending(_));
                 handle_occupied(state, use_loading);
 
+                match state {
+                    TextureStateInternal::Error(_) if was_pending => {
+                        fetch_failed(&mut self.retries, url);
+                    }
+                    TextureStateInternal::Error(e) => {
+                        if retry_due(&mut self.retries, url) {
+                            tracing::debug!("retrying {url} after error: {e}");
+                            *state = TextureStateInternal::Pending(closure());
+                        }
+                    }
+                    TextureStateInternal::Pending(_) => {}
+                    TextureStateInternal::Loading(_) | TextureStateInternal::Loaded(_) => {
+                        if !self.retries.is_empty() {
+                            self.retries.remove(url);
+                        }
+                    }
+                }
+
                 state
             }
             hashbrown::hash_map::RawEntryMut::Vacant(entry) => {
+                self.misses += 1;
                 let res = closure();
                 let (_, state) = entry.insert(url.to_owned(), TextureStateInternal::Pending(res));
 
@@ -76,6 +121,8 @@ impl TexturesCache {
     }
 
     pub fn insert_pending(&mut self, url: &str, promise: Promise<Option<Result<TexturedImage>>>) {
+        // a manual fetch starts the backoff over
+        self.retries.remove(url);
         self.cache
             .insert(url.to_owned(), TextureStateInternal::Pending(promise));
     }
@@ -96,14 +143,94 @@ impl TexturesCache {
         });
     }
 
+    /// How long until `url` is fetched again should it fail. None once the
+    /// automatic retries are used up.
+    pub fn time_until_retry(&self, url: &str) -> Option<Duration> {
+        match self.retries.get(url) {
+            Some(retry) => Some(retry.next_attempt.saturating_duration_since(Instant::now())),
+            // a failed url without backoff state was given up on
+            None if matches!(self.cache.get(url), Some(TextureStateInternal::Error(_))) => None,
+            None => Some(FETCH_RETRY_BASE_DELAY),
+        }
+    }
+
+    fn mark_used(&mut self, url: &str) {
+        let now = Instant::now();
+        if let Some(used) = self.last_used.get_mut(url) {
+            *used = now;
+        } else {
+            self.last_used.insert(url.to_owned(), now);
+        }
+    }
+
+    /// Drop a texture, it will be loaded again from the disk cache the next
+    /// time it is needed
+    pub fn evict(&mut self, url: &str) {
+        self.cache.remove(url);
+        self.last_used.remove(url);
+        self.retries.remove(url);
+    }
+
+    /// Forget backoff state of urls that are no longer cached
+    fn prune_retries(&mut self) {
+        let cache = &self.cache;
+        self.retries.retain(|url, _| cache.contains_key(url));
+    }
+
     pub fn get_and_handle(&mut self, url: &str) -> Option<LoadableTextureState<'_>> {
+        if self.cache.contains_key(url) {
+            self.mark_used(url);
+        }
+
+        let retries = &mut self.retries;
         self.cache.get_mut(url).map(|state| {
+            let was_pending = matches!(state, TextureStateInternal::Pending(_));
             handle_occupied(state, true);
+            if was_pending && matches!(state, TextureStateInternal::Error(_)) {
+                fetch_failed(retries, url);
+            }
             state.into()
         })
     }
 }
 
+/// Note a fetch that just failed. The first failure schedules a retry,
+/// once the retries are used up the url is given up on and forgotten.
+fn fetch_failed(retries: &mut HashMap<String, FetchRetry>, url: &str) {
+    match retries.get(url) {
+        None => {
+            retries.insert(
+                url.to_owned(),
+                FetchRetry {
+                    attempts: 0,
+                    next_attempt: Instant::now() + FETCH_RETRY_BASE_DELAY,
+                },
+            );
+        }
+        Some(retry) if retry.attempts >= MAX_FETCH_RETRIES => {
+            retries.remove(url);
+        }
+        Some(_) => {}
+    }
+}
+
+/// Exponential backoff for failed fetches, true once a retry is due
+fn retry_due(retries: &mut HashMap<String, FetchRetry>, url: &str) -> bool {
+    let now = Instant::now();
+
+    let Some(retry) = retries.get_mut(url) else {
+        return false;
+    };
+
+    if retry.attempts >= MAX_FETCH_RETRIES || now < retry.next_attempt {
+        return false;
+    }
+
+    retry.attempts += 1;
+    retry.next_attempt = now + FETCH_RETRY_BASE_DELAY * 2u32.pow(retry.attempts);
+    true
+}
+
 fn handle_occupied(state: &mut TextureStateInternal, use_loading: bool) {
     let TextureStateInternal::Pending(promise) = state else {
         return;
@@ -198,6 +325,21 @@ impl TexturedImage {
             TexturedImage::Animated(animation) => &animation.first_frame.texture,
         }
     }
+
+    /// Approximate GPU memory used by this image
+    pub fn byte_size(&self) -> usize {
+        match self {
+            TexturedImage::Static(texture_handle) => texture_handle.byte_size(),
+            TexturedImage::Animated(animation) => {
+                animation.first_frame.texture.byte_size()
+                    + animation
+                        .other_frames
+                        .iter()
+                        .map(|frame| frame.texture.byte_size())
+                        .sum::<usize>()
+            }
+        }
+    }
 }
 
 pub struct Animation {
@@ -235,6 +377,8 @@ pub struct MediaCache {
     pub textures_cache: TexturesCache,
     pub cache_type: MediaCacheType,
     pub cache_size: Arc<Mutex<Option<u64>>>,
+    /// Disk budget in bytes, 0 for no limit
+    cache_limit: Arc<AtomicU64>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
@@ -250,13 +394,21 @@ impl MediaCache {
         let cache_dir_clone = cache_dir.clone();
         let cache_size = Arc::new(Mutex::new(None));
         let cache_size_clone = Arc::clone(&cache_size);
+        let cache_limit = Arc::new(AtomicU64::new(0));
+        let cache_limit_clone = Arc::clone(&cache_limit);
 
         thread::spawn(move || {
             let mut last_checked = Instant::now() - Duration::from_secs(999);
             loop {
                 // check cache folder size every 60 s
                 if last_checked.elapsed() >= Duration::from_secs(60) {
-                    let size = compute_folder_size(&cache_dir_clone);
+                    let mut size = compute_folder_size(&cache_dir_clone);
+
+                    let limit = cache_limit_clone.load(Ordering::Relaxed);
+                    if limit > 0 && size > limit {
+                        size = evict_lru(&cache_dir_clone, size, limit);
+                    }
+
                     *cache_size_clone.lock().unwrap() = Some(size);
                     last_checked = Instant::now();
                 }
@@ -269,6 +421,21 @@ impl MediaCache {
             textures_cache: TexturesCache::default(),
             cache_type,
             cache_size,
+            cache_limit,
+        }
+    }
+
+    /// Set the disk budget in bytes. The least recently used files are
+    /// evicted in the background once the cache grows past it. 0 disables
+    /// the limit.
+    pub fn set_cache_limit(&self, limit_bytes: u64) {
+        self.cache_limit.store(limit_bytes, Ordering::Relaxed);
+    }
+
+    /// Mark a cached file as recently used so it is evicted last
+    pub(crate) fn touch(path: &Path) {
+        if let Ok(file) = File::options().write(true).open(path) {
+            let _ = file.set_modified(SystemTime::now());
         }
     }
 
@@ -371,6 +538,8 @@ impl MediaCache {
 
     fn clear(&mut self) {
         self.textures_cache.cache.clear();
+        self.textures_cache.last_used.clear();
+        self.textures_cache.retries.clear();
         *self.cache_size.try_lock().unwrap() = Some(0);
     }
 }
@@ -389,7 +558,7 @@ fn color_image_to_rgba(color_image: ColorImage) -> image::RgbaImage {
         .expect("Failed to create RgbaImage from ColorImage")
 }
 
-fn compute_folder_size<P: AsRef<Path>>(path: P) -> u64 {
+pub(crate) fn compute_folder_size<P: AsRef<Path>>(path: P) -> u64 {
     fn walk(path: &Path) -> u64 {
         let mut size = 0;
         if let Ok(entries) = fs::read_dir(path) {
@@ -409,6 +578,51 @@ fn compute_folder_size<P: AsRef<Path>>(path: P) -> u64 {
     walk(path.as_ref())
 }
 
+/// Delete the least recently used files under `path` until it fits in
+/// `limit` bytes. Files are touched when read back from disk, so their
+/// modified time doubles as the last access time. Returns the new size.
+fn evict_lru(path: &Path, size: u64, limit: u64) -> u64 {
+    fn collect(path: &Path, files: &mut Vec<(PathBuf, u64, SystemTime)>) {
+        let Ok(entries) = fs::read_dir(path) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            if metadata.is_dir() {
+                collect(&entry.path(), files);
+            } else if metadata.is_file() {
+                let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push((entry.path(), metadata.len(), used));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    collect(path, &mut files);
+    files.sort_by_key(|(_, _, used)| *used);
+
+    let mut size = size;
+    let mut evicted = 0;
+    for (file, len, _) in files {
+        if size <= limit {
+            break;
+        }
+
+        if fs::remove_file(&file).is_ok() {
+            size = size.saturating_sub(len);
+            evicted += 1;
+        }
+    }
+
+    tracing::debug!("evicted {evicted} files from {}", path.display());
+
+    size
+}
+
 pub struct Images {
     pub base_path: path::PathBuf,
     pub static_imgs: MediaCache,
@@ -417,8 +631,58 @@ pub struct Images {
     /// cached imeta data
     pub metadata: HashMap<String, ImageMetadata>,
     pub gif_states: GifStateMap,
+    /// Prefetches that haven't finished yet
+    prefetching: Vec<(String, MediaCacheType)>,
+    /// GPU memory budget for loaded textures in bytes
+    texture_budget: usize,
+    last_texture_check: Instant,
+    /// Media the user tapped to see, by url
+    revealed: HashSet<String>,
 }
 
+/// Details about media shown in the media viewer's info panel
+#[derive(Debug, Clone, Default)]
+pub struct MediaDetails {
+    pub mime_type: Option<String>,
+    /// Width and height from the note's `imeta` tag, if it had one
+    pub dimensions: Option<(u32, u32)>,
+    /// Size of our cached copy on disk. This is the re-encoded file, so it
+    /// can differ from the size of the original.
+    pub cached_size: Option<u64>,
+}
+
+/// Loaded textures and how often lookups found them, for the frame
+/// overlay
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TextureStats {
+    pub loaded: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl TextureStats {
+    pub fn hit_rate(&self) -> Option<f32> {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return None;
+        }
+
+        Some(self.hits as f32 / lookups as f32)
+    }
+}
+
+/// Default GPU memory budget for media textures
+const DEFAULT_TEXTURE_BUDGET: usize = 512 * 1024 * 1024;
+
+/// Textures used more recently than this are probably on screen and are
+/// never evicted
+const TEXTURE_MIN_IDLE: Duration = Duration::from_secs(10);
+
+/// How many prefetches can be in flight at once, so they don't compete
+/// with media that is actually on screen
+const MAX_PREFETCHES: usize = 4;
+
 impl Images {
     /// path to directory to place [`MediaCache`]s
     pub fn new(path: path::PathBuf) -> Self {
@@ -429,14 +693,170 @@ impl Images {
             urls: UrlMimes::new(UrlCache::new(path.join(UrlCache::rel_dir()))),
             gif_states: Default::default(),
             metadata: Default::default(),
+            prefetching: Vec::new(),
+            texture_budget: DEFAULT_TEXTURE_BUDGET,
+            last_texture_check: Instant::now(),
+            revealed: HashSet::new(),
         }
     }
 
+    /// The user tapped blurred media to see it
+    pub fn reveal(&mut self, url: &str) {
+        self.revealed.insert(url.to_owned());
+    }
+
+    pub fn is_revealed(&self, url: &str) -> bool {
+        self.revealed.contains(url)
+    }
+
+    pub fn set_texture_budget(&mut self, budget_bytes: usize) {
+        self.texture_budget = budget_bytes;
+    }
+
+    /// Evict the least recently used textures once loaded media goes over the
+    /// texture budget. Call once per frame, it only does work every second.
+    #[profiling::function]
+    pub fn enforce_texture_budget(&mut self) {
+        if self.last_texture_check.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.last_texture_check = Instant::now();
+        self.static_imgs.textures_cache.prune_retries();
+        self.gifs.textures_cache.prune_retries();
+
+        let mut total = 0;
+        let mut loaded = Vec::new();
+        for cache in [&self.static_imgs, &self.gifs] {
+            let textures = &cache.textures_cache;
+            for (url, state) in &textures.cache {
+                let (TextureStateInternal::Loading(image) | TextureStateInternal::Loaded(image)) =
+                    state
+                else {
+                    continue;
+                };
+
+                let bytes = image.byte_size();
+                total += bytes;
+                let used = textures.last_used.get(url).copied();
+                loaded.push((used, url.clone(), cache.cache_type, bytes));
+            }
+        }
+
+        if total <= self.texture_budget {
+            return;
+        }
+
+        // never used sorts first
+        loaded.sort_by_key(|(used, ..)| *used);
+
+        let now = Instant::now();
+        let mut evicted = 0;
+        for (used, url, cache_type, bytes) in loaded {
+            if total <= self.texture_budget {
+                break;
+            }
+
+            if used.is_some_and(|used| now.duration_since(used) < TEXTURE_MIN_IDLE) {
+                // everything after this was used even more recently
+                break;
+            }
+
+            self.get_cache_mut(cache_type).textures_cache.evict(&url);
+            self.gif_states.remove(&url);
+            total -= bytes;
+            evicted += 1;
+        }
+
+        tracing::debug!("evicted {evicted} textures, {total} bytes still loaded");
+    }
+
+    pub fn texture_stats(&self) -> TextureStats {
+        let mut stats = TextureStats::default();
+        for cache in [&self.static_imgs, &self.gifs] {
+            let textures = &cache.textures_cache;
+            stats.hits += textures.hits;
+            stats.misses += textures.misses;
+            for state in textures.cache.values() {
+                if let TextureStateInternal::Loading(image) | TextureStateInternal::Loaded(image) =
+                    state
+                {
+                    stats.loaded += 1;
+                    stats.bytes += image.byte_size();
+                }
+            }
+        }
+        stats
+    }
+
+    /// Start loading media before it is shown. Prefetches run on `job_pool`,
+    /// so they wait behind other background work. Returns false if too many
+    /// prefetches are already in flight.
+    pub fn prefetch(
+        &mut self,
+        ctx: &egui::Context,
+        job_pool: &JobPool,
+        url: &str,
+        cache_type: MediaCacheType,
+    ) -> bool {
+        self.prefetching.retain(|(pending_url, pending_type)| {
+            let cache = match pending_type {
+                MediaCacheType::Image => &self.static_imgs,
+                MediaCacheType::Gif => &self.gifs,
+            };
+
+            matches!(
+                cache.textures_cache.cache.get(pending_url),
+                Some(TextureStateInternal::Pending(promise)) if promise.ready().is_none()
+            )
+        });
+
+        let cache = self.get_cache_mut(cache_type);
+        if cache.textures_cache.cache.contains_key(url) {
+            return true;
+        }
+
+        if self.prefetching.len() >= MAX_PREFETCHES {
+            return false;
+        }
+
+        let cache = self.get_cache_mut(cache_type);
+        let cache_dir = cache.cache_dir.clone();
+        let job_ctx = ctx.clone();
+        let job_url = url.to_owned();
+        // fetching spawns tokio tasks, so the job needs our runtime
+        let runtime = tokio::runtime::Handle::current();
+        let job = job_pool.schedule(move || {
+            let _guard = runtime.enter();
+            crate::media::images::fetch_img(
+                &cache_dir,
+                &job_ctx,
+                &job_url,
+                ImageType::Content(None),
+                cache_type,
+            )
+            .block_and_take()
+        });
+        cache
+            .textures_cache
+            .insert_pending(url, Promise::spawn_async(job));
+        self.prefetching.push((url.to_owned(), cache_type));
+
+        true
+    }
+
     pub fn migrate_v0(&self) -> Result<()> {
         self.static_imgs.migrate_v0()?;
         self.gifs.migrate_v0()
     }
 
+    /// Set the total disk budget for cached media, split evenly between
+    /// static images and gifs. 0 disables the limit.
+    pub fn set_cache_limit(&self, limit_bytes: u64) {
+        let per_cache = limit_bytes / 2;
+        self.static_imgs.set_cache_limit(per_cache);
+        self.gifs.set_cache_limit(per_cache);
+    }
+
     pub fn get_renderable_media(&mut self, url: &str) -> Option<RenderableMedia> {
         Self::find_renderable_media(&mut self.urls, &self.metadata, url)
     }
@@ -448,15 +868,19 @@ impl Images {
     ) -> Option<RenderableMedia> {
         let media_type = crate::urls::supported_mime_hosted_at_url(urls, url)?;
 
-        let obfuscation_type = match imeta.get(url) {
-            Some(blur) => ObfuscationType::Blurhash(blur.clone()),
-            None => ObfuscationType::Default,
+        let meta = imeta.get(url);
+        let obfuscation_type = match meta {
+            Some(blur) if blur.blurhash.is_some() => ObfuscationType::Blurhash(blur.clone()),
+            _ => ObfuscationType::Default,
         };
 
         Some(RenderableMedia {
             url: url.to_string(),
             media_type,
             obfuscation_type,
+            alt: meta.and_then(|m| m.alt.clone()),
+            dimensions: meta.and_then(|m| m.dimensions.clone()),
+            content_warning: meta.and_then(|m| m.content_warning.clone()),
         })
     }
 
@@ -496,6 +920,29 @@ impl Images {
         )
     }
 
+    /// Like [`Images::latest_texture`], but only for media that's already
+    /// loaded. Nothing is fetched.
+    pub fn loaded_texture(
+        &mut self,
+        ui: &mut egui::Ui,
+        url: &str,
+        animation_mode: AnimationMode,
+    ) -> Option<TextureHandle> {
+        let cache_type = crate::urls::supported_mime_hosted_at_url(&mut self.urls, url)?;
+        let cache = match cache_type {
+            MediaCacheType::Image => &mut self.static_imgs,
+            MediaCacheType::Gif => &mut self.gifs,
+        };
+
+        ensure_latest_texture_from_cache(
+            ui,
+            url,
+            &mut self.gif_states,
+            &mut cache.textures_cache,
+            animation_mode,
+        )
+    }
+
     pub fn get_cache(&self, cache_type: MediaCacheType) -> &MediaCache {
         match cache_type {
             MediaCacheType::Image => &self.static_imgs,
@@ -510,6 +957,32 @@ impl Images {
         }
     }
 
+    /// What we know about a piece of media without fetching it again
+    pub fn media_details(&self, url: &str) -> MediaDetails {
+        let mime_type = self.urls.cache.get_type(url).or_else(|| {
+            let path = url::Url::parse(url).ok()?.path().to_owned();
+            mime_guess::from_path(path).first().map(|m| m.to_string())
+        });
+
+        let dimensions = self
+            .metadata
+            .get(url)
+            .and_then(|m| m.dimensions.as_ref())
+            .map(|d| (d.x, d.y));
+
+        let key = MediaCache::key(url);
+        let cached_size = [&self.static_imgs, &self.gifs]
+            .iter()
+            .find_map(|cache| fs::metadata(cache.cache_dir.join(&key)).ok())
+            .map(|meta| meta.len());
+
+        MediaDetails {
+            mime_type,
+            dimensions,
+            cached_size,
+        }
+    }
+
     pub fn clear_folder_contents(&mut self) -> io::Result<()> {
         for entry in fs::read_dir(self.base_path.clone())? {
             let entry = entry?;
@@ -571,3 +1044,75 @@ pub struct RenderState<'a> {
     pub texture_state: TextureState<'a>,
     pub gifs: &'a mut GifStateMap,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(path: &Path, len: usize, age_secs: u64) {
+        fs::write(path, vec![0u8; len]).unwrap();
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn evict_lru_removes_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("ab");
+        create_dir_all(&sub).unwrap();
+
+        let oldest = sub.join("oldest");
+        let middle = dir.path().join("middle");
+        let newest = dir.path().join("newest");
+        write_file(&oldest, 100, 300);
+        write_file(&middle, 100, 200);
+        write_file(&newest, 100, 100);
+
+        let size = compute_folder_size(dir.path());
+        assert_eq!(size, 300);
+
+        let size = evict_lru(dir.path(), size, 150);
+        assert_eq!(size, 100);
+        assert!(!oldest.exists());
+        assert!(!middle.exists());
+        assert!(newest.exists());
+    }
+
+    #[test]
+    fn retry_backoff_is_capped() {
+        let mut retries = HashMap::new();
+        let url = "https://example.com/a.png";
+
+        // first failure only schedules a retry
+        fetch_failed(&mut retries, url);
+        assert!(!retry_due(&mut retries, url));
+
+        for attempt in 1..=MAX_FETCH_RETRIES {
+            retries.get_mut(url).unwrap().next_attempt = Instant::now();
+            assert!(retry_due(&mut retries, url));
+            assert_eq!(retries[url].attempts, attempt);
+            fetch_failed(&mut retries, url);
+        }
+
+        // given up, and nothing is left behind
+        assert!(retries.is_empty());
+        assert!(!retry_due(&mut retries, url));
+    }
+
+    #[test]
+    fn touch_protects_from_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("first");
+        let second = dir.path().join("second");
+        write_file(&first, 100, 300);
+        write_file(&second, 100, 200);
+
+        MediaCache::touch(&first);
+
+        let size = evict_lru(dir.path(), 200, 100);
+        assert_eq!(size, 100);
+        assert!(first.exists());
+        assert!(!second.exists());
+    }
+}
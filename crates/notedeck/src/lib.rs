@@ -3,9 +3,11 @@
 pub mod abbrev;
 mod account;
 mod app;
+mod app_subs;
 mod args;
 pub mod contacts;
 mod context;
+pub mod db_maintenance;
 pub mod debouncer;
 mod error;
 pub mod filter;
@@ -16,38 +18,52 @@ mod imgcache;
 mod job_pool;
 mod jobs;
 pub mod media;
+pub mod motion;
 mod muted;
 pub mod name;
 mod nip51_set;
+pub mod nostr_uri;
 pub mod note;
 mod notecache;
+mod notifications;
 mod options;
+mod outbox;
 mod persist;
 pub mod platform;
+pub mod pow;
 pub mod profile;
+mod publish;
 pub mod relay_debug;
 pub mod relayspec;
 mod result;
 mod route;
 mod setup;
+pub mod shortcuts;
 pub mod storage;
 mod style;
 pub mod theme;
 mod time;
 mod timecache;
 mod timed_serializer;
+mod translate;
 pub mod ui;
 mod unknowns;
 mod urls;
 mod user_account;
 mod wallet;
 mod zaps;
+pub mod zoom;
 
-pub use account::accounts::{AccountData, AccountSubs, Accounts};
+pub use account::accounts::{AccountData, AccountSubs, Accounts, AddAccountResponse};
+pub use account::backup::{AccountBackup, BackupContents, BackupError, RestoredAccount};
 pub use account::contacts::{ContactState, IsFollowing};
+pub use account::export::EventExport;
+pub use account::mute::MuteAction;
 pub use account::relay::RelayAction;
+pub use account::signer::{RemoteSigner, SignerMethod, SignerRequest, SignerStatus};
 pub use account::FALLBACK_PUBKEY;
 pub use app::{App, AppAction, AppResponse, Notedeck};
+pub use app_subs::AppSubscriptions;
 pub use args::Args;
 pub use context::{AppContext, SoftKeyboardContext};
 pub use error::{show_one_error_message, Error, FilterError, ZapError};
@@ -56,18 +72,19 @@ pub use fonts::NamedFontFamily;
 pub use i18n::{CacheStats, FluentArgs, FluentValue, LanguageIdentifier, Localization};
 pub use imgcache::{
     get_render_state, Animation, GifState, GifStateMap, ImageFrame, Images, LatestTexture,
-    LoadableTextureState, MediaCache, MediaCacheType, RenderState, TextureFrame, TextureState,
-    TexturedImage, TexturesCache,
+    LoadableTextureState, MediaCache, MediaCacheType, MediaDetails, RenderState, TextureFrame,
+    TextureState, TextureStats, TexturedImage, TexturesCache,
 };
 pub use job_pool::JobPool;
 pub use jobs::{
     BlurhashParams, Job, JobError, JobId, JobParams, JobParamsOwned, JobState, JobsCache,
 };
 pub use media::{
-    compute_blurhash, update_imeta_blurhashes, ImageMetadata, ImageType, MediaAction,
-    ObfuscationType, PixelDimensions, PointDimensions, RenderableMedia,
+    compute_blurhash, note_content_warning, set_image_proxy, update_imeta_blurhashes,
+    ImageMetadata, ImageProxy, ImageType, MediaAction, ObfuscationType, PixelDimensions,
+    PointDimensions, RenderableMedia,
 };
-pub use muted::{MuteFun, Muted};
+pub use muted::{MuteFun, MuteItem, Muted};
 pub use name::NostrName;
 pub use nip51_set::{create_nip51_set, Nip51Set, Nip51SetCache};
 pub use note::{
@@ -75,19 +92,24 @@ pub use note::{
     RootIdError, RootNoteId, RootNoteIdBuf, ScrollInfo, ZapAction,
 };
 pub use notecache::{CachedNote, NoteCache};
+pub use notifications::{Notification, NotificationKind, Notifications};
 pub use options::NotedeckOptions;
+pub use outbox::Outbox;
 pub use persist::*;
 pub use profile::*;
+pub use publish::{PublishReceipt, PublishTracker, RelayReceipt};
 pub use relay_debug::RelayDebugView;
 pub use relayspec::RelaySpec;
 pub use result::Result;
 pub use route::DrawerRouter;
+pub use shortcuts::Shortcut;
 pub use storage::{AccountStorage, DataPath, DataPathType, Directory};
 pub use style::NotedeckTextStyle;
-pub use theme::ColorTheme;
+pub use theme::{Accent, ColorTheme, Palette, ThemeOptions};
 pub use time::time_ago_since;
 pub use time::time_format;
 pub use timecache::TimeCached;
+pub use translate::{TranslationView, Translations, Translator};
 pub use unknowns::{get_unknown_note_ids, NoteRefsUnkIdAction, SingleUnkIdAction, UnknownIds};
 pub use urls::{supported_mime_hosted_at_url, SupportedMimeType, UrlMimes};
 pub use user_account::UserAccount;
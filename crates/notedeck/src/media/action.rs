@@ -1,4 +1,5 @@
 use crate::{Images, MediaCacheType, TexturedImage};
+use enostr::{NoteId, Pubkey};
 use poll_promise::Promise;
 
 /// Tracks where media was on the screen so that
@@ -13,6 +14,13 @@ pub struct MediaInfo {
     pub url: String,
 }
 
+/// The note a set of media was posted in
+#[derive(Debug, Clone, Copy)]
+pub struct MediaSource {
+    pub note_id: NoteId,
+    pub author: Pubkey,
+}
+
 /// Contains various information for when a user
 /// clicks a piece of media. It contains the current
 /// location on screen for each piece of media.
@@ -23,6 +31,8 @@ pub struct MediaInfo {
 pub struct ViewMediaInfo {
     pub clicked_index: usize,
     pub medias: Vec<MediaInfo>,
+    /// Where the media came from, if it was in a note
+    pub source: Option<MediaSource>,
 }
 
 impl ViewMediaInfo {
@@ -55,10 +65,12 @@ impl std::fmt::Debug for MediaAction {
             Self::ViewMedias(ViewMediaInfo {
                 clicked_index,
                 medias,
+                source,
             }) => f
                 .debug_struct("ViewMedias")
                 .field("clicked_index", clicked_index)
                 .field("media", medias)
+                .field("source", source)
                 .finish(),
             Self::FetchImage {
                 url,
@@ -9,8 +9,13 @@ use crate::{
 
 #[derive(Clone)]
 pub struct ImageMetadata {
-    pub blurhash: String,
+    pub blurhash: Option<String>,
     pub dimensions: Option<PixelDimensions>, // width and height in pixels
+    /// Description of the media for people who can't see it
+    pub alt: Option<String>,
+    /// NIP-36 style `content-warning` or `sensitive` marker on this media.
+    /// The reason may be empty.
+    pub content_warning: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -91,7 +96,7 @@ impl ImageMetadata {
     }
 }
 
-/// Find blurhashes in image metadata and update our cache
+/// Find blurhashes, dimensions and alt text in image metadata and update our cache
 pub fn update_imeta_blurhashes(note: &Note, blurs: &mut HashMap<String, ImageMetadata>) {
     for tag in note.tags() {
         let mut tag_iter = tag.into_iter();
@@ -104,40 +109,59 @@ pub fn update_imeta_blurhashes(note: &Note, blurs: &mut HashMap<String, ImageMet
             continue;
         }
 
-        let Some((url, blur)) = find_blur(tag_iter) else {
+        let Some((url, meta)) = find_imeta(tag_iter) else {
             continue;
         };
 
-        blurs.insert(url.to_string(), blur);
+        blurs.insert(url.to_string(), meta);
     }
 }
 
-fn find_blur(tag_iter: nostrdb::TagIter<'_>) -> Option<(String, ImageMetadata)> {
+fn find_imeta(tag_iter: nostrdb::TagIter<'_>) -> Option<(String, ImageMetadata)> {
     let mut url = None;
     let mut blurhash = None;
     let mut dims = None;
+    let mut alt = None;
+    let mut content_warning = None;
 
     for tag_elem in tag_iter {
         let Some(s) = tag_elem.str() else { continue };
-        let mut split = s.split_whitespace();
 
-        let Some(first) = split.next() else { continue };
-        let Some(second) = split.next() else { continue };
-
-        match first {
-            "url" => url = Some(second),
-            "blurhash" => blurhash = Some(second),
-            "dim" => dims = Some(second),
+        // `sensitive` and `content-warning` may come without a value
+        match s.trim() {
+            "sensitive" | "content-warning" => {
+                content_warning.get_or_insert("");
+                continue;
+            }
             _ => {}
         }
 
-        if url.is_some() && blurhash.is_some() && dims.is_some() {
-            break;
+        // alt text contains spaces, so only split on the first one
+        let Some((first, rest)) = s.trim().split_once(' ') else {
+            continue;
+        };
+        let rest = rest.trim();
+        if rest.is_empty() {
+            continue;
+        }
+
+        match first {
+            "url" => url = Some(rest),
+            "blurhash" => blurhash = Some(rest),
+            "dim" => dims = Some(rest),
+            "alt" => alt = Some(rest),
+            "content-warning" => content_warning = Some(rest),
+            "sensitive" if rest != "false" => {
+                content_warning.get_or_insert("");
+            }
+            _ => {}
         }
     }
 
     let url = url?;
-    let blurhash = blurhash?;
+    if blurhash.is_none() && dims.is_none() && alt.is_none() && content_warning.is_none() {
+        return None;
+    }
 
     let dimensions = dims.and_then(|d| {
         let mut split = d.split('x');
@@ -153,12 +177,28 @@ fn find_blur(tag_iter: nostrdb::TagIter<'_>) -> Option<(String, ImageMetadata)>
     Some((
         url.to_string(),
         ImageMetadata {
-            blurhash: blurhash.to_string(),
+            blurhash: blurhash.map(|b| b.to_string()),
             dimensions,
+            alt: alt.map(|a| a.to_string()),
+            content_warning: content_warning.map(|c| c.to_string()),
         },
     ))
 }
 
+/// The NIP-36 `content-warning` of a note, if it has one. The reason may be
+/// empty.
+pub fn note_content_warning(note: &Note) -> Option<String> {
+    for tag in note.tags() {
+        if tag.get_str(0) != Some("content-warning") {
+            continue;
+        }
+
+        return Some(tag.get_str(1).unwrap_or_default().to_owned());
+    }
+
+    None
+}
+
 #[derive(Clone)]
 pub enum ObfuscationType {
     Blurhash(ImageMetadata),
@@ -0,0 +1,149 @@
+//! Saving every media item of a note into a folder, for "Save all" in the
+//! media viewer. Files are fetched from their original urls, since our
+//! disk cache only has resized copies.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub saved: usize,
+    pub failed: usize,
+    /// Every url was tried, or the download was cancelled
+    pub finished: bool,
+}
+
+/// Media being saved one file at a time on a background thread
+pub struct MediaDownload {
+    total: usize,
+    progress: Arc<Mutex<DownloadProgress>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl MediaDownload {
+    pub fn start(ctx: &egui::Context, urls: Vec<String>, dir: PathBuf) -> Self {
+        let total = urls.len();
+        let progress = Arc::new(Mutex::new(DownloadProgress::default()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thread_progress = progress.clone();
+        let thread_cancelled = cancelled.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            for (index, url) in urls.iter().enumerate() {
+                if thread_cancelled.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let path = dir.join(file_name(index, url));
+                let result = download(url, &path);
+                if let Err(err) = &result {
+                    tracing::warn!("could not save {url} to {}: {err}", path.display());
+                }
+
+                if let Ok(mut progress) = thread_progress.lock() {
+                    if result.is_ok() {
+                        progress.saved += 1;
+                    } else {
+                        progress.failed += 1;
+                    }
+                }
+                ctx.request_repaint();
+            }
+
+            if let Ok(mut progress) = thread_progress.lock() {
+                progress.finished = true;
+            }
+            ctx.request_repaint();
+        });
+
+        Self {
+            total,
+            progress,
+            cancelled,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        self.progress.lock().map(|p| *p).unwrap_or_default()
+    }
+
+    /// Stop after the file being saved now
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+fn download(url: &str, path: &Path) -> Result<(), Error> {
+    let response = ehttp::fetch_blocking(&ehttp::Request::get(url)).map_err(Error::Generic)?;
+    if !response.ok {
+        return Err(Error::Generic(format!(
+            "{} {}",
+            response.status, response.status_text
+        )));
+    }
+
+    std::fs::write(path, &response.bytes)?;
+    Ok(())
+}
+
+/// The name to save a url as, numbered so the files keep the note's order
+fn file_name(index: usize, url: &str) -> String {
+    let last_segment = url::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .next_back()
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned)
+        })
+        .unwrap_or_default();
+
+    let name: String = last_segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.');
+
+    if name.is_empty() {
+        format!("{:02}-media", index + 1)
+    } else {
+        format!("{:02}-{name}", index + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_keep_order_and_stay_safe() {
+        assert_eq!(
+            file_name(0, "https://example.com/a/photo.jpg?size=large"),
+            "01-photo.jpg"
+        );
+        assert_eq!(
+            file_name(11, "https://example.com/b/my%20cat.png"),
+            "12-my_20cat.png"
+        );
+        assert_eq!(file_name(2, "https://example.com/"), "03-media");
+        assert_eq!(file_name(3, "https://example.com/..."), "04-media");
+    }
+}
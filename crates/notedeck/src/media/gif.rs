@@ -42,8 +42,11 @@ fn process_gif_frame(
 
     match frame_state {
         Some(prev_state) => {
+            // frames due within the slack are shown a little early, so they
+            // share a repaint with whichever gif is due first
             let should_advance = animation_mode.can_animate()
-                && (now - prev_state.last_frame_rendered >= prev_state.last_frame_duration);
+                && (now + FRAME_SLACK - prev_state.last_frame_rendered
+                    >= prev_state.last_frame_duration);
 
             if should_advance {
                 let maybe_new_index = if animation.receiver.is_some()
@@ -120,6 +123,108 @@ fn process_gif_frame(
     }
 }
 
+/// How far off its deadline a gif frame may be shown to share a repaint
+/// with another gif
+const FRAME_SLACK: Duration = Duration::from_millis(8);
+
+/// The earliest next-frame deadline of the gifs rendered this pass. Gifs
+/// report their deadlines here instead of each requesting a repaint, so
+/// one repaint wakes all of them.
+#[derive(Clone, Copy, Default)]
+struct FrameScheduler {
+    pass: u64,
+    earliest: Option<Instant>,
+}
+
+impl FrameScheduler {
+    /// Note a gif wants a frame at `deadline`. True if a repaint needs
+    /// to be requested for it, when no earlier repaint covers it.
+    fn schedule(&mut self, pass: u64, deadline: Instant) -> bool {
+        if self.pass != pass {
+            self.pass = pass;
+            self.earliest = None;
+        }
+
+        if self
+            .earliest
+            .is_some_and(|earliest| earliest <= deadline + FRAME_SLACK)
+        {
+            return false;
+        }
+
+        self.earliest = Some(deadline);
+        true
+    }
+}
+
+/// Repaint when a gif's next frame is due, unless a repaint is already
+/// coming by then
+fn schedule_frame(ctx: &egui::Context, url: &str, repaint_at: SystemTime) {
+    let Ok(wait) = repaint_at.duration_since(SystemTime::now()) else {
+        return;
+    };
+
+    let pass = ctx.cumulative_pass_nr();
+    let deadline = Instant::now() + wait;
+    let needs_repaint = ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<FrameScheduler>(egui::Id::new("gif_frame_scheduler"))
+            .schedule(pass, deadline)
+    });
+
+    if needs_repaint {
+        tracing::trace!("requesting repaint for {url} after {wait:?}");
+        ctx.request_repaint_after(wait);
+    }
+}
+
+/// The most gifs we animate in a single frame. Anything rendered after this
+/// shows its current frame until fewer gifs are on screen.
+const MAX_ANIMATING_GIFS: usize = 8;
+
+/// Count the gifs that want to animate this frame and stop animating the
+/// ones that go over [`MAX_ANIMATING_GIFS`]
+fn limit_animations(ctx: &egui::Context, animation_mode: AnimationMode) -> AnimationMode {
+    if !animation_mode.can_animate() {
+        return animation_mode;
+    }
+
+    if crate::motion::reduce_motion(ctx) {
+        return AnimationMode::NoAnimation;
+    }
+
+    let pass = ctx.cumulative_pass_nr();
+    let over_budget = ctx.data_mut(|d| {
+        let (counted_pass, count) =
+            d.get_temp_mut_or_insert_with(egui::Id::new("animating_gifs"), || (pass, 0_usize));
+        if *counted_pass != pass {
+            *counted_pass = pass;
+            *count = 0;
+        }
+        *count += 1;
+        *count > MAX_ANIMATING_GIFS
+    });
+
+    if over_budget {
+        AnimationMode::NoAnimation
+    } else {
+        animation_mode
+    }
+}
+
+/// Pause animations for media that won't be visible at `rect`, so gifs
+/// that are scrolled out of view don't keep requesting repaints
+pub fn animation_mode_if_visible(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    animation_mode: AnimationMode,
+) -> AnimationMode {
+    if ui.is_rect_visible(rect) {
+        animation_mode
+    } else {
+        AnimationMode::NoAnimation
+    }
+}
+
 pub fn ensure_latest_texture(
     ui: &egui::Ui,
     url: &str,
@@ -145,20 +250,40 @@ pub fn ensure_latest_texture(
                 }
             }
 
+            let animation_mode = limit_animations(ui.ctx(), animation_mode);
             let next_state = process_gif_frame(animation, gifs.get(url), animation_mode);
 
             if let Some(new_state) = next_state.maybe_new_state {
                 gifs.insert(url.to_owned(), new_state);
             }
 
-            if let Some(repaint) = next_state.repaint_at {
-                tracing::trace!("requesting repaint for {url} after {repaint:?}");
-                if let Ok(dur) = repaint.duration_since(SystemTime::now()) {
-                    ui.ctx().request_repaint_after(dur);
-                }
+            if let Some(repaint_at) = next_state.repaint_at {
+                schedule_frame(ui.ctx(), url, repaint_at);
             }
 
             next_state.texture
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduler_coalesces_deadlines() {
+        let now = Instant::now();
+        let mut scheduler = FrameScheduler::default();
+
+        assert!(scheduler.schedule(1, now + Duration::from_millis(100)));
+        // covered by the repaint already requested
+        assert!(!scheduler.schedule(1, now + Duration::from_millis(100)));
+        assert!(!scheduler.schedule(1, now + Duration::from_millis(200)));
+        assert!(!scheduler.schedule(1, now + Duration::from_millis(95)));
+        // due well before it
+        assert!(scheduler.schedule(1, now + Duration::from_millis(40)));
+
+        // a new pass starts over
+        assert!(scheduler.schedule(2, now + Duration::from_millis(200)));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::media::load_texture_checked;
-use crate::{Animation, ImageFrame, MediaCache, MediaCacheType, TextureFrame, TexturedImage};
+use crate::{
+    Animation, ImageFrame, JobPool, MediaCache, MediaCacheType, TextureFrame, TexturedImage,
+};
 use egui::{pos2, Color32, ColorImage, Context, Rect, Sense, SizeHint};
 use image::codecs::gif::GifDecoder;
 use image::imageops::FilterType;
@@ -9,8 +11,10 @@ use std::collections::VecDeque;
 use std::io::Cursor;
 use std::path::PathBuf;
 use std::path::{self, Path};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::SyncSender;
+use std::sync::OnceLock;
 use std::thread;
 use std::time::Duration;
 use tokio::fs;
@@ -126,6 +130,21 @@ fn resize_image_if_too_big(
     }
 }
 
+/// Content images are never kept larger than this on their longest edge.
+/// Read on the decoding threads, so it lives outside of the settings.
+static MAX_IMG_LENGTH: AtomicU32 = AtomicU32::new(2048);
+
+/// Set the longest edge content images are downscaled to. Only media
+/// loaded afterwards is affected.
+pub fn set_max_image_length(length: u32) {
+    let length = length.clamp(256, super::MAX_SIZE_WGPU as u32);
+    MAX_IMG_LENGTH.store(length, Ordering::Relaxed);
+}
+
+fn max_image_length() -> u32 {
+    MAX_IMG_LENGTH.load(Ordering::Relaxed)
+}
+
 ///
 /// Process an image, resizing so we don't blow up video memory or even crash
 ///
@@ -133,18 +152,17 @@ fn resize_image_if_too_big(
 /// For everything else, either:
 ///
 ///   - resize to the size hint
-///   - keep the size if the longest dimension is less than MAX_IMG_LENGTH
+///   - keep the size if the longest dimension is less than [`max_image_length`]
 ///   - resize if any larger, using [`resize_image_if_too_big`]
 ///
 #[profiling::function]
 fn process_image(imgtyp: ImageType, mut image: image::DynamicImage) -> ColorImage {
-    const MAX_IMG_LENGTH: u32 = 2048;
     const FILTER_TYPE: FilterType = FilterType::CatmullRom;
 
     match imgtyp {
         ImageType::Content(size_hint) => {
             let image = match size_hint {
-                None => resize_image_if_too_big(image, MAX_IMG_LENGTH, FILTER_TYPE),
+                None => resize_image_if_too_big(image, max_image_length(), FILTER_TYPE),
                 Some((w, h)) => image.resize(w, h, FILTER_TYPE),
             };
 
@@ -183,25 +201,64 @@ fn process_image(imgtyp: ImageType, mut image: image::DynamicImage) -> ColorImag
     }
 }
 
+/// Largest svg document we will try to rasterize
+const MAX_SVG_BYTES: usize = 1024 * 1024;
+
+/// Longest edge of a rasterized svg when we don't have a size hint
+const MAX_SVG_EDGE: u32 = 1024;
+
+/// `<use>` elements can reference each other, so a small file can expand
+/// into an enormous render tree. Anything past this is rejected.
+const MAX_SVG_USES: usize = 512;
+
+/// Reject svg documents that are too big or that could blow up while
+/// parsing or rendering. Entities are never needed for images we display
+/// and are the usual way to build an expansion bomb.
+fn check_svg(bytes: &[u8]) -> Result<(), crate::Error> {
+    if bytes.len() > MAX_SVG_BYTES {
+        return Err(format!("svg too large ({} bytes)", bytes.len()).into());
+    }
+
+    let text = std::str::from_utf8(bytes).map_err(|_| "svg is not valid utf-8".to_owned())?;
+
+    if text.contains("<!ENTITY") {
+        return Err("svg contains entity declarations".to_owned().into());
+    }
+
+    if text.matches("<use").count() > MAX_SVG_USES {
+        return Err("svg contains too many <use> elements".to_owned().into());
+    }
+
+    Ok(())
+}
+
+#[profiling::function]
+fn load_svg(bytes: &[u8], imgtyp: ImageType) -> Result<ColorImage, crate::Error> {
+    check_svg(bytes)?;
+
+    let size_hint = match imgtyp {
+        ImageType::Profile(size) => SizeHint::Size(size, size),
+        ImageType::Content(Some((w, h))) => SizeHint::Size(w, h),
+        ImageType::Content(None) => SizeHint::Size(MAX_SVG_EDGE, MAX_SVG_EDGE),
+    };
+
+    let mut color_image = egui_extras::image::load_svg_bytes_with_size(bytes, Some(size_hint))?;
+    if let ImageType::Profile(_) = imgtyp {
+        round_image(&mut color_image);
+    }
+
+    Ok(color_image)
+}
+
 #[profiling::function]
 fn parse_img_response(
     response: ehttp::Response,
     imgtyp: ImageType,
 ) -> Result<ColorImage, crate::Error> {
     let content_type = response.content_type().unwrap_or_default();
-    let size_hint = match imgtyp {
-        ImageType::Profile(size) => SizeHint::Size(size, size),
-        ImageType::Content(Some((w, h))) => SizeHint::Size(w, h),
-        ImageType::Content(None) => SizeHint::default(),
-    };
 
     if content_type.starts_with("image/svg") {
-        profiling::scope!("load_svg");
-
-        let mut color_image =
-            egui_extras::image::load_svg_bytes_with_size(&response.bytes, Some(size_hint))?;
-        round_image(&mut color_image);
-        Ok(color_image)
+        load_svg(&response.bytes, imgtyp)
     } else if content_type.starts_with("image/") {
         profiling::scope!("load_from_memory");
         let dyn_image = image::load_from_memory(&response.bytes)?;
@@ -226,15 +283,42 @@ fn fetch_img_from_disk(
     })
 }
 
+/// Decoding gets its own small pool so a burst of large images is decoded a
+/// few at a time, rather than all at once on the http and async threads
+fn decode_pool() -> &'static JobPool {
+    static POOL: OnceLock<JobPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get().clamp(2, 4))
+            .unwrap_or(2);
+        JobPool::new(threads)
+    })
+}
+
 async fn async_fetch_img_from_disk(
     ctx: egui::Context,
     url: String,
     path: &path::Path,
     cache_type: MediaCacheType,
+) -> Result<TexturedImage, crate::Error> {
+    let data = fs::read(path).await?;
+    MediaCache::touch(path);
+
+    let path = path.to_owned();
+    decode_pool()
+        .schedule(move || decode_img_from_disk(ctx, url, &path, data, cache_type))
+        .await
+}
+
+fn decode_img_from_disk(
+    ctx: egui::Context,
+    url: String,
+    path: &path::Path,
+    data: Vec<u8>,
+    cache_type: MediaCacheType,
 ) -> Result<TexturedImage, crate::Error> {
     match cache_type {
         MediaCacheType::Image => {
-            let data = fs::read(path).await?;
             let image_buffer = image::load_from_memory(&data).map_err(crate::Error::Image)?;
 
             let img = buffer_to_color_image(
@@ -249,12 +333,9 @@ async fn async_fetch_img_from_disk(
                 Default::default(),
             )))
         }
-        MediaCacheType::Gif => {
-            let gif_bytes = fs::read(path).await?; // Read entire file into a Vec<u8>
-            generate_gif(ctx, url, path, gif_bytes, false, |i| {
-                buffer_to_color_image(i.as_flat_samples_u8(), i.width(), i.height())
-            })
-        }
+        MediaCacheType::Gif => generate_gif(ctx, url, path, data, false, |i| {
+            buffer_to_color_image(i.as_flat_samples_u8(), i.width(), i.height())
+        }),
     }
 }
 
@@ -394,6 +475,17 @@ pub enum ImageType {
     Content(Option<(u32, u32)>),
 }
 
+impl ImageType {
+    /// The widest variant worth fetching for this image
+    fn fetch_width(&self) -> u32 {
+        match self {
+            ImageType::Profile(size) => *size,
+            ImageType::Content(Some((w, _))) => *w,
+            ImageType::Content(None) => max_image_length(),
+        }
+    }
+}
+
 pub fn fetch_img(
     img_cache_path: &Path,
     ctx: &egui::Context,
@@ -421,47 +513,50 @@ fn fetch_img_from_net(
     cache_type: MediaCacheType,
 ) -> Promise<Option<Result<TexturedImage, crate::Error>>> {
     let (sender, promise) = Promise::new();
-    let request = ehttp::Request::get(url);
+    let request = ehttp::Request::get(crate::media::proxy::fetch_url(url, imgtyp.fetch_width()));
     let ctx = ctx.clone();
     let cloned_url = url.to_owned();
     let cache_path = cache_path.to_owned();
     ehttp::fetch(request, move |response| {
-        let handle = response.map_err(crate::Error::Generic).and_then(|resp| {
-            match cache_type {
-                MediaCacheType::Image => {
-                    let img = parse_img_response(resp, imgtyp);
-                    img.map(|img| {
-                        let texture_handle = load_texture_checked(
-                            &ctx,
-                            &cloned_url,
-                            img.clone(),
-                            Default::default(),
-                        );
-
-                        // write to disk
-                        std::thread::spawn(move || {
-                            MediaCache::write(&cache_path, &cloned_url, img)
-                        });
-
-                        TexturedImage::Static(texture_handle)
-                    })
-                }
-                MediaCacheType::Gif => {
-                    let gif_bytes = resp.bytes;
-                    generate_gif(
-                        ctx.clone(),
-                        cloned_url,
-                        &cache_path,
-                        gif_bytes,
-                        true,
-                        move |img| process_image(imgtyp, img),
-                    )
+        // the result is delivered through the promise, not the job future
+        let _ = decode_pool().schedule(move || {
+            let handle = response.map_err(crate::Error::Generic).and_then(|resp| {
+                match cache_type {
+                    MediaCacheType::Image => {
+                        let img = parse_img_response(resp, imgtyp);
+                        img.map(|img| {
+                            let texture_handle = load_texture_checked(
+                                &ctx,
+                                &cloned_url,
+                                img.clone(),
+                                Default::default(),
+                            );
+
+                            // write to disk
+                            std::thread::spawn(move || {
+                                MediaCache::write(&cache_path, &cloned_url, img)
+                            });
+
+                            TexturedImage::Static(texture_handle)
+                        })
+                    }
+                    MediaCacheType::Gif => {
+                        let gif_bytes = resp.bytes;
+                        generate_gif(
+                            ctx.clone(),
+                            cloned_url,
+                            &cache_path,
+                            gif_bytes,
+                            true,
+                            move |img| process_image(imgtyp, img),
+                        )
+                    }
                 }
-            }
-        });
+            });
 
-        sender.send(Some(handle)); // send the results back to the UI thread.
-        ctx.request_repaint();
+            sender.send(Some(handle)); // send the results back to the UI thread.
+            ctx.request_repaint();
+        });
     });
 
     promise
@@ -479,3 +574,26 @@ pub fn fetch_no_pfp_promise(
         MediaCacheType::Image,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_svg_rejects_pathological_files() {
+        let ok = br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"/>"#;
+        assert!(check_svg(ok).is_ok());
+
+        let entities = br#"<?xml version="1.0"?><!DOCTYPE svg [<!ENTITY a "aaaa">]><svg/>"#;
+        assert!(check_svg(entities).is_err());
+
+        let uses = format!(
+            "<svg>{}</svg>",
+            "<use href=\"#a\"/>".repeat(MAX_SVG_USES + 1)
+        );
+        assert!(check_svg(uses.as_bytes()).is_err());
+
+        let huge = vec![b' '; MAX_SVG_BYTES + 1];
+        assert!(check_svg(&huge).is_err());
+    }
+}
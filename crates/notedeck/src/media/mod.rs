@@ -1,17 +1,22 @@
 pub mod action;
 pub mod blur;
+pub mod download;
 pub mod gif;
 pub mod images;
 pub mod imeta;
+pub mod proxy;
 pub mod renderable;
+pub mod upload;
 
-pub use action::{MediaAction, MediaInfo, ViewMediaInfo};
+pub use action::{MediaAction, MediaInfo, MediaSource, ViewMediaInfo};
 pub use blur::{
-    compute_blurhash, update_imeta_blurhashes, ImageMetadata, ObfuscationType, PixelDimensions,
-    PointDimensions,
+    compute_blurhash, note_content_warning, update_imeta_blurhashes, ImageMetadata,
+    ObfuscationType, PixelDimensions, PointDimensions,
 };
+pub use download::{DownloadProgress, MediaDownload};
 use egui::{ColorImage, TextureHandle};
 pub use images::ImageType;
+pub use proxy::{set_image_proxy, ImageProxy};
 pub use renderable::RenderableMedia;
 
 #[derive(Copy, Clone, Debug)]
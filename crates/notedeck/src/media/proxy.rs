@@ -0,0 +1,96 @@
+use std::sync::RwLock;
+
+/// The configured resize endpoint. This is read from the http callback
+/// threads when media is fetched, so it lives outside of [`crate::Images`].
+static IMAGE_PROXY: RwLock<Option<ImageProxy>> = RwLock::new(None);
+
+/// An imgproxy or Blossom style transform endpoint that serves resized
+/// variants of remote media.
+///
+/// The template may contain `{url}`, replaced with the percent-encoded
+/// original url, and `{width}`, replaced with the requested width. If
+/// there is no `{url}` placeholder the original url is appended.
+///
+/// ```text
+/// https://imgproxy.example.com/insecure/rs:fit:{width}/plain/{url}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageProxy {
+    template: String,
+}
+
+impl ImageProxy {
+    pub fn new(template: &str) -> Option<Self> {
+        let template = template.trim();
+        let parsed = url::Url::parse(&template.replace(['{', '}'], ""));
+        if !parsed.is_ok_and(|u| u.scheme() == "https" || u.scheme() == "http") {
+            return None;
+        }
+
+        Some(Self {
+            template: template.to_owned(),
+        })
+    }
+
+    /// The url to fetch instead of `url`, asking for a variant at most
+    /// `width` pixels wide.
+    pub fn proxied_url(&self, url: &str, width: u32) -> String {
+        let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+        let proxied = self.template.replace("{width}", &width.to_string());
+
+        if proxied.contains("{url}") {
+            proxied.replace("{url}", &encoded)
+        } else {
+            format!("{proxied}{encoded}")
+        }
+    }
+}
+
+/// Set or clear the endpoint used for all subsequent media fetches
+pub fn set_image_proxy(proxy: Option<ImageProxy>) {
+    if let Ok(mut current) = IMAGE_PROXY.write() {
+        *current = proxy;
+    }
+}
+
+/// The url media should actually be fetched from
+pub fn fetch_url(url: &str, width: u32) -> String {
+    // data urls and local files can't go through a proxy
+    if !url.starts_with("http") {
+        return url.to_owned();
+    }
+
+    match IMAGE_PROXY.read().ok().as_deref() {
+        Some(Some(proxy)) => proxy.proxied_url(url, width),
+        _ => url.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxied_url_fills_template() {
+        let proxy =
+            ImageProxy::new("https://imgproxy.example.com/insecure/rs:fit:{width}/plain/{url}")
+                .unwrap();
+        assert_eq!(
+            proxy.proxied_url("https://example.com/a b.jpg", 512),
+            "https://imgproxy.example.com/insecure/rs:fit:512/plain/https%3A%2F%2Fexample.com%2Fa+b.jpg"
+        );
+
+        let appended = ImageProxy::new("https://resize.example.com/?w={width}&src=").unwrap();
+        assert_eq!(
+            appended.proxied_url("https://example.com/a.png", 64),
+            "https://resize.example.com/?w=64&src=https%3A%2F%2Fexample.com%2Fa.png"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        assert!(ImageProxy::new("").is_none());
+        assert!(ImageProxy::new("not a url").is_none());
+        assert!(ImageProxy::new("ftp://example.com/{url}").is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use super::ObfuscationType;
+use super::{ObfuscationType, PixelDimensions};
 use crate::MediaCacheType;
 
 /// Media that is prepared for rendering. Use [`Images::get_renderable_media`] to get these
@@ -6,4 +6,11 @@ pub struct RenderableMedia {
     pub url: String,
     pub media_type: MediaCacheType,
     pub obfuscation_type: ObfuscationType,
+    /// imeta `alt` description, if provided
+    pub alt: Option<String>,
+    /// imeta `dim`, used to size placeholders before the media loads
+    pub dimensions: Option<PixelDimensions>,
+    /// Content warning from the imeta or the note. Media with a warning is
+    /// only shown after a deliberate tap, even from trusted authors.
+    pub content_warning: Option<String>,
 }
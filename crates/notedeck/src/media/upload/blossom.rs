@@ -0,0 +1,116 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use nostrdb::NoteBuilder;
+use serde::Deserialize;
+
+use super::{fetch_blocking, Nip94Event, PreparedMedia, UploadAuth};
+use crate::Error;
+
+/// How long an upload authorization stays valid
+const AUTH_EXPIRATION_SECS: u64 = 5 * 60;
+
+/// BUD-02 blob descriptor returned by `PUT /upload`
+#[derive(Deserialize)]
+struct BlobDescriptor {
+    url: String,
+    sha256: String,
+    #[serde(rename = "type")]
+    mime_type: Option<String>,
+}
+
+/// BUD-01 authorization event for uploading a blob with the given hash, as
+/// the base64 value of the Authorization header
+fn create_upload_auth(auth: &UploadAuth, media: &PreparedMedia) -> Result<String, Error> {
+    let expiration = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+        + AUTH_EXPIRATION_SECS;
+    let expiration = expiration.to_string();
+    let content = format!("Upload {}", media.file_name);
+
+    let note = NoteBuilder::new()
+        .kind(24242)
+        .content(&content)
+        .start_tag()
+        .tag_str("t")
+        .tag_str("upload")
+        .start_tag()
+        .tag_str("x")
+        .tag_str(&media.sha256)
+        .start_tag()
+        .tag_str("expiration")
+        .tag_str(&expiration);
+
+    auth.sign(note).map(|json| BASE64_STANDARD.encode(json))
+}
+
+fn upload_endpoint(server: &str) -> String {
+    format!("{}/upload", server.trim_end_matches('/'))
+}
+
+/// Upload prepared media to a Blossom server. Blocks, so call this from an
+/// upload thread.
+pub(super) fn upload(
+    auth: &UploadAuth,
+    server: &str,
+    media: &PreparedMedia,
+) -> Result<Nip94Event, Error> {
+    let auth = create_upload_auth(auth, media)?;
+
+    let headers = ehttp::Headers::new(&[
+        ("Content-Type", media.mime_type.as_str()),
+        ("Authorization", format!("Nostr {auth}").as_str()),
+    ]);
+
+    let request = ehttp::Request {
+        method: "PUT".to_owned(),
+        url: upload_endpoint(server),
+        headers,
+        body: media.bytes.clone(),
+    };
+
+    let response = fetch_blocking(request)?;
+    if !response.ok {
+        // servers explain rejections in the X-Reason header
+        let reason = response
+            .headers
+            .get("x-reason")
+            .unwrap_or(&response.status_text)
+            .to_owned();
+        return Err(Error::Generic(format!(
+            "Blossom upload failed with code {}: {reason}",
+            response.status
+        )));
+    }
+
+    let blob: BlobDescriptor =
+        serde_json::from_slice(&response.bytes).map_err(|e| Error::Generic(e.to_string()))?;
+
+    if blob.sha256 != media.sha256 {
+        return Err(Error::Generic(
+            "Blossom server returned a different hash than the uploaded file".to_owned(),
+        ));
+    }
+
+    let mut event = Nip94Event::from_url(blob.url);
+    event.x = Some(blob.sha256);
+    event.media_type = blob.mime_type;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::upload_endpoint;
+
+    #[test]
+    fn upload_endpoint_handles_trailing_slash() {
+        assert_eq!(
+            upload_endpoint("https://blossom.example.com/"),
+            "https://blossom.example.com/upload"
+        );
+        assert_eq!(
+            upload_endpoint("https://blossom.example.com"),
+            "https://blossom.example.com/upload"
+        );
+    }
+}
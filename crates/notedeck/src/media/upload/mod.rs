@@ -0,0 +1,370 @@
+//! Uploading media to Blossom and NIP-96 servers.
+//!
+//! Apps start uploads through the [`MediaUploader`] in the app context so
+//! every upload gets the same server choice, auth and `imeta` fields.
+
+mod blossom;
+mod nip94;
+mod nip96;
+mod prepare;
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    time::Duration,
+};
+
+use enostr::{FullKeypair, Pubkey, RelayPool};
+use nostrdb::NoteBuilder;
+use poll_promise::Promise;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::signer::unsigned_json, platform::file::SelectedMedia, Accounts, Error, SettingsHandler,
+};
+
+pub use nip94::Nip94Event;
+pub use prepare::PreparedMedia;
+
+/// Where an account uploads its media
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "url", rename_all = "lowercase")]
+pub enum UploadServer {
+    /// A NIP-96 provider, the api url is looked up from its well-known file
+    Nip96(String),
+    /// A Blossom server
+    Blossom(String),
+}
+
+impl Default for UploadServer {
+    fn default() -> Self {
+        UploadServer::Nip96("https://nostr.build".to_owned())
+    }
+}
+
+impl UploadServer {
+    pub fn url(&self) -> &str {
+        match self {
+            UploadServer::Nip96(url) | UploadServer::Blossom(url) => url,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadOptions {
+    /// Re-encode photos so camera metadata like location isn't uploaded
+    pub strip_exif: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum UploadStage {
+    Preparing,
+    Authorizing,
+    Uploading,
+    Done,
+    Failed,
+}
+
+impl UploadStage {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => UploadStage::Preparing,
+            1 => UploadStage::Authorizing,
+            2 => UploadStage::Uploading,
+            3 => UploadStage::Done,
+            _ => UploadStage::Failed,
+        }
+    }
+}
+
+/// Shared view of how far along an upload is. The http client doesn't
+/// report bytes sent, so progress moves in stages.
+#[derive(Debug, Clone)]
+pub struct UploadProgress(Arc<AtomicU8>);
+
+impl Default for UploadProgress {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU8::new(UploadStage::Preparing as u8)))
+    }
+}
+
+impl UploadProgress {
+    pub fn stage(&self) -> UploadStage {
+        UploadStage::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Rough completion between 0 and 1, for progress bars
+    pub fn fraction(&self) -> f32 {
+        match self.stage() {
+            UploadStage::Preparing => 0.1,
+            UploadStage::Authorizing => 0.25,
+            UploadStage::Uploading => 0.5,
+            UploadStage::Done | UploadStage::Failed => 1.0,
+        }
+    }
+
+    fn set(&self, stage: UploadStage) {
+        self.0.store(stage as u8, Ordering::Relaxed);
+    }
+}
+
+/// An upload in flight
+pub struct MediaUpload {
+    pub file_name: String,
+    pub progress: UploadProgress,
+    pub promise: Promise<Result<Nip94Event, Error>>,
+}
+
+/// How long to wait for a remote signer to sign an upload authorization,
+/// the user may have to approve it first
+const REMOTE_AUTH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Signs the authorization events servers want with uploads
+enum UploadAuth {
+    /// The account's own secret key
+    Local([u8; 32]),
+    /// The account's remote signer, reached through the ui thread
+    Remote {
+        account: Pubkey,
+        requests: Sender<AuthRequest>,
+    },
+}
+
+impl UploadAuth {
+    /// Sign an auth event and return its json. Blocks until a remote
+    /// signer answers.
+    fn sign(&self, note: NoteBuilder) -> Result<String, Error> {
+        match self {
+            UploadAuth::Local(seckey) => note
+                .sign(seckey)
+                .build()
+                .ok_or_else(|| Error::Generic("could not build upload auth".to_owned()))?
+                .json()
+                .map_err(|e| Error::Generic(e.to_string())),
+
+            UploadAuth::Remote { account, requests } => {
+                // the signer replaces the key, this only gets us the tags
+                // and timestamp
+                let throwaway = FullKeypair::generate();
+                let note = note
+                    .sign(&throwaway.secret_key.secret_bytes())
+                    .build()
+                    .ok_or_else(|| Error::Generic("could not build upload auth".to_owned()))?;
+                let unsigned = unsigned_json(&note).map_err(|e| Error::Generic(e.to_string()))?;
+
+                let (reply, response) = mpsc::channel();
+                requests
+                    .send(AuthRequest {
+                        account: *account,
+                        kind: note.kind(),
+                        unsigned,
+                        reply,
+                    })
+                    .map_err(|_| Error::Generic("the uploader has shut down".to_owned()))?;
+
+                response
+                    .recv_timeout(REMOTE_AUTH_TIMEOUT)
+                    .map_err(|_| {
+                        Error::Generic("the remote signer didn't sign the upload".to_owned())
+                    })?
+                    .map_err(|e| Error::Generic(format!("remote signer: {e}")))
+            }
+        }
+    }
+}
+
+/// An upload authorization waiting for the account's remote signer
+struct AuthRequest {
+    account: Pubkey,
+    kind: u32,
+    unsigned: String,
+    reply: Sender<Result<String, String>>,
+}
+
+/// An upload waiting for [`MediaUploader::update`] to start it
+struct QueuedUpload {
+    account: Pubkey,
+    media: SelectedMedia,
+    progress: UploadProgress,
+    sender: poll_promise::Sender<Result<Nip94Event, Error>>,
+}
+
+/// Uploads media for any app. The server and options are read from the
+/// settings when the upload starts, so views only have to say which
+/// account uploads.
+pub struct MediaUploader {
+    /// Uploads asked for this frame. The views asking can't reach the
+    /// settings or signers, so they're started in [`MediaUploader::update`].
+    queued: RefCell<Vec<QueuedUpload>>,
+    auth_sender: Sender<AuthRequest>,
+    auth_requests: Receiver<AuthRequest>,
+}
+
+impl Default for MediaUploader {
+    fn default() -> Self {
+        let (auth_sender, auth_requests) = mpsc::channel();
+        Self {
+            queued: RefCell::new(Vec::new()),
+            auth_sender,
+            auth_requests,
+        }
+    }
+}
+
+impl MediaUploader {
+    /// Upload media as `account`. It starts at the end of the frame.
+    pub fn upload(&self, account: &Pubkey, media: SelectedMedia) -> MediaUpload {
+        let file_name = media.file_name.clone();
+        let progress = UploadProgress::default();
+        let (sender, promise) = Promise::new();
+
+        self.queued.borrow_mut().push(QueuedUpload {
+            account: *account,
+            media,
+            progress: progress.clone(),
+            sender,
+        });
+
+        MediaUpload {
+            file_name,
+            progress,
+            promise,
+        }
+    }
+
+    /// Start queued uploads and pass their authorizations to remote
+    /// signers. Call once per frame, after the apps ran.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        settings: &SettingsHandler,
+        accounts: &mut Accounts,
+        pool: &mut RelayPool,
+    ) {
+        for upload in self.queued.get_mut().drain(..) {
+            start_upload(ctx, settings, accounts, &self.auth_sender, upload);
+        }
+
+        // only the selected account's signer is listened to
+        let selected = *accounts.selected_account_pubkey();
+        while let Ok(request) = self.auth_requests.try_recv() {
+            match accounts.selected_signer_mut() {
+                Some(signer) if request.account == selected => {
+                    signer.sign_for(request.unsigned, request.kind, pool, request.reply);
+                }
+                _ => {
+                    let _ = request
+                        .reply
+                        .send(Err("switch to the account to sign its upload".to_owned()));
+                }
+            }
+        }
+    }
+}
+
+fn start_upload(
+    ctx: &egui::Context,
+    settings: &SettingsHandler,
+    accounts: &Accounts,
+    auth_sender: &Sender<AuthRequest>,
+    upload: QueuedUpload,
+) {
+    let QueuedUpload {
+        account,
+        media,
+        progress,
+        sender,
+    } = upload;
+
+    let has_signer = accounts
+        .cache
+        .get(&account)
+        .is_some_and(|acc| acc.signer.is_some());
+    let auth = match accounts.get_full(&account) {
+        Some(kp) => UploadAuth::Local(kp.secret_key.secret_bytes()),
+        None if has_signer => UploadAuth::Remote {
+            account,
+            requests: auth_sender.clone(),
+        },
+        None => {
+            progress.set(UploadStage::Failed);
+            sender.send(Err(Error::Generic(
+                "this account can't sign uploads".to_owned(),
+            )));
+            return;
+        }
+    };
+
+    let server = settings.upload_server(&account);
+    let options = UploadOptions {
+        strip_exif: settings.strip_exif(),
+    };
+
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        let result = upload_blocking(&auth, &server, media, options, &progress);
+
+        if let Err(e) = &result {
+            tracing::error!("upload to {} failed: {e}", server.url());
+        }
+
+        sender.send(result);
+        ctx.request_repaint();
+    });
+}
+
+fn upload_blocking(
+    auth: &UploadAuth,
+    server: &UploadServer,
+    media: SelectedMedia,
+    options: UploadOptions,
+    progress: &UploadProgress,
+) -> Result<Nip94Event, Error> {
+    let result = (|| {
+        progress.set(UploadStage::Preparing);
+        let prepared = PreparedMedia::prepare(media, options)?;
+
+        let mut event = match server {
+            UploadServer::Nip96(provider) => {
+                progress.set(UploadStage::Authorizing);
+                let upload_url = nip96::resolve_upload_url(provider)?;
+                progress.set(UploadStage::Uploading);
+                nip96::upload(auth, &upload_url, &prepared)?
+            }
+            UploadServer::Blossom(url) => {
+                progress.set(UploadStage::Uploading);
+                blossom::upload(auth, url, &prepared)?
+            }
+        };
+
+        // fill in whatever the server didn't tell us
+        if event.dimensions.is_none() {
+            event.dimensions = prepared.dimensions;
+        }
+        if event.blurhash.is_none() {
+            event.blurhash = prepared.blurhash;
+        }
+        if event.media_type.is_none() {
+            event.media_type = Some(prepared.mime_type);
+        }
+
+        Ok(event)
+    })();
+
+    progress.set(if result.is_ok() {
+        UploadStage::Done
+    } else {
+        UploadStage::Failed
+    });
+
+    result
+}
+
+fn fetch_blocking(request: ehttp::Request) -> Result<ehttp::Response, Error> {
+    ehttp::fetch_blocking(&request).map_err(Error::Generic)
+}
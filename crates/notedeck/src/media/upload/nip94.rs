@@ -0,0 +1,87 @@
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Nip94Event {
+    pub url: String,
+    pub ox: Option<String>,
+    pub x: Option<String>,
+    pub media_type: Option<String>,
+    pub dimensions: Option<(u32, u32)>,
+    pub blurhash: Option<String>,
+    pub thumb: Option<String>,
+    pub content: String,
+}
+
+impl Nip94Event {
+    pub fn new(url: String, width: u32, height: u32) -> Self {
+        Self {
+            dimensions: Some((width, height)),
+            ..Self::from_url(url)
+        }
+    }
+
+    /// An event that only knows the url so far
+    pub fn from_url(url: String) -> Self {
+        Self {
+            url,
+            ox: None,
+            x: None,
+            media_type: None,
+            dimensions: None,
+            blurhash: None,
+            thumb: None,
+            content: String::new(),
+        }
+    }
+}
+
+const URL: &str = "url";
+const OX: &str = "ox";
+const X: &str = "x";
+const M: &str = "m";
+const DIM: &str = "dim";
+const BLURHASH: &str = "blurhash";
+const THUMB: &str = "thumb";
+
+impl Nip94Event {
+    pub(crate) fn from_tags_and_content(
+        tags: Vec<Vec<String>>,
+        content: String,
+    ) -> Result<Self, &'static str> {
+        let mut url = None;
+        let mut ox = None;
+        let mut x = None;
+        let mut media_type = None;
+        let mut dimensions = None;
+        let mut blurhash = None;
+        let mut thumb = None;
+
+        for tag in tags {
+            match tag.as_slice() {
+                [key, value] if key == URL => url = Some(value.to_string()),
+                [key, value] if key == OX => ox = Some(value.to_string()),
+                [key, value] if key == X => x = Some(value.to_string()),
+                [key, value] if key == M => media_type = Some(value.to_string()),
+                [key, value] if key == DIM => {
+                    if let Some((w, h)) = value.split_once('x') {
+                        if let (Ok(w), Ok(h)) = (w.parse::<u32>(), h.parse::<u32>()) {
+                            dimensions = Some((w, h));
+                        }
+                    }
+                }
+                [key, value] if key == BLURHASH => blurhash = Some(value.to_string()),
+                [key, value] if key == THUMB => thumb = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            url: url.ok_or("Missing url")?,
+            ox,
+            x,
+            media_type,
+            dimensions,
+            blurhash,
+            thumb,
+            content,
+        })
+    }
+}
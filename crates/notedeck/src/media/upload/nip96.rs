@@ -0,0 +1,221 @@
+#![cfg_attr(target_os = "android", allow(dead_code, unused_variables))]
+
+use base64::{prelude::BASE64_URL_SAFE, Engine};
+use ehttp::Request;
+use nostrdb::NoteBuilder;
+use poll_promise::Promise;
+use url::Url;
+
+use super::{fetch_blocking, Nip94Event, PreparedMedia, UploadAuth};
+use crate::Error;
+
+const NIP96_WELL_KNOWN: &str = ".well-known/nostr/nip96.json";
+
+fn get_upload_url(nip96_url: Url) -> Promise<Result<String, Error>> {
+    let request = Request::get(nip96_url);
+    let (sender, promise) = Promise::new();
+
+    ehttp::fetch(request, move |response| {
+        let result = match response {
+            Ok(resp) => {
+                if resp.status == 200 {
+                    if let Some(text) = resp.text() {
+                        get_api_url_from_json(text)
+                    } else {
+                        Err(Error::Generic(
+                            "ehttp::Response payload is not text".to_owned(),
+                        ))
+                    }
+                } else {
+                    Err(Error::Generic(format!(
+                        "ehttp::Response status: {}",
+                        resp.status
+                    )))
+                }
+            }
+            Err(e) => Err(Error::Generic(e)),
+        };
+
+        sender.send(result);
+    });
+
+    promise
+}
+
+fn get_api_url_from_json(json: &str) -> Result<String, Error> {
+    match serde_json::from_str::<serde_json::Value>(json) {
+        Ok(json) => {
+            if let Some(url) = json
+                .get("api_url")
+                .and_then(|url| url.as_str())
+                .map(|url| url.to_string())
+            {
+                Ok(url)
+            } else {
+                Err(Error::Generic(
+                    "api_url key not found in ehttp::Response".to_owned(),
+                ))
+            }
+        }
+        Err(e) => Err(Error::Generic(e.to_string())),
+    }
+}
+
+fn get_upload_url_from_provider(mut provider_url: Url) -> Promise<Result<String, Error>> {
+    provider_url.set_path(NIP96_WELL_KNOWN);
+    get_upload_url(provider_url)
+}
+
+/// NIP-98 authorization event for the upload, as json
+fn create_nip98_auth(
+    auth: &UploadAuth,
+    upload_url: &str,
+    payload_hash: &str,
+) -> Result<String, Error> {
+    let note = NoteBuilder::new()
+        .kind(27235)
+        .start_tag()
+        .tag_str("u")
+        .tag_str(upload_url)
+        .start_tag()
+        .tag_str("method")
+        .tag_str("POST")
+        .start_tag()
+        .tag_str("payload")
+        .tag_str(payload_hash);
+
+    auth.sign(note)
+}
+
+fn create_nip96_request(
+    upload_url: &str,
+    file_name: &str,
+    media_type: &str,
+    file_contents: &[u8],
+    nip98_base64: &str,
+) -> ehttp::Request {
+    let boundary = "----boundary";
+
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: {media_type}\r\n\r\n",
+    )
+    .into_bytes();
+    body.extend_from_slice(file_contents);
+    body.extend(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let headers = ehttp::Headers::new(&[
+        (
+            "Content-Type",
+            format!("multipart/form-data; boundary={boundary}").as_str(),
+        ),
+        ("Authorization", format!("Nostr {nip98_base64}").as_str()),
+    ]);
+
+    Request {
+        method: "POST".to_string(),
+        url: upload_url.to_string(),
+        headers,
+        body,
+    }
+}
+
+/// Look up the api url of a NIP-96 provider. Blocks, so call this from an
+/// upload thread.
+pub(super) fn resolve_upload_url(provider: &str) -> Result<String, Error> {
+    let provider_url = Url::parse(provider).map_err(|e| Error::Generic(e.to_string()))?;
+    get_upload_url_from_provider(provider_url)
+        .block_and_take()
+        .map_err(|e| Error::Generic(format!("could not get upload url from {provider}: {e}")))
+}
+
+/// Upload prepared media to a NIP-96 api url with NIP-98 auth. Blocks, so
+/// call this from an upload thread.
+pub(super) fn upload(
+    auth: &UploadAuth,
+    upload_url: &str,
+    media: &PreparedMedia,
+) -> Result<Nip94Event, Error> {
+    let nip98_json = create_nip98_auth(auth, upload_url, &media.sha256)?;
+    let nip98_base64 = BASE64_URL_SAFE.encode(nip98_json);
+
+    let request = create_nip96_request(
+        upload_url,
+        &media.file_name,
+        &media.mime_type,
+        &media.bytes,
+        &nip98_base64,
+    );
+
+    let response = fetch_blocking(request)?;
+    if !response.ok {
+        return Err(Error::Generic(format!(
+            "ehttp Response was unsuccessful. Code {} with message: {}",
+            response.status, response.status_text
+        )));
+    }
+
+    match String::from_utf8(response.bytes) {
+        Ok(str_response) => find_nip94_ev_in_json(str_response),
+        Err(e) => Err(Error::Generic(e.to_string())),
+    }
+}
+
+fn find_nip94_ev_in_json(json: String) -> Result<Nip94Event, Error> {
+    match serde_json::from_str::<serde_json::Value>(&json) {
+        Ok(v) => {
+            let tags = v["nip94_event"]["tags"].clone();
+            let content = v["nip94_event"]["content"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            match serde_json::from_value::<Vec<Vec<String>>>(tags) {
+                Ok(tags) => Nip94Event::from_tags_and_content(tags, content)
+                    .map_err(|e| Error::Generic(e.to_owned())),
+                Err(e) => Err(Error::Generic(e.to_string())),
+            }
+        }
+        Err(e) => Err(Error::Generic(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use enostr::FullKeypair;
+
+    use super::{get_upload_url_from_provider, upload};
+    use crate::media::upload::{PreparedMedia, UploadAuth, UploadOptions, UploadServer};
+    use crate::platform::file::SelectedMedia;
+
+    #[test]
+    fn test_nostrbuild_upload_url() {
+        let UploadServer::Nip96(provider) = UploadServer::default() else {
+            panic!("default server should be nip96");
+        };
+        let promise = get_upload_url_from_provider(url::Url::parse(&provider).unwrap());
+
+        let url = promise.block_until_ready();
+
+        assert!(url.is_ok());
+    }
+
+    #[test]
+    #[ignore] // this test should not run automatically since it sends data to a real server
+    fn test_nip96_upload() {
+        // just a random image to test image upload
+        let file_path = PathBuf::from_str("../../assets/damus_rounded_80.png").unwrap();
+        let selected_media = SelectedMedia::from_path(file_path).unwrap();
+        let media = PreparedMedia::prepare(selected_media, UploadOptions::default()).unwrap();
+        let kp = FullKeypair::generate();
+        println!("Using pubkey: {:?}", kp.pubkey);
+
+        let UploadServer::Nip96(provider) = UploadServer::default() else {
+            panic!("default server should be nip96");
+        };
+        let upload_url = super::resolve_upload_url(&provider).unwrap();
+        let auth = UploadAuth::Local(kp.secret_key.secret_bytes());
+        let res = upload(&auth, &upload_url, &media);
+        assert!(res.is_ok())
+    }
+}
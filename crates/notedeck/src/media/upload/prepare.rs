@@ -0,0 +1,185 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::jpeg::JpegEncoder, metadata::Orientation, DynamicImage, ImageDecoder, ImageFormat,
+    ImageReader,
+};
+use sha2::{Digest, Sha256};
+
+use super::UploadOptions;
+use crate::{
+    media::images::fetch_binary_from_disk,
+    platform::file::{MediaFrom, SelectedMedia},
+    Error,
+};
+
+/// Quality used when re-encoding jpegs to drop their metadata
+const JPEG_QUALITY: u8 = 90;
+
+/// Media read from disk or memory and ready to upload, along with the
+/// `imeta` fields we can work out locally
+pub struct PreparedMedia {
+    pub file_name: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+    /// hex sha256 of `bytes`
+    pub sha256: String,
+    /// Unknown for video and formats we can't read
+    pub dimensions: Option<(u32, u32)>,
+    /// Only computed for images we decode to strip their metadata
+    pub blurhash: Option<String>,
+}
+
+impl PreparedMedia {
+    pub fn prepare(selected: SelectedMedia, options: UploadOptions) -> Result<Self, Error> {
+        let mime_type = selected.media_type.to_mime().to_owned();
+        let mut bytes = bytes_from_media(selected.from)
+            .map_err(|e| Error::Generic(format!("could not read file to upload: {e}")))?;
+
+        let format = image::guess_format(&bytes).ok();
+
+        // only decode what has to be re-encoded. Video and formats the
+        // image crate can't read, like HEIC, are uploaded as they are.
+        let (dimensions, blurhash) = if options.strip_exif && carries_exif(format) {
+            let image = decode_oriented(&bytes)?;
+            bytes = strip_metadata(&image, format)?;
+            (
+                Some((image.width(), image.height())),
+                compute_upload_blurhash(&image),
+            )
+        } else {
+            (read_dimensions(&bytes), None)
+        };
+
+        Ok(Self {
+            file_name: selected.file_name,
+            mime_type,
+            sha256: sha256_hex(&bytes),
+            dimensions,
+            blurhash,
+            bytes,
+        })
+    }
+}
+
+fn bytes_from_media(media: MediaFrom) -> Result<Vec<u8>, Error> {
+    match media {
+        MediaFrom::PathBuf(full_path) => fetch_binary_from_disk(full_path),
+        MediaFrom::Memory(bytes) => Ok(bytes),
+    }
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(contents);
+    hex::encode(hasher.finalize())
+}
+
+/// Decode the first frame, rotated the way the camera intended
+fn decode_oriented(bytes: &[u8]) -> Result<DynamicImage, Error> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Size as shown, read from the header without decoding the pixels
+fn read_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    let (width, height) = decoder.dimensions();
+    let rotated = matches!(
+        decoder.orientation().ok()?,
+        Orientation::Rotate90
+            | Orientation::Rotate270
+            | Orientation::Rotate90FlipH
+            | Orientation::Rotate270FlipH
+    );
+    Some(if rotated {
+        (height, width)
+    } else {
+        (width, height)
+    })
+}
+
+/// Formats that commonly carry EXIF, like camera location
+fn carries_exif(format: Option<ImageFormat>) -> bool {
+    matches!(format, Some(ImageFormat::Jpeg | ImageFormat::Png))
+}
+
+/// Re-encode an image from [`carries_exif`] so its metadata isn't
+/// published with the file
+fn strip_metadata(image: &DynamicImage, format: Option<ImageFormat>) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    if format == Some(ImageFormat::Jpeg) {
+        // jpeg has no alpha channel
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))?;
+    } else {
+        image.write_to(&mut Cursor::new(&mut out), ImageFormat::Png)?;
+    }
+
+    Ok(out)
+}
+
+fn compute_upload_blurhash(image: &DynamicImage) -> Option<String> {
+    // blurhash only needs a handful of pixels
+    let thumb = image.thumbnail(64, 64).to_rgba8();
+    blurhash::encode(4, 3, thumb.width(), thumb.height(), thumb.as_raw())
+        .inspect_err(|e| tracing::error!("could not compute blurhash for upload: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SupportedMimeType;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::new_rgba8(width, height);
+        let mut out = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut out), ImageFormat::Png)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn prepare_fills_imeta_fields() {
+        let selected = SelectedMedia {
+            from: MediaFrom::Memory(png_bytes(30, 20)),
+            file_name: "test.png".to_owned(),
+            media_type: SupportedMimeType::from_extension("png").unwrap(),
+        };
+
+        let options = UploadOptions { strip_exif: true };
+        let prepared = PreparedMedia::prepare(selected, options).unwrap();
+
+        assert_eq!(prepared.dimensions, Some((30, 20)));
+        assert_eq!(prepared.mime_type, "image/png");
+        assert_eq!(prepared.sha256, sha256_hex(&prepared.bytes));
+        assert!(prepared.blurhash.is_some());
+    }
+
+    #[test]
+    fn prepare_uploads_undecodable_media_as_is() {
+        let bytes = b"\x00\x00\x00\x18ftypmp42 not an image".to_vec();
+        let selected = SelectedMedia {
+            from: MediaFrom::Memory(bytes.clone()),
+            file_name: "test.png".to_owned(),
+            media_type: SupportedMimeType::from_extension("png").unwrap(),
+        };
+
+        let options = UploadOptions { strip_exif: true };
+        let prepared = PreparedMedia::prepare(selected, options).unwrap();
+
+        assert_eq!(prepared.bytes, bytes);
+        assert_eq!(prepared.dimensions, None);
+    }
+}
@@ -0,0 +1,90 @@
+//! Reduced motion. While on, animations finish right away and gifs show
+//! their first frame. It's on when the user turns it on in settings or
+//! the OS accessibility settings ask for it.
+
+use std::sync::OnceLock;
+
+const REDUCE_MOTION_KEY: &str = "notedeck_reduce_motion";
+
+/// Turn reduced motion on or off from the user's setting, also honoring
+/// the OS preference
+pub fn apply(ctx: &egui::Context, setting: bool) {
+    set_reduce_motion(ctx, setting || os_prefers_reduced_motion());
+}
+
+pub fn set_reduce_motion(ctx: &egui::Context, reduce: bool) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(REDUCE_MOTION_KEY), reduce));
+
+    // egui's own animations, like collapsing headers and scroll bars
+    let animation_time = if reduce {
+        0.0
+    } else {
+        egui::Style::default().animation_time
+    };
+    ctx.all_styles_mut(|style| style.animation_time = animation_time);
+}
+
+pub fn reduce_motion(ctx: &egui::Context) -> bool {
+    ctx.data(|d| d.get_temp(egui::Id::new(REDUCE_MOTION_KEY)))
+        .unwrap_or(false)
+}
+
+/// How long an animation should take, none at all with reduced motion
+pub fn anim_time(ctx: &egui::Context, time: f32) -> f32 {
+    if reduce_motion(ctx) {
+        0.0
+    } else {
+        time
+    }
+}
+
+/// Did the user ask their OS to reduce motion? Only known on desktops
+/// that expose it, checked once.
+pub fn os_prefers_reduced_motion() -> bool {
+    static PREFERS: OnceLock<bool> = OnceLock::new();
+    *PREFERS.get_or_init(detect_os_preference)
+}
+
+#[cfg(target_os = "linux")]
+fn detect_os_preference() -> bool {
+    // GNOME and most GTK desktops
+    std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "enable-animations"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .is_some_and(|out| String::from_utf8_lossy(&out.stdout).trim() == "false")
+}
+
+#[cfg(target_os = "macos")]
+fn detect_os_preference() -> bool {
+    std::process::Command::new("defaults")
+        .args(["read", "com.apple.universalaccess", "reduceMotion"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .is_some_and(|out| String::from_utf8_lossy(&out.stdout).trim() == "1")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn detect_os_preference() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_motion_skips_animations() {
+        let ctx = egui::Context::default();
+        assert_eq!(anim_time(&ctx, 0.3), 0.3);
+
+        set_reduce_motion(&ctx, true);
+        assert_eq!(anim_time(&ctx, 0.3), 0.0);
+        assert_eq!(ctx.style().animation_time, 0.0);
+
+        set_reduce_motion(&ctx, false);
+        assert!(ctx.style().animation_time > 0.0);
+    }
+}
@@ -1,3 +1,4 @@
+use enostr::{NoteId, Pubkey};
 use nostrdb::Note;
 use std::collections::BTreeSet;
 
@@ -6,7 +7,16 @@ use std::collections::BTreeSet;
 // If the note is muted return a reason string, otherwise None
 pub type MuteFun = dyn Fn(&Note, &[u8; 32]) -> bool;
 
-#[derive(Default)]
+/// Something that can be on a NIP-51 mute list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MuteItem {
+    Pubkey(Pubkey),
+    Hashtag(String),
+    Word(String),
+    Thread(NoteId),
+}
+
+#[derive(Default, Clone)]
 pub struct Muted {
     // TODO - implement private mutes
     pub pubkeys: BTreeSet<[u8; 32]>,
@@ -53,19 +63,14 @@ impl Muted {
             */
             return true;
         }
-        // FIXME - Implement hashtag muting here
-
-        // TODO - let's not add this for now, we will likely need to
-        // have an optimized data structure in nostrdb to properly
-        // mute words. this mutes substrings which is not ideal.
-        //
-        // let content = note.content().to_lowercase();
-        // for word in &self.words {
-        //     if content.contains(&word.to_lowercase()) {
-        //         debug!("{}: MUTED word: {}", hex::encode(note.id()), word);
-        //         return Some(format!("muted word {}", word));
-        //     }
-        // }
+
+        if !self.hashtags.is_empty() && self.has_muted_hashtag(note) {
+            return true;
+        }
+
+        if !self.words.is_empty() && self.has_muted_word(note.content()) {
+            return true;
+        }
 
         if self.threads.contains(thread) {
             /*
@@ -84,4 +89,104 @@ impl Muted {
     pub fn is_pk_muted(&self, pk: &[u8; 32]) -> bool {
         self.pubkeys.contains(pk)
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.pubkeys.is_empty()
+            && self.hashtags.is_empty()
+            && self.words.is_empty()
+            && self.threads.is_empty()
+    }
+
+    pub fn contains(&self, item: &MuteItem) -> bool {
+        match item {
+            MuteItem::Pubkey(pk) => self.pubkeys.contains(pk.bytes()),
+            MuteItem::Hashtag(tag) => self.hashtags.contains(&normalize_hashtag(tag)),
+            MuteItem::Word(word) => self.words.contains(&word.to_lowercase()),
+            MuteItem::Thread(id) => self.threads.contains(id.bytes()),
+        }
+    }
+
+    /// Returns false if the item was already muted
+    pub fn add(&mut self, item: MuteItem) -> bool {
+        match item {
+            MuteItem::Pubkey(pk) => self.pubkeys.insert(*pk.bytes()),
+            MuteItem::Hashtag(tag) => self.hashtags.insert(normalize_hashtag(&tag)),
+            MuteItem::Word(word) => self.words.insert(word.to_lowercase()),
+            MuteItem::Thread(id) => self.threads.insert(*id.bytes()),
+        }
+    }
+
+    /// Returns false if the item wasn't muted
+    pub fn remove(&mut self, item: &MuteItem) -> bool {
+        match item {
+            MuteItem::Pubkey(pk) => self.pubkeys.remove(pk.bytes()),
+            MuteItem::Hashtag(tag) => self.hashtags.remove(&normalize_hashtag(tag)),
+            MuteItem::Word(word) => self.words.remove(&word.to_lowercase()),
+            MuteItem::Thread(id) => self.threads.remove(id.bytes()),
+        }
+    }
+
+    fn has_muted_hashtag(&self, note: &Note) -> bool {
+        note.tags().iter().any(|tag| {
+            tag.count() >= 2
+                && tag.get_str(0) == Some("t")
+                && tag
+                    .get_str(1)
+                    .is_some_and(|t| self.hashtags.contains(&normalize_hashtag(t)))
+        })
+    }
+
+    /// Whole words only, so muting "art" doesn't hide notes about parties
+    fn has_muted_word(&self, content: &str) -> bool {
+        let content = content.to_lowercase();
+        self.words.iter().any(|word| {
+            content.match_indices(word.as_str()).any(|(start, _)| {
+                let end = start + word.len();
+                let before = content[..start].chars().next_back();
+                let after = content[end..].chars().next();
+                !before.is_some_and(char::is_alphanumeric)
+                    && !after.is_some_and(char::is_alphanumeric)
+            })
+        })
+    }
+}
+
+/// Hashtags are muted without the `#` and regardless of case
+fn normalize_hashtag(tag: &str) -> String {
+    tag.trim_start_matches('#').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_match_whole_words() {
+        let mut muted = Muted::default();
+        muted.add(MuteItem::Word("Art".to_owned()));
+
+        assert!(muted.has_muted_word("I love art!"));
+        assert!(muted.has_muted_word("ART, mostly"));
+        assert!(!muted.has_muted_word("let's party"));
+        assert!(!muted.has_muted_word("artsy"));
+    }
+
+    #[test]
+    fn add_and_remove_items() {
+        let mut muted = Muted::default();
+        assert!(muted.is_empty());
+
+        assert!(muted.add(MuteItem::Hashtag("#Nostr".to_owned())));
+        assert!(!muted.add(MuteItem::Hashtag("nostr".to_owned())));
+        assert!(muted.contains(&MuteItem::Hashtag("NOSTR".to_owned())));
+
+        let pk = Pubkey::new([1; 32]);
+        assert!(muted.add(MuteItem::Pubkey(pk)));
+        assert!(muted.is_pk_muted(pk.bytes()));
+
+        assert!(muted.remove(&MuteItem::Hashtag("nostr".to_owned())));
+        assert!(muted.remove(&MuteItem::Pubkey(pk)));
+        assert!(!muted.remove(&MuteItem::Thread(NoteId::new([2; 32]))));
+        assert!(muted.is_empty());
+    }
 }
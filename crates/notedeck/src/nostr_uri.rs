@@ -0,0 +1,118 @@
+//! `nostr:` links (NIP-21). The OS hands them to us on the command line
+//! or through the android activity, chrome picks them up with
+//! [`get_next_opened_uri`] and routes them to the right view.
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use enostr::{NoteId, Pubkey};
+use nostr::nips::nip19::{FromBech32, Nip19};
+use once_cell::sync::Lazy;
+
+/// What a `nostr:` link points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NostrUri {
+    /// npub or nprofile
+    Profile(Pubkey),
+    /// note or nevent
+    Note(NoteId),
+    /// naddr, an addressable event like an article or a calendar event
+    Address {
+        kind: u32,
+        author: Pubkey,
+        identifier: String,
+    },
+}
+
+impl NostrUri {
+    /// Parse a `nostr:` uri, or a bare npub, nprofile, note, nevent or
+    /// naddr
+    pub fn parse(uri: &str) -> Option<Self> {
+        let uri = uri.trim();
+        let bech = uri.strip_prefix("nostr:").unwrap_or(uri);
+        let bech = bech.trim_start_matches("//");
+
+        match Nip19::from_bech32(bech).ok()? {
+            Nip19::Pubkey(pk) => Some(NostrUri::Profile(Pubkey::new(pk.to_bytes()))),
+            Nip19::Profile(profile) => Some(NostrUri::Profile(Pubkey::new(
+                profile.public_key.to_bytes(),
+            ))),
+            Nip19::EventId(id) => Some(NostrUri::Note(NoteId::new(id.to_bytes()))),
+            Nip19::Event(event) => Some(NostrUri::Note(NoteId::new(event.event_id.to_bytes()))),
+            Nip19::Coordinate(coordinate) => Some(NostrUri::Address {
+                kind: coordinate.kind.as_u16() as u32,
+                author: Pubkey::new(coordinate.public_key.to_bytes()),
+                identifier: coordinate.identifier,
+            }),
+            // keys aren't something to open
+            _ => None,
+        }
+    }
+}
+
+struct OpenedUriChannel {
+    sender: Sender<NostrUri>,
+    receiver: Receiver<NostrUri>,
+}
+
+impl Default for OpenedUriChannel {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+        Self { sender, receiver }
+    }
+}
+
+static OPENED_URI_CHANNEL: Lazy<OpenedUriChannel> = Lazy::new(OpenedUriChannel::default);
+
+/// Called when the OS asks us to open a `nostr:` link
+pub fn emit_uri_opened(uri: NostrUri) {
+    let _ = OPENED_URI_CHANNEL.sender.send(uri);
+}
+
+/// The next `nostr:` link the user wants to see
+pub fn get_next_opened_uri() -> Option<NostrUri> {
+    OPENED_URI_CHANNEL.receiver.try_recv().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bech32::{Bech32, Hrp};
+
+    #[test]
+    fn parses_nip19_entities() {
+        let pk =
+            Pubkey::from_hex("3bf0c63fcb93463407af97a5e5ee64fa883d107ef9e558472c4eb9aaaefa459d")
+                .unwrap();
+
+        // from the NIP-19 examples
+        assert_eq!(
+            NostrUri::parse("nostr:nprofile1qqsrhuxx8l9ex335q7he0f09aej04zpazpl0ne2cgukyawd24mayt8gpp4mhxue69uhhytnc9e3k7mgpz4mhxue69uhkg6nzv9ejuumpv34kytnrdaksjlyr9p"),
+            Some(NostrUri::Profile(pk))
+        );
+        assert_eq!(
+            NostrUri::parse(&pk.npub().unwrap()),
+            Some(NostrUri::Profile(pk))
+        );
+
+        let mut tlv = vec![0, 3];
+        tlv.extend_from_slice(b"abc");
+        tlv.extend_from_slice(&[1, 20]);
+        tlv.extend_from_slice(b"wss://relay.damus.io");
+        tlv.extend_from_slice(&[2, 32]);
+        tlv.extend_from_slice(pk.bytes());
+        tlv.extend_from_slice(&[3, 4]);
+        tlv.extend_from_slice(&31923u32.to_be_bytes());
+        let naddr = bech32::encode::<Bech32>(Hrp::parse("naddr").unwrap(), &tlv).unwrap();
+
+        assert_eq!(
+            NostrUri::parse(&format!("nostr:{naddr}")),
+            Some(NostrUri::Address {
+                kind: 31923,
+                author: pk,
+                identifier: "abc".to_owned(),
+            })
+        );
+
+        assert_eq!(NostrUri::parse("nostr:nsec1garbage"), None);
+        assert_eq!(NostrUri::parse("https://damus.io"), None);
+    }
+}
@@ -2,6 +2,8 @@ use enostr::{ClientMessage, NoteId, Pubkey, RelayPool};
 use nostrdb::{Note, NoteKey};
 use tracing::error;
 
+use crate::PublishTracker;
+
 /// When broadcasting notes, this determines whether to broadcast
 /// over the local network via multicast, or globally
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -19,6 +21,9 @@ pub enum NoteContextSelection {
     CopyNoteJSON,
     Broadcast(BroadcastContext),
     CopyLink,
+    /// Follow the note's author. The app publishes the new contact list.
+    FollowAuthor,
+    UnfollowAuthor,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -33,6 +38,7 @@ impl NoteContextSelection {
         ui: &mut egui::Ui,
         note: &Note<'_>,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         note_author_is_selected_acc: bool,
     ) {
         match self {
@@ -44,7 +50,9 @@ impl NoteContextSelection {
                     }
 
                     BroadcastContext::Everywhere => {
-                        pool.send(&ClientMessage::event(note).unwrap());
+                        if let Err(err) = publish_tracker.publish(pool, note) {
+                            error!("could not broadcast note: {err}");
+                        }
                     }
                 }
             }
@@ -84,6 +92,9 @@ impl NoteContextSelection {
                     ui.ctx().copy_text(damus_url(bech));
                 }
             }
+            NoteContextSelection::FollowAuthor | NoteContextSelection::UnfollowAuthor => {
+                // needs the account's contact list, handled by the app
+            }
         }
     }
 }
@@ -4,10 +4,13 @@ mod context;
 pub use action::{NoteAction, ReactAction, ScrollInfo, ZapAction, ZapTargetAmount};
 pub use context::{BroadcastContext, ContextSelection, NoteContextSelection};
 
+use crate::media::upload::MediaUploader;
 use crate::Accounts;
 use crate::GlobalWallet;
 use crate::JobPool;
 use crate::Localization;
+use crate::PublishTracker;
+use crate::Translations;
 use crate::UnknownIds;
 use crate::{notecache::NoteCache, zaps::Zaps, Images};
 use enostr::{NoteId, RelayPool};
@@ -30,6 +33,12 @@ pub struct NoteContext<'d> {
     pub job_pool: &'d mut JobPool,
     pub unknown_ids: &'d mut UnknownIds,
     pub clipboard: &'d mut egui_winit::clipboard::Clipboard,
+    pub media_uploader: &'d MediaUploader,
+    pub publish_tracker: &'d PublishTracker,
+    pub translations: &'d mut Translations,
+    /// Minimum NIP-13 difficulty for notes from people we don't follow,
+    /// 0 when the filter is off
+    pub min_pow: u8,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
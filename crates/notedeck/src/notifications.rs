@@ -0,0 +1,468 @@
+//! A notification inbox shared by every app.
+//!
+//! Core notedeck fills it with mentions, replies, zaps and reposts of the
+//! selected account, or of every account in all-accounts mode. Apps can
+//! push their own, like calendar invites, with
+//! [`crate::AppContext::notify`].
+
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Filter, Ndb, Note, Subscription, Transaction};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{platform, tr, Accounts, Localization, FALLBACK_PUBKEY};
+
+/// How many notifications we keep around
+const MAX_NOTIFICATIONS: usize = 200;
+
+/// How much of a note's content to show in a notification
+const MAX_TEXT_CHARS: usize = 140;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Mention,
+    Reply,
+    Zap,
+    Repost,
+    CalendarInvite,
+    CalendarRsvp,
+    CalendarReminder,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 7] = [
+        NotificationKind::Mention,
+        NotificationKind::Reply,
+        NotificationKind::Zap,
+        NotificationKind::Repost,
+        NotificationKind::CalendarInvite,
+        NotificationKind::CalendarRsvp,
+        NotificationKind::CalendarReminder,
+    ];
+
+    pub fn label(&self, i18n: &mut Localization) -> String {
+        match self {
+            NotificationKind::Mention => tr!(i18n, "Mentions", "Notification type for mentions"),
+            NotificationKind::Reply => tr!(i18n, "Replies", "Notification type for replies"),
+            NotificationKind::Zap => tr!(i18n, "Zaps", "Notification type for zaps"),
+            NotificationKind::Repost => tr!(i18n, "Reposts", "Notification type for reposts"),
+            NotificationKind::CalendarInvite => tr!(
+                i18n,
+                "Calendar invites",
+                "Notification type for calendar invites"
+            ),
+            NotificationKind::CalendarRsvp => tr!(
+                i18n,
+                "Calendar RSVPs",
+                "Notification type for replies to calendar invites"
+            ),
+            NotificationKind::CalendarReminder => tr!(
+                i18n,
+                "Calendar reminders",
+                "Notification type for upcoming calendar events"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    /// Short description to show in the inbox
+    pub text: String,
+    /// The note this is about, if any
+    pub note_id: Option<NoteId>,
+    /// Who caused it, if known
+    pub author: Option<Pubkey>,
+    /// Which of our accounts it is for, if known
+    pub account: Option<Pubkey>,
+    pub created_at: u64,
+    pub read: bool,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, text: impl Into<String>, created_at: u64) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+            note_id: None,
+            author: None,
+            account: None,
+            created_at,
+            read: false,
+        }
+    }
+
+    pub fn note(mut self, note_id: NoteId) -> Self {
+        self.note_id = Some(note_id);
+        self
+    }
+
+    pub fn author(mut self, author: Pubkey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    pub fn account(mut self, account: Pubkey) -> Self {
+        self.account = Some(account);
+        self
+    }
+
+    /// Build a notification for a note that tags `account`, if it is one
+    /// we notify about
+    fn from_note(note: &Note, account: &Pubkey) -> Option<Self> {
+        if note.pubkey() == account.bytes() {
+            return None;
+        }
+
+        let kind = match note.kind() {
+            1 if has_tag(note, "e") => NotificationKind::Reply,
+            1 => NotificationKind::Mention,
+            6 => NotificationKind::Repost,
+            9735 => NotificationKind::Zap,
+            _ => return None,
+        };
+
+        let author = match kind {
+            // the zap receipt is signed by the zapper's service, the sender
+            // is in the uppercase P tag
+            NotificationKind::Zap => tag_value(note, "P")
+                .and_then(|pk| Pubkey::from_hex(pk).ok())
+                .unwrap_or_else(|| Pubkey::new(*note.pubkey())),
+            _ => Pubkey::new(*note.pubkey()),
+        };
+
+        let text = match kind {
+            NotificationKind::Mention | NotificationKind::Reply => note.content(),
+            _ => "",
+        };
+
+        Some(
+            Notification::new(kind, truncate(text), note.created_at())
+                .note(NoteId::new(*note.id()))
+                .author(author)
+                .account(*account),
+        )
+    }
+}
+
+fn tags_pubkey(note: &Note, pubkey: &Pubkey) -> bool {
+    note.tags().iter().any(|tag| {
+        tag.get(0).and_then(|t| t.variant().str()) == Some("p")
+            && tag.get(1).and_then(|t| t.variant().id()) == Some(pubkey.bytes())
+    })
+}
+
+fn has_tag(note: &Note, name: &str) -> bool {
+    note.tags()
+        .iter()
+        .any(|tag| tag.get(0).and_then(|t| t.variant().str()) == Some(name))
+}
+
+fn tag_value<'a>(note: &Note<'a>, name: &str) -> Option<&'a str> {
+    note.tags().iter().find_map(|tag| {
+        if tag.get(0).and_then(|t| t.variant().str()) != Some(name) {
+            return None;
+        }
+        tag.get(1).and_then(|t| t.variant().str())
+    })
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_owned(),
+    }
+}
+
+/// The notification inbox for the selected account, or every account
+#[derive(Default)]
+pub struct Notifications {
+    /// Newest first
+    items: VecDeque<Notification>,
+    /// Kinds the user turned off
+    disabled: BTreeSet<NotificationKind>,
+    /// Notes we already have a notification for
+    seen: HashSet<NoteId>,
+    /// Added since the last [`Notifications::announce`]
+    fresh: Vec<Notification>,
+    /// The accounts we are subscribed for
+    accounts: Vec<Pubkey>,
+    sub: Option<Subscription>,
+}
+
+impl Notifications {
+    pub fn new(disabled: BTreeSet<NotificationKind>) -> Self {
+        Self {
+            disabled,
+            ..Default::default()
+        }
+    }
+
+    /// Add a notification. Returns false if its kind is turned off or we
+    /// already have one for the same note.
+    pub fn push(&mut self, notification: Notification) -> bool {
+        if !self.is_enabled(notification.kind) {
+            return false;
+        }
+
+        if let Some(note_id) = notification.note_id {
+            if !self.seen.insert(note_id) {
+                return false;
+            }
+        }
+
+        // keep newest first, notes don't always arrive in order
+        let pos = self
+            .items
+            .iter()
+            .position(|n| n.created_at <= notification.created_at)
+            .unwrap_or(self.items.len());
+        self.fresh.push(notification.clone());
+        self.items.insert(pos, notification);
+
+        if self.items.len() > MAX_NOTIFICATIONS {
+            if let Some(dropped) = self.items.pop_back() {
+                if let Some(note_id) = dropped.note_id {
+                    self.seen.remove(&note_id);
+                }
+            }
+        }
+
+        true
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.items.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.items.iter().filter(|n| !n.read).count()
+    }
+
+    pub fn mark_read(&mut self, index: usize) {
+        if let Some(notification) = self.items.get_mut(index) {
+            notification.read = true;
+        }
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for notification in &mut self.items {
+            notification.read = true;
+        }
+    }
+
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        !self.disabled.contains(&kind)
+    }
+
+    /// Turn a kind on or off. Turning it off hides the ones we have.
+    pub fn set_enabled(&mut self, kind: NotificationKind, enabled: bool) {
+        if enabled {
+            self.disabled.remove(&kind);
+        } else {
+            self.disabled.insert(kind);
+            self.items.retain(|n| n.kind != kind);
+        }
+    }
+
+    pub fn disabled(&self) -> &BTreeSet<NotificationKind> {
+        &self.disabled
+    }
+
+    /// Pick up notes tagging the selected account, or any of our accounts
+    /// with `all_accounts`. We only watch the local database, the notes
+    /// arrive through whichever app subscribed for them, like the
+    /// notifications column.
+    pub fn update(&mut self, ndb: &mut Ndb, accounts: &Accounts, all_accounts: bool) {
+        let selected = *accounts.selected_account_pubkey();
+        let mut watched = vec![selected];
+        if all_accounts {
+            let fallback = FALLBACK_PUBKEY();
+            watched.extend(
+                (&accounts.cache)
+                    .into_iter()
+                    .map(|(pk, _)| *pk)
+                    .filter(|pk| *pk != selected && *pk != fallback),
+            );
+        }
+
+        if self.accounts != watched {
+            self.switch_accounts(ndb, watched);
+        }
+
+        let Some(sub) = self.sub else {
+            return;
+        };
+
+        let keys = ndb.poll_for_notes(sub, MAX_NOTIFICATIONS as u32);
+        if keys.is_empty() {
+            return;
+        }
+
+        let Ok(txn) = Transaction::new(ndb) else {
+            return;
+        };
+        let is_muted = accounts.mutefun();
+
+        for key in keys {
+            let Ok(note) = ndb.get_note_by_key(&txn, key) else {
+                continue;
+            };
+
+            if is_muted(&note, note.id()) {
+                continue;
+            }
+
+            // with one account the filter already made sure it's tagged
+            let account = if self.accounts.len() == 1 {
+                self.accounts.first()
+            } else {
+                self.accounts.iter().find(|pk| tags_pubkey(&note, pk))
+            };
+
+            if let Some(notification) =
+                account.and_then(|account| Notification::from_note(&note, account))
+            {
+                self.push(notification);
+            }
+        }
+    }
+
+    /// Show notifications that came in since the last call as native
+    /// notifications. When `quiet` they are only dropped, like while the
+    /// window has focus and they already show up in the inbox.
+    pub fn announce(&mut self, ndb: &Ndb, i18n: &mut Localization, quiet: bool) {
+        let fresh = std::mem::take(&mut self.fresh);
+        if quiet || fresh.is_empty() {
+            return;
+        }
+
+        let Ok(txn) = Transaction::new(ndb) else {
+            return;
+        };
+
+        for notification in fresh {
+            let title = notification.kind.label(i18n);
+            let who = notification.author.map(|pk| {
+                let profile = ndb.get_profile_by_pubkey(&txn, pk.bytes());
+                crate::name::get_display_name(profile.as_ref().ok())
+                    .name()
+                    .to_owned()
+            });
+
+            let body = match who {
+                Some(who) if notification.text.is_empty() => who,
+                Some(who) => format!("{who}: {}", notification.text),
+                None => notification.text,
+            };
+
+            platform::show_notification(&title, &body, notification.note_id);
+        }
+    }
+
+    fn switch_accounts(&mut self, ndb: &mut Ndb, accounts: Vec<Pubkey>) {
+        if let Some(sub) = self.sub.take() {
+            if let Err(err) = ndb.unsubscribe(sub) {
+                error!("could not unsubscribe from notifications: {err}");
+            }
+        }
+
+        self.items.clear();
+        self.fresh.clear();
+        self.seen.clear();
+
+        // only notify about things that happen from now on
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let filter = Filter::new()
+            .pubkeys(accounts.iter().map(|pk| pk.bytes()))
+            .kinds([1, 6, 9735])
+            .since(now)
+            .build();
+        self.accounts = accounts;
+
+        match ndb.subscribe(&[filter]) {
+            Ok(sub) => self.sub = Some(sub),
+            Err(err) => error!("could not subscribe to notifications: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(kind: NotificationKind, id: u8, created_at: u64) -> Notification {
+        Notification::new(kind, "", created_at).note(NoteId::new([id; 32]))
+    }
+
+    #[test]
+    fn push_dedupes_and_sorts() {
+        let mut notifications = Notifications::default();
+        assert!(notifications.push(notification(NotificationKind::Reply, 1, 10)));
+        assert!(notifications.push(notification(NotificationKind::Zap, 2, 30)));
+        assert!(notifications.push(notification(NotificationKind::Mention, 3, 20)));
+        assert!(!notifications.push(notification(NotificationKind::Reply, 1, 10)));
+
+        let times: Vec<u64> = notifications.iter().map(|n| n.created_at).collect();
+        assert_eq!(times, vec![30, 20, 10]);
+        assert_eq!(notifications.unread_count(), 3);
+
+        notifications.mark_read(0);
+        assert_eq!(notifications.unread_count(), 2);
+        notifications.mark_all_read();
+        assert_eq!(notifications.unread_count(), 0);
+    }
+
+    #[test]
+    fn disabled_kinds_are_dropped() {
+        let mut notifications = Notifications::default();
+        assert!(notifications.push(notification(NotificationKind::Repost, 1, 10)));
+
+        notifications.set_enabled(NotificationKind::Repost, false);
+        assert!(notifications.is_empty());
+        assert!(!notifications.push(notification(NotificationKind::Repost, 2, 20)));
+
+        let invite = Notification::new(NotificationKind::CalendarInvite, "Standup", 30);
+        assert!(notifications.push(invite));
+    }
+
+    #[test]
+    fn finds_the_tagged_account() {
+        let ours = Pubkey::new([5; 32]);
+        let other = Pubkey::new([6; 32]);
+        let note = nostrdb::NoteBuilder::new()
+            .kind(1)
+            .content("hey")
+            .start_tag()
+            .tag_str("p")
+            .tag_id(ours.bytes())
+            .sign(&[1; 32])
+            .build()
+            .unwrap();
+
+        assert!(tags_pubkey(&note, &ours));
+        assert!(!tags_pubkey(&note, &other));
+
+        let notification = Notification::from_note(&note, &ours).unwrap();
+        assert_eq!(notification.kind, NotificationKind::Mention);
+        assert_eq!(notification.account, Some(ours));
+    }
+
+    #[test]
+    fn truncates_long_text() {
+        let long = "a".repeat(MAX_TEXT_CHARS + 10);
+        assert_eq!(truncate(&long).chars().count(), MAX_TEXT_CHARS + 1);
+        assert_eq!(truncate("short"), "short");
+    }
+}
@@ -0,0 +1,292 @@
+//! Notes we published that not every write relay has answered yet.
+//! They're written to disk so they survive a restart, and sent to each
+//! write relay as it connects until it accepts or refuses them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+use enostr::{ClientMessage, NoteId, PoolRelay, RelayPool, RelayStatus};
+use tracing::{error, info};
+
+use crate::{storage, DataPath, DataPathType, Directory};
+
+const OUTBOX_FILE: &str = "outbox.jsonl";
+
+/// How long every relay has to be down before we call it offline, so a
+/// reconnect or the startup connect doesn't flash the banner
+const OFFLINE_GRACE: Duration = Duration::from_secs(5);
+
+/// How long a relay has to answer a note before we stop waiting on it
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Is any relay connected?
+pub fn is_connected(pool: &RelayPool) -> bool {
+    pool.relays.iter().any(|relay| {
+        matches!(relay, PoolRelay::Websocket(_)) && matches!(relay.status(), RelayStatus::Connected)
+    })
+}
+
+/// The websocket relays we publish to, and whether they're connected
+fn write_relays(pool: &RelayPool) -> Vec<(String, bool)> {
+    pool.relays
+        .iter()
+        .filter(|relay| matches!(relay, PoolRelay::Websocket(_)) && relay.usage().write)
+        .map(|relay| {
+            (
+                relay.url().to_owned(),
+                matches!(relay.status(), RelayStatus::Connected),
+            )
+        })
+        .collect()
+}
+
+struct Entry {
+    json: String,
+    note_id: Option<NoteId>,
+    /// Relays we sent it to since they last connected
+    sent: BTreeMap<String, Instant>,
+    /// Relays that accepted it, refused it or never answered
+    answered: BTreeSet<String>,
+}
+
+impl Entry {
+    fn new(json: String) -> Self {
+        Self {
+            note_id: queued_note_id(&json),
+            json,
+            sent: BTreeMap::new(),
+            answered: BTreeSet::new(),
+        }
+    }
+
+    fn is_unsent(&self) -> bool {
+        self.sent.is_empty() && self.answered.is_empty()
+    }
+}
+
+#[derive(Default)]
+pub struct Outbox {
+    /// Where the queue is saved, none keeps it in memory only
+    directory: Option<Directory>,
+    /// Oldest first
+    queued: Vec<Entry>,
+    disconnected_since: Option<Instant>,
+}
+
+impl Outbox {
+    pub fn new(path: &DataPath) -> Self {
+        let directory = Directory::new(path.path(DataPathType::Setting));
+        let queued = directory
+            .get_file(OUTBOX_FILE.to_owned())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| Entry::new(line.to_owned()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            directory: Some(directory),
+            queued,
+            disconnected_since: None,
+        }
+    }
+
+    /// Keep a note until every write relay answered it. It goes out on
+    /// the next [`Outbox::update`].
+    pub fn queue(&mut self, note_json: String) {
+        self.queued.push(Entry::new(note_json));
+        self.save();
+    }
+
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+
+    /// How many notes no relay got yet
+    pub fn unsent(&self) -> usize {
+        self.queued.iter().filter(|entry| entry.is_unsent()).count()
+    }
+
+    pub fn contains(&self, note_id: &NoteId) -> bool {
+        self.queued
+            .iter()
+            .any(|entry| entry.note_id == Some(*note_id))
+    }
+
+    /// Have all relays been down for a while?
+    pub fn is_offline(&self) -> bool {
+        self.disconnected_since
+            .is_some_and(|since| since.elapsed() >= OFFLINE_GRACE)
+    }
+
+    /// Notice connectivity changes and send each note to the connected
+    /// write relays that haven't got it yet. Relays that disconnect get
+    /// it again when they're back. Returns the ids of the notes that were
+    /// sent for the first time.
+    pub fn update(&mut self, pool: &mut RelayPool) -> Vec<NoteId> {
+        if is_connected(pool) {
+            self.disconnected_since = None;
+        } else {
+            self.disconnected_since.get_or_insert_with(Instant::now);
+        }
+
+        if self.queued.is_empty() {
+            return Vec::new();
+        }
+
+        let before = self.queued.len();
+        let relays = write_relays(pool);
+        let mut first_sent = Vec::new();
+        self.queued.retain_mut(|entry| {
+            let event = match ClientMessage::event_json(entry.json.clone()) {
+                Ok(event) => event,
+                Err(err) => {
+                    error!("dropping queued note: {err}");
+                    return false;
+                }
+            };
+
+            let unsent = entry.is_unsent();
+            for (url, connected) in &relays {
+                if entry.answered.contains(url) {
+                    continue;
+                }
+
+                if !connected {
+                    entry.sent.remove(url);
+                    continue;
+                }
+
+                match entry.sent.get(url) {
+                    Some(at) if at.elapsed() >= ANSWER_TIMEOUT => {
+                        entry.sent.remove(url);
+                        entry.answered.insert(url.clone());
+                    }
+                    Some(_) => {}
+                    None => {
+                        pool.send_to(&event, url);
+                        entry.sent.insert(url.clone(), Instant::now());
+                    }
+                }
+            }
+
+            if unsent && !entry.sent.is_empty() {
+                first_sent.extend(entry.note_id);
+            }
+            true
+        });
+
+        if !first_sent.is_empty() {
+            info!("sent {} queued notes", first_sent.len());
+        }
+        self.remove_answered(&relays);
+        if self.queued.len() != before {
+            self.save();
+        }
+
+        first_sent
+    }
+
+    /// Record that `relay` accepted or refused a note. Once every write
+    /// relay did, the note is dropped.
+    pub fn handle_answer(&mut self, pool: &RelayPool, relay: &str, note_id: &NoteId) {
+        let Some(entry) = self
+            .queued
+            .iter_mut()
+            .find(|entry| entry.note_id == Some(*note_id))
+        else {
+            return;
+        };
+
+        entry.sent.remove(relay);
+        entry.answered.insert(relay.to_owned());
+
+        let before = self.queued.len();
+        self.remove_answered(&write_relays(pool));
+        if self.queued.len() != before {
+            self.save();
+        }
+    }
+
+    fn remove_answered(&mut self, relays: &[(String, bool)]) {
+        if relays.is_empty() {
+            // nowhere to publish, keep everything for when there is
+            return;
+        }
+
+        self.queued
+            .retain(|entry| !relays.iter().all(|(url, _)| entry.answered.contains(url)));
+    }
+
+    fn save(&self) {
+        let Some(directory) = &self.directory else {
+            return;
+        };
+
+        if self.queued.is_empty() {
+            // nothing left to send, don't leave an empty file behind
+            _ = storage::delete_file(&directory.file_path, OUTBOX_FILE.to_owned());
+            return;
+        }
+
+        let mut contents = String::new();
+        for entry in &self.queued {
+            contents.push_str(&entry.json);
+            contents.push('\n');
+        }
+        if let Err(err) =
+            storage::write_file(&directory.file_path, OUTBOX_FILE.to_owned(), &contents)
+        {
+            error!("could not save outbox: {err}");
+        }
+    }
+}
+
+pub(crate) fn queued_note_id(json: &str) -> Option<NoteId> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    NoteId::from_hex(value.get("id")?.as_str()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_survives_a_restart() {
+        let tmp = std::env::temp_dir().join(format!("notedeck-outbox-{}", std::process::id()));
+        let path = DataPath::new(&tmp);
+        let id = NoteId::new([3; 32]);
+
+        let mut outbox = Outbox::new(&path);
+        outbox.queue(format!(r#"{{"id":"{}","kind":1}}"#, id.hex()));
+
+        let outbox = Outbox::new(&path);
+        assert_eq!(outbox.len(), 1);
+        assert!(outbox.contains(&id));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn kept_until_every_write_relay_answered() {
+        let mut outbox = Outbox::default();
+        let id = NoteId::new([4; 32]);
+        outbox.queue(format!(r#"{{"id":"{}","kind":1}}"#, id.hex()));
+
+        let relays = [("wss://a".to_owned(), true), ("wss://b".to_owned(), false)];
+        outbox.queued[0].answered.insert("wss://a".to_owned());
+        outbox.remove_answered(&relays);
+        assert!(outbox.contains(&id));
+
+        outbox.queued[0].answered.insert("wss://b".to_owned());
+        outbox.remove_answered(&relays);
+        assert!(outbox.is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 mod app_size;
 mod settings_handler;
+mod settings_sync;
 mod token_handler;
 
 pub use app_size::AppSizeHandler;
 pub use settings_handler::Settings;
 pub use settings_handler::SettingsHandler;
 pub use settings_handler::DEFAULT_NOTE_BODY_FONT_SIZE;
+pub use settings_sync::SyncedSettings;
 pub use token_handler::TokenHandler;
@@ -1,8 +1,14 @@
+use super::settings_sync::SyncedSettings;
 use crate::{
-    storage::delete_file, timed_serializer::TimedSerializer, DataPath, DataPathType, Directory,
+    media::upload::UploadServer, storage::delete_file, theme::Accent,
+    timed_serializer::TimedSerializer, DataPath, DataPathType, Directory, NotificationKind,
+    ThemeOptions, Translator,
 };
-use egui::ThemePreference;
+use egui::{KeyboardShortcut, ThemePreference};
+use enostr::Pubkey;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{error, info};
 
 const THEME_FILE: &str = "theme.txt";
@@ -14,6 +20,11 @@ const DEFAULT_LOCALE: &str = "en-US";
 const DEFAULT_ZOOM_FACTOR: f32 = 1.0;
 const DEFAULT_SHOW_SOURCE_CLIENT: &str = "hide";
 const DEFAULT_SHOW_REPLIES_NEWEST_FIRST: bool = false;
+const DEFAULT_MEDIA_CACHE_LIMIT_MB: u64 = 2048;
+const DEFAULT_TEXTURE_BUDGET_MB: u64 = 512;
+const DEFAULT_MAX_IMAGE_LENGTH: u32 = 2048;
+const DEFAULT_STRIP_EXIF: bool = true;
+const DEFAULT_OS_NOTIFICATIONS: bool = true;
 #[cfg(any(target_os = "android", target_os = "ios"))]
 pub const DEFAULT_NOTE_BODY_FONT_SIZE: f32 = 13.0;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
@@ -36,6 +47,92 @@ pub struct Settings {
     pub show_source_client: String,
     pub show_replies_newest_first: bool,
     pub note_body_font_size: f32,
+    /// Disk budget for cached media in MB, 0 for no limit
+    #[serde(default = "default_media_cache_limit_mb")]
+    pub media_cache_limit_mb: u64,
+    /// GPU memory for loaded media in MB, see [`crate::Images`]
+    #[serde(default = "default_texture_budget_mb")]
+    pub texture_budget_mb: u64,
+    /// Longest edge content images are downscaled to when loaded
+    #[serde(default = "default_max_image_length")]
+    pub max_image_length: u32,
+    /// Only load media when tapped and don't animate gifs
+    #[serde(default)]
+    pub data_saver: bool,
+    /// Show the first frame of gifs instead of animating them
+    #[serde(default)]
+    pub reduce_motion: bool,
+    /// Hex pubkeys of accounts that show media behind content warnings
+    /// without an extra tap
+    #[serde(default)]
+    pub show_sensitive_media_accounts: Vec<String>,
+    /// Optional resize endpoint template, see [`crate::ImageProxy`]
+    #[serde(default)]
+    pub image_proxy: Option<String>,
+    /// Upload server per account, keyed by hex pubkey
+    #[serde(default)]
+    pub upload_servers: HashMap<String, UploadServer>,
+    /// Remove camera metadata from photos before uploading them
+    #[serde(default = "default_strip_exif")]
+    pub strip_exif: bool,
+    /// Notification kinds the user turned off in the inbox
+    #[serde(default)]
+    pub disabled_notifications: BTreeSet<NotificationKind>,
+    /// Also show notifications through the operating system
+    #[serde(default = "default_os_notifications")]
+    pub os_notifications: bool,
+    /// Color for links, selections and highlights
+    #[serde(default)]
+    pub accent: Accent,
+    /// Stronger text and borders, for readability
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// Keyboard shortcuts the user bound to other keys, by shortcut id
+    #[serde(default)]
+    pub shortcuts: BTreeMap<String, KeyboardShortcut>,
+    /// Minimum NIP-13 difficulty for notes from people you don't follow,
+    /// 0 to show everything
+    #[serde(default)]
+    pub min_pow: u8,
+    /// Apps turned on or off in the app catalog, by app id. Apps not in
+    /// here use their default.
+    #[serde(default)]
+    pub apps: BTreeMap<String, bool>,
+    /// Merge notifications of every account and pick the account when
+    /// posting, instead of only using the selected one
+    #[serde(default)]
+    pub all_accounts: bool,
+    /// LibreTranslate style endpoint notes are translated with, see
+    /// [`crate::Translator`]
+    #[serde(default)]
+    pub translation_endpoint: Option<String>,
+    /// Key for translation services that need one
+    #[serde(default)]
+    pub translation_api_key: Option<String>,
+    /// When a setting that follows the account across devices last
+    /// changed, in unix seconds. Newer settings from another device win.
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+fn default_media_cache_limit_mb() -> u64 {
+    DEFAULT_MEDIA_CACHE_LIMIT_MB
+}
+
+fn default_texture_budget_mb() -> u64 {
+    DEFAULT_TEXTURE_BUDGET_MB
+}
+
+fn default_max_image_length() -> u32 {
+    DEFAULT_MAX_IMAGE_LENGTH
+}
+
+fn default_strip_exif() -> bool {
+    DEFAULT_STRIP_EXIF
+}
+
+fn default_os_notifications() -> bool {
+    DEFAULT_OS_NOTIFICATIONS
 }
 
 impl Default for Settings {
@@ -47,6 +144,26 @@ impl Default for Settings {
             show_source_client: DEFAULT_SHOW_SOURCE_CLIENT.to_string(),
             show_replies_newest_first: DEFAULT_SHOW_REPLIES_NEWEST_FIRST,
             note_body_font_size: DEFAULT_NOTE_BODY_FONT_SIZE,
+            media_cache_limit_mb: DEFAULT_MEDIA_CACHE_LIMIT_MB,
+            texture_budget_mb: DEFAULT_TEXTURE_BUDGET_MB,
+            max_image_length: DEFAULT_MAX_IMAGE_LENGTH,
+            data_saver: false,
+            reduce_motion: false,
+            show_sensitive_media_accounts: Vec::new(),
+            image_proxy: None,
+            upload_servers: HashMap::new(),
+            strip_exif: DEFAULT_STRIP_EXIF,
+            disabled_notifications: BTreeSet::new(),
+            os_notifications: DEFAULT_OS_NOTIFICATIONS,
+            accent: Accent::default(),
+            high_contrast: false,
+            shortcuts: BTreeMap::new(),
+            min_pow: 0,
+            apps: BTreeMap::new(),
+            all_accounts: false,
+            translation_endpoint: None,
+            translation_api_key: None,
+            updated_at: 0,
         }
     }
 }
@@ -55,6 +172,9 @@ pub struct SettingsHandler {
     directory: Directory,
     serializer: TimedSerializer<Settings>,
     current_settings: Option<Settings>,
+    /// Settings as of the last synced change, to tell when `updated_at`
+    /// needs bumping
+    synced: Option<Settings>,
 }
 
 impl SettingsHandler {
@@ -113,11 +233,13 @@ impl SettingsHandler {
             directory,
             serializer,
             current_settings: None,
+            synced: None,
         }
     }
 
     pub fn load(mut self) -> Self {
         if self.migrate_to_settings_file() {
+            self.synced = self.current_settings.clone();
             return self;
         }
 
@@ -140,14 +262,78 @@ impl SettingsHandler {
             }
         }
 
+        self.synced = self.current_settings.clone();
         self
     }
 
     pub(crate) fn try_save_settings(&mut self) {
-        let settings = self.get_settings_mut().clone();
+        let mut settings = self.get_settings_mut().clone();
+
+        let synced_changed = self
+            .synced
+            .as_ref()
+            .is_some_and(|synced| !synced.synced_eq(&settings));
+        if synced_changed {
+            settings.updated_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            self.current_settings = Some(settings.clone());
+            self.synced = Some(settings.clone());
+        }
+
         self.serializer.try_save(settings);
     }
 
+    /// Use settings `account` published from another device, unless ours
+    /// changed since. Returns whether they were applied.
+    pub fn apply_synced(
+        &mut self,
+        synced: SyncedSettings,
+        account: &Pubkey,
+        created_at: u64,
+    ) -> bool {
+        let settings = self.get_settings_mut();
+        if created_at <= settings.updated_at {
+            return false;
+        }
+
+        synced.apply(settings, account);
+        settings.updated_at = created_at;
+        self.synced = self.current_settings.clone();
+        self.try_save_settings();
+        true
+    }
+
+    /// Replace every setting with ones from a backup
+    pub fn restore(&mut self, mut settings: Settings) {
+        // backups leave the api key out, keep the one on this device
+        settings.translation_api_key = self.get_settings_mut().translation_api_key.take();
+        self.current_settings = Some(settings);
+        self.synced = self.current_settings.clone();
+        self.try_save_settings();
+    }
+
+    /// Take only the backed up settings that belong to `accounts`, and
+    /// keep everything else as it is on this device
+    pub fn restore_accounts(&mut self, backup: &Settings, accounts: &[Pubkey]) {
+        let current = self.get_settings_mut();
+        for account in accounts {
+            let hex = account.hex();
+
+            if let Some(server) = backup.upload_servers.get(&hex) {
+                current.upload_servers.insert(hex.clone(), server.clone());
+            }
+
+            if backup.show_sensitive_media_accounts.contains(&hex)
+                && !current.show_sensitive_media_accounts.contains(&hex)
+            {
+                current.show_sensitive_media_accounts.push(hex);
+            }
+        }
+        self.try_save_settings();
+    }
+
     pub fn get_settings_mut(&mut self) -> &mut Settings {
         if self.current_settings.is_none() {
             self.current_settings = Some(Settings::default());
@@ -160,6 +346,30 @@ impl SettingsHandler {
         self.try_save_settings();
     }
 
+    pub fn set_accent(&mut self, accent: Accent) {
+        self.get_settings_mut().accent = accent;
+        self.try_save_settings();
+    }
+
+    pub fn set_high_contrast(&mut self, value: bool) {
+        self.get_settings_mut().high_contrast = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_shortcut(&mut self, id: &str, binding: Option<KeyboardShortcut>) {
+        let shortcuts = &mut self.get_settings_mut().shortcuts;
+        match binding {
+            Some(binding) => shortcuts.insert(id.to_owned(), binding),
+            None => shortcuts.remove(id),
+        };
+        self.try_save_settings();
+    }
+
+    pub fn set_app_enabled(&mut self, id: &str, enabled: bool) {
+        self.get_settings_mut().apps.insert(id.to_owned(), enabled);
+        self.try_save_settings();
+    }
+
     pub fn set_locale<S>(&mut self, locale: S)
     where
         S: Into<String>,
@@ -191,6 +401,92 @@ impl SettingsHandler {
         self.try_save_settings();
     }
 
+    pub fn set_media_cache_limit_mb(&mut self, value: u64) {
+        self.get_settings_mut().media_cache_limit_mb = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_texture_budget_mb(&mut self, value: u64) {
+        self.get_settings_mut().texture_budget_mb = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_max_image_length(&mut self, value: u32) {
+        self.get_settings_mut().max_image_length = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_data_saver(&mut self, value: bool) {
+        self.get_settings_mut().data_saver = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_reduce_motion(&mut self, value: bool) {
+        self.get_settings_mut().reduce_motion = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_show_sensitive_media(&mut self, account: &Pubkey, show: bool) {
+        let hex = account.hex();
+        let accounts = &mut self.get_settings_mut().show_sensitive_media_accounts;
+        accounts.retain(|pk| *pk != hex);
+        if show {
+            accounts.push(hex);
+        }
+        self.try_save_settings();
+    }
+
+    pub fn set_image_proxy(&mut self, value: Option<String>) {
+        self.get_settings_mut().image_proxy = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_translation(&mut self, endpoint: Option<String>, api_key: Option<String>) {
+        let settings = self.get_settings_mut();
+        settings.translation_endpoint = endpoint;
+        settings.translation_api_key = api_key;
+        self.try_save_settings();
+    }
+
+    pub fn set_upload_server(&mut self, account: &Pubkey, server: Option<UploadServer>) {
+        let servers = &mut self.get_settings_mut().upload_servers;
+        match server {
+            Some(server) => servers.insert(account.hex(), server),
+            None => servers.remove(&account.hex()),
+        };
+        self.try_save_settings();
+    }
+
+    pub fn set_strip_exif(&mut self, value: bool) {
+        self.get_settings_mut().strip_exif = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_min_pow(&mut self, value: u8) {
+        self.get_settings_mut().min_pow = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_notification_enabled(&mut self, kind: NotificationKind, enabled: bool) {
+        let disabled = &mut self.get_settings_mut().disabled_notifications;
+        if enabled {
+            disabled.remove(&kind);
+        } else {
+            disabled.insert(kind);
+        }
+        self.try_save_settings();
+    }
+
+    pub fn set_os_notifications(&mut self, value: bool) {
+        self.get_settings_mut().os_notifications = value;
+        self.try_save_settings();
+    }
+
+    pub fn set_all_accounts(&mut self, value: bool) {
+        self.get_settings_mut().all_accounts = value;
+        self.try_save_settings();
+    }
+
     pub fn update_batch<F>(&mut self, update_fn: F)
     where
         F: FnOnce(&mut Settings),
@@ -250,4 +546,148 @@ impl SettingsHandler {
             .map(|s| s.note_body_font_size)
             .unwrap_or(DEFAULT_NOTE_BODY_FONT_SIZE)
     }
+
+    pub fn media_cache_limit_mb(&self) -> u64 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.media_cache_limit_mb)
+            .unwrap_or(DEFAULT_MEDIA_CACHE_LIMIT_MB)
+    }
+
+    pub fn texture_budget_mb(&self) -> u64 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.texture_budget_mb)
+            .unwrap_or(DEFAULT_TEXTURE_BUDGET_MB)
+    }
+
+    pub fn max_image_length(&self) -> u32 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.max_image_length)
+            .unwrap_or(DEFAULT_MAX_IMAGE_LENGTH)
+    }
+
+    pub fn data_saver(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.data_saver)
+            .unwrap_or(false)
+    }
+
+    pub fn reduce_motion(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.reduce_motion)
+            .unwrap_or(false)
+    }
+
+    pub fn image_proxy(&self) -> Option<String> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.image_proxy.clone())
+    }
+
+    /// The translation service to use, if one is set up
+    pub fn translator(&self) -> Option<Translator> {
+        let settings = self.current_settings.as_ref()?;
+        Translator::new(
+            settings.translation_endpoint.as_deref()?,
+            settings.translation_api_key.clone(),
+        )
+    }
+
+    /// Where `account` uploads media, [`UploadServer::default`] unless
+    /// it picked one
+    pub fn upload_server(&self, account: &Pubkey) -> UploadServer {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.upload_servers.get(&account.hex()).cloned())
+            .unwrap_or_default()
+    }
+
+    pub fn strip_exif(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.strip_exif)
+            .unwrap_or(DEFAULT_STRIP_EXIF)
+    }
+
+    pub fn min_pow(&self) -> u8 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.min_pow)
+            .unwrap_or(0)
+    }
+
+    pub fn disabled_notifications(&self) -> BTreeSet<NotificationKind> {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.disabled_notifications.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn os_notifications(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.os_notifications)
+            .unwrap_or(DEFAULT_OS_NOTIFICATIONS)
+    }
+
+    pub fn all_accounts(&self) -> bool {
+        self.current_settings
+            .as_ref()
+            .is_some_and(|s| s.all_accounts)
+    }
+
+    pub fn show_sensitive_media(&self, account: &Pubkey) -> bool {
+        self.current_settings
+            .as_ref()
+            .is_some_and(|s| s.shows_sensitive_media(account))
+    }
+
+    pub fn shortcuts(&self) -> BTreeMap<String, KeyboardShortcut> {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.shortcuts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the user turned an app on or off, if they did
+    pub fn app_enabled(&self, id: &str) -> Option<bool> {
+        self.current_settings
+            .as_ref()
+            .and_then(|s| s.apps.get(id).copied())
+    }
+
+    pub fn theme_options(&self) -> ThemeOptions {
+        self.current_settings
+            .as_ref()
+            .map(|s| ThemeOptions::new(s.accent, s.high_contrast))
+            .unwrap_or_default()
+    }
+
+    pub fn updated_at(&self) -> u64 {
+        self.current_settings
+            .as_ref()
+            .map(|s| s.updated_at)
+            .unwrap_or_default()
+    }
+
+    /// What we would publish for `account` to sync with its other devices
+    pub fn synced(&self, account: &Pubkey) -> SyncedSettings {
+        self.current_settings
+            .as_ref()
+            .map(|s| SyncedSettings::new(s, account))
+            .unwrap_or_default()
+    }
+}
+
+impl Settings {
+    pub fn shows_sensitive_media(&self, account: &Pubkey) -> bool {
+        let hex = account.hex();
+        self.show_sensitive_media_accounts
+            .iter()
+            .any(|pk| *pk == hex)
+    }
 }
@@ -0,0 +1,158 @@
+use std::collections::BTreeSet;
+
+use egui::ThemePreference;
+use enostr::Pubkey;
+use serde::{Deserialize, Serialize};
+
+use crate::{media::upload::UploadServer, Accent, NotificationKind, Settings};
+
+/// The part of [`Settings`] that follows an account across devices.
+///
+/// Things that depend on the device, like zoom, locale or how much disk
+/// the media cache may use, stay local. Settings kept per account are
+/// only synced for the account they belong to, so publishing them
+/// doesn't link accounts together.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SyncedSettings {
+    pub theme: ThemePreference,
+    pub accent: Accent,
+    pub high_contrast: bool,
+    pub show_source_client: String,
+    pub show_replies_newest_first: bool,
+    pub note_body_font_size: f32,
+    pub reduce_motion: bool,
+    pub show_sensitive_media: bool,
+    pub image_proxy: Option<String>,
+    pub upload_server: Option<UploadServer>,
+    pub strip_exif: bool,
+    pub min_pow: u8,
+    pub disabled_notifications: BTreeSet<NotificationKind>,
+}
+
+impl Default for SyncedSettings {
+    fn default() -> Self {
+        let settings = Settings::default();
+        Self {
+            theme: settings.theme,
+            accent: settings.accent,
+            high_contrast: settings.high_contrast,
+            show_source_client: settings.show_source_client,
+            show_replies_newest_first: settings.show_replies_newest_first,
+            note_body_font_size: settings.note_body_font_size,
+            reduce_motion: settings.reduce_motion,
+            show_sensitive_media: false,
+            image_proxy: settings.image_proxy,
+            upload_server: None,
+            strip_exif: settings.strip_exif,
+            min_pow: settings.min_pow,
+            disabled_notifications: settings.disabled_notifications,
+        }
+    }
+}
+
+impl SyncedSettings {
+    pub fn new(settings: &Settings, account: &Pubkey) -> Self {
+        Self {
+            theme: settings.theme,
+            accent: settings.accent,
+            high_contrast: settings.high_contrast,
+            show_source_client: settings.show_source_client.clone(),
+            show_replies_newest_first: settings.show_replies_newest_first,
+            note_body_font_size: settings.note_body_font_size,
+            reduce_motion: settings.reduce_motion,
+            show_sensitive_media: settings.shows_sensitive_media(account),
+            image_proxy: settings.image_proxy.clone(),
+            upload_server: settings.upload_servers.get(&account.hex()).cloned(),
+            strip_exif: settings.strip_exif,
+            min_pow: settings.min_pow,
+            disabled_notifications: settings.disabled_notifications.clone(),
+        }
+    }
+
+    pub fn apply(self, settings: &mut Settings, account: &Pubkey) {
+        let hex = account.hex();
+
+        settings.theme = self.theme;
+        settings.accent = self.accent;
+        settings.high_contrast = self.high_contrast;
+        settings.show_source_client = self.show_source_client;
+        settings.show_replies_newest_first = self.show_replies_newest_first;
+        settings.note_body_font_size = self.note_body_font_size;
+        settings.reduce_motion = self.reduce_motion;
+        settings.image_proxy = self.image_proxy;
+        settings.strip_exif = self.strip_exif;
+        settings.min_pow = self.min_pow;
+        settings.disabled_notifications = self.disabled_notifications;
+
+        settings
+            .show_sensitive_media_accounts
+            .retain(|pk| *pk != hex);
+        if self.show_sensitive_media {
+            settings.show_sensitive_media_accounts.push(hex.clone());
+        }
+
+        match self.upload_server {
+            Some(server) => settings.upload_servers.insert(hex, server),
+            None => settings.upload_servers.remove(&hex),
+        };
+    }
+}
+
+impl Settings {
+    /// Whether the settings we sync across devices are the same, for any
+    /// account
+    pub(crate) fn synced_eq(&self, other: &Settings) -> bool {
+        self.theme == other.theme
+            && self.accent == other.accent
+            && self.high_contrast == other.high_contrast
+            && self.show_source_client == other.show_source_client
+            && self.show_replies_newest_first == other.show_replies_newest_first
+            && self.note_body_font_size == other.note_body_font_size
+            && self.reduce_motion == other.reduce_motion
+            && self.show_sensitive_media_accounts == other.show_sensitive_media_accounts
+            && self.image_proxy == other.image_proxy
+            && self.upload_servers == other.upload_servers
+            && self.strip_exif == other.strip_exif
+            && self.min_pow == other.min_pow
+            && self.disabled_notifications == other.disabled_notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synced_settings_roundtrip_per_account() {
+        let alice = Pubkey::new([1; 32]);
+        let bob = Pubkey::new([2; 32]);
+
+        let mut remote = Settings::default();
+        remote.theme = ThemePreference::Light;
+        remote.strip_exif = false;
+        remote.show_sensitive_media_accounts = vec![alice.hex(), bob.hex()];
+
+        let synced = SyncedSettings::new(&remote, &alice);
+        let json = serde_json::to_string(&synced).unwrap();
+        assert!(!json.contains(&bob.hex()));
+
+        let mut local = Settings::default();
+        serde_json::from_str::<SyncedSettings>(&json)
+            .unwrap()
+            .apply(&mut local, &alice);
+
+        assert_eq!(local.theme, ThemePreference::Light);
+        assert!(!local.strip_exif);
+        assert!(local.shows_sensitive_media(&alice));
+        assert!(!local.shows_sensitive_media(&bob));
+        assert!(!local.synced_eq(&Settings::default()));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let synced: SyncedSettings = serde_json::from_str(r#"{"reduce_motion":true}"#).unwrap();
+        assert!(synced.reduce_motion);
+        assert_eq!(synced.strip_exif, Settings::default().strip_exif);
+    }
+}
@@ -1,6 +1,12 @@
-use crate::platform::{file::emit_selected_file, SelectedMedia};
+use crate::nostr_uri::{emit_uri_opened, NostrUri};
+use crate::platform::{
+    emit_notification_clicked,
+    file::{emit_selected_file, set_file_requester, FileRequester},
+    SelectedMedia,
+};
+use enostr::NoteId;
 use jni::{
-    objects::{JByteArray, JClass, JObject, JObjectArray, JString},
+    objects::{JByteArray, JClass, JObject, JObjectArray, JString, JValue},
     JNIEnv,
 };
 use std::sync::atomic::{AtomicI32, Ordering};
@@ -81,7 +87,9 @@ pub extern "C" fn Java_com_damus_notedeck_MainActivity_nativeOnFilePickedWithCon
     }
 }
 
-pub fn try_open_file_picker() {
+/// Open the picker, the file it picks goes to `requester`
+pub fn try_open_file_picker(requester: FileRequester) {
+    set_file_requester(requester);
     match open_file_picker() {
         Ok(()) => {
             info!("File picker opened successfully");
@@ -110,3 +118,67 @@ pub fn open_file_picker() -> std::result::Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+/// Called by our main activity when the user taps one of our notifications
+#[no_mangle]
+pub extern "C" fn Java_com_damus_notedeck_MainActivity_nativeOnNotificationClicked(
+    mut env: JNIEnv,
+    _class: JClass,
+    jnote_id: JString,
+) {
+    let note_id: String = env.get_string(&jnote_id).unwrap().into();
+
+    match NoteId::from_hex(&note_id) {
+        Ok(note_id) => emit_notification_clicked(note_id),
+        Err(err) => error!("invalid note id in notification: {err}"),
+    }
+}
+
+/// Called by our main activity when another app opens a `nostr:` link
+#[no_mangle]
+pub extern "C" fn Java_com_damus_notedeck_MainActivity_nativeOnUriOpened(
+    mut env: JNIEnv,
+    _class: JClass,
+    juri: JString,
+) {
+    let uri: String = env.get_string(&juri).unwrap().into();
+
+    match NostrUri::parse(&uri) {
+        Some(uri) => emit_uri_opened(uri),
+        None => error!("could not open nostr link {uri}"),
+    }
+}
+
+pub fn try_show_notification(title: &str, body: &str, note_id: Option<NoteId>) {
+    if let Err(e) = show_notification(title, body, note_id) {
+        error!("Failed to show notification: {}", e);
+    }
+}
+
+pub fn show_notification(
+    title: &str,
+    body: &str,
+    note_id: Option<NoteId>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let vm = get_jvm();
+    let mut env = vm.attach_current_thread()?;
+
+    let jtitle = env.new_string(title)?;
+    let jbody = env.new_string(body)?;
+    // an empty note id means there is nothing to open
+    let jnote_id = env.new_string(note_id.map(|id| id.hex()).unwrap_or_default())?;
+
+    let context = unsafe { JObject::from_raw(ndk_context::android_context().context().cast()) };
+    env.call_method(
+        context,
+        "showNotification",
+        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
+        &[
+            JValue::Object(&jtitle),
+            JValue::Object(&jbody),
+            JValue::Object(&jnote_id),
+        ],
+    )?;
+
+    Ok(())
+}
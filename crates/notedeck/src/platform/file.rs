@@ -1,4 +1,4 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, sync::Mutex};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use once_cell::sync::Lazy;
@@ -87,13 +87,53 @@ impl SelectedMediaChannel {
     }
 }
 
-pub static SELECTED_MEDIA_CHANNEL: Lazy<SelectedMediaChannel> =
+/// Who opened the file picker. The android picker answers some frames
+/// later through [`emit_selected_file`], so each requester gets its own
+/// channel and the composer can't take the profile editor's picture
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FileRequester {
+    #[default]
+    Composer,
+    ProfileEditor,
+}
+
+impl FileRequester {
+    fn channel(self) -> &'static SelectedMediaChannel {
+        match self {
+            FileRequester::Composer => &COMPOSER_MEDIA_CHANNEL,
+            FileRequester::ProfileEditor => &PROFILE_EDITOR_MEDIA_CHANNEL,
+        }
+    }
+}
+
+static COMPOSER_MEDIA_CHANNEL: Lazy<SelectedMediaChannel> =
+    Lazy::new(SelectedMediaChannel::default);
+
+static PROFILE_EDITOR_MEDIA_CHANNEL: Lazy<SelectedMediaChannel> =
     Lazy::new(SelectedMediaChannel::default);
 
+/// Whoever opened the picker last, where [`emit_selected_file`] sends to
+static PICKER_REQUESTER: Mutex<FileRequester> = Mutex::new(FileRequester::Composer);
+
+pub fn set_file_requester(requester: FileRequester) {
+    if let Ok(mut current) = PICKER_REQUESTER.lock() {
+        *current = requester;
+    }
+}
+
+/// Hand a picked file to whoever opened the picker
 pub fn emit_selected_file(media: Result<SelectedMedia, Error>) {
-    SELECTED_MEDIA_CHANNEL.new_selected_file(media);
+    let requester = PICKER_REQUESTER
+        .lock()
+        .map(|requester| *requester)
+        .unwrap_or_default();
+    emit_selected_file_to(requester, media);
+}
+
+pub fn emit_selected_file_to(requester: FileRequester, media: Result<SelectedMedia, Error>) {
+    requester.channel().new_selected_file(media);
 }
 
-pub fn get_next_selected_file() -> Option<Result<SelectedMedia, Error>> {
-    SELECTED_MEDIA_CHANNEL.try_receive()
+pub fn get_next_selected_file(requester: FileRequester) -> Option<Result<SelectedMedia, Error>> {
+    requester.channel().try_receive()
 }
@@ -1,11 +1,17 @@
-use crate::{platform::file::SelectedMedia, Error};
+use crate::{
+    platform::file::{FileRequester, SelectedMedia},
+    Error,
+};
 
 #[cfg(target_os = "android")]
 pub mod android;
 pub mod file;
+mod notify;
 
-pub fn get_next_selected_file() -> Option<Result<SelectedMedia, Error>> {
-    file::get_next_selected_file()
+pub use notify::{emit_notification_clicked, get_next_clicked_notification, show_notification};
+
+pub fn get_next_selected_file(requester: FileRequester) -> Option<Result<SelectedMedia, Error>> {
+    file::get_next_selected_file(requester)
 }
 
 const VIRT_HEIGHT: i32 = 400;
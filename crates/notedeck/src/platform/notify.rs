@@ -0,0 +1,109 @@
+//! Native notifications. On android we go through the main activity, on
+//! desktop we use whatever the system has for notifications.
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use enostr::NoteId;
+use once_cell::sync::Lazy;
+
+struct ClickedNotificationChannel {
+    sender: Sender<NoteId>,
+    receiver: Receiver<NoteId>,
+}
+
+impl Default for ClickedNotificationChannel {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+        Self { sender, receiver }
+    }
+}
+
+static CLICKED_NOTIFICATION_CHANNEL: Lazy<ClickedNotificationChannel> =
+    Lazy::new(ClickedNotificationChannel::default);
+
+/// Called when the user clicks a native notification about a note
+pub fn emit_notification_clicked(note_id: NoteId) {
+    let _ = CLICKED_NOTIFICATION_CHANNEL.sender.send(note_id);
+}
+
+/// The next note the user wants to see from a native notification
+pub fn get_next_clicked_notification() -> Option<NoteId> {
+    CLICKED_NOTIFICATION_CHANNEL.receiver.try_recv().ok()
+}
+
+/// Show a native notification. Clicking it opens `note_id` where the
+/// platform supports it.
+pub fn show_notification(title: &str, body: &str, note_id: Option<NoteId>) {
+    #[cfg(target_os = "android")]
+    {
+        crate::platform::android::try_show_notification(title, body, note_id);
+    }
+
+    #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
+    {
+        show_notify_send(title.to_owned(), body.to_owned(), note_id);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // osascript can't tell us about clicks
+        let _ = note_id;
+        show_osascript(title, body);
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = (title, body, note_id);
+        tracing::debug!("native notifications are not supported on windows yet");
+    }
+}
+
+#[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
+fn show_notify_send(title: String, body: String, note_id: Option<NoteId>) {
+    use std::process::{Command, Stdio};
+
+    // notify-send blocks until the notification is closed when it has an
+    // action, so wait for it off the ui thread
+    std::thread::spawn(move || {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=Notedeck");
+        if note_id.is_some() {
+            cmd.arg("--action=default=Open");
+        }
+        cmd.arg(&title).arg(&body).stderr(Stdio::null());
+
+        let output = match cmd.output() {
+            Ok(output) => output,
+            Err(err) => {
+                tracing::debug!("could not run notify-send: {err}");
+                return;
+            }
+        };
+
+        if let Some(note_id) = note_id {
+            if String::from_utf8_lossy(&output.stdout).trim() == "default" {
+                emit_notification_clicked(note_id);
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn show_osascript(title: &str, body: &str) {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    let script = format!(
+        "display notification {} with title {}",
+        quote(body),
+        quote(title)
+    );
+
+    if let Err(err) = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .spawn()
+    {
+        tracing::debug!("could not run osascript: {err}");
+    }
+}
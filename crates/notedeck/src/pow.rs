@@ -0,0 +1,88 @@
+//! NIP-13 proof of work, used to hide spam from people you don't follow
+
+use nostrdb::Note;
+
+use crate::{IsFollowing, UserAccount};
+
+/// The number of leading zero bits in a note id
+pub fn difficulty(id: &[u8; 32]) -> u32 {
+    let mut bits = 0;
+    for byte in id {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            bits += byte.leading_zeros();
+            break;
+        }
+    }
+    bits
+}
+
+/// The difficulty the author committed to in the note's `nonce` tag.
+/// Without it a note could pass by being lucky instead of doing work.
+pub fn committed_target(note: &Note) -> Option<u32> {
+    note.tags().iter().find_map(|tag| {
+        if tag.get_str(0) != Some("nonce") {
+            return None;
+        }
+        tag.get_str(2)?.parse().ok()
+    })
+}
+
+/// Whether a note should be hidden for not doing enough work, with `min`
+/// the configured difficulty or 0 when the filter is off. Notes by the
+/// account itself and people it follows always pass.
+pub fn below_min_pow(note: &Note, account: &UserAccount, min: u8) -> bool {
+    if min == 0 || note.pubkey() == account.key.pubkey.bytes() {
+        return false;
+    }
+
+    // until the contact list arrives we can't tell strangers apart
+    if !matches!(account.is_following(note.pubkey()), IsFollowing::No) {
+        return false;
+    }
+
+    let min = min as u32;
+    difficulty(note.id()) < min || committed_target(note).is_none_or(|target| target < min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        let mut id = [0xff; 32];
+        assert_eq!(difficulty(&id), 0);
+
+        id[0] = 0;
+        id[1] = 0b0001_0000;
+        assert_eq!(difficulty(&id), 11);
+
+        assert_eq!(difficulty(&[0; 32]), 256);
+    }
+
+    #[test]
+    fn reads_the_committed_target() {
+        let seckey = [1; 32];
+        let note = nostrdb::NoteBuilder::new()
+            .kind(1)
+            .content("work")
+            .start_tag()
+            .tag_str("nonce")
+            .tag_str("776797")
+            .tag_str("20")
+            .sign(&seckey)
+            .build()
+            .unwrap();
+        assert_eq!(committed_target(&note), Some(20));
+
+        let lazy = nostrdb::NoteBuilder::new()
+            .kind(1)
+            .content("no work")
+            .sign(&seckey)
+            .build()
+            .unwrap();
+        assert_eq!(committed_target(&lazy), None);
+    }
+}
@@ -0,0 +1,247 @@
+//! Which relays accepted the notes we published. Relays answer every
+//! EVENT with an OK message (NIP-01) that says whether they stored it and
+//! why not. The tracker keeps those answers per note so apps can show
+//! them next to what the user just posted. Published notes wait in the
+//! [`Outbox`] until every write relay answered them.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use enostr::{ClientMessage, CommandResult, NoteId, PoolRelay, RelayPool};
+use nostrdb::Note;
+
+use crate::outbox::{queued_note_id, Outbox};
+
+/// How many published notes we keep receipts for
+const MAX_TRACKED: usize = 200;
+
+/// How long we wait for a relay to answer before giving up on it
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayReceipt {
+    Pending,
+    Accepted,
+    /// The relay refused the note, with its reason
+    Rejected(String),
+}
+
+/// The answers we got for one published note
+#[derive(Debug, Clone)]
+pub struct PublishReceipt {
+    sent_at: Instant,
+    /// Waiting in the outbox for a relay to connect
+    queued: bool,
+    relays: BTreeMap<String, RelayReceipt>,
+}
+
+impl PublishReceipt {
+    fn new(relays: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            sent_at: Instant::now(),
+            queued: false,
+            relays: relays
+                .into_iter()
+                .map(|relay| (relay, RelayReceipt::Pending))
+                .collect(),
+        }
+    }
+
+    pub fn relays(&self) -> impl Iterator<Item = (&str, &RelayReceipt)> {
+        self.relays
+            .iter()
+            .map(|(url, receipt)| (url.as_str(), receipt))
+    }
+
+    pub fn is_queued(&self) -> bool {
+        self.queued
+    }
+
+    pub fn total(&self) -> usize {
+        self.relays.len()
+    }
+
+    pub fn accepted(&self) -> usize {
+        self.count(|r| *r == RelayReceipt::Accepted)
+    }
+
+    pub fn rejected(&self) -> usize {
+        self.count(|r| matches!(r, RelayReceipt::Rejected(_)))
+    }
+
+    /// Are we still waiting on some relays? Relays that don't answer
+    /// within a while are no longer waited on.
+    pub fn is_pending(&self) -> bool {
+        self.sent_at.elapsed() < RECEIPT_TIMEOUT && self.count(|r| *r == RelayReceipt::Pending) > 0
+    }
+
+    fn count(&self, pred: impl Fn(&RelayReceipt) -> bool) -> usize {
+        self.relays.values().filter(|r| pred(r)).count()
+    }
+}
+
+/// Correlates published notes with the relays' OK answers
+#[derive(Default)]
+pub struct PublishTracker {
+    receipts: HashMap<NoteId, PublishReceipt>,
+    /// Oldest first, so old receipts can be dropped
+    order: VecDeque<NoteId>,
+    outbox: Outbox,
+}
+
+/// The relays that answer what we publish. The multicast relay is other
+/// notedecks on the network, they don't, and read-only relays don't get
+/// our notes.
+fn answering_relays(pool: &RelayPool) -> Vec<String> {
+    pool.relays
+        .iter()
+        .filter(|r| matches!(r, PoolRelay::Websocket(_)) && r.usage().write)
+        .map(|r| r.url().to_string())
+        .collect()
+}
+
+impl PublishTracker {
+    pub fn new(outbox: Outbox) -> Self {
+        Self {
+            outbox,
+            ..Default::default()
+        }
+    }
+
+    /// Send `note` to every write relay in the pool and track their
+    /// answers. It stays in the outbox until they all answered, relays
+    /// that aren't connected get it once they are.
+    pub fn publish(&mut self, pool: &mut RelayPool, note: &Note) -> Result<(), enostr::Error> {
+        self.send(pool, NoteId::new(*note.id()), note.json()?)
+    }
+
+    /// [`PublishTracker::publish`] for notes we only have as json, like
+    /// the ones a remote signer signed
+    pub fn publish_json(
+        &mut self,
+        pool: &mut RelayPool,
+        json: String,
+    ) -> Result<(), enostr::Error> {
+        let note_id = queued_note_id(&json)
+            .ok_or_else(|| enostr::Error::Generic("signed note has no id".to_owned()))?;
+        self.send(pool, note_id, json)
+    }
+
+    fn send(
+        &mut self,
+        pool: &mut RelayPool,
+        note_id: NoteId,
+        json: String,
+    ) -> Result<(), enostr::Error> {
+        // fail here rather than in the outbox
+        ClientMessage::event_json(json.clone())?;
+
+        self.outbox.queue(json);
+        let mut receipt = PublishReceipt::new([]);
+        receipt.queued = true;
+        self.insert(note_id, receipt);
+
+        self.update(pool);
+        Ok(())
+    }
+
+    /// Watch the connection and send the outbox to relays as they
+    /// connect. Called every frame.
+    pub fn update(&mut self, pool: &mut RelayPool) {
+        for note_id in self.outbox.update(pool) {
+            self.track(note_id, answering_relays(pool));
+        }
+    }
+
+    /// Have all relays been down for a while?
+    pub fn is_offline(&self) -> bool {
+        self.outbox.is_offline()
+    }
+
+    /// How many notes are waiting for a connection
+    pub fn queued(&self) -> usize {
+        self.outbox.unsent()
+    }
+
+    /// Start tracking a note that was sent to `relays`
+    pub fn track(&mut self, note_id: NoteId, relays: impl IntoIterator<Item = String>) {
+        self.insert(note_id, PublishReceipt::new(relays));
+    }
+
+    fn insert(&mut self, note_id: NoteId, receipt: PublishReceipt) {
+        if self.receipts.insert(note_id, receipt).is_none() {
+            self.order.push_back(note_id);
+        }
+
+        while self.order.len() > MAX_TRACKED {
+            if let Some(oldest) = self.order.pop_front() {
+                self.receipts.remove(&oldest);
+            }
+        }
+    }
+
+    /// Record a relay's OK message
+    pub fn handle_ok(&mut self, pool: &RelayPool, relay: &str, result: &CommandResult<'_>) {
+        let Ok(note_id) = NoteId::from_hex(result.event_id()) else {
+            return;
+        };
+
+        let answer = if result.accepted() {
+            RelayReceipt::Accepted
+        } else if enostr::is_rate_limited(result.message()) {
+            // the pool sends it again after backing off
+            RelayReceipt::Pending
+        } else {
+            RelayReceipt::Rejected(result.message().to_owned())
+        };
+
+        if answer != RelayReceipt::Pending {
+            self.outbox.handle_answer(pool, relay, &note_id);
+        }
+
+        if let Some(receipt) = self.receipts.get_mut(&note_id) {
+            receipt.relays.insert(relay.to_owned(), answer);
+        }
+    }
+
+    pub fn receipt(&self, note_id: &NoteId) -> Option<&PublishReceipt> {
+        self.receipts.get(note_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enostr::RelayMessage;
+
+    #[test]
+    fn ok_messages_update_receipts() {
+        let pool = RelayPool::new();
+        let mut tracker = PublishTracker::default();
+        let id = NoteId::new([7; 32]);
+        let hex = id.hex();
+        tracker.track(id, ["wss://a".to_owned(), "wss://b".to_owned()]);
+
+        let receipt = tracker.receipt(&id).unwrap();
+        assert!(receipt.is_pending());
+        assert_eq!(receipt.total(), 2);
+
+        let RelayMessage::OK(ok) = RelayMessage::ok(&hex, true, "") else {
+            unreachable!()
+        };
+        tracker.handle_ok(&pool, "wss://a", &ok);
+
+        let RelayMessage::OK(refused) = RelayMessage::ok(&hex, false, "blocked: spam") else {
+            unreachable!()
+        };
+        tracker.handle_ok(&pool, "wss://b", &refused);
+
+        let receipt = tracker.receipt(&id).unwrap();
+        assert!(!receipt.is_pending());
+        assert_eq!(receipt.accepted(), 1);
+        assert_eq!(receipt.rejected(), 1);
+        assert!(receipt
+            .relays()
+            .any(|(_, r)| *r == RelayReceipt::Rejected("blocked: spam".to_owned())));
+    }
+}
@@ -1,3 +1,4 @@
+use enostr::RelayUsage;
 use std::cmp::Ordering;
 use std::fmt;
 
@@ -43,6 +44,14 @@ impl RelaySpec {
     pub fn is_writable(&self) -> bool {
         !self.has_read_marker // only "read" relays are not writable
     }
+
+    /// How the relay pool should use this relay
+    pub fn usage(&self) -> RelayUsage {
+        RelayUsage {
+            read: self.is_readable(),
+            write: self.is_writable(),
+        }
+    }
 }
 
 // just the url part
@@ -1,5 +1,6 @@
 use crate::fonts;
 use crate::theme;
+use crate::theme::ThemeOptions;
 use crate::NotedeckOptions;
 use crate::NotedeckTextStyle;
 use egui::FontId;
@@ -9,6 +10,7 @@ pub fn setup_egui_context(
     ctx: &egui::Context,
     options: NotedeckOptions,
     theme: ThemePreference,
+    theme_options: ThemeOptions,
     note_body_font_size: f32,
     zoom_factor: f32,
 ) {
@@ -19,8 +21,7 @@ pub fn setup_egui_context(
         tracing::info!("Loaded theme {:?} from disk", theme);
         o.theme_preference = theme;
     });
-    ctx.set_visuals_of(egui::Theme::Dark, theme::dark_mode(is_oled));
-    ctx.set_visuals_of(egui::Theme::Light, theme::light_mode());
+    theme::setup_theme(ctx, is_oled, theme_options);
 
     fonts::setup_fonts(ctx);
 
@@ -32,6 +33,8 @@ pub fn setup_egui_context(
 
     ctx.options_mut(|o| {
         o.input_options.max_click_duration = 0.4;
+        // see zoom::handle_shortcuts
+        o.zoom_with_keyboard = false;
     });
     ctx.all_styles_mut(|style| crate::theme::add_custom_style(is_mobile, style));
 
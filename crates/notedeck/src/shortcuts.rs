@@ -0,0 +1,189 @@
+//! Keyboard shortcuts shared by every app, so they can be listed in one
+//! cheat sheet, checked for collisions and remapped by the user.
+//!
+//! Apps declare their shortcuts as [`Shortcut`] constants and check them
+//! with [`consume`] instead of matching keys themselves. The registry
+//! lives in egui's memory, so widgets without an [`crate::AppContext`]
+//! can use it too.
+
+use std::collections::BTreeMap;
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use crate::{tr, Localization};
+
+/// A keyboard shortcut an app offers
+#[derive(Clone, Copy)]
+pub struct Shortcut {
+    /// Stable id that remappings are saved under, eg. `media.next`
+    pub id: &'static str,
+    /// Which part of notedeck it belongs to, for the cheat sheet
+    pub group: fn(&mut Localization) -> String,
+    pub description: fn(&mut Localization) -> String,
+    pub default: KeyboardShortcut,
+}
+
+impl Shortcut {
+    pub const fn new(
+        id: &'static str,
+        group: fn(&mut Localization) -> String,
+        description: fn(&mut Localization) -> String,
+        default: KeyboardShortcut,
+    ) -> Self {
+        Self {
+            id,
+            group,
+            description,
+            default,
+        }
+    }
+}
+
+impl std::fmt::Debug for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shortcut")
+            .field("id", &self.id)
+            .field("default", &self.default)
+            .finish()
+    }
+}
+
+/// The group of shortcuts that work everywhere in notedeck
+pub fn general_group(i18n: &mut Localization) -> String {
+    tr!(
+        i18n,
+        "General",
+        "Keyboard shortcut group for app-wide shortcuts"
+    )
+}
+
+/// Open the list of keyboard shortcuts
+pub const CHEAT_SHEET: Shortcut = Shortcut::new(
+    "general.cheat_sheet",
+    general_group,
+    |i18n| {
+        tr!(
+            i18n,
+            "Show keyboard shortcuts",
+            "Keyboard shortcut description"
+        )
+    },
+    KeyboardShortcut::new(Modifiers::NONE, Key::Questionmark),
+);
+
+#[derive(Default, Clone)]
+struct Registry {
+    /// Everything registered so far, in registration order
+    shortcuts: Vec<Shortcut>,
+    /// User bindings by shortcut id
+    remapped: BTreeMap<String, KeyboardShortcut>,
+}
+
+impl Registry {
+    fn register(&mut self, shortcut: &Shortcut) {
+        if !self.shortcuts.iter().any(|s| s.id == shortcut.id) {
+            self.shortcuts.push(*shortcut);
+        }
+    }
+
+    fn binding(&self, shortcut: &Shortcut) -> KeyboardShortcut {
+        self.remapped
+            .get(shortcut.id)
+            .copied()
+            .unwrap_or(shortcut.default)
+    }
+
+    fn conflicts(&self, shortcut: &Shortcut) -> Vec<Shortcut> {
+        let binding = self.binding(shortcut);
+        self.shortcuts
+            .iter()
+            .filter(|other| other.id != shortcut.id && self.binding(other) == binding)
+            .copied()
+            .collect()
+    }
+}
+
+fn with_registry<R>(ctx: &egui::Context, f: impl FnOnce(&mut Registry) -> R) -> R {
+    ctx.data_mut(|d| f(d.get_temp_mut_or_default(egui::Id::new("notedeck_shortcuts"))))
+}
+
+/// Make shortcuts show up in the cheat sheet and settings before they are
+/// first used
+pub fn register(ctx: &egui::Context, shortcuts: &[Shortcut]) {
+    with_registry(ctx, |registry| {
+        for shortcut in shortcuts {
+            registry.register(shortcut);
+        }
+    });
+}
+
+/// Whether the shortcut was pressed, taking the key press out of the input
+/// so nothing else reacts to it
+pub fn consume(ctx: &egui::Context, shortcut: &Shortcut) -> bool {
+    let binding = with_registry(ctx, |registry| {
+        registry.register(shortcut);
+        registry.binding(shortcut)
+    });
+
+    ctx.input_mut(|i| i.consume_shortcut(&binding))
+}
+
+/// The keys that trigger a shortcut right now
+pub fn binding(ctx: &egui::Context, shortcut: &Shortcut) -> KeyboardShortcut {
+    with_registry(ctx, |registry| registry.binding(shortcut))
+}
+
+/// Other shortcuts bound to the same keys
+pub fn conflicts(ctx: &egui::Context, shortcut: &Shortcut) -> Vec<Shortcut> {
+    with_registry(ctx, |registry| registry.conflicts(shortcut))
+}
+
+/// Everything registered, in registration order
+pub fn registered(ctx: &egui::Context) -> Vec<Shortcut> {
+    with_registry(ctx, |registry| registry.shortcuts.clone())
+}
+
+/// Bind a shortcut to other keys, or back to its default with `None`
+pub fn set_binding(ctx: &egui::Context, id: &str, binding: Option<KeyboardShortcut>) {
+    with_registry(ctx, |registry| match binding {
+        Some(binding) => registry.remapped.insert(id.to_owned(), binding),
+        None => registry.remapped.remove(id),
+    });
+}
+
+/// Use the user's saved bindings
+pub fn set_bindings(ctx: &egui::Context, bindings: BTreeMap<String, KeyboardShortcut>) {
+    with_registry(ctx, |registry| registry.remapped = bindings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Shortcut = Shortcut::new(
+        "test.a",
+        general_group,
+        general_group,
+        KeyboardShortcut::new(Modifiers::NONE, Key::A),
+    );
+    const B: Shortcut = Shortcut::new(
+        "test.b",
+        general_group,
+        general_group,
+        KeyboardShortcut::new(Modifiers::NONE, Key::B),
+    );
+
+    #[test]
+    fn remapping_can_conflict() {
+        let mut registry = Registry::default();
+        registry.register(&A);
+        registry.register(&B);
+        registry.register(&A);
+        assert_eq!(registry.shortcuts.len(), 2);
+        assert!(registry.conflicts(&A).is_empty());
+
+        registry.remapped.insert(B.id.to_owned(), A.default);
+        assert_eq!(registry.binding(&B), A.default);
+        assert_eq!(registry.conflicts(&A)[0].id, B.id);
+    }
+}
@@ -9,7 +9,9 @@ use egui::FontId;
 use egui::Stroke;
 use egui::Style;
 use egui::Visuals;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 pub const PURPLE: Color32 = Color32::from_rgb(0xCC, 0x43, 0xC5);
 const PURPLE_ALT: Color32 = Color32::from_rgb(0x82, 0x56, 0xDD);
@@ -26,6 +28,18 @@ const DARK_BG: Color32 = Color32::from_rgb(0x2C, 0x2C, 0x2C);
 const DARK_ISH_BG: Color32 = Color32::from_rgb(0x25, 0x25, 0x25);
 const SEMI_DARK_BG: Color32 = Color32::from_rgb(0x44, 0x44, 0x44);
 
+// ACCENTS
+const BLUE: Color32 = Color32::from_rgb(0x3B, 0x82, 0xF6);
+const BLUE_ALT: Color32 = Color32::from_rgb(0x25, 0x63, 0xEB);
+const GREEN: Color32 = Color32::from_rgb(0x22, 0xC5, 0x5E);
+const GREEN_ALT: Color32 = Color32::from_rgb(0x16, 0xA3, 0x4A);
+const ORANGE: Color32 = Color32::from_rgb(0xF9, 0x73, 0x16);
+const ORANGE_ALT: Color32 = Color32::from_rgb(0xEA, 0x58, 0x0C);
+const PINK: Color32 = Color32::from_rgb(0xE4, 0x5A, 0xC9);
+const PINK_ALT: Color32 = Color32::from_rgb(0xC0, 0x3E, 0xA8);
+const TEAL: Color32 = Color32::from_rgb(0x14, 0xB8, 0xA6);
+const TEAL_ALT: Color32 = Color32::from_rgb(0x0D, 0x94, 0x88);
+
 const LIGHTER_GRAY: Color32 = Color32::from_rgb(0xf8, 0xf8, 0xf8);
 const LIGHT_GRAY: Color32 = Color32::from_rgb(0xc8, 0xc8, 0xc8); // 78%
 const DARKER_GRAY: Color32 = Color32::from_rgb(0xa5, 0xa5, 0xa5); // 65%
@@ -57,6 +71,68 @@ pub struct ColorTheme {
     pub inactive_weak_bg_fill: Color32,
 }
 
+/// The color links, selections and highlights use
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum Accent {
+    #[default]
+    Purple,
+    Blue,
+    Green,
+    Orange,
+    Pink,
+    Teal,
+}
+
+impl Accent {
+    pub fn color(&self) -> Color32 {
+        match self {
+            Accent::Purple => PURPLE,
+            Accent::Blue => BLUE,
+            Accent::Green => GREEN,
+            Accent::Orange => ORANGE,
+            Accent::Pink => PINK,
+            Accent::Teal => TEAL,
+        }
+    }
+
+    /// Darker shade for selection backgrounds
+    pub fn selection_color(&self) -> Color32 {
+        match self {
+            Accent::Purple => PURPLE_ALT,
+            Accent::Blue => BLUE_ALT,
+            Accent::Green => GREEN_ALT,
+            Accent::Orange => ORANGE_ALT,
+            Accent::Pink => PINK_ALT,
+            Accent::Teal => TEAL_ALT,
+        }
+    }
+}
+
+/// What the user picked on top of light and dark mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeOptions {
+    pub accent: Accent,
+    pub high_contrast: bool,
+}
+
+impl ThemeOptions {
+    pub fn new(accent: Accent, high_contrast: bool) -> Self {
+        Self {
+            accent,
+            high_contrast,
+        }
+    }
+
+    fn apply(&self, theme: ColorTheme) -> ColorTheme {
+        ColorTheme {
+            hyperlink_color: self.accent.color(),
+            selection_color: self.accent.selection_color(),
+            ..theme
+        }
+    }
+}
+
 const WIDGET_CORNER_RADIUS: CornerRadius = CornerRadius::same(8);
 
 pub fn create_themed_visuals(theme: ColorTheme, default: Visuals) -> Visuals {
@@ -179,6 +255,62 @@ pub fn light_color_theme() -> ColorTheme {
     }
 }
 
+pub fn high_contrast_dark_color_theme() -> ColorTheme {
+    ColorTheme {
+        // VISUALS
+        panel_fill: Color32::BLACK,
+        extreme_bg_color: Color32::BLACK,
+        text_color: Color32::WHITE,
+        err_fg_color: Color32::from_rgb(0xFF, 0x6B, 0x6B),
+        warn_fg_color: Color32::from_rgb(0xFF, 0xD1, 0x66),
+        hyperlink_color: PURPLE,
+        selection_color: PURPLE_ALT,
+
+        // WINDOW
+        window_fill: Color32::BLACK,
+        window_stroke_color: LIGHT_GRAY,
+
+        // NONINTERACTIVE WIDGET
+        noninteractive_bg_fill: Color32::BLACK,
+        noninteractive_weak_bg_fill: DARKER_BG,
+        noninteractive_bg_stroke_color: LIGHT_GRAY,
+        noninteractive_fg_stroke_color: LIGHTER_GRAY,
+
+        // INACTIVE WIDGET
+        inactive_bg_stroke_color: LIGHT_GRAY,
+        inactive_bg_fill: Color32::BLACK,
+        inactive_weak_bg_fill: SEMI_DARKER_BG,
+    }
+}
+
+pub fn high_contrast_light_color_theme() -> ColorTheme {
+    ColorTheme {
+        // VISUALS
+        panel_fill: Color32::WHITE,
+        extreme_bg_color: Color32::WHITE,
+        text_color: Color32::BLACK,
+        err_fg_color: Color32::from_rgb(0xA3, 0x00, 0x1F),
+        warn_fg_color: Color32::from_rgb(0x8A, 0x4B, 0x00),
+        hyperlink_color: PURPLE,
+        selection_color: PURPLE_ALT,
+
+        // WINDOW
+        window_fill: Color32::WHITE,
+        window_stroke_color: BLACK,
+
+        // NONINTERACTIVE WIDGET
+        noninteractive_bg_fill: Color32::WHITE,
+        noninteractive_weak_bg_fill: LIGHTER_GRAY,
+        noninteractive_bg_stroke_color: BLACK,
+        noninteractive_fg_stroke_color: DARK_BG,
+
+        // INACTIVE WIDGET
+        inactive_bg_stroke_color: BLACK,
+        inactive_bg_fill: Color32::WHITE,
+        inactive_weak_bg_fill: LIGHT_GRAY,
+    }
+}
+
 /// Create custom text sizes for any FontSizes
 pub fn add_custom_style(is_mobile: bool, style: &mut Style) {
     let font_size = if is_mobile {
@@ -228,17 +360,135 @@ pub fn add_custom_style(is_mobile: bool, style: &mut Style) {
     */
 }
 
-pub fn light_mode() -> Visuals {
-    create_themed_visuals(crate::theme::light_color_theme(), Visuals::light())
+pub fn light_mode(options: ThemeOptions) -> Visuals {
+    let theme = if options.high_contrast {
+        high_contrast_light_color_theme()
+    } else {
+        light_color_theme()
+    };
+    create_themed_visuals(options.apply(theme), Visuals::light())
+}
+
+pub fn dark_mode(is_oled: bool, options: ThemeOptions) -> Visuals {
+    let theme = if options.high_contrast {
+        high_contrast_dark_color_theme()
+    } else if is_oled {
+        mobile_dark_color_theme()
+    } else {
+        desktop_dark_color_theme()
+    };
+    create_themed_visuals(options.apply(theme), Visuals::dark())
+}
+
+fn is_oled_id() -> egui::Id {
+    egui::Id::new("notedeck_theme_is_oled")
+}
+
+fn options_id() -> egui::Id {
+    egui::Id::new("notedeck_theme_options")
+}
+
+/// Set the light and dark visuals for the user's theme options
+pub fn set_theme_options(ctx: &egui::Context, options: ThemeOptions) {
+    let is_oled = ctx.data(|d| d.get_temp(is_oled_id())).unwrap_or(false);
+
+    ctx.set_visuals_of(egui::Theme::Dark, dark_mode(is_oled, options));
+    ctx.set_visuals_of(egui::Theme::Light, light_mode(options));
+    ctx.data_mut(|d| d.insert_temp(options_id(), options));
+}
+
+pub(crate) fn setup_theme(ctx: &egui::Context, is_oled: bool, options: ThemeOptions) {
+    ctx.data_mut(|d| d.insert_temp(is_oled_id(), is_oled));
+    set_theme_options(ctx, options);
 }
 
-pub fn dark_mode(is_oled: bool) -> Visuals {
-    create_themed_visuals(
-        if is_oled {
-            mobile_dark_color_theme()
+pub fn theme_options(ctx: &egui::Context) -> ThemeOptions {
+    ctx.data(|d| d.get_temp(options_id())).unwrap_or_default()
+}
+
+/// Colors for apps to derive their own from, instead of hard-coding them,
+/// so they follow light/dark mode, the accent and high contrast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub accent: Color32,
+    /// Background of selected items
+    pub selection: Color32,
+    pub background: Color32,
+    /// Background of cards, inputs and other things on top of `background`
+    pub surface: Color32,
+    pub text: Color32,
+    pub weak_text: Color32,
+    pub error: Color32,
+    pub warn: Color32,
+    pub high_contrast: bool,
+}
+
+impl Palette {
+    pub fn new(ctx: &egui::Context) -> Self {
+        Self::from_visuals(&ctx.style().visuals, theme_options(ctx).high_contrast)
+    }
+
+    pub fn from_visuals(visuals: &Visuals, high_contrast: bool) -> Self {
+        Self {
+            accent: visuals.hyperlink_color,
+            selection: visuals.selection.bg_fill,
+            background: visuals.panel_fill,
+            surface: visuals.extreme_bg_color,
+            text: visuals.text_color(),
+            weak_text: if high_contrast {
+                visuals.text_color()
+            } else {
+                visuals.weak_text_color()
+            },
+            error: visuals.error_fg_color,
+            warn: visuals.warn_fg_color,
+            high_contrast,
+        }
+    }
+
+    /// A color for the `index`th of a set of things that need telling
+    /// apart, like calendars or event categories. Starts at the accent and
+    /// steps around the color wheel.
+    pub fn series(&self, index: usize) -> Color32 {
+        // golden angle, so neighbours are always far apart
+        const STEP: f32 = 0.381_966;
+
+        if index == 0 {
+            return self.accent;
+        }
+
+        let mut hsva = egui::ecolor::Hsva::from(self.accent);
+        hsva.h = (hsva.h + STEP * index as f32).fract();
+        if self.high_contrast {
+            hsva.s = hsva.s.max(0.8);
+        }
+        Color32::from(hsva)
+    }
+
+    /// Text color that reads well on top of `fill`
+    pub fn text_on(&self, fill: Color32) -> Color32 {
+        let [r, g, b, _] = fill.to_array();
+        let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+        if luma > 150.0 {
+            Color32::BLACK
         } else {
-            desktop_dark_color_theme()
-        },
-        Visuals::dark(),
-    )
+            Color32::WHITE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_follows_accent() {
+        let options = ThemeOptions::new(Accent::Teal, false);
+        let palette = Palette::from_visuals(&dark_mode(false, options), false);
+
+        assert_eq!(palette.accent, TEAL);
+        assert_eq!(palette.series(0), palette.accent);
+        assert_ne!(palette.series(1), palette.series(2));
+        assert_eq!(palette.text_on(Color32::WHITE), Color32::BLACK);
+    }
 }
@@ -0,0 +1,242 @@
+//! Translating note content through a LibreTranslate compatible service.
+//! That can be one running locally or a hosted API with a key.
+
+use std::collections::HashMap;
+
+use enostr::NoteId;
+use poll_promise::Promise;
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// A LibreTranslate style `/translate` endpoint, for example
+///
+/// ```text
+/// http://localhost:5000/translate
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translator {
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+impl Translator {
+    pub fn new(endpoint: &str, api_key: Option<String>) -> Option<Self> {
+        let endpoint = endpoint.trim();
+        let parsed = url::Url::parse(endpoint);
+        if !parsed.is_ok_and(|u| u.scheme() == "https" || u.scheme() == "http") {
+            return None;
+        }
+
+        Some(Self {
+            endpoint: endpoint.to_owned(),
+            api_key: api_key.filter(|key| !key.trim().is_empty()),
+        })
+    }
+
+    fn request(&self, text: &str, target_language: &str) -> Result<ehttp::Request, Error> {
+        let body = serde_json::to_vec(&TranslateRequest {
+            q: text,
+            source: "auto",
+            target: target_language,
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        })?;
+
+        Ok(ehttp::Request {
+            method: "POST".to_owned(),
+            url: self.endpoint.clone(),
+            headers: ehttp::Headers::new(&[
+                ("Accept", "application/json"),
+                ("Content-Type", "application/json"),
+            ]),
+            body,
+        })
+    }
+}
+
+fn translate(
+    ctx: &egui::Context,
+    translator: Option<&Translator>,
+    text: &str,
+    target_language: &str,
+) -> Promise<Result<String, Error>> {
+    let (sender, promise) = Promise::new();
+
+    let Some(translator) = translator else {
+        sender.send(Err(Error::Generic(
+            "no translation service configured".to_owned(),
+        )));
+        return promise;
+    };
+
+    let request = match translator.request(text, target_language) {
+        Ok(request) => request,
+        Err(err) => {
+            sender.send(Err(err));
+            return promise;
+        }
+    };
+
+    let ctx = ctx.clone();
+    ehttp::fetch(request, move |response| {
+        let result = response.map_err(Error::Generic).and_then(|response| {
+            if !response.ok {
+                return Err(Error::Generic(format!(
+                    "translation failed: {} {}",
+                    response.status, response.status_text
+                )));
+            }
+
+            let translated: TranslateResponse = serde_json::from_slice(&response.bytes)?;
+            Ok(translated.translated_text)
+        });
+
+        sender.send(result);
+        ctx.request_repaint();
+    });
+
+    promise
+}
+
+enum TranslationState {
+    Pending(Promise<Result<String, Error>>),
+    Done(String),
+    Failed(String),
+}
+
+struct Translation {
+    state: TranslationState,
+    /// Showing the translation instead of only the original
+    shown: bool,
+}
+
+/// What to show for a note's translation
+pub enum TranslationView<'a> {
+    /// Not translated, or the user reverted to the original
+    Original,
+    Pending,
+    Translated(&'a str),
+    Failed(&'a str),
+}
+
+/// Translations of notes, by note id, so each note is only sent to the
+/// service once
+#[derive(Default)]
+pub struct Translations {
+    /// The configured service, set from settings
+    translator: Option<Translator>,
+    translations: HashMap<NoteId, Translation>,
+}
+
+impl Translations {
+    pub fn new(translator: Option<Translator>) -> Self {
+        Self {
+            translator,
+            translations: HashMap::new(),
+        }
+    }
+
+    /// Set or clear the service used for translations
+    pub fn set_translator(&mut self, translator: Option<Translator>) {
+        self.translator = translator;
+    }
+
+    /// Is there a service to translate with?
+    pub fn is_configured(&self) -> bool {
+        self.translator.is_some()
+    }
+
+    /// Show the translation of a note, asking the service for it if it
+    /// wasn't translated yet or failed before
+    pub fn show(&mut self, ctx: &egui::Context, note_id: NoteId, text: &str, target: &str) {
+        if let Some(translation) = self.translations.get_mut(&note_id) {
+            if !matches!(translation.state, TranslationState::Failed(_)) {
+                translation.shown = true;
+                return;
+            }
+        }
+
+        self.translations.insert(
+            note_id,
+            Translation {
+                state: TranslationState::Pending(translate(
+                    ctx,
+                    self.translator.as_ref(),
+                    text,
+                    target,
+                )),
+                shown: true,
+            },
+        );
+    }
+
+    /// Go back to the original, keeping the translation for later
+    pub fn revert(&mut self, note_id: &NoteId) {
+        if let Some(translation) = self.translations.get_mut(note_id) {
+            translation.shown = false;
+        }
+    }
+
+    pub fn view(&mut self, note_id: &NoteId) -> TranslationView<'_> {
+        let Some(translation) = self.translations.get_mut(note_id) else {
+            return TranslationView::Original;
+        };
+
+        if let TranslationState::Pending(promise) = &mut translation.state {
+            if let Some(result) = promise.ready_mut() {
+                translation.state = match std::mem::replace(result, Ok(String::new())) {
+                    Ok(text) => TranslationState::Done(text),
+                    Err(err) => TranslationState::Failed(err.to_string()),
+                };
+            }
+        }
+
+        if !translation.shown {
+            return TranslationView::Original;
+        }
+
+        match &translation.state {
+            TranslationState::Pending(_) => TranslationView::Pending,
+            TranslationState::Done(text) => TranslationView::Translated(text),
+            TranslationState::Failed(err) => TranslationView::Failed(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translator_builds_libretranslate_request() {
+        assert!(Translator::new("not a url", None).is_none());
+
+        let translator =
+            Translator::new(" http://localhost:5000/translate ", Some(" ".to_owned())).unwrap();
+        let request = translator.request("hola", "en").unwrap();
+        assert_eq!(request.url, "http://localhost:5000/translate");
+
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"q": "hola", "source": "auto", "target": "en", "format": "text"})
+        );
+    }
+}
@@ -4,12 +4,12 @@ use crate::{
     Result,
 };
 
-use enostr::{Filter, NoteId, Pubkey};
+use enostr::{ClientMessage, Filter, NoteId, Pubkey, RelayPool};
 use nostr::RelayUrl;
 use nostrdb::{BlockType, Mention, Ndb, Note, NoteKey, Transaction};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
-use tracing::error;
+use tracing::{debug, error};
 
 #[must_use = "process_action should be used on this result"]
 pub enum SingleUnkIdAction {
@@ -79,10 +79,24 @@ impl SingleUnkIdAction {
     }
 }
 
-/// Unknown Id searcher
+/// How many ids we ask relays for in one request
+const MAX_BATCH: usize = 500;
+
+/// How long we wait for relays to answer before asking for an id again
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Unknown Id searcher. Every app adds the ids it is missing here, and
+/// they are requested from relays together in batches.
 #[derive(Default, Debug)]
 pub struct UnknownIds {
     ids: HashMap<UnknownId, HashSet<RelayUrl>>,
+    /// Ids we asked relays for and when, so ids that keep showing up as
+    /// missing while we wait aren't requested again
+    in_flight: HashMap<UnknownId, Instant>,
+    /// Sub ids of the batches relays are still answering. Every batch
+    /// gets its own so a new one doesn't replace one that isn't done yet
+    batches: HashMap<String, Instant>,
+    next_batch: u64,
     first_updated: Option<Instant>,
     last_updated: Option<Instant>,
 }
@@ -126,6 +140,73 @@ impl UnknownIds {
         get_unknown_ids_filter(&ids)
     }
 
+    fn is_in_flight(&self, id: &UnknownId) -> bool {
+        self.in_flight
+            .get(id)
+            .is_some_and(|requested| requested.elapsed() < REQUEST_TIMEOUT)
+    }
+
+    /// Take the next batch of ids to request, leaving out ones that are
+    /// still in flight. The batch is marked as in flight.
+    pub fn take_batch_filter(&mut self) -> Option<Vec<Filter>> {
+        let now = Instant::now();
+        self.in_flight
+            .retain(|_, requested| now - *requested < REQUEST_TIMEOUT);
+
+        let in_flight = &self.in_flight;
+        self.ids.retain(|id, _| !in_flight.contains_key(id));
+
+        let batch: Vec<UnknownId> = self.ids.keys().take(MAX_BATCH).copied().collect();
+        for id in &batch {
+            self.ids.remove(id);
+            self.in_flight.insert(*id, now);
+        }
+
+        get_unknown_ids_filter(&batch.iter().collect::<Vec<_>>())
+    }
+
+    /// Ask relays for the next batch of unknown ids
+    pub fn send(&mut self, pool: &mut RelayPool) {
+        let Some(filter) = self.take_batch_filter() else {
+            return;
+        };
+
+        // relays that never finished a batch get it closed when it times out
+        let now = Instant::now();
+        let expired: Vec<String> = self
+            .batches
+            .iter()
+            .filter(|(_, sent)| now - **sent >= REQUEST_TIMEOUT)
+            .map(|(subid, _)| subid.clone())
+            .collect();
+        for subid in expired {
+            self.batches.remove(&subid);
+            pool.unsubscribe(subid);
+        }
+
+        let subid = format!("unknownids-{}", self.next_batch);
+        self.next_batch += 1;
+
+        debug!(
+            "Getting {} unknown ids from relays on {subid}, {} left",
+            self.in_flight.len(),
+            self.ids.len()
+        );
+        pool.send(&ClientMessage::req(subid.clone(), filter));
+        self.batches.insert(subid, now);
+    }
+
+    /// Close a batch on a relay once it has sent everything it has.
+    /// Returns false if `subid` isn't one of our batches.
+    pub fn handle_eose(&mut self, pool: &mut RelayPool, subid: &str, relay_url: &str) -> bool {
+        if !self.batches.contains_key(subid) {
+            return false;
+        }
+
+        pool.send_to(&ClientMessage::close(subid.to_string()), relay_url);
+        true
+    }
+
     /// We've updated some unknown ids, update the last_updated time to now
     pub fn mark_updated(&mut self) {
         let now = Instant::now();
@@ -178,6 +259,12 @@ impl UnknownIds {
         if let Err(e) = get_unknown_note_ids(ndb, cached_note, txn, note, unknown_ids.ids_mut()) {
             error!("UnknownIds::update_from_note {e}");
         }
+        let in_flight = &unknown_ids.in_flight;
+        unknown_ids.ids.retain(|id, _| {
+            in_flight
+                .get(id)
+                .is_none_or(|requested| requested.elapsed() >= REQUEST_TIMEOUT)
+        });
         let after = unknown_ids.ids_iter().len();
 
         if before != after {
@@ -202,7 +289,7 @@ impl UnknownIds {
         }
 
         let unknown_id = UnknownId::Pubkey(Pubkey::new(*pubkey));
-        if self.ids.contains_key(&unknown_id) {
+        if self.ids.contains_key(&unknown_id) || self.is_in_flight(&unknown_id) {
             return;
         }
         self.ids.entry(unknown_id).or_default();
@@ -216,7 +303,7 @@ impl UnknownIds {
         }
 
         let unknown_id = UnknownId::Id(NoteId::new(*note_id));
-        if self.ids.contains_key(&unknown_id) {
+        if self.ids.contains_key(&unknown_id) || self.is_in_flight(&unknown_id) {
             return;
         }
         self.ids.entry(unknown_id).or_default();
@@ -361,7 +448,7 @@ fn get_unknown_ids_filter(ids: &[&UnknownId]) -> Option<Vec<Filter>> {
         return None;
     }
 
-    let ids = &ids[0..500.min(ids.len())];
+    let ids = &ids[0..MAX_BATCH.min(ids.len())];
     let mut filters: Vec<Filter> = vec![];
 
     let pks: Vec<&[u8; 32]> = ids
@@ -369,7 +456,8 @@ fn get_unknown_ids_filter(ids: &[&UnknownId]) -> Option<Vec<Filter>> {
         .flat_map(|id| id.is_pubkey().map(|pk| pk.bytes()))
         .collect();
     if !pks.is_empty() {
-        let pk_filter = Filter::new().authors(pks).kinds([0]).build();
+        // profiles, and relay lists so we know where to find their notes
+        let pk_filter = Filter::new().authors(pks).kinds([0, 10002]).build();
         filters.push(pk_filter);
     }
 
@@ -383,3 +471,46 @@ fn get_unknown_ids_filter(ids: &[&UnknownId]) -> Option<Vec<Filter>> {
 
     Some(filters)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_flight_ids_are_not_requested_again() {
+        let mut unknown_ids = UnknownIds::default();
+        let pk = UnknownId::Pubkey(Pubkey::new([1; 32]));
+
+        unknown_ids.ids_mut().entry(pk).or_default();
+        assert!(unknown_ids.take_batch_filter().is_some());
+
+        // still missing next frame, but we're waiting on relays
+        unknown_ids.ids_mut().entry(pk).or_default();
+        assert!(unknown_ids.take_batch_filter().is_none());
+        assert_eq!(unknown_ids.ids_iter().len(), 0);
+
+        unknown_ids
+            .in_flight
+            .insert(pk, Instant::now() - REQUEST_TIMEOUT);
+        unknown_ids.ids_mut().entry(pk).or_default();
+        assert!(unknown_ids.take_batch_filter().is_some());
+    }
+
+    #[test]
+    fn batches_are_capped() {
+        let mut unknown_ids = UnknownIds::default();
+        for i in 0..MAX_BATCH + 10 {
+            let mut bytes = [0; 32];
+            bytes[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            unknown_ids
+                .ids_mut()
+                .entry(UnknownId::Id(NoteId::new(bytes)))
+                .or_default();
+        }
+
+        assert!(unknown_ids.take_batch_filter().is_some());
+        assert_eq!(unknown_ids.ids_iter().len(), 10);
+        assert!(unknown_ids.take_batch_filter().is_some());
+        assert_eq!(unknown_ids.ids_iter().len(), 0);
+    }
+}
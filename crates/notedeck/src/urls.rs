@@ -266,7 +266,18 @@ impl SupportedMimeType {
 }
 
 fn is_mime_supported(mime: &mime_guess::Mime) -> bool {
-    mime.type_() == mime_guess::mime::IMAGE
+    mime.type_() == mime_guess::mime::IMAGE && is_subtype_decodable(mime.subtype().as_str())
+}
+
+/// Image subtypes we can't decode. These are treated as unsupported so they
+/// fall back to a link instead of downloading and failing to decode.
+fn is_subtype_decodable(subtype: &str) -> bool {
+    match subtype {
+        // HEIC/HEIF needs libheif, which we don't ship
+        "heic" | "heif" | "heic-sequence" | "heif-sequence" => false,
+        "avif" => cfg!(feature = "avif"),
+        _ => true,
+    }
 }
 
 fn url_has_supported_mime(url: &str) -> MimeHostedAtUrl {
@@ -309,3 +320,23 @@ enum MimeHostedAtUrl {
     Maybe,
     No,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heic_is_unsupported() {
+        assert!(SupportedMimeType::from_extension("heic").is_err());
+        assert!(SupportedMimeType::from_extension("jpg").is_ok());
+        assert!(SupportedMimeType::from_extension("webp").is_ok());
+    }
+
+    #[test]
+    fn avif_support_follows_feature() {
+        assert_eq!(
+            SupportedMimeType::from_extension("avif").is_ok(),
+            cfg!(feature = "avif")
+        );
+    }
+}
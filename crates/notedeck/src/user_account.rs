@@ -2,6 +2,7 @@ use enostr::{Keypair, KeypairUnowned};
 use tokenator::{ParseError, TokenParser, TokenSerializable};
 
 use crate::{
+    account::signer::{RemoteSigner, RemoteSignerSerializable},
     wallet::{WalletSerializable, ZapWallet},
     AccountData, IsFollowing,
 };
@@ -10,6 +11,8 @@ use crate::{
 pub struct UserAccount {
     pub key: Keypair,
     pub wallet: Option<ZapWallet>,
+    /// Signs for this account when we don't have its secret key
+    pub signer: Option<RemoteSigner>,
     pub data: AccountData,
 }
 
@@ -18,6 +21,7 @@ impl UserAccount {
         Self {
             key,
             wallet: None,
+            signer: None,
             data,
         }
     }
@@ -34,6 +38,11 @@ impl UserAccount {
         self
     }
 
+    pub fn with_signer(mut self, signer: RemoteSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
     pub fn is_following(&self, other_pubkey: &[u8; 32]) -> IsFollowing {
         self.data.contacts.is_following(other_pubkey)
     }
@@ -42,17 +51,27 @@ impl UserAccount {
 pub struct UserAccountSerializable {
     pub key: Keypair,
     pub wallet: Option<WalletSerializable>,
+    pub signer: Option<RemoteSignerSerializable>,
 }
 
 impl UserAccountSerializable {
     pub fn new(key: Keypair) -> Self {
-        Self { key, wallet: None }
+        Self {
+            key,
+            wallet: None,
+            signer: None,
+        }
     }
 
     pub fn with_wallet(mut self, wallet: WalletSerializable) -> Self {
         self.wallet = Some(wallet);
         self
     }
+
+    pub fn with_signer(mut self, signer: RemoteSignerSerializable) -> Self {
+        self.signer = Some(signer);
+        self
+    }
 }
 
 impl From<&UserAccount> for UserAccountSerializable {
@@ -60,6 +79,10 @@ impl From<&UserAccount> for UserAccountSerializable {
         Self {
             key: value.key.clone(),
             wallet: value.wallet.as_ref().map(|z| z.into()),
+            signer: value
+                .signer
+                .as_ref()
+                .and_then(RemoteSignerSerializable::new),
         }
     }
 }
@@ -67,6 +90,7 @@ impl From<&UserAccount> for UserAccountSerializable {
 enum UserAccountRoute {
     Key(Keypair),
     Wallet(WalletSerializable),
+    Signer(RemoteSignerSerializable),
 }
 
 impl TokenSerializable for UserAccountSerializable {
@@ -75,6 +99,7 @@ impl TokenSerializable for UserAccountSerializable {
     ) -> Result<Self, tokenator::ParseError<'a>> {
         let mut m_key = None;
         let mut m_wallet = None;
+        let mut m_signer = None;
 
         loop {
             let res = TokenParser::alt(
@@ -86,12 +111,18 @@ impl TokenSerializable for UserAccountSerializable {
                             WalletSerializable::parse_from_tokens(p)?,
                         ))
                     },
+                    |p| {
+                        Ok(UserAccountRoute::Signer(
+                            RemoteSignerSerializable::parse_from_tokens(p)?,
+                        ))
+                    },
                 ],
             );
 
             match res {
                 Ok(UserAccountRoute::Key(key)) => m_key = Some(key),
                 Ok(UserAccountRoute::Wallet(wallet)) => m_wallet = Some(wallet),
+                Ok(UserAccountRoute::Signer(signer)) => m_signer = Some(signer),
                 Err(ParseError::AltAllFailed) => break,
                 Err(_) => {}
             }
@@ -111,12 +142,21 @@ impl TokenSerializable for UserAccountSerializable {
             user_acc = user_acc.with_wallet(wallet);
         };
 
+        if let Some(signer) = m_signer {
+            user_acc = user_acc.with_signer(signer);
+        }
+
         Ok(user_acc)
     }
 
     fn serialize_tokens(&self, writer: &mut tokenator::TokenWriter) {
         self.key.serialize_tokens(writer);
 
+        // before the wallet, parsing stops once it has the key and wallet
+        if let Some(signer) = &self.signer {
+            signer.serialize_tokens(writer);
+        }
+
         let Some(wallet) = &self.wallet else {
             return;
         };
@@ -127,10 +167,13 @@ impl TokenSerializable for UserAccountSerializable {
 
 #[cfg(test)]
 mod tests {
-    use enostr::FullKeypair;
+    use enostr::{FullKeypair, Keypair};
     use tokenator::{TokenParser, TokenSerializable, TokenWriter};
 
-    use crate::{user_account::UserAccountSerializable, wallet::WalletSerializable};
+    use crate::{
+        account::signer::RemoteSignerSerializable, user_account::UserAccountSerializable,
+        wallet::WalletSerializable,
+    };
 
     const URI: &str = "nostr+walletconnect://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss%3A%2F%2Frelay.damus.io&secret=71a8c14c1407c113601079c4302dab36460f0ccd0ad506f1f2dc73b5100e4f3c&lud16=nostr%40nostr.com";
 
@@ -160,4 +203,31 @@ mod tests {
 
         assert_eq!(wallet.uri, URI);
     }
+
+    #[test]
+    fn test_user_account_with_signer_serialize_deserialize() {
+        let kp = FullKeypair::generate();
+        let bunker = format!(
+            "bunker://{}?relay=wss%3A%2F%2Frelay.nsec.app%2F",
+            FullKeypair::generate().pubkey.hex()
+        );
+        let acc = UserAccountSerializable::new(Keypair::only_pubkey(kp.pubkey))
+            .with_signer(RemoteSignerSerializable {
+                uri: bunker.clone(),
+                client: FullKeypair::generate().to_keypair(),
+            })
+            .with_wallet(WalletSerializable::new(URI.to_owned()));
+
+        let mut writer = TokenWriter::new("\t");
+        acc.serialize_tokens(&mut writer);
+
+        let serialized = writer.str();
+        let data = &serialized.split("\t").collect::<Vec<&str>>();
+        let mut parser = TokenParser::new(data);
+        let new_acc = UserAccountSerializable::parse_from_tokens(&mut parser).unwrap();
+
+        assert_eq!(acc.key, new_acc.key);
+        assert_eq!(new_acc.signer.unwrap().uri, bunker);
+        assert_eq!(new_acc.wallet.unwrap().uri, URI);
+    }
 }
@@ -1,4 +1,8 @@
-use std::{fmt::Display, sync::Arc};
+use std::{
+    fmt::Display,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use nwc::{
     nostr::nips::nip47::{NostrWalletConnectURI, PayInvoiceRequest, PayInvoiceResponse},
@@ -65,10 +69,16 @@ pub enum WalletError {
     NoWallet,
 }
 
+/// How often we ask the wallet for its balance while it is shown
+const BALANCE_REFRESH: Duration = Duration::from_secs(60);
+
 pub struct Wallet {
     pub uri: String,
     wallet: Arc<RwLock<NWC>>,
     balance: Option<Promise<Result<u64, NwcError>>>,
+    /// The last balance we got, shown while a newer one is on its way
+    last_balance: Option<Result<u64, NwcError>>,
+    balance_requested: Option<Instant>,
 }
 
 impl Clone for Wallet {
@@ -77,6 +87,8 @@ impl Clone for Wallet {
             uri: self.uri.clone(),
             wallet: self.wallet.clone(),
             balance: None,
+            last_balance: None,
+            balance_requested: None,
         }
     }
 }
@@ -104,6 +116,7 @@ impl std::fmt::Debug for Wallet {
 
 impl Wallet {
     pub fn new(uri: String) -> Result<Self, crate::Error> {
+        let uri = normalize_uri(&uri);
         let nwc_uri = NostrWalletConnectURI::parse(uri.clone())
             .map_err(|e| crate::Error::Generic(e.to_string()))?;
 
@@ -113,21 +126,35 @@ impl Wallet {
             uri,
             wallet: Arc::new(RwLock::new(nwc)),
             balance: Default::default(),
+            last_balance: None,
+            balance_requested: None,
         })
     }
 
+    /// The wallet's balance in msats, once it answered. Asks again every
+    /// [`BALANCE_REFRESH`] so it stays current after zapping.
     pub fn get_balance(&mut self) -> Option<&Result<u64, NwcError>> {
-        if self.balance.is_none() {
-            self.balance = Some(get_balance(self.wallet.clone()));
-            return None;
+        if let Some(promise) = self.balance.take() {
+            match promise.try_take() {
+                Ok(balance) => self.last_balance = Some(balance),
+                Err(promise) => self.balance = Some(promise),
+            }
         }
-        let promise = self.balance.as_ref().unwrap();
 
-        if let Some(bal) = promise.ready() {
-            Some(bal)
-        } else {
-            None
+        let stale = self
+            .balance_requested
+            .is_none_or(|requested| requested.elapsed() >= BALANCE_REFRESH);
+        if stale && self.balance.is_none() {
+            self.balance = Some(get_balance(self.wallet.clone()));
+            self.balance_requested = Some(Instant::now());
         }
+
+        self.last_balance.as_ref()
+    }
+
+    /// Ask for the balance again the next time it is shown
+    pub fn refresh_balance(&mut self) {
+        self.balance_requested = None;
     }
 
     pub fn pay_invoice(&self, invoice: &str) -> Promise<Result<PayInvoiceResponse, nwc::Error>> {
@@ -172,6 +199,17 @@ impl Display for NwcError {
     }
 }
 
+/// Wallets hand out the uri in a few shapes, as a `nostr:` link or with
+/// whitespace around it when copied from a QR code scanner
+fn normalize_uri(uri: &str) -> String {
+    let uri = uri.trim();
+    let uri = uri.strip_prefix("nostr:").unwrap_or(uri);
+    match uri.strip_prefix("nostrwalletconnect://") {
+        Some(rest) => format!("nostr+walletconnect://{rest}"),
+        None => uri.to_owned(),
+    }
+}
+
 fn get_balance(nwc: Arc<RwLock<NWC>>) -> Promise<Result<u64, NwcError>> {
     let (sender, promise) = Promise::new();
 
@@ -378,6 +416,15 @@ mod tests {
         assert!(Wallet::new(URI.to_owned()).is_ok())
     }
 
+    #[test]
+    fn test_uri_normalized() {
+        let wallet = Wallet::new(format!(" nostr:{URI}\n")).unwrap();
+        assert_eq!(wallet.uri, URI);
+
+        let legacy = URI.replace("nostr+walletconnect://", "nostrwalletconnect://");
+        assert_eq!(Wallet::new(legacy).unwrap().uri, URI);
+    }
+
     #[test]
     fn test_wallet_serialize_deserialize() {
         let wallet = WalletSerializable::new(URI.to_owned());
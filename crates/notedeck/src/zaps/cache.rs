@@ -8,7 +8,7 @@ use tokio::task::JoinError;
 use url::Url;
 
 use crate::{
-    get_wallet_for,
+    get_current_wallet_mut, get_wallet_for,
     zaps::{
         get_users_zap_address,
         networking::{fetch_invoice_promise, FetchedInvoiceResponse, LNUrlPayResponse, PayEntry},
@@ -115,20 +115,12 @@ fn process_new_zap_event(
         });
     };
 
-    // TODO(kernelkind): support ZapTarget::Profile
-    let ZapTargetOwned::Note(note_target) = zap_ctx.key.target.clone() else {
-        return NextState::Event(EventResponse {
-            id: zap_ctx.id,
-            event: Err(ZappingError::UnsupportedOperation),
-        });
-    };
-
     let id = zap_ctx.id;
-    let m_promise = send_note_zap(
+    let m_promise = send_zap_to(
         cache,
         ndb,
         txn,
-        note_target,
+        zap_ctx.key.target.clone(),
         zap_ctx.msats,
         &full_kp.secret_key.secret_bytes(),
         sender_relays,
@@ -151,25 +143,18 @@ fn process_new_zap_event(
     NextState::Transition(promise)
 }
 
-fn send_note_zap(
+fn send_zap_to(
     cache: &PayCache,
     ndb: &Ndb,
     txn: &Transaction,
-    note_target: NoteZapTargetOwned,
+    target: ZapTargetOwned,
     msats: u64,
     nsec: &[u8; 32],
     relays: Vec<String>,
 ) -> Result<FetchingInvoice, ZapError> {
-    let address = get_users_zap_address(txn, ndb, &note_target.zap_recipient)?;
+    let address = get_users_zap_address(txn, ndb, target.pubkey())?;
 
-    fetch_invoice_promise(
-        cache,
-        address,
-        msats,
-        *nsec,
-        ZapTargetOwned::Note(note_target),
-        relays,
-    )
+    fetch_invoice_promise(cache, address, msats, *nsec, target, relays)
 }
 
 fn try_get_promise_response(
@@ -328,6 +313,10 @@ impl Zaps {
                     self.in_flight.push(in_flight_promise);
                 }
                 NextState::Success { id, zap } => {
+                    // the payment went through, so the balance changed
+                    if let Some(wallet) = get_current_wallet_mut(accounts, global_wallet) {
+                        wallet.wallet.refresh_balance();
+                    }
                     self.zaps.insert(id, ZapState::LocalConfirm(zap));
                 }
             }
@@ -490,7 +479,7 @@ pub enum ZapEvent {
 pub enum ZappingError {
     InvoiceFetchFailed(ZapError),
     InvalidAccount,
-    UnsupportedOperation, // TODO(kernelkind): support profile zaps
+    UnsupportedOperation,
     InvalidZapAddress,
     SenderNoWallet,
     InvalidNWCResponse(String),
@@ -502,9 +491,7 @@ impl std::fmt::Display for ZappingError {
         match self {
             ZappingError::InvoiceFetchFailed(err) => write!(f, "Failed to fetch invoice: {err}"),
             ZappingError::InvalidAccount => write!(f, "Invalid account"),
-            ZappingError::UnsupportedOperation => {
-                write!(f, "Unsupported operation (e.g. profile zaps)")
-            }
+            ZappingError::UnsupportedOperation => write!(f, "Unsupported operation"),
             ZappingError::InvalidZapAddress => write!(f, "Invalid zap address"),
             ZappingError::SenderNoWallet => write!(f, "Sender has no wallet"),
             ZappingError::InvalidNWCResponse(msg) => write!(f, "Invalid NWC response: {msg}"),
@@ -586,7 +573,7 @@ pub enum ZapTarget<'a> {
 }
 
 impl ZapTargetOwned {
-    #[allow(dead_code)]
+    /// Who receives the zap
     pub fn pubkey(&self) -> &Pubkey {
         match &self {
             ZapTargetOwned::Profile(pubkey) => pubkey,
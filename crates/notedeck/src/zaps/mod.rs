@@ -3,6 +3,8 @@ mod default_zap;
 mod networking;
 mod zap;
 
+pub(crate) use zap::valid_note_signature;
+
 pub use cache::{
     AnyZapState, NoteZapTarget, NoteZapTargetOwned, ZapTarget, ZapTargetOwned, ZappingError, Zaps,
 };
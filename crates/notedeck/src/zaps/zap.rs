@@ -98,14 +98,19 @@ pub fn event_commitment(
 
 // TODO(kernelkind): i think we may be able to validate just with the nostrdb::Note. Not exactly sure yet how though
 fn valid_zap_request(note: enostr::Note) -> bool {
-    let sig = note.sig.clone();
+    valid_note_signature(&note)
+}
+
+/// Does the note's id match its contents and is it signed by its pubkey?
+pub(crate) fn valid_note_signature(note: &enostr::Note) -> bool {
+    let sig = &note.sig;
 
     let commitment = event_commitment(
         note.pubkey,
         note.created_at,
         note.kind,
-        note.tags,
-        note.content,
+        note.tags.clone(),
+        note.content.clone(),
     );
 
     let commitment_bytes = commitment.as_bytes();
@@ -0,0 +1,84 @@
+//! Scaling the whole interface. The zoom factor is saved in the settings
+//! every frame, so changes from the keyboard and from the settings view
+//! both stick.
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use crate::shortcuts::{self, Shortcut};
+use crate::{tr, Localization};
+
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 3.0;
+pub const ZOOM_STEP: f32 = 0.1;
+pub const RESET_ZOOM: f32 = 1.0;
+
+fn zoom_group(i18n: &mut Localization) -> String {
+    tr!(i18n, "Zoom", "Keyboard shortcut group for zooming")
+}
+
+pub const ZOOM_IN: Shortcut = Shortcut::new(
+    "zoom.in",
+    zoom_group,
+    |i18n| tr!(i18n, "Zoom in", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Plus),
+);
+
+pub const ZOOM_OUT: Shortcut = Shortcut::new(
+    "zoom.out",
+    zoom_group,
+    |i18n| tr!(i18n, "Zoom out", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Minus),
+);
+
+pub const ZOOM_RESET: Shortcut = Shortcut::new(
+    "zoom.reset",
+    zoom_group,
+    |i18n| tr!(i18n, "Reset zoom", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::Num0),
+);
+
+pub const ZOOM_SHORTCUTS: [Shortcut; 3] = [ZOOM_IN, ZOOM_OUT, ZOOM_RESET];
+
+/// `current` moved by `steps` zoom steps, kept in range
+pub fn stepped(current: f32, steps: i32) -> f32 {
+    let stepped = current + steps as f32 * ZOOM_STEP;
+    // avoid drifting away from round percentages
+    let stepped = (stepped / ZOOM_STEP).round() * ZOOM_STEP;
+    stepped.clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Zoom with the keyboard. egui's own zoom keys are turned off at setup,
+/// since they can't be remapped and zoom past our limits.
+pub fn handle_shortcuts(ctx: &egui::Context) {
+    let current = ctx.zoom_factor();
+    // on most layouts ctrl and = is the unshifted +
+    let zoom_in = shortcuts::consume(ctx, &ZOOM_IN)
+        || ctx.input_mut(|i| i.consume_shortcut(&egui::gui_zoom::kb_shortcuts::ZOOM_IN_SECONDARY));
+
+    let zoom = if zoom_in {
+        stepped(current, 1)
+    } else if shortcuts::consume(ctx, &ZOOM_OUT) {
+        stepped(current, -1)
+    } else if shortcuts::consume(ctx, &ZOOM_RESET) {
+        RESET_ZOOM
+    } else {
+        return;
+    };
+
+    if zoom != current {
+        ctx.set_zoom_factor(zoom);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_stay_in_range() {
+        assert!((stepped(1.0, 1) - 1.1).abs() < 1e-6);
+        assert!((stepped(1.04, -1) - 0.9).abs() < 1e-6);
+        assert_eq!(stepped(MAX_ZOOM, 1), MAX_ZOOM);
+        assert_eq!(stepped(MIN_ZOOM, -3), MIN_ZOOM);
+    }
+}
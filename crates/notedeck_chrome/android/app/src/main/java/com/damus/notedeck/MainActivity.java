@@ -1,9 +1,14 @@
 package com.damus.notedeck;
 
+import android.app.NotificationChannel;
+import android.app.NotificationManager;
+import android.app.PendingIntent;
 import android.content.ClipData;
 import android.content.Intent;
+import android.content.pm.PackageManager;
 import android.database.Cursor;
 import android.net.Uri;
+import android.os.Build;
 import android.os.Bundle;
 import android.os.ParcelFileDescriptor;
 import android.provider.OpenableColumns;
@@ -12,6 +17,9 @@ import android.view.MotionEvent;
 import android.view.View;
 import android.view.ViewGroup;
 
+import androidx.core.app.NotificationCompat;
+import androidx.core.app.NotificationManagerCompat;
+import androidx.core.content.ContextCompat;
 import androidx.core.graphics.Insets;
 import androidx.core.view.ViewCompat;
 import androidx.core.view.WindowCompat;
@@ -27,9 +35,73 @@ import java.io.InputStream;
 
 public class MainActivity extends GameActivity {
     static final int REQUEST_CODE_PICK_FILE = 420;
+    static final int REQUEST_CODE_NOTIFICATIONS = 421;
+    static final String NOTIFICATION_CHANNEL_ID = "notifications";
+    static final String EXTRA_NOTE_ID = "com.damus.notedeck.NOTE_ID";
+    // Manifest.permission.POST_NOTIFICATIONS, added in API 33 which is newer
+    // than our compileSdk
+    static final String POST_NOTIFICATIONS = "android.permission.POST_NOTIFICATIONS";
+
+  private int nextNotificationId = 0;
 
   private native void nativeOnFilePickedFailed(String uri, String e);
   private native void nativeOnFilePickedWithContent(Object[] uri_info, byte[] content);
+  private native void nativeOnNotificationClicked(String noteId);
+  private native void nativeOnUriOpened(String uri);
+
+  public void showNotification(String title, String body, String noteId) {
+      runOnUiThread(() -> {
+          if (Build.VERSION.SDK_INT >= 33
+                  && ContextCompat.checkSelfPermission(this, POST_NOTIFICATIONS)
+                      != PackageManager.PERMISSION_GRANTED) {
+              requestPermissions(new String[] { POST_NOTIFICATIONS }, REQUEST_CODE_NOTIFICATIONS);
+              return;
+          }
+
+          if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.O) {
+              NotificationChannel channel = new NotificationChannel(NOTIFICATION_CHANNEL_ID,
+                      "Notifications", NotificationManager.IMPORTANCE_DEFAULT);
+              getSystemService(NotificationManager.class).createNotificationChannel(channel);
+          }
+
+          int id = nextNotificationId++;
+          Intent intent = new Intent(this, MainActivity.class);
+          intent.setFlags(Intent.FLAG_ACTIVITY_SINGLE_TOP);
+          intent.putExtra(EXTRA_NOTE_ID, noteId);
+          PendingIntent pending = PendingIntent.getActivity(this, id, intent,
+                  PendingIntent.FLAG_UPDATE_CURRENT | PendingIntent.FLAG_IMMUTABLE);
+
+          NotificationCompat.Builder builder = new NotificationCompat.Builder(this, NOTIFICATION_CHANNEL_ID)
+                  .setSmallIcon(R.mipmap.ic_launcher)
+                  .setContentTitle(title)
+                  .setContentText(body)
+                  .setContentIntent(pending)
+                  .setAutoCancel(true);
+
+          NotificationManagerCompat.from(this).notify(id, builder.build());
+      });
+  }
+
+  private void handleNotificationIntent(Intent intent) {
+      if (intent == null) return;
+
+      String noteId = intent.getStringExtra(EXTRA_NOTE_ID);
+      if (noteId != null && !noteId.isEmpty()) {
+          nativeOnNotificationClicked(noteId);
+      }
+      intent.removeExtra(EXTRA_NOTE_ID);
+  }
+
+  private void handleUriIntent(Intent intent) {
+      if (intent == null || !Intent.ACTION_VIEW.equals(intent.getAction())) return;
+
+      Uri uri = intent.getData();
+      if (uri != null && "nostr".equals(uri.getScheme())) {
+          nativeOnUriOpened(uri.toString());
+      }
+      // don't open it again when the activity is recreated
+      intent.setData(null);
+  }
 
   public void openFilePicker() {
         Intent intent = new Intent(Intent.ACTION_OPEN_DOCUMENT);
@@ -160,6 +232,16 @@ public class MainActivity extends GameActivity {
         //setupFullscreen()
 
         super.onCreate(savedInstanceState);
+
+        handleNotificationIntent(getIntent());
+        handleUriIntent(getIntent());
+    }
+
+    @Override
+    protected void onNewIntent(Intent intent) {
+        super.onNewIntent(intent);
+        handleNotificationIntent(intent);
+        handleUriIntent(intent);
     }
 
     @Override
@@ -39,6 +39,7 @@ pub async fn android_main(android_app: AndroidApp) {
     tracing_subscriber::registry()
         .with(filter_layer)
         .with(fmt_layer)
+        .with(crate::RecentLogsLayer)
         .init();
 
     let path = android_app.internal_data_path().expect("data path");
@@ -4,13 +4,33 @@ use notedeck_columns::Damus;
 use notedeck_dave::Dave;
 use notedeck_notebook::Notebook;
 
+pub const COLUMNS_ID: &str = notedeck_columns::COLUMNS_ID;
+pub const DAVE_ID: &str = "dave";
+pub const NOTEBOOK_ID: &str = "notebook";
+pub const CLNDASH_ID: &str = "clndash";
+
 #[allow(clippy::large_enum_variant)]
 pub enum NotedeckApp {
     Dave(Box<Dave>),
     Columns(Box<Damus>),
     Notebook(Box<Notebook>),
     ClnDash(Box<ClnDash>),
-    Other(Box<dyn notedeck::App>),
+    /// An app from the catalog that notedeck doesn't know about, with its
+    /// catalog id
+    Other(&'static str, Box<dyn notedeck::App>),
+}
+
+impl NotedeckApp {
+    /// The app's id in the app catalog
+    pub fn id(&self) -> &'static str {
+        match self {
+            NotedeckApp::Dave(_) => DAVE_ID,
+            NotedeckApp::Columns(_) => COLUMNS_ID,
+            NotedeckApp::Notebook(_) => NOTEBOOK_ID,
+            NotedeckApp::ClnDash(_) => CLNDASH_ID,
+            NotedeckApp::Other(id, _) => id,
+        }
+    }
 }
 
 impl notedeck::App for NotedeckApp {
@@ -21,7 +41,7 @@ impl notedeck::App for NotedeckApp {
             NotedeckApp::Columns(columns) => columns.update(ctx, ui),
             NotedeckApp::Notebook(notebook) => notebook.update(ctx, ui),
             NotedeckApp::ClnDash(clndash) => clndash.update(ctx, ui),
-            NotedeckApp::Other(other) => other.update(ctx, ui),
+            NotedeckApp::Other(_, other) => other.update(ctx, ui),
         }
     }
 }
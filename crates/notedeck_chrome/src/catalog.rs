@@ -0,0 +1,186 @@
+//! Apps that can be turned on and off while notedeck runs
+
+use egui::{vec2, RichText};
+use notedeck::{tr, AppContext, Localization, NotedeckTextStyle};
+
+use crate::app::NotedeckApp;
+
+type CreateApp = Box<dyn Fn(&mut AppContext) -> NotedeckApp>;
+
+/// An app in the catalog. Anything implementing [`notedeck::App`] can be
+/// added with [`NotedeckApp::Other`].
+pub struct AppEntry {
+    /// Stable id, used to remember whether the app is on
+    pub id: &'static str,
+    pub name: fn(&mut Localization) -> String,
+    pub description: fn(&mut Localization) -> String,
+    /// Paint the app's icon into a drawer item sized cell
+    pub icon: fn(&mut egui::Ui),
+    /// Whether the app is on until the user says otherwise
+    pub default_enabled: bool,
+    create: CreateApp,
+}
+
+impl AppEntry {
+    pub fn new(
+        id: &'static str,
+        name: fn(&mut Localization) -> String,
+        description: fn(&mut Localization) -> String,
+        icon: fn(&mut egui::Ui),
+        create: impl Fn(&mut AppContext) -> NotedeckApp + 'static,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            icon,
+            default_enabled: false,
+            create: Box::new(create),
+        }
+    }
+
+    pub fn enabled_by_default(mut self, enabled: bool) -> Self {
+        self.default_enabled = enabled;
+        self
+    }
+
+    pub fn create(&self, ctx: &mut AppContext) -> NotedeckApp {
+        (self.create)(ctx)
+    }
+}
+
+#[derive(Default)]
+pub struct AppCatalog {
+    entries: Vec<AppEntry>,
+}
+
+impl AppCatalog {
+    /// Add an app, replacing one with the same id
+    pub fn register(&mut self, entry: AppEntry) {
+        match self.entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AppEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    pub fn entries(&self) -> &[AppEntry] {
+        &self.entries
+    }
+
+    /// The user's choice, or the app's default if they didn't make one
+    pub fn is_enabled(&self, ctx: &AppContext, id: &str) -> bool {
+        ctx.settings
+            .app_enabled(id)
+            .or_else(|| self.get(id).map(|e| e.default_enabled))
+            .unwrap_or(false)
+    }
+}
+
+pub enum CatalogAction {
+    Close,
+    SetEnabled(&'static str, bool),
+    Open(&'static str),
+}
+
+/// The catalog screen: every app with a switch to turn it on or off
+pub fn catalog_ui(
+    catalog: &AppCatalog,
+    ctx: &mut AppContext,
+    ui: &mut egui::Ui,
+) -> Option<CatalogAction> {
+    let mut action = None;
+    let mut open = true;
+
+    let title = tr!(ctx.i18n, "Apps", "Title of the app catalog");
+    egui::Window::new(title)
+        .id(egui::Id::new("app_catalog"))
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(360.0)
+        .show(ui.ctx(), |ui| {
+            if catalog.entries().is_empty() {
+                ui.weak(tr!(
+                    ctx.i18n,
+                    "No apps to add",
+                    "Shown when the app catalog is empty"
+                ));
+            }
+
+            for entry in catalog.entries() {
+                let enabled = catalog.is_enabled(ctx, entry.id);
+
+                ui.horizontal(|ui| {
+                    let (rect, _) = ui.allocate_exact_size(vec2(32.0, 32.0), egui::Sense::hover());
+                    (entry.icon)(&mut ui.new_child(egui::UiBuilder::new().max_rect(rect)));
+
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new((entry.name)(ctx.i18n))
+                                .text_style(NotedeckTextStyle::Body.text_style())
+                                .strong(),
+                        );
+                        ui.weak((entry.description)(ctx.i18n));
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let mut checked = enabled;
+                        if ui.checkbox(&mut checked, "").changed() {
+                            action = Some(CatalogAction::SetEnabled(entry.id, checked));
+                        }
+
+                        if enabled
+                            && ui
+                                .small_button(tr!(
+                                    ctx.i18n,
+                                    "Open",
+                                    "Button to switch to an app from the app catalog"
+                                ))
+                                .clicked()
+                        {
+                            action = Some(CatalogAction::Open(entry.id));
+                        }
+                    });
+                });
+
+                ui.add_space(6.0);
+            }
+        });
+
+    if !open {
+        return Some(CatalogAction::Close);
+    }
+
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &'static str, default_enabled: bool) -> AppEntry {
+        AppEntry::new(
+            id,
+            |_| String::new(),
+            |_| String::new(),
+            |_| {},
+            |_| NotedeckApp::Notebook(Box::default()),
+        )
+        .enabled_by_default(default_enabled)
+    }
+
+    #[test]
+    fn register_replaces_same_id() {
+        let mut catalog = AppCatalog::default();
+        catalog.register(entry("a", false));
+        catalog.register(entry("b", false));
+        catalog.register(entry("a", true));
+
+        assert_eq!(catalog.entries().len(), 2);
+        assert!(catalog.get("a").unwrap().default_enabled);
+        assert!(catalog.get("c").is_none());
+    }
+}
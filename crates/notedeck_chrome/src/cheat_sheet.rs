@@ -0,0 +1,71 @@
+//! The list of keyboard shortcuts, opened with `?`
+
+use egui::RichText;
+use notedeck::{shortcuts, tr, Localization, Shortcut};
+
+/// Shortcuts grouped by the part of notedeck they belong to, in the
+/// order the groups were first registered
+fn grouped(i18n: &mut Localization, registered: Vec<Shortcut>) -> Vec<(String, Vec<Shortcut>)> {
+    let mut groups: Vec<(String, Vec<Shortcut>)> = Vec::new();
+    for shortcut in registered {
+        let group = (shortcut.group)(i18n);
+        match groups.iter_mut().find(|(name, _)| *name == group) {
+            Some((_, shortcuts)) => shortcuts.push(shortcut),
+            None => groups.push((group, vec![shortcut])),
+        }
+    }
+    groups
+}
+
+/// Returns false once closed
+pub fn cheat_sheet_ui(i18n: &mut Localization, ui: &mut egui::Ui) -> bool {
+    let mut open = !ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+
+    let ctx = ui.ctx().clone();
+    let groups = grouped(i18n, shortcuts::registered(&ctx));
+
+    let title = tr!(
+        i18n,
+        "Keyboard shortcuts",
+        "Title of the keyboard shortcut list"
+    );
+    egui::Window::new(title)
+        .id(egui::Id::new("shortcut_cheat_sheet"))
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(360.0)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(&ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(480.0)
+                .show(ui, |ui| {
+                    for (group, group_shortcuts) in groups {
+                        ui.add_space(6.0);
+                        ui.label(RichText::new(group).strong());
+
+                        egui::Grid::new(("cheat_sheet", group_shortcuts[0].id))
+                            .num_columns(2)
+                            .spacing([24.0, 4.0])
+                            .show(ui, |ui| {
+                                for shortcut in group_shortcuts {
+                                    let binding = shortcuts::binding(&ctx, &shortcut);
+                                    ui.label(
+                                        RichText::new(ctx.format_shortcut(&binding)).monospace(),
+                                    );
+                                    ui.label((shortcut.description)(i18n));
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                });
+
+            ui.add_space(8.0);
+            ui.weak(tr!(
+                i18n,
+                "Shortcuts can be changed in Settings",
+                "Hint at the bottom of the keyboard shortcut list"
+            ));
+        });
+
+    open
+}
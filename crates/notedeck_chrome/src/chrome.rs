@@ -1,7 +1,12 @@
 // Entry point for wasm
 //#[cfg(target_arch = "wasm32")]
 //use wasm_bindgen::prelude::*;
-use crate::app::NotedeckApp;
+use crate::app::{NotedeckApp, CLNDASH_ID, COLUMNS_ID, DAVE_ID, NOTEBOOK_ID};
+use crate::catalog::{catalog_ui, AppCatalog, AppEntry, CatalogAction};
+use crate::cheat_sheet::cheat_sheet_ui;
+use crate::diagnostics::{diagnostics_ui, subscription_lines, DIAGNOSTICS_SHORTCUT};
+use crate::frame_overlay::{frame_overlay_ui, AppTimings, FRAME_OVERLAY_SHORTCUT};
+use crate::search::{GlobalSearch, GlobalSearchAction, SEARCH_SHORTCUT};
 use crate::ChromeOptions;
 use bitflags::bitflags;
 use eframe::CreationContext;
@@ -12,21 +17,25 @@ use egui::{
 use egui_extras::{Size, StripBuilder};
 use egui_nav::RouteResponse;
 use egui_nav::{NavAction, NavDrawer};
-use nostrdb::{ProfileRecord, Transaction};
+use nostrdb::{Filter, Ndb, ProfileRecord, Transaction};
+use notedeck::enostr::NoteId;
 use notedeck::fonts::get_font_size;
 use notedeck::name::get_display_name;
+use notedeck::nostr_uri::NostrUri;
+use notedeck::shortcuts;
 use notedeck::ui::is_compiled_as_mobile;
 use notedeck::AppResponse;
 use notedeck::DrawerRouter;
 use notedeck::Error;
 use notedeck::SoftKeyboardContext;
 use notedeck::{
-    tr, App, AppAction, AppContext, Localization, Notedeck, NotedeckOptions, NotedeckTextStyle,
+    get_current_wallet_mut, tr, App, AppAction, AppContext, Localization, NoteAction, Notedeck,
+    NotedeckOptions, NotedeckTextStyle, NotificationKind, RemoteSigner, SignerMethod, SignerStatus,
     UserAccount, WalletType,
 };
-use notedeck_columns::{timeline::TimelineKind, Damus};
+use notedeck_columns::{timeline::TimelineKind, ui::wallet::format_balance, Damus};
 use notedeck_dave::{Dave, DaveAvatar};
-use notedeck_ui::{app_images, expanding_button, galley_centered_pos, ProfilePic};
+use notedeck_ui::{app_images, colors, expanding_button, galley_centered_pos, ProfilePic};
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -40,6 +49,18 @@ pub struct Chrome {
 
     pub repaint_causes: HashMap<egui::RepaintCause, u64>,
     nav: DrawerRouter,
+
+    /// The Ctrl+K search, while open
+    search: Option<GlobalSearch>,
+
+    /// Apps that can be turned on and off
+    catalog: AppCatalog,
+
+    /// The app whose subscriptions are open, see [`notedeck::AppSubscriptions`]
+    foreground: Option<&'static str>,
+
+    /// How long each app's update takes, for the frame overlay
+    app_timings: AppTimings,
 }
 
 #[derive(Clone)]
@@ -53,6 +74,8 @@ pub enum ChromePanelAction {
     Settings,
     Account,
     Wallet,
+    Notifications,
+    Apps,
     SaveTheme(ThemePreference),
     Profile(notedeck::enostr::Pubkey),
 }
@@ -114,6 +137,14 @@ impl ChromePanelAction {
             Self::Profile(pk) => {
                 columns_route_to_profile(pk, chrome, ctx, ui);
             }
+
+            Self::Notifications => {
+                chrome.options.toggle(ChromeOptions::NotificationInbox);
+            }
+
+            Self::Apps => {
+                chrome.options.toggle(ChromeOptions::AppCatalog);
+            }
         }
     }
 }
@@ -144,27 +175,163 @@ impl Chrome {
     ) -> Result<Self, Error> {
         stop_debug_mode(notedeck.options());
 
+        // so the cheat sheet and settings list shortcuts of views that
+        // weren't opened yet
+        shortcuts::register(
+            &cc.egui_ctx,
+            &[
+                shortcuts::CHEAT_SHEET,
+                SEARCH_SHORTCUT,
+                DIAGNOSTICS_SHORTCUT,
+                FRAME_OVERLAY_SHORTCUT,
+            ],
+        );
+        shortcuts::register(&cc.egui_ctx, &notedeck_ui::media::MEDIA_VIEWER_SHORTCUTS);
+        shortcuts::register(&cc.egui_ctx, &notedeck_ui::datetime::SHORTCUTS);
+        shortcuts::register(&cc.egui_ctx, &notedeck::zoom::ZOOM_SHORTCUTS);
+
+        let notebook = notedeck.has_option(NotedeckOptions::FeatureNotebook);
+        let clndash = notedeck.has_option(NotedeckOptions::FeatureClnDash);
+
         let context = &mut notedeck.app_context();
-        let dave = Dave::new(cc.wgpu_render_state.as_ref());
         let columns = Damus::new(context, app_args);
         let mut chrome = Chrome::default();
 
         notedeck.check_args(columns.unrecognized_args())?;
 
         chrome.add_app(NotedeckApp::Columns(Box::new(columns)));
-        chrome.add_app(NotedeckApp::Dave(Box::new(dave)));
 
-        if notedeck.has_option(NotedeckOptions::FeatureNotebook) {
-            chrome.add_app(NotedeckApp::Notebook(Box::default()));
+        let context = &mut notedeck.app_context();
+        let render_state = cc.wgpu_render_state.clone();
+        chrome.register_app(
+            context,
+            AppEntry::new(
+                DAVE_ID,
+                |i18n| tr!(i18n, "Dave", "Button to go to the Dave app"),
+                |i18n| {
+                    tr!(
+                        i18n,
+                        "Your nostr AI assistant",
+                        "Description of the Dave app"
+                    )
+                },
+                |ui| glyph_icon(ui, "🤖"),
+                move |_| NotedeckApp::Dave(Box::new(Dave::new(render_state.as_ref()))),
+            )
+            .enabled_by_default(true),
+        );
+        chrome.register_app(
+            context,
+            AppEntry::new(
+                NOTEBOOK_ID,
+                |i18n| tr!(i18n, "Notebook", "Button to go to the Notebook app"),
+                |i18n| {
+                    tr!(
+                        i18n,
+                        "An infinite canvas of notes",
+                        "Description of the Notebook app"
+                    )
+                },
+                |ui| {
+                    ui.add(app_images::algo_image().max_size(ui.available_size()));
+                },
+                |_| NotedeckApp::Notebook(Box::default()),
+            )
+            .enabled_by_default(notebook),
+        );
+        chrome.register_app(
+            context,
+            AppEntry::new(
+                CLNDASH_ID,
+                |i18n| tr!(i18n, "ClnDash", "Button to go to the ClnDash app"),
+                |i18n| {
+                    tr!(
+                        i18n,
+                        "Dashboard for a Core Lightning node",
+                        "Description of the ClnDash app"
+                    )
+                },
+                |ui| {
+                    ui.add(app_images::cln_image().max_size(ui.available_size()));
+                },
+                |_| NotedeckApp::ClnDash(Box::default()),
+            )
+            .enabled_by_default(clndash),
+        );
+
+        chrome.set_active(0);
+
+        Ok(chrome)
+    }
+
+    /// Add an app to the catalog, and start it if it's turned on
+    pub fn register_app(&mut self, ctx: &mut AppContext, entry: AppEntry) {
+        let id = entry.id;
+        self.catalog.register(entry);
+        if self.catalog.is_enabled(ctx, id) {
+            self.set_app_enabled(ctx, id, true);
+        }
+    }
+
+    /// Start or stop an app from the catalog
+    fn set_app_enabled(&mut self, ctx: &mut AppContext, id: &str, enabled: bool) {
+        // columns is where notes, profiles and settings open
+        if id == COLUMNS_ID {
+            return;
+        }
+
+        let index = self.apps.iter().position(|app| app.id() == id);
+        match (enabled, index) {
+            (true, None) => {
+                if let Some(entry) = self.catalog.get(id) {
+                    let app = entry.create(ctx);
+                    self.add_app(app);
+                }
+            }
+            (false, Some(index)) => {
+                self.apps.remove(index);
+                ctx.subs.close_owner(ctx.pool, id);
+                let active = self.active as usize;
+                if active == index {
+                    self.switch_to_columns();
+                } else if active > index {
+                    self.active -= 1;
+                }
+            }
+            _ => {}
         }
+    }
 
-        if notedeck.has_option(NotedeckOptions::FeatureClnDash) {
-            chrome.add_app(NotedeckApp::ClnDash(Box::default()));
+    /// Move subscriptions along when the active app changes
+    fn update_foreground(&mut self, ctx: &mut AppContext) {
+        let active = self.apps.get(self.active as usize).map(|app| app.id());
+        if active == self.foreground {
+            return;
         }
 
-        chrome.set_active(0);
+        if let Some(prev) = self.foreground {
+            ctx.subs.background(ctx.pool, prev);
+        }
+        if let Some(next) = active {
+            ctx.subs.foreground(ctx.pool, next);
+        }
+        self.foreground = active;
+    }
 
-        Ok(chrome)
+    /// Switch to an app, starting it first if it isn't running
+    fn open_app(&mut self, ctx: &mut AppContext, id: &str) {
+        if !self.apps.iter().any(|app| app.id() == id) {
+            if self.catalog.get(id).is_none() {
+                tracing::warn!("no app with id {id}");
+                return;
+            }
+            ctx.settings.set_app_enabled(id, true);
+            self.set_app_enabled(ctx, id, true);
+        }
+
+        if let Some(index) = self.apps.iter().position(|app| app.id() == id) {
+            self.active = index as i32;
+        }
     }
 
     pub fn toggle(&mut self) {
@@ -244,6 +411,11 @@ impl Chrome {
 
                         ui.with_layout(Layout::bottom_up(egui::Align::Center), |ui| {
                             ui.add(milestone_name(app_ctx.i18n));
+                            ui.add_space(8.0);
+                            ui.add(notedeck_ui::RelayStatusChip::new(
+                                app_ctx.relay_summary(),
+                                app_ctx.i18n,
+                            ));
                         });
 
                         RouteResponse {
@@ -254,7 +426,10 @@ impl Chrome {
                     .inner
             }
             ChromeRoute::App => {
-                let resp = self.apps[self.active as usize].update(app_ctx, ui);
+                let app = &mut self.apps[self.active as usize];
+                let started = std::time::Instant::now();
+                let resp = app.update(app_ctx, ui);
+                self.app_timings.record(app.id(), started.elapsed());
 
                 if let Some(action) = resp.action {
                     chrome_handle_app_action(self, app_ctx, action, ui);
@@ -345,10 +520,122 @@ impl Chrome {
 
 impl notedeck::App for Chrome {
     fn update(&mut self, ctx: &mut notedeck::AppContext, ui: &mut egui::Ui) -> AppResponse {
+        notedeck_ui::set_rtl(ui.ctx(), ctx.i18n.is_rtl());
+
         if let Some(action) = self.show(ctx, ui) {
             action.process(ctx, self, ui);
             self.nav.close();
         }
+
+        if let Some(signer) = ctx.accounts.selected_signer_mut() {
+            remote_signer_ui(signer, ctx.i18n, ui);
+        }
+
+        if ctx.publish_tracker.is_offline() {
+            offline_banner(ctx.publish_tracker.queued(), ctx.i18n, ui);
+        }
+
+        if shortcuts::consume(ui.ctx(), &SEARCH_SHORTCUT) {
+            self.search = match self.search {
+                Some(_) => None,
+                None => Some(GlobalSearch::default()),
+            };
+        }
+
+        if let Some(search) = &mut self.search {
+            match search.ui(ctx.ndb, &ctx.accounts.mute(), ctx.i18n, ui) {
+                Some(GlobalSearchAction::Close) => self.search = None,
+                Some(GlobalSearchAction::Open(action)) => {
+                    self.search = None;
+                    chrome_handle_app_action(self, ctx, AppAction::Note(action), ui);
+                }
+                None => {}
+            }
+        }
+
+        // don't take ? away from someone typing it
+        if !ui.ctx().wants_keyboard_input() && shortcuts::consume(ui.ctx(), &shortcuts::CHEAT_SHEET)
+        {
+            self.options.toggle(ChromeOptions::ShortcutSheet);
+        }
+
+        if self.options.contains(ChromeOptions::ShortcutSheet) && !cheat_sheet_ui(ctx.i18n, ui) {
+            self.options.remove(ChromeOptions::ShortcutSheet);
+        }
+
+        if shortcuts::consume(ui.ctx(), &DIAGNOSTICS_SHORTCUT) {
+            self.options.toggle(ChromeOptions::Diagnostics);
+        }
+
+        if self.options.contains(ChromeOptions::Diagnostics) {
+            let mut subs = self
+                .get_columns_app()
+                .map(|columns| subscription_lines(columns.subscriptions()))
+                .unwrap_or_default();
+            subs.extend(ctx.subs.list().map(|(owner, subid, open)| {
+                let state = if open { "open" } else { "closed" };
+                format!("{owner} {subid} {state}")
+            }));
+            if !diagnostics_ui(ctx, &subs, ui) {
+                self.options.remove(ChromeOptions::Diagnostics);
+            }
+        }
+
+        if shortcuts::consume(ui.ctx(), &FRAME_OVERLAY_SHORTCUT) {
+            self.options.toggle(ChromeOptions::FrameOverlay);
+        }
+
+        if self.options.contains(ChromeOptions::FrameOverlay) {
+            let active = self.apps.get(self.active as usize).map(|app| app.id());
+            frame_overlay_ui(&self.app_timings, active, ctx, ui);
+            // keep the numbers moving
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(250));
+        }
+
+        while let Some(note_id) = notedeck::platform::get_next_clicked_notification() {
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Focus);
+            open_note(self, ctx, note_id, ui);
+        }
+
+        while let Some(uri) = notedeck::nostr_uri::get_next_opened_uri() {
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Focus);
+            open_uri(self, ctx, uri, ui);
+        }
+
+        if self.options.contains(ChromeOptions::AppCatalog) {
+            match catalog_ui(&self.catalog, ctx, ui) {
+                Some(CatalogAction::Close) => {
+                    self.options.remove(ChromeOptions::AppCatalog);
+                }
+                Some(CatalogAction::SetEnabled(id, enabled)) => {
+                    ctx.settings.set_app_enabled(id, enabled);
+                    self.set_app_enabled(ctx, id, enabled);
+                }
+                Some(CatalogAction::Open(id)) => {
+                    self.options.remove(ChromeOptions::AppCatalog);
+                    self.open_app(ctx, id);
+                    self.nav.close();
+                }
+                None => {}
+            }
+        }
+
+        if self.options.contains(ChromeOptions::NotificationInbox) {
+            match notification_inbox_ui(ctx, ui) {
+                Some(InboxAction::Close) => {
+                    self.options.remove(ChromeOptions::NotificationInbox);
+                }
+                Some(InboxAction::OpenNote(note_id)) => {
+                    self.options.remove(ChromeOptions::NotificationInbox);
+                    open_note(self, ctx, note_id, ui);
+                }
+                None => {}
+            }
+        }
+
+        self.update_foreground(ctx);
+
         // TODO: unify this constant with the columns side panel width. ui crate?
         AppResponse::none()
     }
@@ -391,6 +678,22 @@ fn milestone_name<'a>(i18n: &'a mut Localization) -> impl Widget + 'a {
     }
 }
 
+/// A single character as a drawer icon
+fn glyph_icon(ui: &mut egui::Ui, glyph: &str) {
+    let painter = ui.painter();
+    let galley = painter.layout_no_wrap(
+        glyph.to_owned(),
+        NotedeckTextStyle::Heading3.get_font_id(ui.ctx()),
+        ui.visuals().text_color(),
+    );
+
+    painter.galley(
+        galley_centered_pos(&galley, ui.available_rect_before_wrap().center()),
+        galley,
+        ui.visuals().text_color(),
+    );
+}
+
 fn clndash_button(ui: &mut egui::Ui) -> egui::Response {
     expanding_button(
         "clndash-button",
@@ -453,6 +756,10 @@ fn chrome_handle_app_action(
             chrome.toggle();
         }
 
+        AppAction::OpenApp(id) => {
+            chrome.open_app(ctx, id);
+        }
+
         AppAction::Note(note_action) => {
             chrome.switch_to_columns();
             let Some(columns) = chrome.get_columns_app() else {
@@ -474,6 +781,8 @@ fn chrome_handle_app_action(
                 &mut columns.threads,
                 ctx.note_cache,
                 ctx.pool,
+                ctx.subs,
+                ctx.publish_tracker,
                 &txn,
                 ctx.unknown_ids,
                 ctx.accounts,
@@ -493,6 +802,54 @@ fn chrome_handle_app_action(
     }
 }
 
+/// Open a note's thread in the columns app, for notifications
+fn open_note(chrome: &mut Chrome, ctx: &mut AppContext, note_id: NoteId, ui: &mut egui::Ui) {
+    let action = NoteAction::Note {
+        note_id,
+        preview: false,
+        scroll_offset: 0.0,
+    };
+    chrome_handle_app_action(chrome, ctx, AppAction::Note(action), ui);
+}
+
+/// Route a `nostr:` link to the view for it. Profiles and notes open in
+/// the columns app, addresses open the latest note at that address.
+fn open_uri(chrome: &mut Chrome, ctx: &mut AppContext, uri: NostrUri, ui: &mut egui::Ui) {
+    let action = match uri {
+        NostrUri::Profile(pk) => NoteAction::Profile(pk),
+        NostrUri::Note(note_id) => return open_note(chrome, ctx, note_id, ui),
+        NostrUri::Address {
+            kind,
+            author,
+            identifier,
+        } => match latest_at_address(ctx.ndb, kind, &author, &identifier) {
+            Some(note_id) => return open_note(chrome, ctx, note_id, ui),
+            // we haven't seen it yet, show whoever made it
+            None => NoteAction::Profile(author),
+        },
+    };
+
+    chrome_handle_app_action(chrome, ctx, AppAction::Note(action), ui);
+}
+
+fn latest_at_address(
+    ndb: &Ndb,
+    kind: u32,
+    author: &notedeck::enostr::Pubkey,
+    identifier: &str,
+) -> Option<NoteId> {
+    let txn = Transaction::new(ndb).ok()?;
+    let filter = Filter::new()
+        .kinds([kind as u64])
+        .authors([author.bytes()])
+        .tags([identifier], 'd')
+        .limit(1)
+        .build();
+
+    let results = ndb.query(&txn, &[filter], 1).ok()?;
+    results.first().map(|r| NoteId::new(*r.note.id()))
+}
+
 fn columns_route_to_profile(
     pk: &notedeck::enostr::Pubkey,
     chrome: &mut Chrome,
@@ -530,6 +887,8 @@ fn columns_route_to_profile(
         &mut columns.threads,
         ctx.note_cache,
         ctx.pool,
+        ctx.subs,
+        ctx.publish_tracker,
         &txn,
         ctx.unknown_ids,
         ctx.accounts,
@@ -558,6 +917,9 @@ fn topdown_sidebar(
     let previous_spacing = ui.spacing().item_spacing;
     ui.spacing_mut().item_spacing.y = 12.0;
 
+    let balance = get_current_wallet_mut(ctx.accounts, ctx.global_wallet)
+        .and_then(|wallet| wallet.wallet.get_balance()?.as_ref().ok().copied());
+
     let loc = &mut ctx.i18n;
 
     // macos needs a bit of space to make room for window
@@ -618,7 +980,7 @@ fn topdown_sidebar(
     let theme = ui.ctx().theme();
 
     StripBuilder::new(ui)
-        .sizes(Size::exact(40.0), 6)
+        .sizes(Size::exact(40.0), 8)
         .clip(true)
         .vertical(|mut strip| {
             strip.strip(|b| {
@@ -664,6 +1026,16 @@ fn topdown_sidebar(
             });
 
             strip.strip(|b| {
+                let text = match balance {
+                    Some(msats) => tr!(
+                        loc,
+                        "Wallet ({balance})",
+                        "Button to go to the wallet view, with the wallet's balance",
+                        balance = format_balance(msats)
+                    ),
+                    None => tr!(loc, "Wallet", "Button to go to the wallet view"),
+                };
+
                 if drawer_item(
                     b,
                     |ui| {
@@ -675,7 +1047,7 @@ fn topdown_sidebar(
 
                         ui.add(img);
                     },
-                    tr!(loc, "Wallet", "Button to go to the wallet view"),
+                    text,
                 )
                 .clicked()
                 {
@@ -683,6 +1055,42 @@ fn topdown_sidebar(
                 }
             });
 
+            strip.strip(|b| {
+                let unread = ctx.notifications.unread_count();
+                let text = if unread == 0 {
+                    tr!(loc, "Notifications", "Button to open the notification inbox")
+                } else {
+                    tr!(
+                        loc,
+                        "Notifications ({count})",
+                        "Button to open the notification inbox, with the number of unread notifications",
+                        count = unread
+                    )
+                };
+
+                if drawer_item(
+                    b,
+                    |ui| glyph_icon(ui, "🔔"),
+                    text,
+                )
+                .clicked()
+                {
+                    action = Some(ChromePanelAction::Notifications);
+                }
+            });
+
+            strip.strip(|b| {
+                if drawer_item(
+                    b,
+                    |ui| glyph_icon(ui, "▦"),
+                    tr!(loc, "Apps", "Button to open the app catalog"),
+                )
+                .clicked()
+                {
+                    action = Some(ChromePanelAction::Apps);
+                }
+            });
+
             strip.strip(|b| {
                 if drawer_item(
                     b,
@@ -705,23 +1113,13 @@ fn topdown_sidebar(
                 if drawer_item(
                     b,
                     |ui| {
-                        let c = match theme {
-                            egui::Theme::Dark => "🔆",
-                            egui::Theme::Light => "🌒",
-                        };
-
-                        let painter = ui.painter();
-                        let galley = painter.layout_no_wrap(
-                            c.to_owned(),
-                            NotedeckTextStyle::Heading3.get_font_id(ui.ctx()),
-                            ui.visuals().text_color(),
-                        );
-
-                        painter.galley(
-                            galley_centered_pos(&galley, ui.available_rect_before_wrap().center()),
-                            galley,
-                            ui.visuals().text_color(),
-                        );
+                        glyph_icon(
+                            ui,
+                            match theme {
+                                egui::Theme::Dark => "🔆",
+                                egui::Theme::Light => "🌒",
+                            },
+                        )
                     },
                     tr!(loc, "Theme", "Button to change the theme (light or dark)"),
                 )
@@ -762,14 +1160,10 @@ fn topdown_sidebar(
             continue;
         }
 
-        let text = match &app {
-            NotedeckApp::Dave(_) => tr!(loc, "Dave", "Button to go to the Dave app"),
-            NotedeckApp::Columns(_) => tr!(loc, "Columns", "Button to go to the Columns app"),
-            NotedeckApp::Notebook(_) => {
-                tr!(loc, "Notebook", "Button to go to the Notebook app")
-            }
-            NotedeckApp::ClnDash(_) => tr!(loc, "ClnDash", "Button to go to the ClnDash app"),
-            NotedeckApp::Other(_) => tr!(loc, "Other", "Button to go to the Other app"),
+        let catalog = &chrome.catalog;
+        let text = match catalog.get(app.id()) {
+            Some(entry) => (entry.name)(loc),
+            None => tr!(loc, "Columns", "Button to go to the Columns app"),
         };
 
         StripBuilder::new(ui)
@@ -779,34 +1173,33 @@ fn topdown_sidebar(
                 strip.strip(|b| {
                     let resp = drawer_item(
                         b,
-                        |ui| {
-                            match app {
-                                NotedeckApp::Columns(_columns_app) => {
-                                    ui.add(app_images::columns_image());
-                                }
+                        |ui| match app {
+                            NotedeckApp::Columns(_columns_app) => {
+                                ui.add(app_images::columns_image());
+                            }
 
-                                NotedeckApp::Dave(dave) => {
-                                    dave_button(
-                                        dave.avatar_mut(),
-                                        ui,
-                                        Rect::from_center_size(
-                                            ui.available_rect_before_wrap().center(),
-                                            vec2(30.0, 30.0),
-                                        ),
-                                    );
-                                }
+                            NotedeckApp::Dave(dave) => {
+                                dave_button(
+                                    dave.avatar_mut(),
+                                    ui,
+                                    Rect::from_center_size(
+                                        ui.available_rect_before_wrap().center(),
+                                        vec2(30.0, 30.0),
+                                    ),
+                                );
+                            }
 
-                                NotedeckApp::ClnDash(_clndash) => {
-                                    clndash_button(ui);
-                                }
+                            NotedeckApp::ClnDash(_clndash) => {
+                                clndash_button(ui);
+                            }
 
-                                NotedeckApp::Notebook(_notebook) => {
-                                    notebook_button(ui);
-                                }
+                            NotedeckApp::Notebook(_notebook) => {
+                                notebook_button(ui);
+                            }
 
-                                NotedeckApp::Other(_other) => {
-                                    // app provides its own button rendering ui?
-                                    panic!("TODO: implement other apps")
+                            NotedeckApp::Other(id, _other) => {
+                                if let Some(entry) = catalog.get(id) {
+                                    (entry.icon)(ui);
                                 }
                             }
                         },
@@ -875,6 +1268,256 @@ fn topdown_sidebar(
     action
 }
 
+/// Shown while no relay is connected
+fn offline_banner(queued: usize, i18n: &mut Localization, ui: &mut egui::Ui) {
+    let text = if queued == 0 {
+        tr!(
+            i18n,
+            "Offline, reconnecting to relays...",
+            "Banner shown while no relay is connected"
+        )
+    } else {
+        tr!(
+            i18n,
+            "Offline, {count} notes will be sent when relays reconnect",
+            "Banner shown while no relay is connected and notes are queued",
+            count = queued
+        )
+    };
+
+    egui::Area::new(egui::Id::new("offline_banner"))
+        .anchor(egui::Align2::CENTER_TOP, vec2(0.0, 8.0))
+        .interactable(false)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::new()
+                .fill(ui.visuals().warn_fg_color)
+                .corner_radius(8.0)
+                .inner_margin(Margin::symmetric(12, 6))
+                .show(ui, |ui| {
+                    ui.label(RichText::new(text).color(Color32::BLACK));
+                });
+        });
+}
+
+/// Requests waiting on the selected account's remote signer. We only
+/// pop up once one of them needs the user, most are answered right away.
+fn remote_signer_ui(signer: &mut RemoteSigner, i18n: &mut Localization, ui: &mut egui::Ui) {
+    let needs_attention = signer
+        .requests()
+        .iter()
+        .any(|r| r.status != SignerStatus::Waiting);
+    if !needs_attention {
+        return;
+    }
+
+    let mut dismiss = None;
+    let title = tr!(
+        i18n,
+        "Remote signer",
+        "Title of the window with pending remote signer requests"
+    );
+    egui::Window::new(title)
+        .id(egui::Id::new("remote_signer_requests"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ui.ctx(), |ui| {
+            for request in signer.requests() {
+                let what = match request.method {
+                    SignerMethod::Connect => {
+                        tr!(i18n, "Connect", "Remote signer request to connect")
+                    }
+                    SignerMethod::GetPublicKey => tr!(
+                        i18n,
+                        "Share public key",
+                        "Remote signer request for the user's public key"
+                    ),
+                    SignerMethod::SignEvent(kind) => tr!(
+                        i18n,
+                        "Sign event of kind {kind}",
+                        "Remote signer request to sign an event",
+                        kind = kind
+                    ),
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label(what);
+
+                    match &request.status {
+                        SignerStatus::Waiting => {
+                            ui.add(egui::Spinner::new());
+                        }
+                        SignerStatus::NeedsAuth(url) => {
+                            ui.hyperlink_to(
+                                tr!(i18n, "Approve", "Link to approve a remote signer request"),
+                                url,
+                            );
+                        }
+                        SignerStatus::Failed(err) => {
+                            ui.colored_label(ui.visuals().error_fg_color, err);
+                        }
+                    }
+
+                    if request.status != SignerStatus::Waiting
+                        && ui
+                            .button(tr!(
+                                i18n,
+                                "Dismiss",
+                                "Button to dismiss a remote signer request"
+                            ))
+                            .clicked()
+                    {
+                        dismiss = Some(request.id.clone());
+                    }
+                });
+            }
+        });
+
+    if let Some(id) = dismiss {
+        signer.dismiss(&id);
+    }
+}
+
+enum InboxAction {
+    Close,
+    OpenNote(NoteId),
+}
+
+/// The notification inbox window, shared by every app
+fn notification_inbox_ui(ctx: &mut AppContext, ui: &mut egui::Ui) -> Option<InboxAction> {
+    let mut action = None;
+    let mut open = true;
+
+    let title = tr!(ctx.i18n, "Notifications", "Title of the notification inbox");
+    egui::Window::new(title)
+        .id(egui::Id::new("notification_inbox"))
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(360.0)
+        .show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                let mark_read = tr!(
+                    ctx.i18n,
+                    "Mark all read",
+                    "Button to mark every notification as read"
+                );
+                if ui.button(mark_read).clicked() {
+                    ctx.notifications.mark_all_read();
+                }
+            });
+
+            let types = tr!(
+                ctx.i18n,
+                "Notification types",
+                "Section to turn notification types on or off"
+            );
+            egui::CollapsingHeader::new(types).show(ui, |ui| {
+                for kind in NotificationKind::ALL {
+                    let mut enabled = ctx.notifications.is_enabled(kind);
+                    let label = kind.label(ctx.i18n);
+                    if ui.checkbox(&mut enabled, label).changed() {
+                        ctx.notifications.set_enabled(kind, enabled);
+                        ctx.settings.set_notification_enabled(kind, enabled);
+                    }
+                }
+
+                let mut os_notifications = ctx.settings.os_notifications();
+                let label = tr!(
+                    ctx.i18n,
+                    "Show system notifications",
+                    "Toggle to also show notifications through the operating system"
+                );
+                if ui.checkbox(&mut os_notifications, label).changed() {
+                    ctx.settings.set_os_notifications(os_notifications);
+                }
+            });
+
+            ui.separator();
+
+            if ctx.notifications.is_empty() {
+                ui.weak(tr!(
+                    ctx.i18n,
+                    "No notifications yet",
+                    "Shown when the notification inbox is empty"
+                ));
+                return;
+            }
+
+            let Ok(txn) = Transaction::new(ctx.ndb) else {
+                return;
+            };
+
+            let all_accounts = ctx.settings.all_accounts();
+            let mut clicked = None;
+            egui::ScrollArea::vertical()
+                .max_height(400.0)
+                .show(ui, |ui| {
+                    for (i, notification) in ctx.notifications.iter().enumerate() {
+                        let kind = notification.kind.label(ctx.i18n);
+                        let when = notedeck::time_ago_since(ctx.i18n, notification.created_at);
+                        let who = notification.author.map(|pk| {
+                            let profile = ctx.ndb.get_profile_by_pubkey(&txn, pk.bytes());
+                            get_display_name(profile.as_ref().ok()).name().to_owned()
+                        });
+
+                        let mut header = RichText::new(match who {
+                            Some(who) => format!("{kind} · {who} · {when}"),
+                            None => format!("{kind} · {when}"),
+                        });
+                        if !notification.read {
+                            header = header.strong();
+                        }
+
+                        let resp = ui
+                            .vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    // tell the accounts apart when they're merged
+                                    if let Some(account) =
+                                        notification.account.filter(|_| all_accounts)
+                                    {
+                                        let (rect, _) =
+                                            ui.allocate_exact_size(vec2(8.0, 8.0), Sense::hover());
+                                        ui.painter().circle_filled(
+                                            rect.center(),
+                                            4.0,
+                                            colors::account_color(&account),
+                                        );
+                                        ui.add_space(4.0);
+                                    }
+                                    ui.add(Label::new(header).selectable(false));
+                                });
+                                if !notification.text.is_empty() {
+                                    ui.add(
+                                        Label::new(notification.text.as_str()).selectable(false),
+                                    );
+                                }
+                            })
+                            .response
+                            .interact(Sense::click())
+                            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+                        if resp.clicked() {
+                            clicked = Some((i, notification.note_id));
+                        }
+
+                        ui.separator();
+                    }
+                });
+
+            if let Some((i, note_id)) = clicked {
+                ctx.notifications.mark_read(i);
+                if let Some(note_id) = note_id {
+                    action = Some(InboxAction::OpenNote(note_id));
+                }
+            }
+        });
+
+    if !open {
+        return Some(InboxAction::Close);
+    }
+
+    action
+}
+
 fn drawer_item(builder: StripBuilder, icon: impl FnOnce(&mut Ui), text: String) -> egui::Response {
     builder
         .cell_layout(Layout::left_to_right(egui::Align::Center))
@@ -0,0 +1,295 @@
+//! Recent warnings, relay traffic, open subscriptions and frame times in
+//! one window, with a bug report bundle to copy and paste into an issue.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use egui::{Key, KeyboardShortcut, Modifiers, RichText};
+use notedeck::enostr::RelayStatus;
+use notedeck::{shortcuts, tr, AppContext, Shortcut};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// How many warnings and errors we remember
+const MAX_LOG_LINES: usize = 200;
+
+/// Open or close the diagnostics window
+pub const DIAGNOSTICS_SHORTCUT: Shortcut = Shortcut::new(
+    "general.diagnostics",
+    shortcuts::general_group,
+    |i18n| tr!(i18n, "Diagnostics", "Keyboard shortcut description"),
+    KeyboardShortcut::new(
+        Modifiers {
+            shift: true,
+            ..Modifiers::COMMAND
+        },
+        Key::D,
+    ),
+);
+
+#[derive(Clone)]
+struct LogLine {
+    /// Unix time
+    at: u64,
+    level: Level,
+    target: String,
+    message: String,
+}
+
+static RECENT_LOGS: Mutex<VecDeque<LogLine>> = Mutex::new(VecDeque::new());
+
+fn recent_logs() -> Vec<LogLine> {
+    RECENT_LOGS
+        .lock()
+        .map(|logs| logs.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// A tracing layer that keeps the last warnings and errors around for the
+/// diagnostics window
+pub struct RecentLogsLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        // more verbose levels compare greater
+        if *meta.level() > Level::WARN {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let line = LogLine {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            level: *meta.level(),
+            target: meta.target().to_owned(),
+            message: visitor.message + &visitor.fields,
+        };
+
+        let Ok(mut logs) = RECENT_LOGS.lock() else {
+            return;
+        };
+        logs.push_back(line);
+        while logs.len() > MAX_LOG_LINES {
+            logs.pop_front();
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    /// Other fields, as ` name=value`
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+fn status_name(status: RelayStatus) -> &'static str {
+    match status {
+        RelayStatus::Connected => "connected",
+        RelayStatus::Connecting => "connecting",
+        RelayStatus::Disconnected => "disconnected",
+    }
+}
+
+/// The columns' relay subscriptions as `subid kind`, sorted so the list
+/// doesn't jump around between frames
+pub fn subscription_lines(subs: &HashMap<String, impl std::fmt::Debug>) -> Vec<String> {
+    let mut lines: Vec<String> = subs
+        .iter()
+        .map(|(subid, kind)| format!("{subid} {kind:?}"))
+        .collect();
+    lines.sort();
+    lines
+}
+
+/// Everything in the window as plain text, for bug reports
+fn bug_report(ctx: &AppContext, subs: &[String], logs: &[LogLine]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "notedeck {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "{} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(
+        out,
+        "{:.0} fps, {:.1} ms/frame",
+        ctx.frame_history.fps(),
+        ctx.frame_history.mean_frame_time() * 1e3
+    );
+
+    let _ = writeln!(out, "\nrelays:");
+    for relay in &ctx.pool.relays {
+        let _ = write!(out, "  {} {}", relay.url(), status_name(relay.status()));
+        if let Some(stats) = relay.stats() {
+            let _ = write!(
+                out,
+                " sent={} received={} ({} bytes) connects={} failures={}",
+                stats.messages_sent,
+                stats.messages_received,
+                stats.bytes_received,
+                stats.connects,
+                stats.failures
+            );
+            if let Some(latency) = stats.latency {
+                let _ = write!(out, " latency={}ms", latency.as_millis());
+            }
+            let _ = write!(out, " queued={}", relay.queued());
+        }
+        let _ = writeln!(out);
+    }
+
+    let _ = writeln!(out, "\nsubscriptions:");
+    for sub in subs {
+        let _ = writeln!(out, "  {sub}");
+    }
+
+    let _ = writeln!(out, "\nrecent warnings:");
+    for line in logs {
+        let _ = writeln!(
+            out,
+            "  {} {} {}: {}",
+            line.at, line.level, line.target, line.message
+        );
+    }
+
+    out
+}
+
+/// Returns false once closed
+pub fn diagnostics_ui(ctx: &mut AppContext, subs: &[String], ui: &mut egui::Ui) -> bool {
+    let mut open = !ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape));
+    let logs = recent_logs();
+
+    let title = tr!(ctx.i18n, "Diagnostics", "Title of the diagnostics window");
+    let egui_ctx = ui.ctx().clone();
+    egui::Window::new(title)
+        .id(egui::Id::new("diagnostics"))
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(520.0)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(&egui_ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{:.0} fps, {:.1} ms/frame",
+                    ctx.frame_history.fps(),
+                    ctx.frame_history.mean_frame_time() * 1e3
+                ));
+
+                let copy = tr!(
+                    ctx.i18n,
+                    "Copy bug report",
+                    "Button to copy diagnostics as text for a bug report"
+                );
+                if ui.button(copy).clicked() {
+                    ui.ctx().copy_text(bug_report(ctx, subs, &logs));
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .max_height(480.0)
+                .show(ui, |ui| {
+                    let relays = tr!(
+                        ctx.i18n,
+                        "Relays",
+                        "Relay section of the diagnostics window"
+                    );
+                    ui.add_space(6.0);
+                    ui.label(RichText::new(relays).strong());
+                    egui::Grid::new("diagnostics_relays")
+                        .num_columns(4)
+                        .spacing([16.0, 4.0])
+                        .show(ui, |ui| {
+                            for relay in &ctx.pool.relays {
+                                ui.label(relay.url());
+                                ui.label(status_name(relay.status()));
+                                match relay.stats() {
+                                    Some(stats) => {
+                                        ui.label(format!(
+                                            "↑{} ↓{} queued {}",
+                                            stats.messages_sent,
+                                            stats.messages_received,
+                                            relay.queued()
+                                        ));
+                                        ui.label(notedeck_ui::format_size(stats.bytes_received));
+                                    }
+                                    None => {
+                                        ui.label("");
+                                        ui.label("");
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                    let subs = tr!(
+                        ctx.i18n,
+                        "Subscriptions",
+                        "Subscription section of the diagnostics window"
+                    );
+                    ui.add_space(6.0);
+                    ui.label(RichText::new(subs).strong());
+                    for sub in subs {
+                        ui.label(sub);
+                    }
+
+                    let warnings = tr!(
+                        ctx.i18n,
+                        "Recent warnings",
+                        "Warnings and errors section of the diagnostics window"
+                    );
+                    ui.add_space(6.0);
+                    ui.label(RichText::new(warnings).strong());
+                    for line in logs.iter().rev() {
+                        let color = if line.level == Level::ERROR {
+                            ui.visuals().error_fg_color
+                        } else {
+                            ui.visuals().warn_fg_color
+                        };
+                        ui.horizontal_wrapped(|ui| {
+                            ui.weak(notedeck::time_format(ctx.i18n, line.at));
+                            ui.colored_label(color, &line.target);
+                            ui.label(&line.message);
+                        });
+                    }
+                });
+        });
+
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn keeps_warnings_not_info() {
+        let subscriber = tracing_subscriber::registry().with(RecentLogsLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("just chatting");
+            tracing::warn!(relay = "wss://a", "relay went away");
+        });
+
+        let logs = recent_logs();
+        assert!(logs
+            .iter()
+            .any(|l| l.level == Level::WARN && l.message == "relay went away relay=\"wss://a\""));
+        assert!(!logs.iter().any(|l| l.message.contains("just chatting")));
+    }
+}
@@ -0,0 +1,107 @@
+//! An on-screen overlay with how long each app takes per frame and how
+//! the media caches are doing, so jank can be pinned on the right app.
+//! Toggled with ctrl+shift+f.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use egui::{Key, KeyboardShortcut, Modifiers, RichText};
+use notedeck::{shortcuts, tr, AppContext, Shortcut};
+
+/// How much a new frame moves the average
+const SMOOTHING: f32 = 0.1;
+
+pub const FRAME_OVERLAY_SHORTCUT: Shortcut = Shortcut::new(
+    "general.frame_overlay",
+    shortcuts::general_group,
+    |i18n| tr!(i18n, "Frame time overlay", "Keyboard shortcut description"),
+    KeyboardShortcut::new(
+        Modifiers {
+            shift: true,
+            ..Modifiers::COMMAND
+        },
+        Key::F,
+    ),
+);
+
+/// Smoothed update time of each app, in ms. Only the active app runs, so
+/// the others keep their last value.
+#[derive(Default)]
+pub struct AppTimings {
+    ms: BTreeMap<&'static str, f32>,
+}
+
+impl AppTimings {
+    pub fn record(&mut self, app: &'static str, took: Duration) {
+        let ms = took.as_secs_f32() * 1e3;
+        self.ms
+            .entry(app)
+            .and_modify(|avg| *avg += (ms - *avg) * SMOOTHING)
+            .or_insert(ms);
+    }
+}
+
+pub fn frame_overlay_ui(
+    timings: &AppTimings,
+    active: Option<&'static str>,
+    ctx: &mut AppContext,
+    ui: &mut egui::Ui,
+) {
+    let textures = ctx.img_cache.texture_stats();
+
+    egui::Area::new(egui::Id::new("frame_overlay"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+        .interactable(false)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                let small = |s: String| RichText::new(s).small().monospace();
+
+                ui.label(small(format!(
+                    "frame {:5.1} ms  {:3.0} fps",
+                    ctx.frame_history.mean_frame_time() * 1e3,
+                    ctx.frame_history.fps()
+                )));
+
+                for (app, ms) in &timings.ms {
+                    let line = small(format!("{app:<10} {ms:5.1} ms"));
+                    if Some(*app) == active {
+                        ui.label(line.strong());
+                    } else {
+                        ui.weak(line);
+                    }
+                }
+
+                let hit_rate = textures
+                    .hit_rate()
+                    .map(|rate| format!("{:.0}%", rate * 100.0))
+                    .unwrap_or_else(|| "-".to_owned());
+                ui.label(small(tr!(
+                    ctx.i18n,
+                    "textures {count} ({size}), {rate} hits",
+                    "Texture cache line in the frame time overlay",
+                    count = textures.loaded,
+                    size = notedeck_ui::format_size(textures.bytes as u64),
+                    rate = hit_rate
+                )));
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_are_smoothed_per_app() {
+        let mut timings = AppTimings::default();
+        timings.record("columns", Duration::from_millis(10));
+        assert_eq!(timings.ms.get("columns"), Some(&10.0));
+
+        timings.record("columns", Duration::from_millis(20));
+        let ms = timings.ms["columns"];
+        assert!((ms - 11.0).abs() < 0.01);
+
+        assert!(!timings.ms.contains_key("dave"));
+    }
+}
@@ -4,9 +4,16 @@ pub mod setup;
 mod android;
 
 mod app;
+mod catalog;
+mod cheat_sheet;
 mod chrome;
+mod diagnostics;
+mod frame_overlay;
 mod options;
+mod search;
 
 pub use app::NotedeckApp;
+pub use catalog::{AppCatalog, AppEntry};
 pub use chrome::Chrome;
+pub use diagnostics::RecentLogsLayer;
 pub use options::ChromeOptions;
@@ -10,7 +10,7 @@ static GLOBAL: AccountingAllocator<std::alloc::System> =
     AccountingAllocator::new(std::alloc::System);
 
 use notedeck::{DataPath, DataPathType, Notedeck};
-use notedeck_chrome::{setup::generate_native_options, Chrome};
+use notedeck_chrome::{setup::generate_native_options, Chrome, RecentLogsLayer};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::EnvFilter;
 
@@ -54,6 +54,7 @@ fn setup_logging(path: &DataPath) -> Option<WorkerGuard> {
         tracing_subscriber::registry()
             .with(console_layer)
             .with(file_layer)
+            .with(RecentLogsLayer)
             .with(env_filter)
             .init();
     } else {
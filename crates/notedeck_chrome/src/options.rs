@@ -20,6 +20,21 @@ bitflags! {
 
         /// We need soft keyboard visibility
         const KeyboardVisibility = 1 << 4;
+
+        /// Are we showing the notification inbox?
+        const NotificationInbox = 1 << 5;
+
+        /// Are we showing the keyboard shortcut cheat sheet?
+        const ShortcutSheet = 1 << 6;
+
+        /// Are we showing the app catalog?
+        const AppCatalog = 1 << 7;
+
+        /// Are we showing the diagnostics window?
+        const Diagnostics = 1 << 8;
+
+        /// Are we showing the frame time overlay?
+        const FrameOverlay = 1 << 9;
     }
 }
 
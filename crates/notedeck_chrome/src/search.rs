@@ -0,0 +1,495 @@
+//! Search across everything in nostrdb from anywhere in notedeck (Ctrl+K).
+//! Results open in whichever app shows them, through [`NoteAction`].
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use egui::{Key, KeyboardShortcut, Modifiers, RichText};
+use nostrdb::{Filter, Ndb, Note, NoteReply, Transaction};
+use notedeck::enostr::{NoteId, Pubkey};
+use notedeck::name::get_display_name;
+use notedeck::{shortcuts, tr, Localization, MuteItem, Muted, NoteAction, Shortcut};
+
+/// Open or close the search from anywhere
+pub const SEARCH_SHORTCUT: Shortcut = Shortcut::new(
+    "general.search",
+    shortcuts::general_group,
+    |i18n| tr!(i18n, "Search", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::K),
+);
+
+/// Results per section
+const MAX_PER_SECTION: usize = 5;
+
+/// How long typing has to pause before we search
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// NIP-52 date and time based calendar events
+const CALENDAR_EVENT_KINDS: [u64; 2] = [31922, 31923];
+
+/// How many calendar events we look through for a match
+const CALENDAR_SCAN_LIMIT: i32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Profiles,
+    Hashtags,
+    Notes,
+    CalendarEvents,
+}
+
+impl Section {
+    fn title(&self, i18n: &mut Localization) -> String {
+        match self {
+            Section::Profiles => tr!(i18n, "Profiles", "Global search section for profiles"),
+            Section::Hashtags => tr!(i18n, "Hashtags", "Global search section for hashtags"),
+            Section::Notes => tr!(i18n, "Notes", "Global search section for notes"),
+            Section::CalendarEvents => {
+                tr!(
+                    i18n,
+                    "Calendar events",
+                    "Global search section for calendar events"
+                )
+            }
+        }
+    }
+
+    /// Which sections come first for a query. A `#` asks for hashtags, a
+    /// single word is most likely a name, anything longer is probably text.
+    fn ranked(query: &str) -> [Section; 4] {
+        if query.starts_with('#') {
+            [
+                Section::Hashtags,
+                Section::Notes,
+                Section::Profiles,
+                Section::CalendarEvents,
+            ]
+        } else if query.split_whitespace().count() <= 1 {
+            [
+                Section::Profiles,
+                Section::Hashtags,
+                Section::Notes,
+                Section::CalendarEvents,
+            ]
+        } else {
+            [
+                Section::Notes,
+                Section::CalendarEvents,
+                Section::Profiles,
+                Section::Hashtags,
+            ]
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SearchResult {
+    Profile { pubkey: Pubkey, name: String },
+    Hashtag(String),
+    Note { id: NoteId, preview: String },
+    CalendarEvent { id: NoteId, title: String },
+}
+
+impl SearchResult {
+    fn section(&self) -> Section {
+        match self {
+            SearchResult::Profile { .. } => Section::Profiles,
+            SearchResult::Hashtag(_) => Section::Hashtags,
+            SearchResult::Note { .. } => Section::Notes,
+            SearchResult::CalendarEvent { .. } => Section::CalendarEvents,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            SearchResult::Profile { name, .. } => name.clone(),
+            SearchResult::Hashtag(tag) => format!("#{tag}"),
+            SearchResult::Note { preview, .. } => preview.clone(),
+            SearchResult::CalendarEvent { title, .. } => title.clone(),
+        }
+    }
+
+    fn into_action(self) -> NoteAction {
+        match self {
+            SearchResult::Profile { pubkey, .. } => NoteAction::Profile(pubkey),
+            SearchResult::Hashtag(tag) => NoteAction::Hashtag(tag),
+            SearchResult::Note { id, .. } | SearchResult::CalendarEvent { id, .. } => {
+                NoteAction::note(id)
+            }
+        }
+    }
+}
+
+pub enum GlobalSearchAction {
+    Close,
+    Open(NoteAction),
+}
+
+/// The Ctrl+K search palette
+#[derive(Default)]
+pub struct GlobalSearch {
+    query: String,
+    /// The query `results` are for, or are being searched for
+    searched: String,
+    /// When the query last changed, until we searched for it
+    edited_at: Option<Instant>,
+    /// Results of the search running on its thread
+    pending: Option<Receiver<Vec<SearchResult>>>,
+    /// Ordered by section rank, so the index is also the keyboard order
+    results: Vec<SearchResult>,
+    selected: usize,
+}
+
+impl GlobalSearch {
+    pub fn ui(
+        &mut self,
+        ndb: &Ndb,
+        muted: &Arc<Muted>,
+        i18n: &mut Localization,
+        ui: &mut egui::Ui,
+    ) -> Option<GlobalSearchAction> {
+        if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            return Some(GlobalSearchAction::Close);
+        }
+
+        if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown)) {
+            self.selected = (self.selected + 1).min(self.results.len().saturating_sub(1));
+        }
+        if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowUp)) {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        let enter = ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Enter));
+
+        let mut action = None;
+        let title = tr!(i18n, "Search", "Title of the global search window");
+        egui::Window::new(title)
+            .id(egui::Id::new("global_search"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(480.0)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+            .show(ui.ctx(), |ui| {
+                let hint = tr!(
+                    i18n,
+                    "Search notes, profiles, #hashtags and events",
+                    "Placeholder for the global search input"
+                );
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(hint)
+                        .desired_width(f32::INFINITY),
+                );
+                resp.request_focus();
+
+                if resp.changed() {
+                    self.edited_at = Some(Instant::now());
+                }
+                self.update(ndb, muted, ui.ctx());
+
+                if let Some(selected) = self.show_results(i18n, ui) {
+                    action = Some(GlobalSearchAction::Open(selected.into_action()));
+                }
+            });
+
+        if enter && action.is_none() {
+            if let Some(result) = self.results.get(self.selected) {
+                action = Some(GlobalSearchAction::Open(result.clone().into_action()));
+            }
+        }
+
+        action
+    }
+
+    fn show_results(&self, i18n: &mut Localization, ui: &mut egui::Ui) -> Option<SearchResult> {
+        if self.query.trim().is_empty() {
+            return None;
+        }
+
+        if self.results.is_empty() {
+            if self.is_searching() {
+                ui.add(egui::Spinner::new());
+                return None;
+            }
+
+            ui.weak(tr!(
+                i18n,
+                "No results",
+                "Shown when the global search found nothing"
+            ));
+            return None;
+        }
+
+        let mut clicked = None;
+        let mut section = None;
+        egui::ScrollArea::vertical()
+            .max_height(420.0)
+            .show(ui, |ui| {
+                for (index, result) in self.results.iter().enumerate() {
+                    if section != Some(result.section()) {
+                        section = Some(result.section());
+                        ui.add_space(6.0);
+                        ui.label(RichText::new(result.section().title(i18n)).small().weak());
+                    }
+
+                    let selected = index == self.selected;
+                    let resp = ui.selectable_label(selected, result.label());
+                    if selected {
+                        resp.scroll_to_me(None);
+                    }
+                    if resp.clicked() {
+                        clicked = Some(result.clone());
+                    }
+                }
+            });
+
+        clicked
+    }
+
+    fn is_searching(&self) -> bool {
+        self.edited_at.is_some() || self.pending.is_some()
+    }
+
+    /// Search once typing paused, and pick up results that came in
+    fn update(&mut self, ndb: &Ndb, muted: &Arc<Muted>, ctx: &egui::Context) {
+        if let Some(edited_at) = self.edited_at {
+            let waited = edited_at.elapsed();
+            if waited >= SEARCH_DEBOUNCE {
+                self.edited_at = None;
+                if self.query != self.searched {
+                    self.search(ndb, muted, ctx);
+                }
+            } else {
+                ctx.request_repaint_after(SEARCH_DEBOUNCE - waited);
+            }
+        }
+
+        let Some(pending) = &self.pending else {
+            return;
+        };
+
+        match pending.try_recv() {
+            Ok(results) => {
+                self.results = results;
+                self.selected = 0;
+                self.pending = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    /// Start searching for the query on a thread. Results of an older
+    /// search still running are dropped.
+    fn search(&mut self, ndb: &Ndb, muted: &Arc<Muted>, ctx: &egui::Context) {
+        self.searched = self.query.clone();
+        self.pending = None;
+
+        let query = self.query.trim().to_owned();
+        if query.is_empty() {
+            self.results.clear();
+            self.selected = 0;
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        self.pending = Some(rx);
+
+        let ndb = ndb.clone();
+        let muted = Arc::clone(muted);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            if tx.send(search(&ndb, &muted, &query)).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+    }
+}
+
+fn search(ndb: &Ndb, muted: &Muted, query: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    let Ok(txn) = Transaction::new(ndb) else {
+        return results;
+    };
+
+    let text = query.trim_start_matches('#');
+    let notes = search_notes(ndb, &txn, muted, text);
+    for section in Section::ranked(query) {
+        match section {
+            Section::Profiles => results.extend(search_profiles(ndb, &txn, muted, text)),
+            Section::Hashtags => results.extend(
+                hashtags(text, &notes)
+                    .into_iter()
+                    .filter(|tag| !muted.contains(&MuteItem::Hashtag(tag.clone())))
+                    .map(SearchResult::Hashtag),
+            ),
+            Section::Notes => {
+                results.extend(
+                    notes
+                        .iter()
+                        .take(MAX_PER_SECTION)
+                        .map(|note| SearchResult::Note {
+                            id: NoteId::new(*note.id()),
+                            preview: preview(note.content()),
+                        }),
+                )
+            }
+            Section::CalendarEvents => {
+                results.extend(search_calendar_events(ndb, &txn, muted, text))
+            }
+        }
+    }
+
+    results
+}
+
+/// Muted authors, words, hashtags and threads stay out of the results
+fn is_muted(muted: &Muted, note: &Note) -> bool {
+    let root = NoteReply::new(note.tags())
+        .root()
+        .map(|root| *root.id)
+        .unwrap_or(*note.id());
+    muted.is_muted(note, &root)
+}
+
+fn search_profiles(ndb: &Ndb, txn: &Transaction, muted: &Muted, query: &str) -> Vec<SearchResult> {
+    let Ok(pubkeys) = ndb.search_profile(txn, query, MAX_PER_SECTION as u32) else {
+        return Vec::new();
+    };
+
+    pubkeys
+        .into_iter()
+        .filter(|pk| !muted.is_pk_muted(pk))
+        .map(|pk| {
+            let profile = ndb.get_profile_by_pubkey(txn, pk).ok();
+            let name = get_display_name(profile.as_ref()).name().to_owned();
+            SearchResult::Profile {
+                pubkey: Pubkey::new(*pk),
+                name,
+            }
+        })
+        .collect()
+}
+
+fn search_notes<'a>(ndb: &Ndb, txn: &'a Transaction, muted: &Muted, query: &str) -> Vec<Note<'a>> {
+    // extra notes to find hashtags in
+    let limit = MAX_PER_SECTION as u64 * 10;
+    let filter = Filter::new().search(query).kinds([1]).limit(limit).build();
+
+    let Ok(results) = ndb.query(txn, &[filter], limit as i32) else {
+        return Vec::new();
+    };
+
+    results
+        .into_iter()
+        .map(|r| r.note)
+        .filter(|note| !is_muted(muted, note))
+        .collect()
+}
+
+/// The query itself as a hashtag, then hashtags starting with it that
+/// show up on matching notes
+fn hashtags(query: &str, notes: &[Note]) -> Vec<String> {
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+
+    let query = query.to_lowercase();
+    let mut tags = vec![query.clone()];
+    for note in notes {
+        for tag in note.tags() {
+            if tag.count() < 2 || tag.get_str(0) != Some("t") {
+                continue;
+            }
+
+            let Some(hashtag) = tag.get_str(1).map(str::to_lowercase) else {
+                continue;
+            };
+
+            if hashtag.starts_with(&query) && !tags.contains(&hashtag) {
+                tags.push(hashtag);
+            }
+        }
+    }
+
+    tags.truncate(MAX_PER_SECTION);
+    tags
+}
+
+/// Fulltext search only covers text notes, so look through recent
+/// calendar events by title and description
+fn search_calendar_events(
+    ndb: &Ndb,
+    txn: &Transaction,
+    muted: &Muted,
+    query: &str,
+) -> Vec<SearchResult> {
+    let filter = Filter::new()
+        .kinds(CALENDAR_EVENT_KINDS)
+        .limit(CALENDAR_SCAN_LIMIT as u64)
+        .build();
+
+    let Ok(results) = ndb.query(txn, &[filter], CALENDAR_SCAN_LIMIT) else {
+        return Vec::new();
+    };
+
+    let query = query.to_lowercase();
+    results
+        .into_iter()
+        .filter(|r| !is_muted(muted, &r.note))
+        .filter_map(|r| {
+            let title = event_title(&r.note);
+            let matches = title.to_lowercase().contains(&query)
+                || r.note.content().to_lowercase().contains(&query);
+
+            matches.then(|| SearchResult::CalendarEvent {
+                id: NoteId::new(*r.note.id()),
+                title,
+            })
+        })
+        .take(MAX_PER_SECTION)
+        .collect()
+}
+
+fn event_title(note: &Note) -> String {
+    let tag_value = |name: &str| {
+        note.tags().iter().find_map(|tag| {
+            (tag.count() >= 2 && tag.get_str(0) == Some(name))
+                .then(|| tag.get_str(1))
+                .flatten()
+        })
+    };
+
+    tag_value("title")
+        .or_else(|| tag_value("name"))
+        .map(str::to_owned)
+        .unwrap_or_else(|| preview(note.content()))
+}
+
+fn preview(content: &str) -> String {
+    let line = content.lines().next().unwrap_or_default();
+    match line.char_indices().nth(80) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_ranked_by_query() {
+        assert_eq!(Section::ranked("#nostr")[0], Section::Hashtags);
+        assert_eq!(Section::ranked("jb55")[0], Section::Profiles);
+        assert_eq!(Section::ranked("meetup in austin")[0], Section::Notes);
+    }
+
+    #[test]
+    fn preview_is_first_line() {
+        assert_eq!(preview("hello\nworld"), "hello");
+        assert_eq!(preview(&"a".repeat(100)).chars().count(), 81);
+    }
+}
@@ -54,6 +54,10 @@ pub fn note_hover_ui(
             clipboard: ctx.clipboard,
             i18n: ctx.i18n,
             global_wallet: ctx.global_wallet,
+            media_uploader: ctx.media_uploader,
+            publish_tracker: ctx.publish_tracker,
+            translations: ctx.translations,
+            min_pow: ctx.settings.min_pow(),
         };
 
         let mut jobs = notedeck::JobsCache::default();
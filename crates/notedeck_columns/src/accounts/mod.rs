@@ -1,17 +1,22 @@
-use enostr::{FullKeypair, Pubkey};
+use enostr::Pubkey;
 use nostrdb::{Ndb, Transaction};
 
-use notedeck::{Accounts, AppContext, JobsCache, Localization, SingleUnkIdAction, UnknownIds};
+use notedeck::{
+    AccountBackup, Accounts, AddAccountResponse, AppContext, ImageProxy, JobsCache, Localization,
+    RestoredAccount, SingleUnkIdAction, UnknownIds,
+};
 use notedeck_ui::nip51_set::Nip51SetUiCache;
 
 pub use crate::accounts::route::AccountsResponse;
 use crate::app::get_active_columns_mut;
 use crate::decks::DecksCache;
 use crate::nav::BodyResponse;
-use crate::onboarding::Onboarding;
-use crate::profile::send_new_contact_list;
+use crate::onboarding::{Onboarding, OnboardingStep};
+use crate::profile::{send_new_contact_list, SaveProfileChanges};
 use crate::subscriptions::Subscriptions;
-use crate::ui::onboarding::{FollowPackOnboardingView, FollowPacksResponse, OnboardingResponse};
+use crate::ui::onboarding::{
+    FollowPackOnboardingView, FollowPacksResponse, NewAccountOnboardingView, OnboardingResponse,
+};
 use crate::{
     login_manager::AcquireKeyState,
     route::Route,
@@ -95,12 +100,18 @@ pub fn render_accounts_route(
         .map_output(AccountsResponse::Account),
         AccountsRoute::AddAccount => {
             let action = AccountLoginView::new(login_state, app_ctx.clipboard, app_ctx.i18n)
+                .pending_signer(app_ctx.accounts.pending_signer())
                 .ui(ui)
                 .inner
                 .map(AccountsRouteResponse::AddAccount)
                 .map(AccountsResponse::Account);
             BodyResponse::output(action)
         }
+        AccountsRoute::Onboarding if onboarding.step != OnboardingStep::FollowPacks => {
+            NewAccountOnboardingView::new(onboarding, app_ctx.i18n)
+                .ui(ui)
+                .map_output(onboarding_response)
+        }
         AccountsRoute::Onboarding => FollowPackOnboardingView::new(
             onboarding,
             follow_packs_ui,
@@ -111,14 +122,18 @@ pub fn render_accounts_route(
             jobs,
         )
         .ui(ui)
-        .map_output(|r| match r {
-            OnboardingResponse::FollowPacks(follow_packs_response) => {
-                AccountsResponse::Account(AccountsRouteResponse::AddAccount(
-                    AccountLoginResponse::Onboarding(follow_packs_response),
-                ))
-            }
-            OnboardingResponse::ViewProfile(pubkey) => AccountsResponse::ViewProfile(pubkey),
-        }),
+        .map_output(onboarding_response),
+    }
+}
+
+fn onboarding_response(response: OnboardingResponse) -> AccountsResponse {
+    match response {
+        OnboardingResponse::FollowPacks(follow_packs_response) => {
+            AccountsResponse::Account(AccountsRouteResponse::AddAccount(
+                AccountLoginResponse::Onboarding(follow_packs_response),
+            ))
+        }
+        OnboardingResponse::ViewProfile(pubkey) => AccountsResponse::ViewProfile(pubkey),
     }
 }
 
@@ -158,6 +173,7 @@ pub fn process_login_view_response(
     onboarding: &mut Onboarding,
     col: usize,
     response: AccountLoginResponse,
+    ui_ctx: &egui::Context,
 ) -> AddAccountAction {
     let cur_router = get_active_columns_mut(app_ctx.i18n, app_ctx.accounts, decks)
         .column_mut(col)
@@ -168,6 +184,30 @@ pub fn process_login_view_response(
             cur_router.go_back();
             app_ctx.accounts.add_account(keypair)
         }
+        AccountLoginResponse::ConnectSigner(uri) => {
+            app_ctx.accounts.connect_remote_signer(uri);
+            None
+        }
+        AccountLoginResponse::NostrConnect => {
+            app_ctx.accounts.start_nostrconnect();
+            None
+        }
+        AccountLoginResponse::CancelSigner => {
+            app_ctx.accounts.cancel_remote_signer();
+            None
+        }
+        AccountLoginResponse::SignerConnected => {
+            cur_router.go_back();
+            app_ctx.accounts.add_remote_signer_account()
+        }
+        AccountLoginResponse::Restore {
+            backup,
+            accounts,
+            settings,
+        } => {
+            cur_router.go_back();
+            restore_backup(app_ctx, ui_ctx, backup, accounts, settings)
+        }
         AccountLoginResponse::CreatingNew => {
             cur_router.route_to(Route::Accounts(AccountsRoute::Onboarding));
 
@@ -183,13 +223,31 @@ pub fn process_login_view_response(
             FollowPacksResponse::UserSelectedPacks(nip51_sets_ui_state) => {
                 let pks_to_follow = nip51_sets_ui_state.get_all_selected();
 
-                let kp = FullKeypair::generate();
+                let kp = onboarding.take_keypair();
+                let profile = std::mem::take(&mut onboarding.profile);
 
-                send_new_contact_list(kp.to_filled(), app_ctx.ndb, app_ctx.pool, pks_to_follow);
+                SaveProfileChanges::new(kp.clone(), profile).send(
+                    app_ctx.ndb,
+                    app_ctx.pool,
+                    app_ctx.publish_tracker,
+                );
+                send_new_contact_list(
+                    kp.to_filled(),
+                    app_ctx.ndb,
+                    app_ctx.pool,
+                    app_ctx.publish_tracker,
+                    pks_to_follow,
+                );
                 cur_router.go_back();
                 onboarding.end_onboarding(app_ctx.pool, app_ctx.ndb);
 
-                app_ctx.accounts.add_account(kp.to_keypair())
+                let response = app_ctx.accounts.add_account(kp.to_keypair());
+                app_ctx.accounts.publish_starter_relays(
+                    app_ctx.pool,
+                    app_ctx.publish_tracker,
+                    &kp.pubkey,
+                );
+                response
             }
         },
     };
@@ -211,12 +269,48 @@ pub fn process_login_view_response(
     }
 }
 
+/// Bring back the accounts and settings from a backup. Returns the first
+/// account so we can switch to it.
+fn restore_backup(
+    app_ctx: &mut AppContext,
+    ui_ctx: &egui::Context,
+    backup: AccountBackup,
+    accounts: Vec<RestoredAccount>,
+    restore_settings: bool,
+) -> Option<AddAccountResponse> {
+    backup.ingest_events(app_ctx.ndb);
+
+    let settings = &mut *app_ctx.settings;
+    if restore_settings {
+        settings.restore(backup.settings);
+        ui_ctx.set_theme(settings.theme());
+        ui_ctx.set_zoom_factor(settings.zoom_factor());
+        notedeck::theme::set_theme_options(ui_ctx, settings.theme_options());
+        notedeck::shortcuts::set_bindings(ui_ctx, settings.shortcuts());
+        notedeck::set_image_proxy(settings.image_proxy().as_deref().and_then(ImageProxy::new));
+    } else {
+        let pubkeys: Vec<_> = accounts.iter().map(|a| a.keypair.pubkey).collect();
+        settings.restore_accounts(&backup.settings, &pubkeys);
+    }
+
+    info!("restored {} accounts from backup", accounts.len());
+    accounts.into_iter().fold(None, |first, account| {
+        let pubkey = account.keypair.pubkey;
+        let added = match account.signer.and_then(|s| s.into_signer(pubkey)) {
+            Some(signer) => app_ctx.accounts.add_signer_account(pubkey, signer),
+            None => app_ctx.accounts.add_account(account.keypair),
+        };
+        first.or(added)
+    })
+}
+
 impl AccountsRouteResponse {
     pub fn process(
         self,
         app_ctx: &mut AppContext,
         app: &mut crate::Damus,
         col: usize,
+        ui_ctx: &egui::Context,
     ) -> AddAccountAction {
         match self {
             AccountsRouteResponse::Accounts(response) => {
@@ -240,6 +334,7 @@ impl AccountsRouteResponse {
                     &mut app.onboarding,
                     col,
                     response,
+                    ui_ctx,
                 );
                 app.view_state.login = Default::default();
 
@@ -45,6 +45,7 @@ impl TokenSerializable for AccountsRoute {
             &[
                 |p| parse_accounts_route(p, AccountsRoute::Accounts),
                 |p| parse_accounts_route(p, AccountsRoute::AddAccount),
+                |p| parse_accounts_route(p, AccountsRoute::Onboarding),
             ],
         )
     }
@@ -92,4 +93,17 @@ mod tests {
         assert_eq!(expected, parsed);
         assert_eq!(token_writer.str(), data_str);
     }
+
+    #[test]
+    fn test_onboarding_route_serialize() {
+        let data_str = "accounts:onboarding";
+        let data = &data_str.split(":").collect::<Vec<&str>>();
+        let mut token_writer = TokenWriter::default();
+        let mut parser = TokenParser::new(data);
+        let parsed = AccountsRoute::parse_from_tokens(&mut parser).unwrap();
+        let expected = AccountsRoute::Onboarding;
+        parsed.serialize_tokens(&mut token_writer);
+        assert_eq!(expected, parsed);
+        assert_eq!(token_writer.str(), data_str);
+    }
 }
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use crate::{
     column::Columns,
     nav::{RouterAction, RouterType},
+    profile::ProfileAction,
     route::Route,
     timeline::{
         thread::{selected_has_at_least_n_replies, NoteSeenFlags, ThreadNode, Threads},
@@ -17,8 +18,8 @@ use nostrdb::{IngestMetadata, Ndb, NoteBuilder, NoteKey, Transaction};
 use notedeck::{
     get_wallet_for,
     note::{reaction_sent_id, ReactAction, ZapTargetAmount},
-    Accounts, GlobalWallet, Images, NoteAction, NoteCache, NoteZapTargetOwned, UnknownIds,
-    ZapAction, ZapTarget, ZappingError, Zaps,
+    Accounts, AppSubscriptions, GlobalWallet, Images, NoteAction, NoteCache, NoteContextSelection,
+    NoteZapTargetOwned, PublishTracker, UnknownIds, ZapAction, ZapTarget, ZappingError, Zaps,
 };
 use notedeck_ui::media::MediaViewerFlags;
 use tracing::error;
@@ -52,6 +53,8 @@ fn execute_note_action(
     threads: &mut Threads,
     note_cache: &mut NoteCache,
     pool: &mut RelayPool,
+    subs: &mut AppSubscriptions,
+    publish_tracker: &mut PublishTracker,
     txn: &Transaction,
     accounts: &mut Accounts,
     global_wallet: &mut GlobalWallet,
@@ -80,7 +83,9 @@ fn execute_note_action(
         }
         NoteAction::React(react_action) => {
             if let Some(filled) = accounts.selected_filled() {
-                if let Err(err) = send_reaction_event(ndb, txn, pool, filled, &react_action) {
+                if let Err(err) =
+                    send_reaction_event(ndb, txn, pool, publish_tracker, filled, &react_action)
+                {
                     tracing::error!("Failed to send reaction: {err}");
                 }
                 ui.ctx().data_mut(|d| {
@@ -116,6 +121,7 @@ fn execute_note_action(
                     ndb,
                     txn,
                     pool,
+                    subs,
                     &thread_selection,
                     preview,
                     col,
@@ -186,17 +192,33 @@ fn execute_note_action(
                 }
             }
         }
-        NoteAction::Context(context) => match ndb.get_note_by_key(txn, context.note_key) {
-            Err(err) => tracing::error!("{err}"),
-            Ok(note) => {
-                context.action.process(
-                    ui,
-                    &note,
-                    pool,
-                    *accounts.selected_account_pubkey().bytes() == *note.pubkey(),
-                );
+        NoteAction::Context(context) => {
+            match ndb.get_note_by_key(txn, context.note_key) {
+                Err(err) => tracing::error!("{err}"),
+                Ok(note) => match context.action {
+                    NoteContextSelection::FollowAuthor => {
+                        ProfileAction::Follow(Pubkey::new(*note.pubkey())).process_profile_action(
+                            ui.ctx(),
+                            ndb,
+                            pool,
+                            publish_tracker,
+                            accounts,
+                        );
+                    }
+                    NoteContextSelection::UnfollowAuthor => {
+                        ProfileAction::Unfollow(Pubkey::new(*note.pubkey()))
+                            .process_profile_action(ui.ctx(), ndb, pool, publish_tracker, accounts);
+                    }
+                    _ => context.action.process(
+                        ui,
+                        &note,
+                        pool,
+                        publish_tracker,
+                        *accounts.selected_account_pubkey().bytes() == *note.pubkey(),
+                    ),
+                },
             }
-        },
+        }
         NoteAction::Media(media_action) => {
             media_action.on_view_media(|medias| {
                 view_state.media_viewer.media_info = medias.clone();
@@ -228,6 +250,8 @@ pub fn execute_and_process_note_action(
     threads: &mut Threads,
     note_cache: &mut NoteCache,
     pool: &mut RelayPool,
+    subs: &mut AppSubscriptions,
+    publish_tracker: &mut PublishTracker,
     txn: &Transaction,
     unknown_ids: &mut UnknownIds,
     accounts: &mut Accounts,
@@ -254,6 +278,8 @@ pub fn execute_and_process_note_action(
         threads,
         note_cache,
         pool,
+        subs,
+        publish_tracker,
         txn,
         accounts,
         global_wallet,
@@ -283,6 +309,7 @@ fn send_reaction_event(
     ndb: &mut Ndb,
     txn: &Transaction,
     pool: &mut RelayPool,
+    publish_tracker: &mut PublishTracker,
     kp: FilledKeypair<'_>,
     reaction: &ReactAction,
 ) -> Result<(), String> {
@@ -344,9 +371,9 @@ fn send_reaction_event(
 
     let _ = ndb.process_event_with(&json, IngestMetadata::new().client(true));
 
-    pool.send(event);
-
-    Ok(())
+    publish_tracker
+        .publish(pool, &note)
+        .map_err(|err| format!("failed to publish reaction: {err}"))
 }
 
 fn find_addressable_d_tag(note: &nostrdb::Note<'_>) -> Option<String> {
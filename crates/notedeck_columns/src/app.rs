@@ -1,4 +1,5 @@
 use crate::{
+    accounts::AccountsRoute,
     args::{ColumnsArgs, ColumnsFlag},
     column::Columns,
     decks::{Decks, DecksCache},
@@ -17,19 +18,20 @@ use crate::{
     Result,
 };
 use egui_extras::{Size, StripBuilder};
-use enostr::{ClientMessage, PoolRelay, Pubkey, RelayEvent, RelayMessage, RelayPool};
-use nostrdb::Transaction;
+use enostr::{ClientMessage, PoolRelay, Pubkey, RelayEvent, RelayMessage};
+use nostrdb::{Ndb, Transaction};
 use notedeck::{
-    tr, ui::is_narrow, Accounts, AppAction, AppContext, AppResponse, DataPath, DataPathType,
-    FilterState, Images, JobsCache, Localization, NotedeckOptions, SettingsHandler, UnknownIds,
+    name::get_display_name, tr, ui::is_narrow, Accounts, AppAction, AppContext, AppResponse,
+    DataPath, DataPathType, FilterState, Images, JobPool, JobsCache, Localization, NotedeckOptions,
+    SettingsHandler, FALLBACK_PUBKEY,
 };
 use notedeck_ui::{
-    media::{MediaViewer, MediaViewerFlags, MediaViewerState},
+    media::{MediaViewer, MediaViewerAction, MediaViewerFlags, MediaViewerState},
     NoteOptions,
 };
 use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
-use tracing::{debug, error, info, trace, warn};
+use tracing::{error, info, trace, warn};
 use uuid::Uuid;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -130,6 +132,7 @@ fn try_process_event(
                 app_ctx
                     .accounts
                     .send_initial_filters(app_ctx.pool, &ev.relay);
+                app_ctx.subs.send_initial_filters(app_ctx.pool, &ev.relay);
 
                 timeline::send_initial_timeline_filters(
                     damus.options.contains(AppOptions::SinceOptimize),
@@ -186,25 +189,9 @@ fn try_process_event(
         follow_packs.poll_for_notes(app_ctx.ndb, app_ctx.unknown_ids);
     }
 
-    if app_ctx.unknown_ids.ready_to_send() {
-        unknown_id_send(app_ctx.unknown_ids, app_ctx.pool);
-    }
-
     Ok(())
 }
 
-fn unknown_id_send(unknown_ids: &mut UnknownIds, pool: &mut RelayPool) {
-    debug!("unknown_id_send called on: {:?}", &unknown_ids);
-    let filter = unknown_ids.filter().expect("filter");
-    debug!(
-        "Getting {} unknown ids from relays",
-        unknown_ids.ids_iter().len()
-    );
-    let msg = ClientMessage::req("unknownids".to_string(), filter);
-    unknown_ids.clear();
-    pool.send(&msg);
-}
-
 #[profiling::function]
 fn update_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ctx: &egui::Context) {
     app_ctx.img_cache.urls.cache.handle_io();
@@ -218,10 +205,6 @@ fn update_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ctx: &egui::Con
     match damus.state {
         DamusState::Initializing => {
             damus.state = DamusState::Initialized;
-            // this lets our eose handler know to close unknownids right away
-            damus
-                .subscriptions()
-                .insert("unknownids".to_string(), SubKind::OneShot);
             if let Err(err) = timeline::setup_initial_nostrdb_subs(
                 app_ctx.ndb,
                 app_ctx.note_cache,
@@ -363,8 +346,15 @@ fn process_message(damus: &mut Damus, ctx: &mut AppContext<'_>, relay: &str, msg
             }
         }
         RelayMessage::Notice(msg) => warn!("Notice from {}: {}", relay, msg),
-        RelayMessage::OK(cr) => info!("OK {:?}", cr),
+        RelayMessage::OK(cr) => {
+            info!("OK {:?}", cr);
+            ctx.publish_tracker.handle_ok(ctx.pool, relay, cr);
+        }
         RelayMessage::Eose(sid) => {
+            ctx.accounts.handle_eose(sid);
+            if ctx.unknown_ids.handle_eose(ctx.pool, sid, relay) {
+                return;
+            }
             if let Err(err) = handle_eose(
                 &damus.subscriptions,
                 &mut damus.timeline_cache,
@@ -382,6 +372,19 @@ fn render_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ui: &mut egui::
     damus
         .note_options
         .set(NoteOptions::Wide, is_narrow(ui.ctx()));
+    damus.note_options.set(
+        NoteOptions::ShowSensitiveMedia,
+        app_ctx
+            .settings
+            .show_sensitive_media(app_ctx.accounts.selected_account_pubkey()),
+    );
+    if let Some(deck) = damus
+        .decks_cache
+        .decks(app_ctx.accounts.selected_account_pubkey())
+        .active_deck()
+    {
+        deck.media_size.apply(&mut damus.note_options);
+    }
 
     let app_resp = if notedeck::ui::is_narrow(ui.ctx()) {
         render_damus_mobile(damus, app_ctx, ui)
@@ -389,7 +392,26 @@ fn render_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ui: &mut egui::
         render_damus_desktop(damus, app_ctx, ui)
     };
 
-    fullscreen_media_viewer_ui(ui, &mut damus.view_state.media_viewer, app_ctx.img_cache);
+    match fullscreen_media_viewer_ui(
+        ui,
+        &mut damus.view_state.media_viewer,
+        app_ctx.ndb,
+        app_ctx.img_cache,
+        app_ctx.job_pool,
+        app_ctx.i18n,
+    ) {
+        Some(MediaViewerAction::OpenNote(note_id)) => {
+            fullscreen_media_close(&mut damus.view_state.media_viewer);
+            nav::open_note_in_selected_column(damus, app_ctx, ui, note_id);
+        }
+        Some(MediaViewerAction::SaveAll) => {
+            #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                damus.view_state.media_viewer.save_all(ui.ctx(), dir);
+            }
+        }
+        None => {}
+    }
 
     // We use this for keeping timestamps and things up to date
     //ui.ctx().request_repaint_after(Duration::from_secs(5));
@@ -403,8 +425,11 @@ fn render_damus(damus: &mut Damus, app_ctx: &mut AppContext<'_>, ui: &mut egui::
 fn fullscreen_media_viewer_ui(
     ui: &mut egui::Ui,
     state: &mut MediaViewerState,
+    ndb: &Ndb,
     img_cache: &mut Images,
-) {
+    job_pool: &JobPool,
+    i18n: &mut Localization,
+) -> Option<MediaViewerAction> {
     if !state.should_show(ui) {
         if state.scene_rect.is_some() {
             // if we shouldn't show yet we will have a scene
@@ -412,14 +437,24 @@ fn fullscreen_media_viewer_ui(
             tracing::debug!("fullscreen_media_viewer_ui: resetting scene rect");
             state.scene_rect = None;
         }
-        return;
+        return None;
     }
 
-    let resp = MediaViewer::new(state).fullscreen(true).ui(img_cache, ui);
+    let txn = Transaction::new(ndb).expect("txn");
+    let author = state.media_info.source.map(|source| source.author);
+    let profile = author.and_then(|pk| ndb.get_profile_by_pubkey(&txn, pk.bytes()).ok());
+    let author_name = author.map(|_| get_display_name(profile.as_ref()).name());
+
+    let resp = MediaViewer::new(state)
+        .fullscreen(true)
+        .author_name(author_name)
+        .ui(img_cache, job_pool, i18n, ui);
 
-    if resp.clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+    if resp.response.clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
         fullscreen_media_close(state);
     }
+
+    resp.action
 }
 
 /// Close the fullscreen media player. This also resets the scene_rect state
@@ -507,7 +542,25 @@ impl Damus {
             decks_cache
         } else {
             info!("DecksCache: creating new with demo configuration");
-            DecksCache::new_with_demo_config(&mut timeline_cache, app_context)
+            let mut decks_cache =
+                DecksCache::new_with_demo_config(&mut timeline_cache, app_context);
+
+            // first run, walk them through making an account
+            let fallback = FALLBACK_PUBKEY();
+            if (&app_context.accounts.cache)
+                .into_iter()
+                .all(|(pk, _)| *pk == fallback)
+            {
+                decks_cache
+                    .fallback_mut()
+                    .active_mut()
+                    .columns_mut()
+                    .column_mut(0)
+                    .router_mut()
+                    .route_to(Route::Accounts(AccountsRoute::Onboarding));
+            }
+
+            decks_cache
             //for (pk, _) in &app_context.accounts.cache {
             //    cache.add_deck_default(*pk);
             //}
@@ -623,6 +676,11 @@ fn get_note_options(args: ColumnsArgs, settings_handler: &mut SettingsHandler) -
         NoteOptions::RepliesNewestFirst,
         settings_handler.show_replies_newest_first(),
     );
+    note_options.set(NoteOptions::DataSaver, settings_handler.data_saver());
+    note_options.set(
+        NoteOptions::NoAnimations,
+        settings_handler.reduce_motion() || notedeck::motion::os_prefers_reduced_motion(),
+    );
     note_options
 }
 
@@ -775,6 +833,8 @@ fn should_show_compose_button(decks: &DecksCache, accounts: &Accounts) -> bool {
         Route::Reply(_) => false,
         Route::Quote(_) => false,
         Route::Relays => false,
+        Route::MuteList => false,
+        Route::Database => false,
         Route::Settings => false,
         Route::ComposeNote => false,
         Route::AddColumn(_) => false,
@@ -1,10 +1,14 @@
-use crate::{app_style::emoji_font_family, decks::Deck};
+use crate::{
+    app_style::emoji_font_family,
+    decks::{Deck, MediaSize},
+};
 
 /// State for UI creating/editing deck
 pub struct DeckState {
     pub deck_name: String,
     pub selected_glyph: Option<char>,
     pub selecting_glyph: bool,
+    pub media_size: MediaSize,
     pub warn_no_title: bool,
     pub warn_no_icon: bool,
     glyph_options: Option<Vec<char>>,
@@ -14,6 +18,7 @@ impl DeckState {
     pub fn load(&mut self, deck: &Deck) {
         self.deck_name = deck.name.clone();
         self.selected_glyph = Some(deck.icon);
+        self.media_size = deck.media_size;
     }
 
     pub fn from_deck(deck: &Deck) -> Self {
@@ -22,6 +27,7 @@ impl DeckState {
         Self {
             deck_name,
             selected_glyph,
+            media_size: deck.media_size,
             ..Default::default()
         }
     }
@@ -42,6 +48,7 @@ impl Default for DeckState {
             deck_name: Default::default(),
             selected_glyph: Default::default(),
             selecting_glyph: true,
+            media_size: Default::default(),
             warn_no_icon: Default::default(),
             warn_no_title: Default::default(),
             glyph_options: Default::default(),
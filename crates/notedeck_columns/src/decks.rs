@@ -3,6 +3,7 @@ use std::collections::{hash_map::ValuesMut, HashMap};
 use enostr::{Pubkey, RelayPool};
 use nostrdb::Transaction;
 use notedeck::{tr, AppContext, Localization, FALLBACK_PUBKEY};
+use notedeck_ui::NoteOptions;
 use tracing::{error, info};
 
 use crate::{
@@ -384,9 +385,52 @@ fn delete_deck(
     }
 }
 
+/// How tall media in the notes of a deck is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaSize {
+    /// 180px
+    Compact,
+    /// 360px
+    #[default]
+    Standard,
+    /// The full width of the column
+    Large,
+}
+
+impl MediaSize {
+    pub const ALL: [MediaSize; 3] = [MediaSize::Compact, MediaSize::Standard, MediaSize::Large];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaSize::Compact => "compact",
+            MediaSize::Standard => "standard",
+            MediaSize::Large => "large",
+        }
+    }
+
+    pub fn from_keyword(s: &str) -> Option<Self> {
+        MediaSize::ALL.into_iter().find(|size| size.as_str() == s)
+    }
+
+    pub fn label(&self, i18n: &mut Localization) -> String {
+        match self {
+            MediaSize::Compact => tr!(i18n, "Compact", "Label for compact media size"),
+            MediaSize::Standard => tr!(i18n, "Standard", "Label for standard media size"),
+            MediaSize::Large => tr!(i18n, "Large", "Label for full width media size"),
+        }
+    }
+
+    /// Set the note options media in notes is sized by
+    pub fn apply(&self, note_options: &mut NoteOptions) {
+        note_options.set(NoteOptions::CompactMedia, *self == MediaSize::Compact);
+        note_options.set(NoteOptions::LargeMedia, *self == MediaSize::Large);
+    }
+}
+
 pub struct Deck {
     pub icon: char,
     pub name: String,
+    pub media_size: MediaSize,
     columns: Columns,
 }
 
@@ -401,6 +445,7 @@ impl Deck {
             columns,
             icon: Deck::default_icon(),
             name: Deck::default_name(i18n).to_string(),
+            media_size: MediaSize::default(),
         }
     }
 
@@ -416,6 +461,7 @@ impl Deck {
         Self {
             icon,
             name,
+            media_size: MediaSize::default(),
             columns,
         }
     }
@@ -424,6 +470,7 @@ impl Deck {
         Self {
             icon,
             name,
+            media_size: MediaSize::default(),
             columns,
         }
     }
@@ -439,6 +486,7 @@ impl Deck {
     pub fn edit(&mut self, changes: ConfigureDeckResponse) {
         self.name = changes.name;
         self.icon = changes.icon;
+        self.media_size = changes.media_size;
     }
 }
 
@@ -1,7 +1,7 @@
 use egui::text::LayoutJob;
-use poll_promise::Promise;
 
-use crate::{media_upload::Nip94Event, post::PostBuffer, ui::note::PostType, Error};
+use crate::{post::PostBuffer, ui::note::PostType};
+use notedeck::media::upload::{MediaUpload, Nip94Event};
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -10,7 +10,7 @@ pub struct Draft {
     pub cur_layout: Option<(String, LayoutJob)>, // `PostBuffer::text_buffer` to current `LayoutJob`
     pub cur_mention_hint: Option<MentionHint>,
     pub uploaded_media: Vec<Nip94Event>, // media uploads to include
-    pub uploading_media: Vec<Promise<Result<Nip94Event, Error>>>, // promises that aren't ready yet
+    pub uploading_media: Vec<MediaUpload>, // uploads that aren't finished yet
     pub upload_errors: Vec<String>,      // media upload errors to show the user
 }
 
@@ -12,6 +12,7 @@ use tracing::error;
 pub enum AcquireKeyError {
     InvalidKey,
     Nip05Failed(String),
+    InvalidBunkerUri(String),
 }
 
 impl std::fmt::Display for AcquireKeyError {
@@ -21,6 +22,7 @@ impl std::fmt::Display for AcquireKeyError {
             AcquireKeyError::Nip05Failed(e) => {
                 write!(f, "Failed to get pubkey from Nip05 address: {e}")
             }
+            AcquireKeyError::InvalidBunkerUri(e) => write!(f, "Invalid bunker address: {e}"),
         }
     }
 }
@@ -14,7 +14,6 @@ mod decks;
 mod draft;
 mod key_parsing;
 pub mod login_manager;
-mod media_upload;
 mod multi_subscriber;
 mod nav;
 mod onboarding;
@@ -43,4 +42,8 @@ pub use app::Damus;
 pub use error::Error;
 pub use route::Route;
 
+/// Catalog id of columns, and the owner of its
+/// [`notedeck::AppSubscriptions`]
+pub const COLUMNS_ID: &str = "columns";
+
 pub type Result<T> = std::result::Result<T, error::Error>;
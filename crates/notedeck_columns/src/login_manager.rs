@@ -1,9 +1,11 @@
 use crate::key_parsing::perform_key_retrieval;
 use crate::key_parsing::AcquireKeyError;
 use egui::{TextBuffer, TextEdit};
+use enostr::nip46::BunkerUri;
 use enostr::Keypair;
-use notedeck::{tr, Localization};
+use notedeck::{tr, AccountBackup, BackupError, Localization, RestoredAccount};
 use poll_promise::Promise;
+use std::path::Path;
 
 /// The state data for acquiring a nostr key
 #[derive(Default)]
@@ -14,8 +16,19 @@ pub struct AcquireKeyState {
     key_on_error: Option<String>,
     should_create_new: bool,
     show_password: bool,
+    /// A backup waiting for its passphrase
+    restore: Option<AccountBackup>,
+    restore_passphrase: String,
+    restore_error: Option<BackupError>,
+    /// Replace this device's settings with the backup's, not just take
+    /// the restored accounts' own
+    restore_settings: bool,
+    /// Decrypting the keys, which is slow on purpose
+    unlocking: Option<Promise<UnlockResult>>,
 }
 
+type UnlockResult = (AccountBackup, Result<Vec<RestoredAccount>, BackupError>);
+
 impl<'a> AcquireKeyState {
     pub fn new() -> Self {
         AcquireKeyState::default()
@@ -51,6 +64,24 @@ impl<'a> AcquireKeyState {
         }
     }
 
+    /// Is the user logging in with a remote signer?
+    pub fn is_bunker_uri(&self) -> bool {
+        self.desired_key.trim_start().starts_with("bunker://")
+    }
+
+    /// Parse the entered bunker:// address, showing an error if it is
+    /// invalid
+    pub fn parse_bunker_uri(&mut self) -> Option<BunkerUri> {
+        match BunkerUri::parse(&self.desired_key) {
+            Ok(uri) => Some(uri),
+            Err(e) => {
+                self.error = Some(AcquireKeyError::InvalidBunkerUri(e.to_string()));
+                self.key_on_error = Some(self.desired_key.clone());
+                None
+            }
+        }
+    }
+
     pub fn is_awaiting_network(&self) -> bool {
         if let Some((_, promise)) = &self.promise_query {
             promise.ready().is_none()
@@ -129,6 +160,93 @@ impl<'a> AcquireKeyState {
     pub fn password_visible(&self) -> bool {
         self.show_password
     }
+
+    /// Read a backup file to restore from once its passphrase is entered
+    pub fn load_backup(&mut self, path: &Path) {
+        self.restore_passphrase.clear();
+
+        let backup = match std::fs::read_to_string(path) {
+            Ok(json) => AccountBackup::from_json(&json),
+            Err(err) => {
+                tracing::error!("could not read backup {}: {err}", path.display());
+                Err(BackupError::Invalid)
+            }
+        };
+
+        match backup {
+            Ok(backup) => {
+                self.restore = Some(backup);
+                self.restore_error = None;
+            }
+            Err(err) => {
+                self.restore = None;
+                self.restore_error = Some(err);
+            }
+        }
+    }
+
+    pub fn is_restoring(&self) -> bool {
+        self.restore.is_some() || self.unlocking.is_some()
+    }
+
+    pub fn is_unlocking(&self) -> bool {
+        self.unlocking.is_some()
+    }
+
+    pub fn restore_settings_mut(&mut self) -> &mut bool {
+        &mut self.restore_settings
+    }
+
+    pub fn restore_passphrase_mut(&mut self) -> &mut String {
+        &mut self.restore_passphrase
+    }
+
+    pub fn restore_error(&self) -> Option<&BackupError> {
+        self.restore_error.as_ref()
+    }
+
+    pub fn cancel_restore(&mut self) {
+        self.restore = None;
+        self.restore_passphrase.clear();
+        self.restore_error = None;
+        self.restore_settings = false;
+        self.unlocking = None;
+    }
+
+    /// Start decrypting the backup's keys with the entered passphrase, see
+    /// [`AcquireKeyState::poll_unlocked`]
+    pub fn unlock_backup(&mut self) {
+        let Some(backup) = self.restore.take() else {
+            return;
+        };
+
+        let passphrase = self.restore_passphrase.clone();
+        self.restore_error = None;
+        self.unlocking = Some(Promise::spawn_thread("unlock_backup", move || {
+            let accounts = backup.accounts(&passphrase);
+            (backup, accounts)
+        }));
+    }
+
+    /// The backup and its accounts, once their keys opened
+    pub fn poll_unlocked(&mut self) -> Option<(AccountBackup, Vec<RestoredAccount>)> {
+        let (backup, accounts) = match self.unlocking.take()?.try_take() {
+            Ok(unlocked) => unlocked,
+            Err(promise) => {
+                self.unlocking = Some(promise);
+                return None;
+            }
+        };
+
+        match accounts {
+            Ok(accounts) => Some((backup, accounts)),
+            Err(err) => {
+                self.restore = Some(backup);
+                self.restore_error = Some(err);
+                None
+            }
+        }
+    }
 }
 
 fn show_error(ui: &mut egui::Ui, i18n: &mut Localization, err: &AcquireKeyError) {
@@ -145,6 +263,15 @@ fn show_error(ui: &mut egui::Ui, i18n: &mut Localization, err: &AcquireKeyError)
             AcquireKeyError::Nip05Failed(e) => {
                 egui::Label::new(egui::RichText::new(e).color(ui.visuals().error_fg_color))
             }
+            AcquireKeyError::InvalidBunkerUri(e) => egui::Label::new(
+                egui::RichText::new(tr!(
+                    i18n,
+                    "Invalid bunker address: {error}",
+                    "Error message for an invalid bunker:// remote signer address",
+                    error = e.as_str()
+                ))
+                .color(ui.visuals().error_fg_color),
+            ),
         };
         ui.add(error_label.truncate());
     });
@@ -2,10 +2,9 @@ use egui_nav::ReturnType;
 use enostr::{Filter, NoteId, RelayPool};
 use hashbrown::HashMap;
 use nostrdb::{Ndb, Subscription};
-use notedeck::{filter::HybridFilter, UnifiedSubscription};
-use uuid::Uuid;
+use notedeck::{filter::HybridFilter, AppSubscriptions, UnifiedSubscription};
 
-use crate::{subscriptions, timeline::ThreadSelection};
+use crate::{subscriptions, timeline::ThreadSelection, COLUMNS_ID};
 
 type RootNoteId = NoteId;
 
@@ -50,6 +49,7 @@ impl ThreadSubs {
         &mut self,
         ndb: &mut Ndb,
         pool: &mut RelayPool,
+        subs: &mut AppSubscriptions,
         meta_id: usize,
         id: &ThreadSelection,
         local_sub_filter: Vec<Filter>,
@@ -70,7 +70,7 @@ impl ThreadSubs {
             hashbrown::hash_map::RawEntryMut::Vacant(entry) => {
                 let (_, res) = entry.insert(
                     NoteId::new(*id.root_id.bytes()),
-                    sub_remote(pool, remote_sub_filter, id),
+                    sub_remote(pool, subs, remote_sub_filter, id),
                 );
 
                 res
@@ -91,6 +91,7 @@ impl ThreadSubs {
         &mut self,
         ndb: &mut Ndb,
         pool: &mut RelayPool,
+        subs: &mut AppSubscriptions,
         meta_id: usize,
         id: &ThreadSelection,
         return_type: ReturnType,
@@ -162,7 +163,7 @@ impl ThreadSubs {
                 .remove(&id.root_id.bytes())
                 .expect("code above should guarentee existence");
             tracing::debug!("Remotely unsubscribed: {}", remote.subid);
-            pool.unsubscribe(remote.subid);
+            subs.unsubscribe(pool, &remote.subid);
         }
 
         tracing::debug!(
@@ -228,26 +229,25 @@ fn ndb_unsub(ndb: &mut Ndb, sub: Subscription, id: impl std::fmt::Debug) -> bool
     }
 }
 
+/// Thread subscriptions go through [`AppSubscriptions`], so they close
+/// while columns is in the background
 fn sub_remote(
     pool: &mut RelayPool,
+    subs: &mut AppSubscriptions,
     remote_sub_filter: impl FnOnce() -> Vec<Filter>,
     id: impl std::fmt::Debug,
 ) -> Remote {
-    let subid = Uuid::new_v4().to_string();
-
     let filter = remote_sub_filter();
 
-    let remote = Remote {
-        filter: filter.clone(),
-        subid: subid.clone(),
-        dependers: 0,
-    };
-
     tracing::debug!("Remote subscribe for {:?}", id);
 
-    pool.subscribe(subid, filter);
+    let subid = subs.subscribe(pool, COLUMNS_ID, filter.clone());
 
-    remote
+    Remote {
+        filter,
+        subid,
+        dependers: 0,
+    }
 }
 
 fn local_sub_new_scope(
@@ -19,7 +19,9 @@ use crate::{
         add_column::render_add_column_routes,
         column::NavTitle,
         configure_deck::ConfigureDeckView,
+        database::DatabaseView,
         edit_deck::{EditDeckResponse, EditDeckView},
+        mutes::MuteListView,
         note::{custom_zap::CustomZapView, NewPostAction, PostAction, PostType},
         profile::EditProfileView,
         repost::RepostDecisionView,
@@ -36,11 +38,11 @@ use egui::scroll_area::ScrollAreaOutput;
 use egui_nav::{
     Nav, NavAction, NavResponse, NavUiType, PopupResponse, PopupSheet, RouteResponse, Split,
 };
-use enostr::ProfileState;
+use enostr::{KeypairUnowned, NoteId, ProfileState, Pubkey};
 use nostrdb::{Filter, Ndb, Transaction};
 use notedeck::{
-    get_current_default_msats, tr, ui::is_narrow, Accounts, AppContext, NoteAction, NoteContext,
-    RelayAction,
+    get_current_default_msats, tr, ui::is_narrow, Accounts, AppContext, MuteAction, NoteAction,
+    NoteContext, RelayAction, ZapTargetOwned,
 };
 use notedeck_ui::NoteOptions;
 use tracing::error;
@@ -71,6 +73,8 @@ pub enum RenderNavAction {
     RelayAction(RelayAction),
     SettingsAction(SettingsAction),
     RepostAction(RepostAction),
+    Zap(ZapTargetOwned),
+    MuteAction(MuteAction),
 }
 
 pub enum SwitchingAction {
@@ -287,12 +291,13 @@ fn process_nav_resp(
 
                 if let Some(Route::Thread(selection)) = &r {
                     app.threads
-                        .close(ctx.ndb, ctx.pool, selection, return_type, col);
+                        .close(ctx.ndb, ctx.pool, ctx.subs, selection, return_type, col);
                 }
 
                 // we should remove profile state once we've returned
                 if let Some(Route::EditProfile(pk)) = &r {
                     app.view_state.pubkey_to_profile_state.remove(pk);
+                    app.view_state.profile_uploads = Default::default();
                 }
 
                 process_result = Some(ProcessNavResult::SwitchOccurred);
@@ -474,7 +479,20 @@ fn process_render_nav_action(
         }
         RenderNavAction::PostAction(new_post_action) => {
             let txn = Transaction::new(ctx.ndb).expect("txn");
-            match new_post_action.execute(ctx.ndb, &txn, ctx.pool, &mut app.drafts) {
+            // the remote signer only signs for the selected account
+            let signer = if new_post_action.author() == ctx.accounts.selected_account_pubkey() {
+                ctx.accounts.selected_signer_mut()
+            } else {
+                None
+            };
+            match new_post_action.execute(
+                ctx.ndb,
+                &txn,
+                ctx.pool,
+                ctx.publish_tracker,
+                &mut app.drafts,
+                signer,
+            ) {
                 Err(err) => tracing::error!("Error executing post action: {err}"),
                 Ok(_) => tracing::debug!("Post action executed"),
             }
@@ -493,6 +511,8 @@ fn process_render_nav_action(
                 &mut app.threads,
                 ctx.note_cache,
                 ctx.pool,
+                ctx.subs,
+                ctx.publish_tracker,
                 &txn,
                 ctx.unknown_ids,
                 ctx.accounts,
@@ -516,22 +536,45 @@ fn process_render_nav_action(
                 return None;
             }
         }
-        RenderNavAction::ProfileAction(profile_action) => {
-            profile_action.process_profile_action(ui.ctx(), ctx.ndb, ctx.pool, ctx.accounts)
-        }
+        RenderNavAction::ProfileAction(profile_action) => profile_action.process_profile_action(
+            ui.ctx(),
+            ctx.ndb,
+            ctx.pool,
+            ctx.publish_tracker,
+            ctx.accounts,
+        ),
         RenderNavAction::WalletAction(wallet_action) => {
             wallet_action.process(ctx.accounts, ctx.global_wallet)
         }
         RenderNavAction::RelayAction(action) => {
             ctx.accounts
-                .process_relay_action(ui.ctx(), ctx.pool, action);
+                .process_relay_action(ui.ctx(), ctx.pool, ctx.publish_tracker, action);
             None
         }
-        RenderNavAction::SettingsAction(action) => {
-            action.process_settings_action(app, ctx.settings, ctx.i18n, ctx.img_cache, ui.ctx())
+        RenderNavAction::SettingsAction(action) => action.process_settings_action(
+            app,
+            ctx.settings,
+            ctx.i18n,
+            ctx.img_cache,
+            ctx.translations,
+            ui.ctx(),
+        ),
+        RenderNavAction::RepostAction(action) => action.process(
+            ctx.ndb,
+            &ctx.accounts.get_selected_account().key,
+            ctx.pool,
+            ctx.publish_tracker,
+        ),
+        RenderNavAction::MuteAction(action) => {
+            ctx.accounts
+                .process_mute_action(ctx.ndb, ctx.pool, ctx.publish_tracker, action);
+            None
         }
-        RenderNavAction::RepostAction(action) => {
-            action.process(ctx.ndb, &ctx.accounts.get_selected_account().key, ctx.pool)
+        RenderNavAction::Zap(target) => {
+            if let Err(err) = ctx.zap(&target, None) {
+                error!("could not zap: {err}");
+            }
+            None
         }
     };
 
@@ -547,6 +590,43 @@ fn process_render_nav_action(
     }
 }
 
+/// Open a note's thread in the selected column, for actions that come from
+/// outside of a column like the media viewer
+pub fn open_note_in_selected_column(
+    app: &mut Damus,
+    ctx: &mut AppContext<'_>,
+    ui: &mut egui::Ui,
+    note_id: NoteId,
+) {
+    let Some(col) = app.decks_cache.selected_column_index(ctx.accounts) else {
+        return;
+    };
+
+    let action = NoteAction::Note {
+        note_id,
+        preview: false,
+        scroll_offset: 0.0,
+    };
+
+    process_render_nav_action(app, ctx, ui, col, RenderNavAction::NoteAction(action));
+}
+
+/// The account to post with: the selected one, or the one picked above
+/// the composer in all-accounts mode
+fn choose_poster<'d>(
+    ui: &mut egui::Ui,
+    all_accounts: bool,
+    note_context: &mut NoteContext<'d>,
+    txn: &Transaction,
+    post_as: &mut Option<Pubkey>,
+) -> Option<KeypairUnowned<'d>> {
+    if all_accounts {
+        ui::note::post_as_ui(ui, note_context, txn, post_as)
+    } else {
+        note_context.accounts.selected_poster()
+    }
+}
+
 fn render_nav_body(
     ui: &mut egui::Ui,
     app: &mut Damus,
@@ -568,6 +648,10 @@ fn render_nav_body(
         clipboard: ctx.clipboard,
         i18n: ctx.i18n,
         global_wallet: ctx.global_wallet,
+        media_uploader: ctx.media_uploader,
+        publish_tracker: ctx.publish_tracker,
+        translations: ctx.translations,
+        min_pow: ctx.settings.min_pow(),
     };
     match top {
         Route::Timeline(kind) => {
@@ -624,7 +708,7 @@ fn render_nav_body(
                     Some(RenderNavAction::NoteAction(NoteAction::Profile(pubkey)))
                 }
                 AccountsResponse::Account(accounts_route_response) => {
-                    let mut action = accounts_route_response.process(ctx, app, col);
+                    let mut action = accounts_route_response.process(ctx, app, col, ui.ctx());
 
                     let txn = Transaction::new(ctx.ndb).expect("txn");
                     action.process_action(ctx.unknown_ids, ctx.ndb, &txn);
@@ -634,9 +718,41 @@ fn render_nav_body(
                 }
             })
         }
-        Route::Relays => RelayView::new(ctx.pool, &mut app.view_state.id_string_map, ctx.i18n)
+        Route::Relays => RelayView::new(
+            ctx.pool,
+            ctx.accounts.get_selected_account().data.advertised_relays(),
+            &mut app.view_state.id_string_map,
+            ctx.i18n,
+        )
+        .ui(ui)
+        .map_output(RenderNavAction::RelayAction),
+
+        Route::MuteList => {
+            let mute = ctx.accounts.mute();
+            let can_edit = ctx.accounts.get_selected_account().key.secret_key.is_some();
+            MuteListView::new(
+                ctx.ndb,
+                &mute,
+                can_edit,
+                &mut app.view_state.id_string_map,
+                ctx.i18n,
+            )
             .ui(ui)
-            .map_output(RenderNavAction::RelayAction),
+            .map_output(RenderNavAction::MuteAction)
+        }
+
+        Route::Database => {
+            let db_dir = notedeck::db_maintenance::db_dir(ctx.path, ctx.args);
+            DatabaseView::new(
+                ctx.ndb,
+                ctx.accounts,
+                &db_dir,
+                &mut app.view_state.database,
+                ctx.i18n,
+            )
+            .show(ui);
+            BodyResponse::none()
+        }
 
         Route::Settings => SettingsView::new(
             ctx.settings.get_settings_mut(),
@@ -670,7 +786,13 @@ fn render_nav_body(
                 return BodyResponse::none();
             };
 
-            let Some(poster) = ctx.accounts.selected_filled() else {
+            let Some(poster) = choose_poster(
+                ui,
+                ctx.settings.all_accounts(),
+                &mut note_context,
+                &txn,
+                &mut app.view_state.post_as,
+            ) else {
                 return BodyResponse::none();
             };
 
@@ -709,7 +831,13 @@ fn render_nav_body(
                 return BodyResponse::none();
             };
 
-            let Some(poster) = ctx.accounts.selected_filled() else {
+            let Some(poster) = choose_poster(
+                ui,
+                ctx.settings.all_accounts(),
+                &mut note_context,
+                &txn,
+                &mut app.view_state.post_as,
+            ) else {
                 return BodyResponse::none();
             };
 
@@ -730,12 +858,18 @@ fn render_nav_body(
             response.map_output_maybe(|o| Some(o.action?.into()))
         }
         Route::ComposeNote => {
-            let Some(kp) = ctx.accounts.get_selected_account().key.to_full() else {
+            let txn = Transaction::new(ctx.ndb).expect("txn");
+            let Some(kp) = choose_poster(
+                ui,
+                ctx.settings.all_accounts(),
+                &mut note_context,
+                &txn,
+                &mut app.view_state.post_as,
+            ) else {
                 return BodyResponse::none();
             };
             let draft = app.drafts.compose_mut();
 
-            let txn = Transaction::new(ctx.ndb).expect("txn");
             let post_response = ui::PostView::new(
                 &mut note_context,
                 draft,
@@ -793,8 +927,9 @@ fn render_nav_body(
             let mut resp = None;
             if let Some(config_resp) = ConfigureDeckView::new(new_deck_state, ctx.i18n).ui(ui) {
                 let cur_acc = ctx.accounts.selected_account_pubkey();
-                app.decks_cache
-                    .add_deck(*cur_acc, Deck::new(config_resp.icon, config_resp.name));
+                let mut deck = Deck::new(config_resp.icon, config_resp.name);
+                deck.media_size = config_resp.media_size;
+                app.decks_cache.add_deck(*cur_acc, deck);
 
                 // set new deck as active
                 let cur_index = get_decks_mut(ctx.i18n, ctx.accounts, &mut app.decks_cache)
@@ -858,22 +993,30 @@ fn render_nav_body(
                 return BodyResponse::none();
             };
 
-            EditProfileView::new(ctx.i18n, state, ctx.img_cache, ctx.clipboard)
-                .ui(ui)
-                .map_output_maybe(|save| {
-                    if save {
-                        app.view_state
-                            .pubkey_to_profile_state
-                            .get(kp.pubkey)
-                            .map(|state| {
-                                RenderNavAction::ProfileAction(ProfileAction::SaveChanges(
-                                    SaveProfileChanges::new(kp.to_full(), state.clone()),
-                                ))
-                            })
-                    } else {
-                        None
-                    }
-                })
+            EditProfileView::new(
+                ctx.i18n,
+                state,
+                ctx.img_cache,
+                ctx.clipboard,
+                &mut app.view_state.profile_uploads,
+                ctx.media_uploader,
+                kp,
+            )
+            .ui(ui)
+            .map_output_maybe(|save| {
+                if save {
+                    app.view_state
+                        .pubkey_to_profile_state
+                        .get(kp.pubkey)
+                        .map(|state| {
+                            RenderNavAction::ProfileAction(ProfileAction::SaveChanges(
+                                SaveProfileChanges::new(kp.to_full(), state.clone()),
+                            ))
+                        })
+                } else {
+                    None
+                }
+            })
         }
         Route::Wallet(wallet_type) => {
             let state = match wallet_type {
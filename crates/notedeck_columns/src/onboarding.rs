@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use egui_virtual_list::VirtualList;
-use enostr::{Pubkey, RelayPool};
+use enostr::{FullKeypair, ProfileState, Pubkey, RelayPool};
 use nostrdb::{Filter, Ndb, NoteKey, Transaction};
 use notedeck::{create_nip51_set, filter::default_limit, Nip51SetCache, UnknownIds};
 use uuid::Uuid;
@@ -14,15 +14,46 @@ enum OnboardingState {
     HaveFollowPacks(Nip51SetCache),
 }
 
+/// The pages a new user goes through, in order
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingStep {
+    #[default]
+    Profile,
+    Backup,
+    FollowPacks,
+}
+
 /// Manages the onboarding process. Responsible for retriving the kind 30000 list of trusted pubkeys
 /// and then retrieving all follow packs from the trusted pks updating when new ones arrive
 #[derive(Default)]
 pub struct Onboarding {
     state: Option<Result<OnboardingState, OnboardingError>>,
     pub list: Rc<RefCell<VirtualList>>,
+    pub step: OnboardingStep,
+    /// The key for the new account, made when onboarding starts so we can
+    /// show it for backing up
+    keypair: Option<FullKeypair>,
+    /// `keypair`'s secret key as an nsec, encoded once for the backup page
+    nsec: Option<String>,
+    /// Profile metadata published when onboarding ends
+    pub profile: ProfileState,
+    /// The user confirmed they saved their key
+    pub backed_up: bool,
 }
 
 impl Onboarding {
+    pub fn nsec(&mut self) -> &str {
+        let keypair = self.keypair.get_or_insert_with(FullKeypair::generate);
+        self.nsec
+            .get_or_insert_with(|| keypair.nsec().unwrap_or_default())
+    }
+
+    /// Hand out the new account's key, onboarding is done with it
+    pub fn take_keypair(&mut self) -> FullKeypair {
+        self.nsec = None;
+        self.keypair.take().unwrap_or_else(FullKeypair::generate)
+    }
+
     pub fn get_follow_packs(&self) -> Option<&Nip51SetCache> {
         let Some(Ok(OnboardingState::HaveFollowPacks(packs))) = &self.state else {
             return None;
@@ -88,17 +119,14 @@ impl Onboarding {
 
     // Unsubscribe and clear state
     pub fn end_onboarding(&mut self, pool: &mut RelayPool, ndb: &mut Ndb) {
-        let Some(Ok(OnboardingState::HaveFollowPacks(state))) = &mut self.state else {
-            self.state = None;
-            return;
-        };
+        if let Some(Ok(OnboardingState::HaveFollowPacks(state))) = &mut self.state {
+            let unified = &state.sub;
 
-        let unified = &state.sub;
-
-        pool.unsubscribe(unified.remote.clone());
-        let _ = ndb.unsubscribe(unified.local);
+            pool.unsubscribe(unified.remote.clone());
+            let _ = ndb.unsubscribe(unified.local);
+        }
 
-        self.state = None;
+        *self = Self::default();
     }
 }
 
@@ -3,7 +3,7 @@ use egui::{
     text_edit::TextEditOutput,
     TextBuffer, TextEdit, TextFormat,
 };
-use enostr::{FullKeypair, Pubkey};
+use enostr::{Keypair, Pubkey};
 use nostrdb::{Note, NoteBuilder, NoteReply};
 use std::{
     any::TypeId,
@@ -13,11 +13,13 @@ use std::{
 };
 use tracing::error;
 
-use crate::media_upload::Nip94Event;
+use notedeck::media::upload::Nip94Event;
 
 pub struct NewPost {
     pub content: String,
-    pub account: FullKeypair,
+    /// Without a secret key, the note is built with a throwaway key and
+    /// signed by the account's remote signer
+    pub account: Keypair,
     pub media: Vec<Nip94Event>,
     pub mentions: Vec<Pubkey>,
 }
@@ -44,7 +46,7 @@ fn add_client_tag(builder: NoteBuilder<'_>) -> NoteBuilder<'_> {
 impl NewPost {
     pub fn new(
         content: String,
-        account: enostr::FullKeypair,
+        account: Keypair,
         media: Vec<Nip94Event>,
         mentions: Vec<Pubkey>,
     ) -> Self {
@@ -756,6 +758,7 @@ pub struct MentionInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use enostr::FullKeypair;
     use pretty_assertions::assert_eq;
 
     impl MentionInfo {
@@ -1273,7 +1276,7 @@ mod tests {
 
         let out = buf.output();
         let kp = FullKeypair::generate();
-        let post = NewPost::new(out.text, kp.clone(), Vec::new(), out.mentions);
+        let post = NewPost::new(out.text, kp.clone().to_keypair(), Vec::new(), out.mentions);
         let note = post.to_note(&kp.pubkey);
 
         let mut tags_iter = note.tags().iter();
@@ -1303,7 +1306,7 @@ mod tests {
 
         let out = buf.output();
         let kp = FullKeypair::generate();
-        let post = NewPost::new(out.text, kp.clone(), Vec::new(), out.mentions);
+        let post = NewPost::new(out.text, kp.clone().to_keypair(), Vec::new(), out.mentions);
         let note = post.to_note(&kp.pubkey);
 
         let mut tags_iter = note.tags().iter();
@@ -1332,7 +1335,7 @@ mod tests {
 
         let out = buf.output();
         let kp = FullKeypair::generate();
-        let post = NewPost::new(out.text, kp.clone(), Vec::new(), out.mentions);
+        let post = NewPost::new(out.text, kp.clone().to_keypair(), Vec::new(), out.mentions);
         let note = post.to_note(&kp.pubkey);
 
         let mut tags_iter = note.tags().iter();
@@ -1,7 +1,7 @@
 use enostr::{FilledKeypair, FullKeypair, ProfileState, Pubkey, RelayPool};
 use nostrdb::{Ndb, Note, NoteBuildOptions, NoteBuilder, Transaction};
 
-use notedeck::{Accounts, ContactState, ProfileContext};
+use notedeck::{Accounts, ContactState, ProfileContext, PublishTracker};
 use tracing::info;
 
 use crate::{nav::RouterAction, route::Route};
@@ -24,6 +24,28 @@ impl SaveProfileChanges {
             .build()
             .expect("should build")
     }
+
+    /// Publish the profile and store it locally
+    pub fn send(&self, ndb: &Ndb, pool: &mut RelayPool, publish_tracker: &mut PublishTracker) {
+        let note = self.to_note();
+        let Ok(event) = enostr::ClientMessage::event(&note) else {
+            tracing::error!("could not serialize profile note?");
+            return;
+        };
+
+        let Ok(json) = event.to_json() else {
+            tracing::error!("could not serialize profile note?");
+            return;
+        };
+
+        // TODO(jb55): do this in a more centralized place
+        let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
+
+        info!("sending {}", &json);
+        if let Err(err) = publish_tracker.publish(pool, &note) {
+            tracing::error!("could not publish profile: {err}");
+        }
+    }
 }
 
 fn add_client_tag(builder: NoteBuilder<'_>) -> NoteBuilder<'_> {
@@ -47,36 +69,21 @@ impl ProfileAction {
         ctx: &egui::Context,
         ndb: &Ndb,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         accounts: &Accounts,
     ) -> Option<RouterAction> {
         match self {
             ProfileAction::Edit(kp) => Some(RouterAction::route_to(Route::EditProfile(kp.pubkey))),
             ProfileAction::SaveChanges(changes) => {
-                let note = changes.to_note();
-                let Ok(event) = enostr::ClientMessage::event(&note) else {
-                    tracing::error!("could not serialize profile note?");
-                    return None;
-                };
-
-                let Ok(json) = event.to_json() else {
-                    tracing::error!("could not serialize profile note?");
-                    return None;
-                };
-
-                // TODO(jb55): do this in a more centralized place
-                let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
-
-                info!("sending {}", &json);
-                pool.send(&event);
-
+                changes.send(ndb, pool, publish_tracker);
                 Some(RouterAction::GoBack)
             }
             ProfileAction::Follow(target_key) => {
-                Self::send_follow_user_event(ndb, pool, accounts, target_key);
+                Self::send_follow_user_event(ndb, pool, publish_tracker, accounts, target_key);
                 None
             }
             ProfileAction::Unfollow(target_key) => {
-                Self::send_unfollow_user_event(ndb, pool, accounts, target_key);
+                Self::send_unfollow_user_event(ndb, pool, publish_tracker, accounts, target_key);
                 None
             }
             ProfileAction::Context(profile_context) => {
@@ -91,19 +98,33 @@ impl ProfileAction {
     fn send_follow_user_event(
         ndb: &Ndb,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         accounts: &Accounts,
         target_key: &Pubkey,
     ) {
-        send_kind_3_event(ndb, pool, accounts, FollowAction::Follow(target_key));
+        send_kind_3_event(
+            ndb,
+            pool,
+            publish_tracker,
+            accounts,
+            FollowAction::Follow(target_key),
+        );
     }
 
     fn send_unfollow_user_event(
         ndb: &Ndb,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         accounts: &Accounts,
         target_key: &Pubkey,
     ) {
-        send_kind_3_event(ndb, pool, accounts, FollowAction::Unfollow(target_key));
+        send_kind_3_event(
+            ndb,
+            pool,
+            publish_tracker,
+            accounts,
+            FollowAction::Unfollow(target_key),
+        );
     }
 }
 
@@ -142,7 +163,13 @@ enum FollowAction<'a> {
     Unfollow(&'a Pubkey),
 }
 
-fn send_kind_3_event(ndb: &Ndb, pool: &mut RelayPool, accounts: &Accounts, action: FollowAction) {
+fn send_kind_3_event(
+    ndb: &Ndb,
+    pool: &mut RelayPool,
+    publish_tracker: &mut PublishTracker,
+    accounts: &Accounts,
+    action: FollowAction,
+) {
     let Some(kp) = accounts.get_selected_account().key.to_full() else {
         return;
     };
@@ -202,10 +229,16 @@ fn send_kind_3_event(ndb: &Ndb, pool: &mut RelayPool, accounts: &Accounts, actio
         ),
     };
 
-    send_note_builder(builder, ndb, pool, kp);
+    send_note_builder(builder, ndb, pool, publish_tracker, kp);
 }
 
-fn send_note_builder(builder: NoteBuilder, ndb: &Ndb, pool: &mut RelayPool, kp: FilledKeypair) {
+fn send_note_builder(
+    builder: NoteBuilder,
+    ndb: &Ndb,
+    pool: &mut RelayPool,
+    publish_tracker: &mut PublishTracker,
+    kp: FilledKeypair,
+) {
     let note = builder
         .sign(&kp.secret_key.secret_bytes())
         .build()
@@ -223,13 +256,16 @@ fn send_note_builder(builder: NoteBuilder, ndb: &Ndb, pool: &mut RelayPool, kp:
 
     let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
     info!("sending {}", &json);
-    pool.send(event);
+    if let Err(err) = publish_tracker.publish(pool, &note) {
+        tracing::error!("send_note_builder: {err}");
+    }
 }
 
 pub fn send_new_contact_list(
     kp: FilledKeypair,
     ndb: &Ndb,
     pool: &mut RelayPool,
+    publish_tracker: &mut PublishTracker,
     mut pks_to_follow: Vec<Pubkey>,
 ) {
     if !pks_to_follow.contains(kp.pubkey) {
@@ -238,7 +274,7 @@ pub fn send_new_contact_list(
 
     let builder = construct_new_contact_list(pks_to_follow);
 
-    send_note_builder(builder, ndb, pool, kp);
+    send_note_builder(builder, ndb, pool, publish_tracker, kp);
 }
 
 fn construct_new_contact_list<'a>(pks: Vec<Pubkey>) -> NoteBuilder<'a> {
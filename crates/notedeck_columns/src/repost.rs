@@ -1,5 +1,6 @@
 use enostr::{Keypair, NoteId, RelayPool};
 use nostrdb::{Ndb, Note, NoteBuilder, Transaction};
+use notedeck::PublishTracker;
 
 use crate::{nav::RouterAction, Route};
 
@@ -60,6 +61,7 @@ impl RepostAction {
         ndb: &nostrdb::Ndb,
         current_user: &Keypair,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
     ) -> Option<RouterAction> {
         match self {
             RepostAction::Quote(note_id) => {
@@ -92,7 +94,9 @@ impl RepostAction {
 
                 let _ = ndb.process_event_with(&json, nostrdb::IngestMetadata::new().client(true));
 
-                pool.send(event);
+                if let Err(err) = publish_tracker.publish(pool, &repost_ev) {
+                    tracing::error!("could not publish repost: {err}");
+                }
 
                 Some(RouterAction::GoBack)
             }
@@ -21,6 +21,8 @@ pub enum Route {
     Quote(NoteId),
     RepostDecision(NoteId),
     Relays,
+    MuteList,
+    Database,
     Settings,
     ComposeNote,
     AddColumn(AddColumnRoute),
@@ -117,6 +119,12 @@ impl Route {
             Route::Relays => {
                 writer.write_token("relay");
             }
+            Route::MuteList => {
+                writer.write_token("mutes");
+            }
+            Route::Database => {
+                writer.write_token("database");
+            }
             Route::Settings => {
                 writer.write_token("settings");
             }
@@ -183,6 +191,18 @@ impl Route {
                         Ok(Route::Relays)
                     })
                 },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("mutes")?;
+                        Ok(Route::MuteList)
+                    })
+                },
+                |p| {
+                    p.parse_all(|p| {
+                        p.parse_token("database")?;
+                        Ok(Route::Database)
+                    })
+                },
                 |p| {
                     p.parse_all(|p| {
                         p.parse_token("settings")?;
@@ -278,6 +298,12 @@ impl Route {
             Route::Relays => {
                 ColumnTitle::formatted(tr!(i18n, "Relays", "Column title for relay management"))
             }
+            Route::MuteList => {
+                ColumnTitle::formatted(tr!(i18n, "Muted", "Column title for the mute list"))
+            }
+            Route::Database => {
+                ColumnTitle::formatted(tr!(i18n, "Database", "Column title for database storage"))
+            }
             Route::Settings => {
                 ColumnTitle::formatted(tr!(i18n, "Settings", "Column title for app settings"))
             }
@@ -596,6 +622,8 @@ impl fmt::Display for Route {
                 write!(f, "{}", tr!("Quote", "Display name for quote composition"))
             }
             Route::Relays => write!(f, "{}", tr!("Relays", "Display name for relay management")),
+            Route::MuteList => write!(f, "{}", tr!("Muted", "Display name for the mute list")),
+            Route::Database => write!(f, "{}", tr!("Database", "Display name for database storage")),
             Route::Settings => write!(f, "{}", tr!("Settings", "Display name for settings management")),
             Route::Accounts(amr) => match amr {
                 AccountsRoute::Accounts => write!(
@@ -7,7 +7,7 @@ use tracing::{debug, error};
 
 use crate::{
     column::{Columns, IntermediaryRoute},
-    decks::{Deck, Decks, DecksCache},
+    decks::{Deck, Decks, DecksCache, MediaSize},
     route::Route,
     timeline::{TimelineCache, TimelineKind},
     Error,
@@ -179,12 +179,14 @@ struct SerializableDeck {
 enum MetadataKeyword {
     Icon,
     Name,
+    MediaSize,
 }
 
 impl MetadataKeyword {
     const MAPPING: &'static [(&'static str, MetadataKeyword)] = &[
         ("icon", MetadataKeyword::Icon),
         ("name", MetadataKeyword::Name),
+        ("media", MetadataKeyword::MediaSize),
     ];
 }
 impl fmt::Display for MetadataKeyword {
@@ -263,6 +265,10 @@ impl SerializableDeck {
         let metadata = serialize_metadata(vec![
             MetadataPayload::new(MetadataKeyword::Icon, deck.icon.to_string()),
             MetadataPayload::new(MetadataKeyword::Name, deck.name.clone()),
+            MetadataPayload::new(
+                MetadataKeyword::MediaSize,
+                deck.media_size.as_str().to_owned(),
+            ),
         ]);
 
         SerializableDeck { metadata, columns }
@@ -287,13 +293,21 @@ impl SerializableDeck {
             .find(|p| p.keyword == MetadataKeyword::Name)
             .map_or_else(|| "Deck", |f| &f.value)
             .to_string();
+        let media_size = deserialized_metadata
+            .iter()
+            .find(|p| p.keyword == MetadataKeyword::MediaSize)
+            .and_then(|f| MediaSize::from_keyword(&f.value))
+            .unwrap_or_default();
 
-        Ok(Deck::new_with_columns(
+        let mut deck = Deck::new_with_columns(
             icon.parse::<char>()
                 .map_err(|_| Error::Generic("could not convert String -> char".to_owned()))?,
             name,
             columns,
-        ))
+        );
+        deck.media_size = media_size;
+
+        Ok(deck)
     }
 }
 
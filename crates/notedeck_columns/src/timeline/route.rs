@@ -6,7 +6,7 @@ use crate::{
 };
 
 use enostr::Pubkey;
-use notedeck::{JobsCache, NoteContext};
+use notedeck::{JobsCache, NoteContext, ZapTargetOwned};
 use notedeck_ui::NoteOptions;
 
 #[allow(clippy::too_many_arguments)]
@@ -132,5 +132,8 @@ pub fn render_profile_route(
         ui::profile::ProfileViewAction::Context(profile_context_selection) => Some(
             RenderNavAction::ProfileAction(ProfileAction::Context(profile_context_selection)),
         ),
+        ui::profile::ProfileViewAction::Zap(target_key) => {
+            Some(RenderNavAction::Zap(ZapTargetOwned::Profile(target_key)))
+        }
     })
 }
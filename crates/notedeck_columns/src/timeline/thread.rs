@@ -3,7 +3,7 @@ use egui_virtual_list::VirtualList;
 use enostr::{NoteId, RelayPool};
 use hashbrown::{hash_map::RawEntryMut, HashMap};
 use nostrdb::{Filter, Ndb, Note, NoteKey, NoteReplyBuf, Transaction};
-use notedeck::{NoteCache, NoteRef, UnknownIds};
+use notedeck::{AppSubscriptions, NoteCache, NoteRef, UnknownIds};
 
 use crate::{
     actionbar::{process_thread_notes, NewThreadNotes},
@@ -66,6 +66,7 @@ impl Threads {
         ndb: &mut Ndb,
         txn: &Transaction,
         pool: &mut RelayPool,
+        subs: &mut AppSubscriptions,
         thread: &ThreadSelection,
         new_scope: bool,
         col: usize,
@@ -112,10 +113,16 @@ impl Threads {
                 .collect::<Vec<_>>()
         });
 
-        self.subs
-            .subscribe(ndb, pool, col, thread, local_sub_filter, new_scope, || {
-                replies_filter_remote(thread)
-            });
+        self.subs.subscribe(
+            ndb,
+            pool,
+            subs,
+            col,
+            thread,
+            local_sub_filter,
+            new_scope,
+            || replies_filter_remote(thread),
+        );
 
         new_notes.map(|notes| NewThreadNotes {
             selected_note_id: NoteId::new(*selected_note_id),
@@ -127,12 +134,14 @@ impl Threads {
         &mut self,
         ndb: &mut Ndb,
         pool: &mut RelayPool,
+        subs: &mut AppSubscriptions,
         thread: &ThreadSelection,
         return_type: ReturnType,
         id: usize,
     ) {
         tracing::info!("Closing thread: {:?}", thread);
-        self.subs.unsubscribe(ndb, pool, id, thread, return_type);
+        self.subs
+            .unsubscribe(ndb, pool, subs, id, thread, return_type);
     }
 
     /// Responsible for making sure the chain and the direct replies are up to date
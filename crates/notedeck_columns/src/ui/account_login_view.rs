@@ -5,8 +5,12 @@ use egui::{
     Align, Button, Color32, Frame, InnerResponse, Layout, Margin, RichText, TextEdit, Vec2,
 };
 use egui_winit::clipboard::Clipboard;
+use enostr::nip46::BunkerUri;
 use enostr::Keypair;
-use notedeck::{fonts::get_font_size, tr, Localization, NotedeckTextStyle};
+use notedeck::{
+    fonts::get_font_size, tr, AccountBackup, BackupError, Localization, NotedeckTextStyle,
+    RemoteSigner, RestoredAccount, SignerStatus,
+};
 use notedeck_ui::{
     app_images,
     context_menu::{input_context, PasteBehavior},
@@ -16,12 +20,27 @@ pub struct AccountLoginView<'a> {
     manager: &'a mut AcquireKeyState,
     clipboard: &'a mut Clipboard,
     i18n: &'a mut Localization,
+    pending_signer: Option<&'a RemoteSigner>,
 }
 
 pub enum AccountLoginResponse {
     CreatingNew,
     Onboarding(FollowPacksResponse),
     LoginWith(Keypair),
+    /// Start logging in with a remote signer
+    ConnectSigner(BunkerUri),
+    /// Show a nostrconnect:// uri and wait for a remote signer to answer it
+    NostrConnect,
+    CancelSigner,
+    /// The remote signer told us who we are signing for
+    SignerConnected,
+    /// Restore accounts from a backup whose keys opened, and all of its
+    /// settings if the user asked for that
+    Restore {
+        backup: AccountBackup,
+        accounts: Vec<RestoredAccount>,
+        settings: bool,
+    },
 }
 
 impl<'a> AccountLoginView<'a> {
@@ -34,14 +53,33 @@ impl<'a> AccountLoginView<'a> {
             manager,
             clipboard,
             i18n,
+            pending_signer: None,
         }
     }
 
+    pub fn pending_signer(mut self, signer: Option<&'a RemoteSigner>) -> Self {
+        self.pending_signer = signer;
+        self
+    }
+
     pub fn ui(&mut self, ui: &mut egui::Ui) -> InnerResponse<Option<AccountLoginResponse>> {
         Frame::new().outer_margin(12.0).show(ui, |ui| self.show(ui))
     }
 
     fn show(&mut self, ui: &mut egui::Ui) -> Option<AccountLoginResponse> {
+        if let Some(signer) = self.pending_signer {
+            if signer.user().is_some() {
+                return Some(AccountLoginResponse::SignerConnected);
+            }
+
+            return pending_signer_ui(ui, self.i18n, signer);
+        }
+
+        if self.manager.is_restoring() {
+            return restore_ui(ui, self.i18n, self.manager);
+        }
+
+        let mut response = None;
         ui.vertical(|ui| {
             ui.vertical_centered(|ui| {
                 ui.add_space(32.0);
@@ -74,10 +112,33 @@ impl<'a> AccountLoginView<'a> {
                     ).wrap())
                 });
 
+                ui.with_layout(Layout::left_to_right(Align::TOP), |ui| {
+                let help_text_style = NotedeckTextStyle::Small;
+                ui.add(egui::Label::new(
+                    RichText::new(tr!(self.i18n, "To sign with a remote signer instead, paste its bunker:// address.", "Instructions for logging in with a NIP-46 remote signer"))
+                        .text_style(help_text_style.text_style())
+                        .size(get_font_size(ui.ctx(), &help_text_style)).color(ui.visuals().weak_text_color()),
+                    ).wrap())
+                });
+
                 self.manager.loading_and_error_ui(ui, self.i18n);
 
                 if ui.add(login_button(self.i18n)).clicked() {
-                    self.manager.apply_acquire();
+                    if self.manager.is_bunker_uri() {
+                        response = self
+                            .manager
+                            .parse_bunker_uri()
+                            .map(AccountLoginResponse::ConnectSigner);
+                    } else {
+                        self.manager.apply_acquire();
+                    }
+                }
+
+                if ui
+                    .add(Button::new(RichText::new(tr!(self.i18n, "Connect a remote signer", "Button to log in by pasting a nostrconnect:// address into a remote signer"))).frame(false))
+                    .clicked()
+                {
+                    response = Some(AccountLoginResponse::NostrConnect);
                 }
             });
 
@@ -95,8 +156,37 @@ impl<'a> AccountLoginView<'a> {
                     self.manager.should_create_new();
                 }
             });
+
+            #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(tr!(self.i18n, "Have a backup?", "Label asking if the user has a notedeck backup file. Next to it is a button to restore it."))
+                        .color(ui.style().visuals.noninteractive().fg_stroke.color)
+                        .text_style(NotedeckTextStyle::Body.text_style()),
+                );
+
+                if ui
+                    .add(Button::new(RichText::new(tr!(self.i18n, "Restore", "Button to restore accounts from a backup file"))).frame(false))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("notedeck backup", &["json"])
+                        .pick_file()
+                    {
+                        self.manager.load_backup(&path);
+                    }
+                }
+            });
+
+            if let Some(err) = self.manager.restore_error() {
+                ui.colored_label(ui.visuals().error_fg_color, backup_error_text(self.i18n, err));
+            }
         });
 
+        if response.is_some() {
+            return response;
+        }
+
         if self.manager.check_for_create_new() {
             return Some(AccountLoginResponse::CreatingNew);
         }
@@ -108,6 +198,202 @@ impl<'a> AccountLoginView<'a> {
     }
 }
 
+/// Shown while we wait for the remote signer to accept our connection
+fn pending_signer_ui(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    signer: &RemoteSigner,
+) -> Option<AccountLoginResponse> {
+    let mut response = None;
+
+    ui.vertical_centered(|ui| {
+        ui.add_space(32.0);
+        ui.label(login_title_text(i18n));
+        ui.add_space(16.0);
+
+        if let Some(uri) = signer.nostrconnect_uri() {
+            let uri = uri.to_string();
+            ui.label(tr!(
+                i18n,
+                "Paste this address into your remote signer:",
+                "Shown above the nostrconnect:// address the user gives their remote signer"
+            ));
+            ui.add(
+                TextEdit::multiline(&mut uri.as_str())
+                    .desired_rows(3)
+                    .font(egui::TextStyle::Monospace),
+            );
+            if ui
+                .button(tr!(i18n, "Copy", "Button to copy the nostrconnect:// address"))
+                .clicked()
+            {
+                ui.ctx().copy_text(uri);
+            }
+            ui.add_space(8.0);
+            ui.add(egui::Spinner::new());
+            ui.label(tr!(
+                i18n,
+                "Waiting for your remote signer to connect...",
+                "Shown while waiting for a remote signer to answer the nostrconnect:// address"
+            ));
+            ui.add_space(8.0);
+        }
+
+        for request in signer.requests() {
+            match &request.status {
+                SignerStatus::Waiting => {
+                    ui.add(egui::Spinner::new());
+                    ui.label(tr!(
+                        i18n,
+                        "Waiting for your remote signer to approve the connection...",
+                        "Shown while logging in with a remote signer"
+                    ));
+                }
+                SignerStatus::NeedsAuth(url) => {
+                    ui.label(tr!(
+                        i18n,
+                        "Your remote signer needs you to approve this login.",
+                        "Shown when the remote signer asks the user to approve the login on a web page"
+                    ));
+                    ui.hyperlink_to(
+                        tr!(i18n, "Approve", "Link to approve a remote signer login"),
+                        url,
+                    );
+                }
+                SignerStatus::Failed(err) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+            }
+            ui.add_space(8.0);
+        }
+
+        if ui
+            .button(tr!(i18n, "Cancel", "Button to stop logging in with a remote signer"))
+            .clicked()
+        {
+            response = Some(AccountLoginResponse::CancelSigner);
+        }
+    });
+
+    response
+}
+
+/// Ask for the passphrase of a backup picked on the login screen
+fn restore_ui(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    manager: &mut AcquireKeyState,
+) -> Option<AccountLoginResponse> {
+    let settings = *manager.restore_settings_mut();
+    if let Some((backup, accounts)) = manager.poll_unlocked() {
+        manager.cancel_restore();
+        return Some(AccountLoginResponse::Restore {
+            backup,
+            accounts,
+            settings,
+        });
+    }
+
+    let unlocking = manager.is_unlocking();
+    ui.vertical_centered(|ui| {
+        ui.add_space(32.0);
+        ui.label(
+            RichText::new(tr!(
+                i18n,
+                "Restore backup",
+                "Title of the restore backup page"
+            ))
+            .text_style(NotedeckTextStyle::Heading2.text_style())
+            .strong(),
+        );
+        ui.add_space(16.0);
+
+        ui.label(tr!(
+            i18n,
+            "Enter the passphrase the backup was made with",
+            "Asks for the passphrase of a backup file"
+        ));
+
+        let resp = ui.add(
+            TextEdit::singleline(manager.restore_passphrase_mut())
+                .password(true)
+                .min_size(Vec2::new(0.0, 40.0)),
+        );
+        let submitted = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+        ui.checkbox(
+            manager.restore_settings_mut(),
+            tr!(
+                i18n,
+                "Also replace this device's settings with the backup's",
+                "Checkbox to restore every setting from a backup, not just the restored accounts' own"
+            ),
+        );
+
+        if let Some(err) = manager.restore_error() {
+            ui.colored_label(ui.visuals().error_fg_color, backup_error_text(i18n, err));
+        }
+
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr!(i18n, "Cancel", "Button to stop restoring a backup"))
+                .clicked()
+            {
+                manager.cancel_restore();
+            }
+
+            let restore = ui.add_enabled(
+                !unlocking,
+                Button::new(tr!(
+                    i18n,
+                    "Restore",
+                    "Button to restore accounts from a backup file"
+                )),
+            );
+            if (restore.clicked() || submitted) && !unlocking {
+                manager.unlock_backup();
+            }
+
+            if unlocking {
+                ui.add(egui::Spinner::new());
+            }
+        });
+    });
+
+    None
+}
+
+fn backup_error_text(i18n: &mut Localization, err: &BackupError) -> String {
+    match err {
+        BackupError::WrongPassphrase => tr!(
+            i18n,
+            "That passphrase doesn't open this backup",
+            "Error when restoring a backup with the wrong passphrase"
+        ),
+        BackupError::Invalid => tr!(
+            i18n,
+            "This file isn't a notedeck backup",
+            "Error when restoring a file that isn't a backup"
+        ),
+        BackupError::UnsupportedVersion(_) => tr!(
+            i18n,
+            "This backup was made by a newer version of notedeck",
+            "Error when restoring a backup from a newer notedeck"
+        ),
+        BackupError::Encrypt => tr!(
+            i18n,
+            "Could not encrypt your keys",
+            "Error when a backup could not be made"
+        ),
+        BackupError::KeyMismatch => tr!(
+            i18n,
+            "A key in this backup doesn't belong to its account",
+            "Error when restoring a backup whose key doesn't match the account"
+        ),
+    }
+}
+
 fn login_title_text(i18n: &mut Localization) -> RichText {
     RichText::new(tr!(i18n, "Login", "Login page title"))
         .text_style(NotedeckTextStyle::Heading2.text_style())
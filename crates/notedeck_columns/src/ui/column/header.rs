@@ -481,6 +481,8 @@ impl<'a> NavTitle<'a> {
             Route::AddColumn(_add_col_route) => None,
             Route::Support => None,
             Route::Relays => None,
+            Route::MuteList => None,
+            Route::Database => None,
             Route::Settings => None,
             Route::NewDeck => None,
             Route::EditDeck(_) => None,
@@ -1,4 +1,4 @@
-use crate::{app_style::deck_icon_font_sized, deck_state::DeckState};
+use crate::{app_style::deck_icon_font_sized, deck_state::DeckState, decks::MediaSize};
 use egui::{vec2, Button, Color32, Label, RichText, Stroke, Ui, Widget};
 use notedeck::{tr, Localization};
 use notedeck::{NamedFontFamily, NotedeckTextStyle};
@@ -17,6 +17,7 @@ pub struct ConfigureDeckView<'a> {
 pub struct ConfigureDeckResponse {
     pub icon: char,
     pub name: String,
+    pub media_size: MediaSize,
 }
 
 impl<'a> ConfigureDeckView<'a> {
@@ -67,6 +68,23 @@ impl<'a> ConfigureDeckView<'a> {
                 )),
             ));
 
+            ui.add_space(32.0);
+            ui.add(Label::new(
+                RichText::new(tr!(
+                    self.i18n,
+                    "Media size",
+                    "Label for the size of media in the deck's notes"
+                ))
+                .font(title_font.clone()),
+            ));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                for size in MediaSize::ALL {
+                    let label = size.label(self.i18n);
+                    ui.selectable_value(&mut self.state.media_size, size, label);
+                }
+            });
+
             ui.add_space(32.0);
             ui.add(Label::new(
                 RichText::new(tr!(self.i18n, "Icon", "Label for deck icon selection"))
@@ -132,6 +150,7 @@ impl<'a> ConfigureDeckView<'a> {
                         resp = Some(ConfigureDeckResponse {
                             icon: glyph,
                             name: self.state.deck_name.clone(),
+                            media_size: self.state.media_size,
                         });
                     }
                 }
@@ -0,0 +1,272 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use egui::{Frame, Margin, ProgressBar, RichText};
+use nostrdb::Ndb;
+use notedeck::db_maintenance::{
+    is_replaceable, DbMaintenance, DbStats, MaintenanceStatus, PruneOptions,
+};
+use notedeck::{tr, Accounts, Localization, NotedeckTextStyle};
+use notedeck_ui::{format_size, InfoIcon};
+
+pub struct DatabaseViewState {
+    maintenance: DbMaintenance,
+    /// Kinds ticked for pruning
+    prune_kinds: BTreeSet<u32>,
+    older_than_days: u32,
+    /// The last count, kept while a compaction runs
+    stats: Option<DbStats>,
+}
+
+impl Default for DatabaseViewState {
+    fn default() -> Self {
+        Self {
+            maintenance: DbMaintenance::default(),
+            prune_kinds: BTreeSet::new(),
+            older_than_days: 30,
+            stats: None,
+        }
+    }
+}
+
+/// Note counts and disk usage of the database, and pruning old notes
+pub struct DatabaseView<'a> {
+    ndb: &'a Ndb,
+    accounts: &'a Accounts,
+    db_dir: &'a Path,
+    state: &'a mut DatabaseViewState,
+    i18n: &'a mut Localization,
+}
+
+fn kind_name(i18n: &mut Localization, kind: u32) -> Option<String> {
+    Some(match kind {
+        0 => tr!(i18n, "Profiles", "Database kind name for kind 0"),
+        1 => tr!(i18n, "Notes", "Database kind name for kind 1"),
+        3 => tr!(i18n, "Follow lists", "Database kind name for kind 3"),
+        4 => tr!(i18n, "Direct messages", "Database kind name for kind 4"),
+        5 => tr!(i18n, "Deletions", "Database kind name for kind 5"),
+        6 => tr!(i18n, "Reposts", "Database kind name for kind 6"),
+        7 => tr!(i18n, "Reactions", "Database kind name for kind 7"),
+        9735 => tr!(i18n, "Zaps", "Database kind name for kind 9735"),
+        10002 => tr!(i18n, "Relay lists", "Database kind name for kind 10002"),
+        30023 => tr!(i18n, "Articles", "Database kind name for kind 30023"),
+        _ => return None,
+    })
+}
+
+impl<'a> DatabaseView<'a> {
+    pub fn new(
+        ndb: &'a Ndb,
+        accounts: &'a Accounts,
+        db_dir: &'a Path,
+        state: &'a mut DatabaseViewState,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            ndb,
+            accounts,
+            db_dir,
+            state,
+            i18n,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let status = self.state.maintenance.status();
+        if let MaintenanceStatus::Counted(stats) = &status {
+            self.state.stats = Some(stats.clone());
+        }
+
+        if matches!(status, MaintenanceStatus::Idle) {
+            self.state.maintenance.count(self.ndb, self.db_dir);
+        }
+
+        if status.is_running() {
+            ui.ctx().request_repaint_after(Duration::from_millis(250));
+        }
+
+        Frame::new()
+            .inner_margin(Margin::symmetric(10, 0))
+            .show(ui, |ui| {
+                ui.add_space(24.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr!(self.i18n, "Database", "Label for the database page"))
+                            .text_style(NotedeckTextStyle::Heading2.text_style()),
+                    );
+
+                    ui.add_space(4.0);
+                    ui.add(InfoIcon::new(tr!(
+                        self.i18n,
+                        "Every note notedeck has seen is kept here, so timelines load without asking relays again. Pruning keeps profiles, lists, your own notes and anything newer than the age you pick.",
+                        "Tooltip explaining the database page"
+                    )));
+                });
+
+                ui.add_space(8.0);
+                self.status_ui(ui, &status);
+
+                egui::ScrollArea::vertical()
+                    .id_salt("database_scroll")
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        if let Some(stats) = self.state.stats.clone() {
+                            ui.add_space(8.0);
+                            self.stats_ui(ui, &stats, status.is_running());
+                            ui.add_space(16.0);
+                            self.prune_ui(ui, status.is_running());
+                        }
+                    });
+            });
+    }
+
+    fn status_ui(&mut self, ui: &mut egui::Ui, status: &MaintenanceStatus) {
+        match status {
+            MaintenanceStatus::Idle => {}
+            MaintenanceStatus::Counting { scanned } => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr!(
+                        self.i18n,
+                        "Counting notes... {count}",
+                        "Shown while the database page counts notes",
+                        count = *scanned
+                    ));
+                });
+            }
+            MaintenanceStatus::Counted(_) | MaintenanceStatus::Failed(_) => {
+                if let MaintenanceStatus::Failed(err) = status {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+
+                if ui
+                    .button(tr!(
+                        self.i18n,
+                        "Refresh",
+                        "Button to count the notes in the database again"
+                    ))
+                    .clicked()
+                {
+                    self.state.maintenance.count(self.ndb, self.db_dir);
+                }
+            }
+            MaintenanceStatus::Compacting {
+                scanned,
+                kept,
+                total,
+            } => {
+                let text = tr!(
+                    self.i18n,
+                    "Pruning... kept {kept} of {scanned} notes",
+                    "Progress while the database is pruned",
+                    kept = *kept,
+                    scanned = *scanned
+                );
+                let progress = total
+                    .filter(|total| *total > 0)
+                    .map(|total| *scanned as f32 / total as f32)
+                    .unwrap_or(0.0);
+                ui.add(ProgressBar::new(progress.min(1.0)).text(text).animate(true));
+            }
+            MaintenanceStatus::Compacted { kept, dropped } => {
+                ui.label(tr!(
+                    self.i18n,
+                    "Pruned {dropped} notes and kept {kept}. Restart notedeck to use the smaller database.",
+                    "Shown after the database was pruned",
+                    dropped = *dropped,
+                    kept = *kept
+                ));
+            }
+        }
+    }
+
+    fn stats_ui(&mut self, ui: &mut egui::Ui, stats: &DbStats, running: bool) {
+        ui.label(tr!(
+            self.i18n,
+            "{count} notes using {size} on disk",
+            "Summary of the database size",
+            count = stats.total,
+            size = format_size(stats.disk_bytes)
+        ));
+        ui.add_space(8.0);
+
+        // the biggest kinds first
+        let mut kinds: Vec<(u32, u64)> = stats.kinds.iter().map(|(k, c)| (*k, *c)).collect();
+        kinds.sort_by(|a, b| b.1.cmp(&a.1));
+
+        egui::Grid::new("database_kinds")
+            .num_columns(3)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for (kind, count) in kinds {
+                    let prunable = !is_replaceable(kind);
+                    let mut selected = self.state.prune_kinds.contains(&kind);
+                    let resp = ui.add_enabled(
+                        prunable && !running,
+                        egui::Checkbox::without_text(&mut selected),
+                    );
+                    if resp.changed() {
+                        if selected {
+                            self.state.prune_kinds.insert(kind);
+                        } else {
+                            self.state.prune_kinds.remove(&kind);
+                        }
+                    }
+
+                    let name = kind_name(self.i18n, kind)
+                        .map(|name| format!("{name} ({kind})"))
+                        .unwrap_or_else(|| kind.to_string());
+                    ui.label(name);
+                    ui.label(count.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn prune_ui(&mut self, ui: &mut egui::Ui, running: bool) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr!(
+                self.i18n,
+                "Prune ticked kinds older than",
+                "Label before the age picker on the database page"
+            ));
+            ui.add(
+                egui::DragValue::new(&mut self.state.older_than_days)
+                    .range(1..=3650)
+                    .suffix(tr!(self.i18n, " days", "Unit after the prune age")),
+            );
+        });
+
+        ui.add_space(8.0);
+
+        let can_prune = !running && !self.state.prune_kinds.is_empty();
+        if ui
+            .add_enabled(
+                can_prune,
+                egui::Button::new(tr!(self.i18n, "Prune", "Button to prune the database")),
+            )
+            .clicked()
+        {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+
+            let options = PruneOptions {
+                kinds: self.state.prune_kinds.clone(),
+                older_than: now.saturating_sub(self.state.older_than_days as u64 * 86400),
+                keep_authors: (&self.accounts.cache)
+                    .into_iter()
+                    .map(|(pk, _)| *pk.bytes())
+                    .collect(),
+            };
+
+            self.state
+                .maintenance
+                .compact(self.ndb, self.db_dir, options);
+        }
+    }
+}
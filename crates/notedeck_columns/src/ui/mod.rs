@@ -3,9 +3,11 @@ pub mod accounts;
 pub mod add_column;
 pub mod column;
 pub mod configure_deck;
+pub mod database;
 pub mod edit_deck;
 pub mod images;
 pub mod mentions_picker;
+pub mod mutes;
 pub mod note;
 pub mod onboarding;
 pub mod post;
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use egui::{Align, Frame, Id, Layout, Margin, RichText, Ui, Vec2};
+use enostr::{NoteId, Pubkey};
+use nostrdb::{Ndb, Transaction};
+use notedeck::{
+    name::get_display_name, tr, Localization, MuteAction, MuteItem, Muted, NotedeckTextStyle,
+};
+use notedeck_ui::{app_images, colors::PINK, InfoIcon};
+
+use crate::nav::BodyResponse;
+
+use super::widgets::styled_button;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MuteSection {
+    Users,
+    Words,
+    Hashtags,
+    Threads,
+}
+
+impl MuteSection {
+    const ALL: [MuteSection; 4] = [
+        MuteSection::Users,
+        MuteSection::Words,
+        MuteSection::Hashtags,
+        MuteSection::Threads,
+    ];
+
+    fn title(&self, i18n: &mut Localization) -> String {
+        match self {
+            MuteSection::Users => tr!(i18n, "Users", "Heading for muted users"),
+            MuteSection::Words => tr!(i18n, "Words", "Heading for muted words"),
+            MuteSection::Hashtags => tr!(i18n, "Hashtags", "Heading for muted hashtags"),
+            MuteSection::Threads => tr!(i18n, "Threads", "Heading for muted threads"),
+        }
+    }
+
+    fn hint(&self, i18n: &mut Localization) -> String {
+        match self {
+            MuteSection::Users => tr!(
+                i18n,
+                "npub or hex pubkey",
+                "Placeholder for the muted user input"
+            ),
+            MuteSection::Words => tr!(i18n, "Word to mute", "Placeholder for the muted word input"),
+            MuteSection::Hashtags => {
+                tr!(i18n, "#hashtag", "Placeholder for the muted hashtag input")
+            }
+            MuteSection::Threads => tr!(
+                i18n,
+                "note id of the thread",
+                "Placeholder for the muted thread input"
+            ),
+        }
+    }
+
+    /// Turn what the user typed into something to mute
+    fn parse(&self, input: &str) -> Option<MuteItem> {
+        let input = input.trim();
+        let input = input.strip_prefix("nostr:").unwrap_or(input);
+        if input.is_empty() {
+            return None;
+        }
+
+        match self {
+            MuteSection::Users => Pubkey::parse(input).ok().map(MuteItem::Pubkey),
+            MuteSection::Words => Some(MuteItem::Word(input.to_owned())),
+            MuteSection::Hashtags => {
+                let tag = input.trim_start_matches('#');
+                (!tag.is_empty() && !tag.contains(char::is_whitespace))
+                    .then(|| MuteItem::Hashtag(tag.to_owned()))
+            }
+            MuteSection::Threads => NoteId::from_bech(input)
+                .or_else(|| NoteId::from_hex(input).ok())
+                .map(MuteItem::Thread),
+        }
+    }
+}
+
+/// Manage the selected account's NIP-51 mute list
+pub struct MuteListView<'a> {
+    ndb: &'a Ndb,
+    muted: &'a Muted,
+    /// Read-only accounts can look but can't publish changes
+    can_edit: bool,
+    id_string_map: &'a mut HashMap<Id, String>,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> MuteListView<'a> {
+    pub fn new(
+        ndb: &'a Ndb,
+        muted: &'a Muted,
+        can_edit: bool,
+        id_string_map: &'a mut HashMap<Id, String>,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            ndb,
+            muted,
+            can_edit,
+            id_string_map,
+            i18n,
+        }
+    }
+
+    pub fn scroll_id() -> egui::Id {
+        egui::Id::new("mute_list_scroll")
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> BodyResponse<MuteAction> {
+        let scroll_out = Frame::new()
+            .inner_margin(Margin::symmetric(10, 0))
+            .show(ui, |ui| {
+                ui.add_space(24.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(tr!(self.i18n, "Muted", "Label for the mute list section"))
+                            .text_style(NotedeckTextStyle::Heading2.text_style()),
+                    );
+
+                    ui.add_space(4.0);
+                    ui.add(InfoIcon::new(tr!(
+                        self.i18n,
+                        "Notes from muted users, or with muted words or hashtags, are hidden everywhere. Your mute list is published so other clients use it too.",
+                        "Tooltip explaining the mute list"
+                    )));
+                });
+
+                if !self.can_edit {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!(
+                            self.i18n,
+                            "This account is read-only, the mute list can't be changed",
+                            "Warning shown on the mute list of a read-only account"
+                        ),
+                    );
+                }
+
+                ui.add_space(8.0);
+
+                egui::ScrollArea::vertical()
+                    .id_salt(MuteListView::scroll_id())
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        let txn = Transaction::new(self.ndb).ok();
+                        let mut action = None;
+                        for section in MuteSection::ALL {
+                            if let Some(new_action) = self.show_section(ui, txn.as_ref(), section)
+                            {
+                                action = Some(new_action);
+                            }
+                            ui.add_space(16.0);
+                        }
+                        action
+                    })
+            })
+            .inner;
+
+        BodyResponse::scroll(scroll_out)
+    }
+
+    fn show_section(
+        &mut self,
+        ui: &mut Ui,
+        txn: Option<&Transaction>,
+        section: MuteSection,
+    ) -> Option<MuteAction> {
+        let mut action = None;
+
+        ui.label(
+            RichText::new(section.title(self.i18n))
+                .text_style(NotedeckTextStyle::Heading3.text_style()),
+        );
+        ui.add_space(4.0);
+
+        let items = self.items(section, txn);
+        if items.is_empty() {
+            ui.weak(tr!(
+                self.i18n,
+                "Nothing muted",
+                "Shown for an empty mute list section"
+            ));
+        }
+
+        for (item, label) in items {
+            ui.horizontal(|ui| {
+                ui.label(label);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if self.can_edit && ui.add(delete_button(ui.visuals().dark_mode)).clicked() {
+                        action = Some(MuteAction::Remove(item));
+                    }
+                });
+            });
+        }
+
+        if self.can_edit {
+            ui.add_space(4.0);
+            if let Some(item) = self.add_entry(ui, section) {
+                action = Some(MuteAction::Add(item));
+            }
+        }
+
+        action
+    }
+
+    /// The muted items of a section with how to show them
+    fn items(&self, section: MuteSection, txn: Option<&Transaction>) -> Vec<(MuteItem, String)> {
+        match section {
+            MuteSection::Users => self
+                .muted
+                .pubkeys
+                .iter()
+                .map(|pk| {
+                    let pk = Pubkey::new(*pk);
+                    let profile =
+                        txn.and_then(|txn| self.ndb.get_profile_by_pubkey(txn, pk.bytes()).ok());
+                    let name = get_display_name(profile.as_ref()).name().to_owned();
+                    (MuteItem::Pubkey(pk), name)
+                })
+                .collect(),
+            MuteSection::Words => self
+                .muted
+                .words
+                .iter()
+                .map(|word| (MuteItem::Word(word.clone()), word.clone()))
+                .collect(),
+            MuteSection::Hashtags => self
+                .muted
+                .hashtags
+                .iter()
+                .map(|tag| (MuteItem::Hashtag(tag.clone()), format!("#{tag}")))
+                .collect(),
+            MuteSection::Threads => self
+                .muted
+                .threads
+                .iter()
+                .map(|id| {
+                    let id = NoteId::new(*id);
+                    let label = id.to_bech().unwrap_or_else(|| id.hex());
+                    (MuteItem::Thread(id), abbreviate(&label))
+                })
+                .collect(),
+        }
+    }
+
+    fn add_entry(&mut self, ui: &mut Ui, section: MuteSection) -> Option<MuteItem> {
+        let id = ui.id().with(("add-mute", section as u8));
+        let hint = section.hint(self.i18n);
+        let add_text = tr!(
+            self.i18n,
+            "Mute",
+            "Button label to add an entry to the mute list"
+        );
+
+        ui.horizontal(|ui| {
+            let buffer = self.id_string_map.entry(id).or_default();
+            let item = section.parse(buffer);
+
+            let button = ui.add_enabled(item.is_some(), styled_button(add_text.as_str(), PINK));
+            let text_edit = egui::TextEdit::singleline(buffer)
+                .hint_text(RichText::new(hint).text_style(NotedeckTextStyle::Body.text_style()))
+                .vertical_align(Align::Center)
+                .desired_width(f32::INFINITY)
+                .min_size(Vec2::new(0.0, 32.0));
+            let resp = ui.add(text_edit);
+
+            let submitted = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if button.clicked() || submitted {
+                self.id_string_map.remove(&id);
+                item
+            } else {
+                None
+            }
+        })
+        .inner
+    }
+}
+
+fn abbreviate(s: &str) -> String {
+    if s.chars().count() <= 20 {
+        return s.to_owned();
+    }
+    let start: String = s.chars().take(12).collect();
+    let end: String = s
+        .chars()
+        .rev()
+        .take(6)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    format!("{start}…{end}")
+}
+
+fn delete_button(dark_mode: bool) -> egui::Button<'static> {
+    let img = if dark_mode {
+        app_images::delete_dark_image()
+    } else {
+        app_images::delete_light_image()
+    };
+
+    egui::Button::image(img.max_width(10.0)).frame(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mute_input() {
+        let hex = "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245";
+
+        assert_eq!(
+            MuteSection::Users.parse(hex),
+            Some(MuteItem::Pubkey(Pubkey::from_hex(hex).unwrap()))
+        );
+        assert_eq!(MuteSection::Users.parse("not a key"), None);
+        assert_eq!(
+            MuteSection::Hashtags.parse(" #nostr "),
+            Some(MuteItem::Hashtag("nostr".to_owned()))
+        );
+        assert_eq!(MuteSection::Hashtags.parse("two words"), None);
+        assert_eq!(MuteSection::Words.parse("   "), None);
+        assert_eq!(
+            MuteSection::Threads.parse(hex),
+            Some(MuteItem::Thread(NoteId::from_hex(hex).unwrap()))
+        );
+    }
+}
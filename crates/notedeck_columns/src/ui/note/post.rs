@@ -1,5 +1,4 @@
 use crate::draft::{Draft, Drafts, MentionHint};
-use crate::media_upload::nostrbuild_nip96_upload;
 use crate::nav::BodyResponse;
 use crate::post::{downcast_post_buffer, MentionType, NewPost};
 use crate::ui::mentions_picker::MentionPickerView;
@@ -11,16 +10,17 @@ use egui::{
     widgets::text_edit::TextEdit,
     Frame, Layout, Margin, Pos2, ScrollArea, Sense, TextBuffer,
 };
-use enostr::{FilledKeypair, FullKeypair, NoteId, Pubkey, RelayPool};
+use enostr::{FullKeypair, Keypair, KeypairUnowned, NoteId, Pubkey, RelayPool};
 use nostrdb::{Ndb, Transaction};
 use notedeck::media::gif::ensure_latest_texture;
 use notedeck::media::AnimationMode;
 #[cfg(target_os = "android")]
 use notedeck::platform::android::try_open_file_picker;
-use notedeck::platform::get_next_selected_file;
+use notedeck::platform::{file::FileRequester, get_next_selected_file};
 use notedeck::{get_render_state, JobsCache, PixelDimensions, RenderState};
 use notedeck::{
-    name::get_display_name, supported_mime_hosted_at_url, tr, Localization, NoteAction, NoteContext,
+    name::get_display_name, supported_mime_hosted_at_url, tr, Localization, NoteAction,
+    NoteContext, PublishTracker, RemoteSigner,
 };
 use notedeck_ui::{
     app_images,
@@ -30,13 +30,13 @@ use notedeck_ui::{
 };
 use tracing::error;
 #[cfg(not(target_os = "android"))]
-use {notedeck::platform::file::emit_selected_file, notedeck::platform::file::SelectedMedia};
+use {notedeck::platform::file::emit_selected_file_to, notedeck::platform::file::SelectedMedia};
 
 pub struct PostView<'a, 'd> {
     note_context: &'a mut NoteContext<'d>,
     draft: &'a mut Draft,
     post_type: PostType,
-    poster: FilledKeypair<'a>,
+    poster: KeypairUnowned<'a>,
     inner_rect: egui::Rect,
     note_options: NoteOptions,
     jobs: &'a mut JobsCache,
@@ -68,14 +68,27 @@ impl NewPostAction {
         NewPostAction { post_type, post }
     }
 
+    /// The account the note is posted with
+    pub fn author(&self) -> &Pubkey {
+        &self.post.account.pubkey
+    }
+
     pub fn execute(
         &self,
         ndb: &Ndb,
         txn: &Transaction,
         pool: &mut RelayPool,
+        publish_tracker: &mut PublishTracker,
         drafts: &mut Drafts,
+        signer: Option<&mut RemoteSigner>,
     ) -> Result<()> {
-        let seckey = self.post.account.secret_key.to_secret_bytes();
+        // remote signer accounts build the note with a throwaway key, the
+        // signer replaces it with theirs
+        let local_key = self.post.account.secret_key.as_ref();
+        let seckey = match local_key {
+            Some(secret_key) => secret_key.to_secret_bytes(),
+            None => FullKeypair::generate().secret_key.to_secret_bytes(),
+        };
 
         let note = match self.post_type {
             PostType::New => self.post.to_note(&seckey),
@@ -91,13 +104,86 @@ impl NewPostAction {
             }
         };
 
-        pool.send(&enostr::ClientMessage::event(&note)?);
+        // remote signer accounts sign the note with the signer first, it
+        // gets published once the signer answers
+        match (local_key, signer) {
+            (Some(_), _) => publish_tracker.publish(pool, &note)?,
+            (None, Some(signer)) => signer.sign_note(&note, pool)?,
+            (None, None) => {
+                return Err(crate::Error::Generic(
+                    "no key or remote signer to sign the note with".to_owned(),
+                ))
+            }
+        }
         drafts.get_from_post_type(&self.post_type).clear();
 
         Ok(())
     }
 }
 
+/// In all-accounts mode, a row of the accounts the user can post with
+/// above the composer. Returns the account to post with, which is the
+/// selected account until another one is picked.
+pub fn post_as_ui<'d>(
+    ui: &mut egui::Ui,
+    note_context: &mut NoteContext<'d>,
+    txn: &Transaction,
+    post_as: &mut Option<Pubkey>,
+) -> Option<KeypairUnowned<'d>> {
+    let accounts = note_context.accounts;
+    let selected = accounts.selected_poster();
+    let mut choices: Vec<KeypairUnowned<'d>> = (&accounts.cache)
+        .into_iter()
+        .filter_map(|(_, acc)| acc.key.secret_key.is_some().then(|| acc.keypair()))
+        .collect();
+    // a stable order, the cache is a hashmap
+    choices.sort_by_key(|kp| *kp.pubkey);
+
+    if choices.len() < 2 {
+        return selected;
+    }
+
+    let current = (*post_as)
+        .and_then(|pk| choices.iter().find(|kp| *kp.pubkey == pk).copied())
+        .or(selected)?;
+
+    ui.horizontal(|ui| {
+        ui.label(tr!(
+            note_context.i18n,
+            "Post as",
+            "Label before the accounts to pick from when posting"
+        ));
+
+        for kp in choices {
+            let profile = note_context
+                .ndb
+                .get_profile_by_pubkey(txn, kp.pubkey.bytes())
+                .ok();
+            let mut pfp =
+                ProfilePic::from_profile_or_default(note_context.img_cache, profile.as_ref())
+                    .size(24.0)
+                    .sense(Sense::click());
+            if kp.pubkey == current.pubkey {
+                pfp = pfp.border(egui::Stroke::new(
+                    2.0,
+                    notedeck_ui::colors::account_color(kp.pubkey),
+                ));
+            }
+
+            let name = get_display_name(profile.as_ref()).name().to_owned();
+            let resp = ui
+                .add(&mut pfp)
+                .on_hover_text(name)
+                .on_hover_cursor(egui::CursorIcon::PointingHand);
+            if resp.clicked() {
+                *post_as = Some(*kp.pubkey);
+            }
+        }
+    });
+
+    Some(current)
+}
+
 pub struct PostResponse {
     pub action: Option<PostAction>,
     pub edit_response: egui::Response,
@@ -109,16 +195,17 @@ impl<'a, 'd> PostView<'a, 'd> {
         note_context: &'a mut NoteContext<'d>,
         draft: &'a mut Draft,
         post_type: PostType,
-        poster: FilledKeypair<'a>,
+        poster: KeypairUnowned<'a>,
         inner_rect: egui::Rect,
         note_options: NoteOptions,
         jobs: &'a mut JobsCache,
     ) -> Self {
-        let animation_mode = if note_options.contains(NoteOptions::NoAnimations) {
-            AnimationMode::NoAnimation
-        } else {
-            AnimationMode::Continuous { fps: None }
-        };
+        let animation_mode =
+            if note_options.intersects(NoteOptions::NoAnimations | NoteOptions::DataSaver) {
+                AnimationMode::NoAnimation
+            } else {
+                AnimationMode::Continuous { fps: None }
+            };
         PostView {
             note_context,
             draft,
@@ -373,14 +460,14 @@ impl<'a, 'd> PostView<'a, 'd> {
         txn: &Transaction,
         ui: &mut egui::Ui,
     ) -> BodyResponse<PostResponse> {
-        while let Some(selected_file) = get_next_selected_file() {
+        while let Some(selected_file) = get_next_selected_file(FileRequester::Composer) {
             match selected_file {
                 Ok(selected_media) => {
-                    let promise = nostrbuild_nip96_upload(
-                        self.poster.secret_key.secret_bytes(),
-                        selected_media,
-                    );
-                    self.draft.uploading_media.push(promise);
+                    let upload = self
+                        .note_context
+                        .media_uploader
+                        .upload(self.poster.pubkey, selected_media);
+                    self.draft.uploading_media.push(upload);
                 }
                 Err(e) => {
                     error!("{e}");
@@ -502,7 +589,7 @@ impl<'a, 'd> PostView<'a, 'd> {
                 let output = self.draft.buffer.output();
                 let new_post = NewPost::new(
                     output.text,
-                    self.poster.to_full(),
+                    Keypair::new(*self.poster.pubkey, self.poster.secret_key.cloned()),
                     self.draft.uploaded_media.clone(),
                     output.mentions,
                 );
@@ -566,31 +653,39 @@ impl<'a, 'd> PostView<'a, 'd> {
             {
                 if let Some(files) = rfd::FileDialog::new().pick_files() {
                     for file in files {
-                        emit_selected_file(SelectedMedia::from_path(file));
+                        emit_selected_file_to(
+                            FileRequester::Composer,
+                            SelectedMedia::from_path(file),
+                        );
                     }
                 }
             }
             #[cfg(target_os = "android")]
             {
-                try_open_file_picker();
+                try_open_file_picker(FileRequester::Composer);
             }
         }
     }
 
     fn transfer_uploads(&mut self, ui: &mut egui::Ui) {
         let mut indexes_to_remove = Vec::new();
-        for (i, promise) in self.draft.uploading_media.iter().enumerate() {
-            match promise.ready() {
+        for (i, upload) in self.draft.uploading_media.iter().enumerate() {
+            match upload.promise.ready() {
                 Some(Ok(media)) => {
                     self.draft.uploaded_media.push(media.clone());
                     indexes_to_remove.push(i);
                 }
                 Some(Err(e)) => {
                     self.draft.upload_errors.push(e.to_string());
-                    error!("{e}");
+                    indexes_to_remove.push(i);
                 }
                 None => {
-                    ui.spinner();
+                    ui.add(
+                        egui::ProgressBar::new(upload.progress.fraction())
+                            .desired_width(160.0)
+                            .text(&upload.file_name)
+                            .animate(true),
+                    );
                 }
             }
         }
@@ -822,7 +917,7 @@ fn text_edit_default_layout(ui: &egui::Ui, text: String, wrap_width: f32) -> Lay
 
 mod preview {
 
-    use crate::media_upload::Nip94Event;
+    use notedeck::media::upload::Nip94Event;
 
     use super::*;
     use notedeck::{App, AppContext, AppResponse};
@@ -880,13 +975,20 @@ mod preview {
                 unknown_ids: app.unknown_ids,
                 clipboard: app.clipboard,
                 i18n: app.i18n,
+                media_uploader: app.media_uploader,
+                publish_tracker: app.publish_tracker,
+                translations: app.translations,
+                min_pow: app.settings.min_pow(),
             };
 
             PostView::new(
                 &mut note_context,
                 &mut self.draft,
                 PostType::New,
-                self.poster.to_filled(),
+                KeypairUnowned {
+                    pubkey: &self.poster.pubkey,
+                    secret_key: Some(&self.poster.secret_key),
+                },
                 ui.available_rect_before_wrap(),
                 NoteOptions::default(),
                 &mut self.jobs,
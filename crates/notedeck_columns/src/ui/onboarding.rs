@@ -1,14 +1,18 @@
 use std::mem;
 
-use egui::{Layout, ScrollArea};
+use egui::{Layout, RichText, ScrollArea, TextEdit};
 use nostrdb::Ndb;
-use notedeck::{tr, Images, JobPool, JobsCache, Localization};
+use notedeck::{tr, Images, JobPool, JobsCache, Localization, NotedeckTextStyle};
 use notedeck_ui::{
     colors,
     nip51_set::{Nip51SetUiCache, Nip51SetWidget, Nip51SetWidgetAction, Nip51SetWidgetFlags},
 };
 
-use crate::{nav::BodyResponse, onboarding::Onboarding, ui::widgets::styled_button};
+use crate::{
+    nav::BodyResponse,
+    onboarding::{Onboarding, OnboardingStep},
+    ui::widgets::styled_button,
+};
 
 /// Display Follow Packs for the user to choose from authors trusted by the Damus team
 pub struct FollowPackOnboardingView<'a> {
@@ -117,3 +121,159 @@ impl<'a> FollowPackOnboardingView<'a> {
         BodyResponse::output(action).scroll_raw(scroll_out.id)
     }
 }
+
+/// The first onboarding pages: a name for the new account, then its
+/// secret key and why it needs backing up
+pub struct NewAccountOnboardingView<'a> {
+    onboarding: &'a mut Onboarding,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> NewAccountOnboardingView<'a> {
+    pub fn new(onboarding: &'a mut Onboarding, i18n: &'a mut Localization) -> Self {
+        Self { onboarding, i18n }
+    }
+
+    pub fn scroll_id() -> egui::Id {
+        egui::Id::new("new_account_onboarding")
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> BodyResponse<OnboardingResponse> {
+        let scroll_out = ScrollArea::vertical()
+            .id_salt(Self::scroll_id())
+            .show(ui, |ui| {
+                notedeck_ui::padding(24.0, ui, |ui| match self.onboarding.step {
+                    OnboardingStep::Profile => self.profile_ui(ui),
+                    OnboardingStep::Backup => self.backup_ui(ui),
+                    OnboardingStep::FollowPacks => {}
+                });
+
+                None
+            });
+
+        BodyResponse::scroll(scroll_out)
+    }
+
+    fn profile_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Welcome to nostr",
+                "Heading of the first onboarding page"
+            ))
+            .text_style(NotedeckTextStyle::Heading2.text_style()),
+        );
+        ui.add_space(8.0);
+        ui.label(tr!(
+            self.i18n,
+            "Pick a name people will see next to your notes. You can change it, and add a picture, later from your profile.",
+            "Explanation on the onboarding profile page"
+        ));
+        ui.add_space(16.0);
+
+        ui.label(tr!(
+            self.i18n,
+            "Name",
+            "Label for the name field during onboarding"
+        ));
+        ui.add(
+            TextEdit::singleline(self.onboarding.profile.str_mut("name"))
+                .desired_width(f32::INFINITY),
+        );
+        ui.add_space(8.0);
+
+        ui.label(tr!(
+            self.i18n,
+            "About you",
+            "Label for the about field during onboarding"
+        ));
+        ui.add(
+            TextEdit::multiline(self.onboarding.profile.str_mut("about"))
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        );
+        ui.add_space(16.0);
+
+        let has_name = self
+            .onboarding
+            .profile
+            .get_str("name")
+            .is_some_and(|name| !name.trim().is_empty());
+
+        ui.with_layout(Layout::top_down(egui::Align::Center), |ui| {
+            let next = tr!(
+                self.i18n,
+                "Next",
+                "Button to go to the next onboarding page"
+            );
+            if ui
+                .add_enabled(has_name, styled_button(next.as_str(), colors::PINK))
+                .clicked()
+            {
+                self.onboarding.step = OnboardingStep::Backup;
+            }
+        });
+    }
+
+    fn backup_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            RichText::new(tr!(
+                self.i18n,
+                "Save your key",
+                "Heading of the onboarding page about backing up the secret key"
+            ))
+            .text_style(NotedeckTextStyle::Heading2.text_style()),
+        );
+        ui.add_space(8.0);
+        ui.label(tr!(
+            self.i18n,
+            "Your account is this secret key. There is no password reset on nostr: if you lose the key, you lose the account, and anyone who has it can post as you. Keep a copy in a password manager and never share it.",
+            "Explanation on the onboarding page about backing up the secret key"
+        ));
+        ui.add_space(16.0);
+
+        let nsec = self.onboarding.nsec();
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new(nsec).monospace());
+            if ui
+                .button(tr!(
+                    self.i18n,
+                    "Copy",
+                    "Button to copy the secret key during onboarding"
+                ))
+                .clicked()
+            {
+                ui.ctx().copy_text(nsec.to_owned());
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.checkbox(
+            &mut self.onboarding.backed_up,
+            tr!(
+                self.i18n,
+                "I saved my secret key somewhere safe",
+                "Checkbox confirming the secret key was backed up during onboarding"
+            ),
+        );
+        ui.add_space(16.0);
+
+        ui.with_layout(Layout::top_down(egui::Align::Center), |ui| {
+            let next = tr!(
+                self.i18n,
+                "Next",
+                "Button to go to the next onboarding page"
+            );
+            if ui
+                .add_enabled(
+                    self.onboarding.backed_up,
+                    styled_button(next.as_str(), colors::PINK),
+                )
+                .clicked()
+            {
+                self.onboarding.step = OnboardingStep::FollowPacks;
+            }
+        });
+    }
+}
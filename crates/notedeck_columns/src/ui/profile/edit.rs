@@ -1,33 +1,75 @@
 use core::f32;
 
-use egui::{vec2, Button, CornerRadius, Layout, Margin, RichText, ScrollArea, TextEdit};
+use egui::{vec2, Button, CornerRadius, Layout, Margin, RichText, ScrollArea, Sense, TextEdit};
 use egui_winit::clipboard::Clipboard;
-use enostr::ProfileState;
+use enostr::{FilledKeypair, ProfileState};
+use notedeck::media::upload::{MediaUpload, MediaUploader};
+#[cfg(target_os = "android")]
+use notedeck::platform::android::try_open_file_picker;
+use notedeck::platform::{
+    file::{FileRequester, SelectedMedia},
+    get_next_selected_file,
+};
 use notedeck::{profile::unwrap_profile_url, tr, Images, Localization, NotedeckTextStyle};
 use notedeck_ui::context_menu::{input_context, PasteBehavior};
 use notedeck_ui::{profile::banner, ProfilePic};
 
 use crate::nav::BodyResponse;
 
+/// The profile images that can be uploaded from the editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileImage {
+    Picture,
+    Banner,
+}
+
+impl ProfileImage {
+    fn key(&self) -> &'static str {
+        match self {
+            ProfileImage::Picture => "picture",
+            ProfileImage::Banner => "banner",
+        }
+    }
+}
+
+/// Picture and banner uploads started from the profile editor
+#[derive(Default)]
+pub struct ProfileUploads {
+    /// The image the android file picker is choosing a file for
+    picking: Option<ProfileImage>,
+    uploading: Vec<(ProfileImage, MediaUpload)>,
+    errors: Vec<String>,
+}
+
 pub struct EditProfileView<'a> {
     state: &'a mut ProfileState,
     clipboard: &'a mut Clipboard,
     img_cache: &'a mut Images,
     i18n: &'a mut Localization,
+    uploads: &'a mut ProfileUploads,
+    uploader: &'a MediaUploader,
+    account: FilledKeypair<'a>,
 }
 
 impl<'a> EditProfileView<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         i18n: &'a mut Localization,
         state: &'a mut ProfileState,
         img_cache: &'a mut Images,
         clipboard: &'a mut Clipboard,
+        uploads: &'a mut ProfileUploads,
+        uploader: &'a MediaUploader,
+        account: FilledKeypair<'a>,
     ) -> Self {
         Self {
             i18n,
             state,
             img_cache,
             clipboard,
+            uploads,
+            uploader,
+            account,
         }
     }
 
@@ -37,6 +79,8 @@ impl<'a> EditProfileView<'a> {
 
     // return true to save
     pub fn ui(&mut self, ui: &mut egui::Ui) -> BodyResponse<bool> {
+        self.process_uploads();
+
         let scroll_out = ScrollArea::vertical()
             .id_salt(EditProfileView::scroll_id())
             .stick_to_bottom(true)
@@ -123,6 +167,7 @@ impl<'a> EditProfileView<'a> {
                 .as_str(),
             ));
             multiline_textedit(ui, self.state.str_mut("picture"), self.clipboard);
+            self.upload_ui(ui, ProfileImage::Picture);
         });
 
         in_frame(ui, |ui| {
@@ -130,8 +175,11 @@ impl<'a> EditProfileView<'a> {
                 tr!(self.i18n, "Banner", "Profile banner URL field label").as_str(),
             ));
             multiline_textedit(ui, self.state.str_mut("banner"), self.clipboard);
+            self.upload_ui(ui, ProfileImage::Banner);
         });
 
+        self.upload_errors_ui(ui);
+
         in_frame(ui, |ui| {
             ui.add(label(
                 tr!(self.i18n, "About", "Profile about/bio field label").as_str(),
@@ -211,6 +259,111 @@ impl<'a> EditProfileView<'a> {
     }
 }
 
+impl EditProfileView<'_> {
+    /// Start uploads for files the android picker chose, and fill in the
+    /// url of finished ones
+    fn process_uploads(&mut self) {
+        if let Some(image) = self.uploads.picking {
+            while let Some(selected) = get_next_selected_file(FileRequester::ProfileEditor) {
+                self.uploads.picking = None;
+                self.start_upload(image, selected);
+            }
+        }
+
+        let uploads = &mut *self.uploads;
+        let errors = &mut uploads.errors;
+        let state = &mut *self.state;
+        uploads
+            .uploading
+            .retain(|(image, upload)| match upload.promise.ready() {
+                Some(Ok(media)) => {
+                    *state.str_mut(image.key()) = media.url.clone();
+                    false
+                }
+                Some(Err(err)) => {
+                    errors.push(err.to_string());
+                    false
+                }
+                None => true,
+            });
+    }
+
+    fn start_upload(
+        &mut self,
+        image: ProfileImage,
+        selected: Result<SelectedMedia, notedeck::Error>,
+    ) {
+        match selected {
+            Ok(media) => {
+                let upload = self.uploader.upload(self.account.pubkey, media);
+                self.uploads.uploading.push((image, upload));
+            }
+            Err(err) => self.uploads.errors.push(err.to_string()),
+        }
+    }
+
+    fn upload_ui(&mut self, ui: &mut egui::Ui, image: ProfileImage) {
+        if let Some((_, upload)) = self.uploads.uploading.iter().find(|(i, _)| *i == image) {
+            ui.add(
+                egui::ProgressBar::new(upload.progress.fraction())
+                    .desired_width(160.0)
+                    .text(&upload.file_name)
+                    .animate(true),
+            );
+            return;
+        }
+
+        if !ui
+            .button(tr!(
+                self.i18n,
+                "Upload",
+                "Button to upload a profile picture or banner"
+            ))
+            .clicked()
+        {
+            return;
+        }
+
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        {
+            if let Some(path) = rfd::FileDialog::new().pick_file() {
+                self.start_upload(image, SelectedMedia::from_path(path));
+            }
+        }
+
+        #[cfg(target_os = "android")]
+        {
+            self.uploads.picking = Some(image);
+            try_open_file_picker(FileRequester::ProfileEditor);
+        }
+    }
+
+    fn upload_errors_ui(&mut self, ui: &mut egui::Ui) {
+        let mut dismissed = None;
+        for (i, error) in self.uploads.errors.iter().enumerate() {
+            if ui
+                .add(
+                    egui::Label::new(RichText::new(error).color(ui.visuals().warn_fg_color))
+                        .sense(Sense::click())
+                        .selectable(false),
+                )
+                .on_hover_text_at_pointer(tr!(
+                    self.i18n,
+                    "Dismiss",
+                    "Hover text to dismiss a profile upload error"
+                ))
+                .clicked()
+            {
+                dismissed = Some(i);
+            }
+        }
+
+        if let Some(i) = dismissed {
+            self.uploads.errors.remove(i);
+        }
+    }
+}
+
 fn label(text: &str) -> impl egui::Widget + '_ {
     move |ui: &mut egui::Ui| -> egui::Response {
         ui.label(RichText::new(text).font(NotedeckTextStyle::Body.get_bolded_font(ui.ctx())))
@@ -4,7 +4,7 @@ pub use edit::EditProfileView;
 use egui::{vec2, Color32, CornerRadius, Layout, Rect, RichText, ScrollArea, Sense, Stroke};
 use enostr::Pubkey;
 use nostrdb::{ProfileRecord, Transaction};
-use notedeck::{tr, Localization, ProfileContext};
+use notedeck::{tr, AnyZapState, Localization, ProfileContext, ZapTarget};
 use notedeck_ui::profile::{context::ProfileContextWidget, follow_button};
 use robius_open::Uri;
 use tracing::error;
@@ -39,6 +39,7 @@ pub enum ProfileViewAction {
     Unfollow(Pubkey),
     Follow(Pubkey),
     Context(ProfileContext),
+    Zap(Pubkey),
 }
 
 struct ProfileScrollResponse {
@@ -188,6 +189,7 @@ fn profile_body(
                     pfp_rect,
                     &mut ProfilePic::new(note_context.img_cache, get_profile_url(profile))
                         .size(size)
+                        .animate(true)
                         .border(ProfilePic::border_stroke(ui)),
                 );
 
@@ -243,6 +245,13 @@ fn profile_body(
                                     }
                                 };
                             }
+
+                            if has_zap_address(profile) {
+                                ui.add_space(8.0);
+                                if zap_button(ui, note_context, target_key) {
+                                    action = Some(ProfileViewAction::Zap(target_key.to_owned()));
+                                }
+                            }
                         }
                         ProfileType::ReadOnly => {}
                     }
@@ -376,6 +385,49 @@ fn copy_key_widget<'a>(
     }
 }
 
+fn has_zap_address(profile: Option<&ProfileRecord>) -> bool {
+    profile
+        .and_then(|p| p.record().profile())
+        .is_some_and(|p| p.lud16().is_some() || p.lud06().is_some())
+}
+
+/// Zap the profile with the default amount. The bolt shows how the last
+/// zap from the selected account went.
+fn zap_button(ui: &mut egui::Ui, note_context: &mut NoteContext, target: &Pubkey) -> bool {
+    let sender = note_context.accounts.selected_account_pubkey();
+    let state = note_context
+        .zaps
+        .any_zap_state_for(sender.bytes(), ZapTarget::Profile(target.bytes()));
+
+    let (color, hover) = match state {
+        Ok(AnyZapState::Pending) => {
+            ui.add(egui::Spinner::new());
+            return false;
+        }
+        Ok(AnyZapState::None) => (
+            ui.visuals().text_color(),
+            tr!(
+                note_context.i18n,
+                "Zap",
+                "Hover text for the button to zap a profile"
+            ),
+        ),
+        Ok(AnyZapState::LocalOnly | AnyZapState::Confirmed) => (
+            notedeck_ui::colors::PINK,
+            tr!(
+                note_context.i18n,
+                "Zapped, zap again",
+                "Hover text for the zap button after zapping a profile"
+            ),
+        ),
+        Err(err) => (ui.visuals().warn_fg_color, err.to_string()),
+    };
+
+    ui.button(RichText::new("⚡").size(18.0).color(color))
+        .on_hover_text(hover)
+        .clicked()
+}
+
 fn edit_profile_button<'a>(i18n: &'a mut Localization) -> impl egui::Widget + 'a {
     |ui: &mut egui::Ui| -> egui::Response {
         let (rect, resp) = ui.allocate_exact_size(vec2(124.0, 32.0), Sense::click());
@@ -1,18 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
 
 use crate::nav::BodyResponse;
 use crate::ui::{Preview, PreviewConfig};
 use egui::{Align, Button, CornerRadius, Frame, Id, Layout, Margin, Rgba, RichText, Ui, Vec2};
-use enostr::{RelayPool, RelayStatus};
-use notedeck::{tr, Localization, NotedeckTextStyle, RelayAction};
+use enostr::{RelayPool, RelayStats, RelayStatus};
+use notedeck::{tr, Localization, NotedeckTextStyle, RelayAction, RelaySpec};
 use notedeck_ui::app_images;
-use notedeck_ui::{colors::PINK, padding};
+use notedeck_ui::{colors::PINK, padding, InfoIcon};
 use tracing::debug;
 
 use super::widgets::styled_button;
 
 pub struct RelayView<'a> {
-    pool: &'a RelayPool,
+    pool: &'a mut RelayPool,
+    /// The account's NIP-65 relay list
+    advertised: &'a BTreeSet<RelaySpec>,
     id_string_map: &'a mut HashMap<Id, String>,
     i18n: &'a mut Localization,
 }
@@ -30,6 +33,13 @@ impl RelayView<'_> {
                             RichText::new(tr!(self.i18n, "Relays", "Label for relay list section"))
                                 .text_style(NotedeckTextStyle::Heading2.text_style()),
                         );
+
+                        ui.add_space(4.0);
+                        ui.add(InfoIcon::new(tr!(
+                            self.i18n,
+                            "Relays are the servers your notes are read from and published to",
+                            "Tooltip explaining what relays are"
+                        )));
                     });
                 });
 
@@ -40,10 +50,7 @@ impl RelayView<'_> {
                     .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
                     .auto_shrink([false; 2])
                     .show(ui, |ui| {
-                        let mut action = None;
-                        if let Some(relay_to_remove) = self.show_relays(ui) {
-                            action = Some(RelayAction::Remove(relay_to_remove));
-                        }
+                        let mut action = self.show_relays(ui);
                         ui.add_space(8.0);
                         if let Some(relay_to_add) = self.show_add_relay_ui(ui) {
                             action = Some(RelayAction::Add(relay_to_add));
@@ -63,12 +70,14 @@ impl RelayView<'_> {
 
 impl<'a> RelayView<'a> {
     pub fn new(
-        pool: &'a RelayPool,
+        pool: &'a mut RelayPool,
+        advertised: &'a BTreeSet<RelaySpec>,
         id_string_map: &'a mut HashMap<Id, String>,
         i18n: &'a mut Localization,
     ) -> Self {
         RelayView {
             pool,
+            advertised,
             id_string_map,
             i18n,
         }
@@ -78,10 +87,15 @@ impl<'a> RelayView<'a> {
         egui::CentralPanel::default().show(ui.ctx(), |ui| self.ui(ui));
     }
 
-    /// Show the current relays and return a relay the user selected to delete
-    fn show_relays(&mut self, ui: &mut Ui) -> Option<String> {
-        let mut relay_to_remove = None;
-        for (index, relay_info) in get_relay_infos(self.pool).iter().enumerate() {
+    /// Show the current relays and return any change the user made to them
+    fn show_relays(&mut self, ui: &mut Ui) -> Option<RelayAction> {
+        let mut action = None;
+        let mut relay_to_ping = None;
+        let mut relay_to_pin = None;
+        for (index, relay_info) in get_relay_infos(self.pool, self.advertised)
+            .iter()
+            .enumerate()
+        {
             ui.add_space(8.0);
             ui.vertical_centered_justified(|ui| {
                 relay_frame(ui).show(ui, |ui| {
@@ -118,16 +132,43 @@ impl<'a> RelayView<'a> {
 
                         ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                             if ui.add(delete_button(ui.visuals().dark_mode)).clicked() {
-                                relay_to_remove = Some(relay_info.relay_url.to_string());
+                                action =
+                                    Some(RelayAction::Remove(relay_info.relay_url.to_string()));
                             };
 
                             show_connection_status(ui, self.i18n, relay_info.status);
+
+                            let latency = relay_info.stats.and_then(|s| s.latency);
+                            if ping_button(ui, self.i18n, latency).clicked() {
+                                relay_to_ping = Some(relay_info.relay_url.to_owned());
+                            }
+
+                            if let Some(spec) = relay_info.spec {
+                                if let Some(usage) = usage_toggles(ui, self.i18n, spec) {
+                                    action = Some(usage);
+                                }
+                            }
                         });
                     });
+
+                    if let Some(stats) = relay_info.stats {
+                        if let Some(pinned) = show_relay_stats(ui, self.i18n, stats) {
+                            relay_to_pin = Some((relay_info.relay_url.to_owned(), pinned));
+                        }
+                    }
                 });
             });
         }
-        relay_to_remove
+
+        if let Some(url) = relay_to_ping {
+            self.pool.ping(&url);
+        }
+
+        if let Some((url, pinned)) = relay_to_pin {
+            self.pool.set_pinned(&url, pinned);
+        }
+
+        action
     }
 
     const RELAY_PREFILL: &'static str = "wss://";
@@ -213,11 +254,132 @@ fn add_relay_button2<'a>(i18n: &'a mut Localization, is_enabled: bool) -> impl e
 }
 
 fn get_right_side_width(status: RelayStatus) -> f32 {
-    match status {
-        RelayStatus::Connected => 150.0,
-        RelayStatus::Connecting => 160.0,
-        RelayStatus::Disconnected => 175.0,
+    // room for the ping button and read/write toggles
+    const CONTROLS_WIDTH: f32 = 180.0;
+
+    CONTROLS_WIDTH
+        + match status {
+            RelayStatus::Connected => 150.0,
+            RelayStatus::Connecting => 160.0,
+            RelayStatus::Disconnected => 175.0,
+        }
+}
+
+/// Read and write toggles for a relay in the account's relay list
+fn usage_toggles(ui: &mut Ui, i18n: &mut Localization, spec: &RelaySpec) -> Option<RelayAction> {
+    let (mut read, mut write) = (spec.is_readable(), spec.is_writable());
+
+    // right to left, so write goes first
+    let write_resp = ui
+        .toggle_value(
+            &mut write,
+            tr!(i18n, "Write", "Toggle to publish notes to a relay"),
+        )
+        .on_hover_text(tr!(
+            i18n,
+            "Publish your notes to this relay",
+            "Hover text for the relay write toggle"
+        ));
+    let read_resp = ui
+        .toggle_value(
+            &mut read,
+            tr!(i18n, "Read", "Toggle to read notes from a relay"),
+        )
+        .on_hover_text(tr!(
+            i18n,
+            "Load notes from this relay",
+            "Hover text for the relay read toggle"
+        ));
+
+    // a relay has to be used for something, remove it instead
+    if !(write_resp.changed() || read_resp.changed()) || !(read || write) {
+        return None;
     }
+
+    Some(RelayAction::SetUsage {
+        url: spec.url.clone(),
+        read,
+        write,
+    })
+}
+
+/// Connection health of a relay, with a toggle to pin it. Returns the new
+/// pinned state if it was toggled.
+fn show_relay_stats(ui: &mut Ui, i18n: &mut Localization, stats: &RelayStats) -> Option<bool> {
+    let mut pinned = stats.pinned;
+    let weak = ui.visuals().weak_text_color();
+
+    let resp = ui.horizontal(|ui| {
+        if let Some(rate) = stats.success_rate() {
+            let rate = format!("{:.0}%", rate * 100.0);
+            ui.label(
+                RichText::new(tr!(
+                    i18n,
+                    "Connected {rate} of the time",
+                    "Relay connection success rate",
+                    rate = rate
+                ))
+                .small()
+                .color(weak),
+            );
+        }
+
+        if let Some(eose) = stats.eose_time {
+            let ms = eose.as_millis().to_string();
+            ui.label(
+                RichText::new(tr!(
+                    i18n,
+                    "Loads in {ms} ms",
+                    "Average time for a relay to return stored notes",
+                    ms = ms
+                ))
+                .small()
+                .color(weak),
+            );
+        }
+
+        if stats.is_failing() {
+            ui.label(
+                RichText::new(tr!(
+                    i18n,
+                    "Failing, not used for loading notes",
+                    "Shown on relays that keep failing to connect"
+                ))
+                .small()
+                .color(ui.visuals().error_fg_color),
+            );
+        }
+
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.toggle_value(
+                &mut pinned,
+                RichText::new(tr!(i18n, "Pin", "Toggle to keep using a relay")).small(),
+            )
+            .on_hover_text(tr!(
+                i18n,
+                "Keep loading notes from this relay even if it keeps failing",
+                "Hover text for the relay pin toggle"
+            ))
+            .changed()
+        })
+        .inner
+    });
+
+    resp.inner.then_some(pinned)
+}
+
+/// Shows the last measured latency, click to measure again
+fn ping_button(ui: &mut Ui, i18n: &mut Localization, latency: Option<Duration>) -> egui::Response {
+    let text = match latency {
+        Some(latency) => format!("{} ms", latency.as_millis()),
+        None => tr!(i18n, "Ping", "Button to measure the latency of a relay"),
+    };
+
+    ui.small_button(text).on_hover_text(tr!(
+        i18n,
+        "Measure how long the relay takes to respond",
+        "Hover text for the relay ping button"
+    ))
 }
 
 fn delete_button(dark_mode: bool) -> egui::Button<'static> {
@@ -275,14 +437,22 @@ fn get_connection_icon(status: RelayStatus) -> egui::Image<'static> {
 struct RelayInfo<'a> {
     pub relay_url: &'a str,
     pub status: RelayStatus,
+    pub stats: Option<&'a RelayStats>,
+    /// Set if the relay is in the account's relay list
+    pub spec: Option<&'a RelaySpec>,
 }
 
-fn get_relay_infos(pool: &RelayPool) -> Vec<RelayInfo<'_>> {
+fn get_relay_infos<'a>(
+    pool: &'a RelayPool,
+    advertised: &'a BTreeSet<RelaySpec>,
+) -> Vec<RelayInfo<'a>> {
     pool.relays
         .iter()
         .map(|relay| RelayInfo {
             relay_url: relay.url(),
             status: relay.status(),
+            stats: relay.stats(),
+            spec: advertised.get(&RelaySpec::new(relay.url(), false, false)),
         })
         .collect()
 }
@@ -310,7 +480,8 @@ mod preview {
         fn update(&mut self, app: &mut AppContext<'_>, ui: &mut egui::Ui) -> AppResponse {
             self.pool.try_recv();
             let mut id_string_map = HashMap::new();
-            RelayView::new(app.pool, &mut id_string_map, app.i18n).ui(ui);
+            let advertised = BTreeSet::new();
+            RelayView::new(app.pool, &advertised, &mut id_string_map, app.i18n).ui(ui);
             AppResponse::none()
         }
     }
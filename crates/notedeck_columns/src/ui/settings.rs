@@ -1,20 +1,25 @@
 use egui::{
-    vec2, Button, Color32, ComboBox, CornerRadius, FontId, Frame, Layout, Margin, RichText,
-    ScrollArea, TextEdit, ThemePreference,
+    vec2, Button, Color32, ComboBox, CornerRadius, FontId, Frame, KeyboardShortcut, Layout, Margin,
+    RichText, ScrollArea, TextEdit, ThemePreference,
 };
 use egui_extras::{Size, StripBuilder};
-use enostr::NoteId;
+use enostr::{NoteId, Pubkey};
 use nostrdb::Transaction;
 use notedeck::{
-    tr,
-    ui::{is_narrow, richtext_small},
-    Images, JobsCache, LanguageIdentifier, Localization, NoteContext, NotedeckTextStyle, Settings,
-    SettingsHandler, DEFAULT_NOTE_BODY_FONT_SIZE,
+    media::upload::UploadServer,
+    shortcuts, tr, tr_plural,
+    ui::{is_compiled_as_mobile, is_narrow, richtext_small},
+    zoom, Accent, ImageProxy, Images, JobsCache, LanguageIdentifier, Localization, NoteContext,
+    NotedeckTextStyle, Settings, SettingsHandler, Translations, Translator,
+    DEFAULT_NOTE_BODY_FONT_SIZE,
 };
 use notedeck_ui::{
     app_images::{copy_to_clipboard_dark_image, copy_to_clipboard_image},
-    AnimationHelper, NoteOptions, NoteView,
+    format_size, AnimationHelper, DatePicker, InfoIcon, NoteOptions, NoteView,
 };
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+use std::sync::{Arc, Mutex};
+use strum::IntoEnumIterator;
 
 use crate::{
     nav::{BodyResponse, RouterAction},
@@ -24,20 +29,51 @@ use crate::{
 
 const PREVIEW_NOTE_ID: &str = "note1edjc8ggj07hwv77g2405uh6j2jkk5aud22gktxrvc2wnre4vdwgqzlv2gw";
 
-const MIN_ZOOM: f32 = 0.5;
-const MAX_ZOOM: f32 = 3.0;
-const ZOOM_STEP: f32 = 0.1;
-const RESET_ZOOM: f32 = 1.0;
+/// Media cache limits offered in the storage section, in MB. 0 is no limit.
+const MEDIA_CACHE_LIMITS_MB: [u64; 6] = [256, 512, 1024, 2048, 5120, 0];
+
+/// GPU memory budgets for loaded media offered in the storage section, in MB
+const TEXTURE_BUDGETS_MB: [u64; 4] = [256, 512, 1024, 2048];
+
+/// Longest image edges offered in the storage section, in pixels
+const MAX_IMAGE_LENGTHS: [u32; 3] = [1024, 2048, 4096];
+
+/// Where the backup being exported is at, set from its thread
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+enum BackupStatus {
+    Saving,
+    Saved,
+    Failed,
+}
 
 pub enum SettingsAction {
     SetZoomFactor(f32),
     SetTheme(ThemePreference),
+    SetAccent(Accent),
+    SetHighContrast(bool),
+    /// Bind a shortcut to other keys, or back to its default with `None`
+    SetShortcut(&'static str, Option<KeyboardShortcut>),
     SetLocale(LanguageIdentifier),
     SetRepliestNewestFirst(bool),
     SetNoteBodyFontSize(f32),
     OpenRelays,
+    OpenMuteList,
+    OpenDatabase,
     OpenCacheFolder,
     ClearCacheFolder,
+    SetMediaCacheLimit(u64),
+    SetTextureBudget(u64),
+    SetMaxImageLength(u32),
+    SetDataSaver(bool),
+    SetReduceMotion(bool),
+    SetShowSensitiveMedia(Pubkey, bool),
+    SetImageProxy(Option<String>),
+    /// Translation endpoint and api key
+    SetTranslation(Option<String>, Option<String>),
+    SetUploadServer(Pubkey, Option<UploadServer>),
+    SetStripExif(bool),
+    SetMinPow(u8),
+    SetAllAccounts(bool),
 }
 
 impl SettingsAction {
@@ -47,6 +83,7 @@ impl SettingsAction {
         settings: &'a mut SettingsHandler,
         i18n: &'a mut Localization,
         img_cache: &mut Images,
+        translations: &mut Translations,
         ctx: &egui::Context,
     ) -> Option<RouterAction> {
         let mut route_action: Option<RouterAction> = None;
@@ -55,6 +92,12 @@ impl SettingsAction {
             Self::OpenRelays => {
                 route_action = Some(RouterAction::route_to(Route::Relays));
             }
+            Self::OpenMuteList => {
+                route_action = Some(RouterAction::route_to(Route::MuteList));
+            }
+            Self::OpenDatabase => {
+                route_action = Some(RouterAction::route_to(Route::Database));
+            }
             Self::SetZoomFactor(zoom_factor) => {
                 ctx.set_zoom_factor(zoom_factor);
                 settings.set_zoom_factor(zoom_factor);
@@ -63,6 +106,18 @@ impl SettingsAction {
                 ctx.set_theme(theme);
                 settings.set_theme(theme);
             }
+            Self::SetAccent(accent) => {
+                settings.set_accent(accent);
+                notedeck::theme::set_theme_options(ctx, settings.theme_options());
+            }
+            Self::SetHighContrast(value) => {
+                settings.set_high_contrast(value);
+                notedeck::theme::set_theme_options(ctx, settings.theme_options());
+            }
+            Self::SetShortcut(id, binding) => {
+                shortcuts::set_binding(ctx, id, binding);
+                settings.set_shortcut(id, binding);
+            }
             Self::SetLocale(language) => {
                 if i18n.set_locale(language.clone()).is_ok() {
                     settings.set_locale(language.to_string());
@@ -79,6 +134,51 @@ impl SettingsAction {
             Self::ClearCacheFolder => {
                 let _ = img_cache.clear_folder_contents();
             }
+            Self::SetMediaCacheLimit(limit_mb) => {
+                img_cache.set_cache_limit(limit_mb * 1024 * 1024);
+                settings.set_media_cache_limit_mb(limit_mb);
+            }
+            Self::SetTextureBudget(budget_mb) => {
+                img_cache.set_texture_budget(budget_mb as usize * 1024 * 1024);
+                settings.set_texture_budget_mb(budget_mb);
+            }
+            Self::SetMaxImageLength(length) => {
+                notedeck::media::images::set_max_image_length(length);
+                settings.set_max_image_length(length);
+            }
+            Self::SetDataSaver(value) => {
+                app.note_options.set(NoteOptions::DataSaver, value);
+                settings.set_data_saver(value);
+            }
+            Self::SetReduceMotion(value) => {
+                app.note_options.set(NoteOptions::NoAnimations, value);
+                settings.set_reduce_motion(value);
+                notedeck::motion::apply(ctx, value);
+            }
+            Self::SetAllAccounts(value) => {
+                settings.set_all_accounts(value);
+            }
+            Self::SetShowSensitiveMedia(account, value) => {
+                app.note_options.set(NoteOptions::ShowSensitiveMedia, value);
+                settings.set_show_sensitive_media(&account, value);
+            }
+            Self::SetUploadServer(account, server) => {
+                settings.set_upload_server(&account, server);
+            }
+            Self::SetStripExif(value) => {
+                settings.set_strip_exif(value);
+            }
+            Self::SetMinPow(difficulty) => {
+                settings.set_min_pow(difficulty);
+            }
+            Self::SetImageProxy(template) => {
+                notedeck::set_image_proxy(template.as_deref().and_then(ImageProxy::new));
+                settings.set_image_proxy(template);
+            }
+            Self::SetTranslation(endpoint, api_key) => {
+                settings.set_translation(endpoint, api_key);
+                translations.set_translator(settings.translator());
+            }
             Self::SetNoteBodyFontSize(size) => {
                 let mut style = (*ctx.style()).clone();
                 style.text_styles.insert(
@@ -101,6 +201,32 @@ pub struct SettingsView<'a> {
     jobs: &'a mut JobsCache,
 }
 
+fn accent_name(i18n: &mut Localization, accent: Accent) -> String {
+    match accent {
+        Accent::Purple => tr!(i18n, "Purple", "Accent color name"),
+        Accent::Blue => tr!(i18n, "Blue", "Accent color name"),
+        Accent::Green => tr!(i18n, "Green", "Accent color name"),
+        Accent::Orange => tr!(i18n, "Orange", "Accent color name"),
+        Accent::Pink => tr!(i18n, "Pink", "Accent color name"),
+        Accent::Teal => tr!(i18n, "Teal", "Accent color name"),
+    }
+}
+
+fn accent_swatch(ui: &mut egui::Ui, accent: Accent, selected: bool) -> egui::Response {
+    let size = 20.0;
+    let (rect, resp) = ui.allocate_exact_size(vec2(size, size), egui::Sense::click());
+    let painter = ui.painter();
+    painter.circle_filled(rect.center(), size / 2.0 - 2.0, accent.color());
+    if selected {
+        painter.circle_stroke(
+            rect.center(),
+            size / 2.0,
+            egui::Stroke::new(2.0, ui.visuals().text_color()),
+        );
+    }
+    resp.on_hover_cursor(egui::CursorIcon::PointingHand)
+}
+
 fn settings_group<S>(ui: &mut egui::Ui, title: S, contents: impl FnOnce(&mut egui::Ui))
 where
     S: Into<String>,
@@ -163,6 +289,12 @@ impl<'a> SettingsView<'a> {
                     "Label for font size, Appearance settings section",
                 )));
 
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Size of the text in note bodies",
+                    "Tooltip for font size, Appearance settings section",
+                )));
+
                 if ui
                     .add(
                         egui::Slider::new(&mut self.settings.note_body_font_size, 8.0..=32.0)
@@ -223,8 +355,14 @@ impl<'a> SettingsView<'a> {
                     "Label for zoom level, Appearance settings section",
                 )));
 
-                let min_reached = current_zoom <= MIN_ZOOM;
-                let max_reached = current_zoom >= MAX_ZOOM;
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Scales the whole interface, including text, icons and media",
+                    "Tooltip for zoom level, Appearance settings section",
+                )));
+
+                let min_reached = current_zoom <= zoom::MIN_ZOOM;
+                let max_reached = current_zoom >= zoom::MAX_ZOOM;
 
                 if ui
                     .add_enabled(
@@ -235,14 +373,36 @@ impl<'a> SettingsView<'a> {
                     )
                     .clicked()
                 {
-                    let new_zoom = (current_zoom - ZOOM_STEP).max(MIN_ZOOM);
-                    action = Some(SettingsAction::SetZoomFactor(new_zoom));
+                    action = Some(SettingsAction::SetZoomFactor(zoom::stepped(
+                        current_zoom,
+                        -1,
+                    )));
                 };
 
-                ui.label(
-                    RichText::new(format!("{:.0}%", current_zoom * 100.0))
-                        .text_style(NotedeckTextStyle::Small.text_style()),
+                // zooming while dragging moves the slider under the
+                // pointer, so only apply it once the drag is done
+                let id = ui.id().with("zoom_slider");
+                let mut dragged_zoom = ui.data(|d| d.get_temp::<f32>(id)).unwrap_or(current_zoom);
+                let slider = ui.add(
+                    egui::Slider::new(&mut dragged_zoom, zoom::MIN_ZOOM..=zoom::MAX_ZOOM)
+                        .step_by(zoom::ZOOM_STEP as f64)
+                        .custom_formatter(|n, _| format!("{:.0}%", n * 100.0))
+                        .custom_parser(|s| {
+                            s.trim_end_matches('%')
+                                .trim()
+                                .parse::<f64>()
+                                .ok()
+                                .map(|n| n / 100.0)
+                        }),
                 );
+                if slider.dragged() {
+                    ui.data_mut(|d| d.insert_temp(id, dragged_zoom));
+                } else {
+                    ui.data_mut(|d| d.remove::<f32>(id));
+                    if slider.changed() || slider.drag_stopped() {
+                        action = Some(SettingsAction::SetZoomFactor(dragged_zoom));
+                    }
+                }
 
                 if ui
                     .add_enabled(
@@ -253,8 +413,10 @@ impl<'a> SettingsView<'a> {
                     )
                     .clicked()
                 {
-                    let new_zoom = (current_zoom + ZOOM_STEP).min(MAX_ZOOM);
-                    action = Some(SettingsAction::SetZoomFactor(new_zoom));
+                    action = Some(SettingsAction::SetZoomFactor(zoom::stepped(
+                        current_zoom,
+                        1,
+                    )));
                 };
 
                 if ui
@@ -265,7 +427,7 @@ impl<'a> SettingsView<'a> {
                     )))
                     .clicked()
                 {
-                    action = Some(SettingsAction::SetZoomFactor(RESET_ZOOM));
+                    action = Some(SettingsAction::SetZoomFactor(zoom::RESET_ZOOM));
                 }
             });
 
@@ -334,6 +496,48 @@ impl<'a> SettingsView<'a> {
                     action = Some(SettingsAction::SetTheme(ThemePreference::Dark));
                 }
             });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Accent:",
+                    "Label for accent color, Appearance settings section",
+                )));
+
+                for accent in Accent::iter() {
+                    let selected = self.settings.accent == accent;
+                    if accent_swatch(ui, accent, selected)
+                        .on_hover_text(accent_name(self.note_context.i18n, accent))
+                        .clicked()
+                    {
+                        self.settings.accent = accent;
+                        action = Some(SettingsAction::SetAccent(accent));
+                    }
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "High contrast:",
+                    "Label for high contrast mode, Appearance settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.high_contrast,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to turn on high contrast mode"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetHighContrast(self.settings.high_contrast));
+                }
+            });
         });
 
         action
@@ -376,6 +580,158 @@ impl<'a> SettingsView<'a> {
                     .text_style(NotedeckTextStyle::Small.text_style()),
                 );
 
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Images and gifs are cached on disk so they load quickly next time",
+                    "Tooltip for image cache size, Storage settings section",
+                )));
+
+                ui.end_row();
+
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Cache limit:",
+                    "Label for media cache limit, Storage settings section"
+                )));
+
+                let unlimited = tr!(
+                    self.note_context.i18n,
+                    "No limit",
+                    "Option for no media cache limit, Storage settings section"
+                );
+                let limit_label = |limit_mb: u64| {
+                    if limit_mb == 0 {
+                        unlimited.clone()
+                    } else {
+                        format_size(limit_mb * 1024 * 1024)
+                    }
+                };
+
+                ComboBox::from_id_salt(id.with("media_cache_limit"))
+                    .selected_text(limit_label(self.settings.media_cache_limit_mb))
+                    .show_ui(ui, |ui| {
+                        for limit_mb in MEDIA_CACHE_LIMITS_MB {
+                            if ui
+                                .selectable_value(
+                                    &mut self.settings.media_cache_limit_mb,
+                                    limit_mb,
+                                    limit_label(limit_mb),
+                                )
+                                .clicked()
+                            {
+                                action = Some(SettingsAction::SetMediaCacheLimit(limit_mb));
+                            }
+                        }
+                    });
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "When the cache grows past this size, the media you haven't viewed in the longest time is removed",
+                    "Tooltip for media cache limit, Storage settings section",
+                )));
+
+                ui.end_row();
+
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Memory limit:",
+                    "Label for loaded media memory limit, Storage settings section"
+                )));
+
+                ComboBox::from_id_salt(id.with("texture_budget"))
+                    .selected_text(format_size(self.settings.texture_budget_mb * 1024 * 1024))
+                    .show_ui(ui, |ui| {
+                        for budget_mb in TEXTURE_BUDGETS_MB {
+                            if ui
+                                .selectable_value(
+                                    &mut self.settings.texture_budget_mb,
+                                    budget_mb,
+                                    format_size(budget_mb * 1024 * 1024),
+                                )
+                                .clicked()
+                            {
+                                action = Some(SettingsAction::SetTextureBudget(budget_mb));
+                            }
+                        }
+                    });
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Media that went off screen the longest time ago is unloaded when loaded media uses more memory than this",
+                    "Tooltip for loaded media memory limit, Storage settings section",
+                )));
+
+                ui.end_row();
+
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Image size:",
+                    "Label for maximum image size, Storage settings section"
+                )));
+
+                ComboBox::from_id_salt(id.with("max_image_length"))
+                    .selected_text(format!("{} px", self.settings.max_image_length))
+                    .show_ui(ui, |ui| {
+                        for length in MAX_IMAGE_LENGTHS {
+                            if ui
+                                .selectable_value(
+                                    &mut self.settings.max_image_length,
+                                    length,
+                                    format!("{length} px"),
+                                )
+                                .clicked()
+                            {
+                                action = Some(SettingsAction::SetMaxImageLength(length));
+                            }
+                        }
+                    });
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Larger images are scaled down to this size on their longest edge. Applies to newly loaded media",
+                    "Tooltip for maximum image size, Storage settings section",
+                )));
+
+                ui.end_row();
+
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Image proxy:",
+                    "Label for image proxy, Storage settings section"
+                )));
+
+                // edit a copy so we only apply the proxy once editing is done
+                let proxy_id = id.with("image_proxy");
+                let mut template = ui
+                    .data(|d| d.get_temp::<String>(proxy_id))
+                    .unwrap_or_else(|| self.settings.image_proxy.clone().unwrap_or_default());
+
+                let resp = ui.add(
+                    TextEdit::singleline(&mut template)
+                        .hint_text("https://imgproxy.example.com/rs:fit:{width}/plain/{url}")
+                        .desired_width(220.0),
+                );
+
+                if resp.lost_focus() {
+                    ui.data_mut(|d| d.remove::<String>(proxy_id));
+                    let template = Some(template.trim().to_owned()).filter(|t| !t.is_empty());
+                    let valid = template
+                        .as_deref()
+                        .is_none_or(|t| ImageProxy::new(t).is_some());
+                    if valid && template != self.settings.image_proxy {
+                        self.settings.image_proxy = template.clone();
+                        action = Some(SettingsAction::SetImageProxy(template));
+                    }
+                } else if resp.has_focus() {
+                    ui.data_mut(|d| d.insert_temp(proxy_id, template));
+                }
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Fetch smaller versions of images through a resizing service like imgproxy or a Blossom server. Use width and url in curly braces where the requested width and the original address go",
+                    "Tooltip for image proxy, Storage settings section",
+                )));
+
                 ui.end_row();
 
                 if !notedeck::ui::is_compiled_as_mobile()
@@ -436,12 +792,27 @@ impl<'a> SettingsView<'a> {
                     }
                 };
             });
+
+            if ui
+                .add_sized(
+                    [ui.available_width(), 30.0],
+                    Button::new(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Database storage and pruning",
+                        "Label for the database button, Storage settings section",
+                    ))),
+                )
+                .clicked()
+            {
+                action = Some(SettingsAction::OpenDatabase);
+            }
         });
 
         action
     }
 
     fn other_options_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let id = ui.id();
         let mut action = None;
 
         let title = tr!(
@@ -457,6 +828,12 @@ impl<'a> SettingsView<'a> {
                     "Label for Sort replies newest first, others settings section",
                 )));
 
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Show the most recent replies at the top of threads",
+                    "Tooltip for Sort replies newest first, others settings section",
+                )));
+
                 if ui
                     .toggle_value(
                         &mut self.settings.show_replies_newest_first,
@@ -474,6 +851,348 @@ impl<'a> SettingsView<'a> {
                     ));
                 }
             });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Data saver:",
+                    "Label for data saver, others settings section",
+                )));
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Only load images when you tap them and don't play gifs, even from people you follow",
+                    "Tooltip for data saver, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.data_saver,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to turn on data saver mode"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetDataSaver(self.settings.data_saver));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Reduce motion:",
+                    "Label for reduce motion, others settings section",
+                )));
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Turn off animations and show gifs and animated avatars as still images. Also on when your system asks to reduce motion.",
+                    "Tooltip for reduce motion, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.reduce_motion,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to turn on reduce motion"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetReduceMotion(self.settings.reduce_motion));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "All accounts:",
+                    "Label for all accounts mode, others settings section",
+                )));
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Get notifications for every account you're logged into, and pick which account to use when posting",
+                    "Tooltip for all accounts mode, others settings section",
+                )));
+
+                if ui
+                    .toggle_value(
+                        &mut self.settings.all_accounts,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to turn on all accounts mode"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetAllAccounts(self.settings.all_accounts));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Always show sensitive media:",
+                    "Label for always show sensitive media, others settings section",
+                )));
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Show media marked with a content warning without tapping it first. This only applies to the current account",
+                    "Tooltip for always show sensitive media, others settings section",
+                )));
+
+                let account = *self.note_context.accounts.selected_account_pubkey();
+                let mut show = self.settings.shows_sensitive_media(&account);
+                if ui
+                    .toggle_value(
+                        &mut show,
+                        RichText::new(tr!(
+                            self.note_context.i18n,
+                            "On",
+                            "Setting to always show media marked as sensitive"
+                        ))
+                        .text_style(NotedeckTextStyle::Small.text_style()),
+                    )
+                    .changed()
+                {
+                    action = Some(SettingsAction::SetShowSensitiveMedia(account, show));
+                }
+            });
+
+            if let Some(upload_action) = self.upload_options(ui, id) {
+                action = Some(upload_action);
+            }
+
+            if let Some(translation_action) = self.translation_options(ui, id) {
+                action = Some(translation_action);
+            }
+        });
+
+        action
+    }
+
+    fn upload_options(&mut self, ui: &mut egui::Ui, id: egui::Id) -> Option<SettingsAction> {
+        let mut action = None;
+        let account = *self.note_context.accounts.selected_account_pubkey();
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Media server:",
+                "Label for media upload server, others settings section",
+            )));
+
+            ui.add(InfoIcon::new(tr!(
+                self.note_context.i18n,
+                "Where media you attach to notes is uploaded. Works with NIP-96 and Blossom servers and only applies to the current account",
+                "Tooltip for media upload server, others settings section",
+            )));
+
+            let current = self
+                .settings
+                .upload_servers
+                .get(&account.hex())
+                .cloned()
+                .unwrap_or_default();
+            let was_blossom = matches!(current, UploadServer::Blossom(_));
+            let mut blossom = was_blossom;
+
+            ComboBox::from_id_salt(id.with("upload_server_kind"))
+                .selected_text(if blossom { "Blossom" } else { "NIP-96" })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut blossom, false, "NIP-96");
+                    ui.selectable_value(&mut blossom, true, "Blossom");
+                });
+
+            // edit a copy so we only switch servers once editing is done
+            let url_id = id.with("upload_server_url");
+            let mut url = ui
+                .data(|d| d.get_temp::<String>(url_id))
+                .unwrap_or_else(|| current.url().to_owned());
+
+            let resp = ui.add(TextEdit::singleline(&mut url).desired_width(220.0));
+
+            let mut edited_url = None;
+            if resp.lost_focus() {
+                ui.data_mut(|d| d.remove::<String>(url_id));
+                if url.trim() != current.url() {
+                    edited_url = Some(url.trim().to_owned());
+                }
+            } else if resp.has_focus() {
+                ui.data_mut(|d| d.insert_temp(url_id, url));
+            }
+
+            if blossom == was_blossom && edited_url.is_none() {
+                return;
+            }
+
+            let url = edited_url.unwrap_or_else(|| current.url().to_owned());
+            let server = if url.is_empty() {
+                None
+            } else if url::Url::parse(&url).is_err() {
+                return;
+            } else if blossom {
+                Some(UploadServer::Blossom(url))
+            } else {
+                Some(UploadServer::Nip96(url))
+            };
+
+            match &server {
+                Some(server) => self
+                    .settings
+                    .upload_servers
+                    .insert(account.hex(), server.clone()),
+                None => self.settings.upload_servers.remove(&account.hex()),
+            };
+            action = Some(SettingsAction::SetUploadServer(account, server));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Remove location from photos:",
+                "Label for stripping photo metadata, others settings section",
+            )));
+
+            ui.add(InfoIcon::new(tr!(
+                self.note_context.i18n,
+                "Re-save photos before uploading them so camera details like location aren't published",
+                "Tooltip for stripping photo metadata, others settings section",
+            )));
+
+            if ui
+                .toggle_value(
+                    &mut self.settings.strip_exif,
+                    RichText::new(tr!(
+                        self.note_context.i18n,
+                        "On",
+                        "Setting to remove metadata from photos before uploading"
+                    ))
+                    .text_style(NotedeckTextStyle::Small.text_style()),
+                )
+                .changed()
+            {
+                action = Some(SettingsAction::SetStripExif(self.settings.strip_exif));
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Minimum proof of work:",
+                "Label for the proof of work spam filter, others settings section",
+            )));
+
+            ui.add(InfoIcon::new(tr!(
+                self.note_context.i18n,
+                "Hide notes from people you don't follow unless their id starts with this many zero bits (NIP-13). 0 shows everything",
+                "Tooltip for the proof of work spam filter, others settings section",
+            )));
+
+            if ui
+                .add(egui::Slider::new(&mut self.settings.min_pow, 0..=32).text(""))
+                .changed()
+            {
+                action = Some(SettingsAction::SetMinPow(self.settings.min_pow));
+            }
+        });
+
+        action
+    }
+
+    fn translation_options(&mut self, ui: &mut egui::Ui, id: egui::Id) -> Option<SettingsAction> {
+        let mut action = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(richtext_small(tr!(
+                self.note_context.i18n,
+                "Translation service:",
+                "Label for the translation service, others settings section",
+            )));
+
+            ui.add(InfoIcon::new(tr!(
+                self.note_context.i18n,
+                "A LibreTranslate compatible address notes are translated with, running on your computer or hosted. Leave empty to hide the Translate button",
+                "Tooltip for the translation service, others settings section",
+            )));
+
+            // edit copies so we only apply them once editing is done
+            let endpoint_id = id.with("translation_endpoint");
+            let mut endpoint = ui
+                .data(|d| d.get_temp::<String>(endpoint_id))
+                .unwrap_or_else(|| {
+                    self.settings
+                        .translation_endpoint
+                        .clone()
+                        .unwrap_or_default()
+                });
+
+            let endpoint_resp = ui.add(
+                TextEdit::singleline(&mut endpoint)
+                    .hint_text("http://localhost:5000/translate")
+                    .desired_width(220.0),
+            );
+
+            let key_id = id.with("translation_api_key");
+            let mut api_key = ui
+                .data(|d| d.get_temp::<String>(key_id))
+                .unwrap_or_else(|| self.settings.translation_api_key.clone().unwrap_or_default());
+
+            let key_resp = ui.add(
+                TextEdit::singleline(&mut api_key)
+                    .hint_text(tr!(
+                        self.note_context.i18n,
+                        "API key (optional)",
+                        "Hint for the translation service api key, others settings section",
+                    ))
+                    .password(true)
+                    .desired_width(140.0),
+            )
+            .on_hover_text(tr!(
+                self.note_context.i18n,
+                "The API key is stored unencrypted in your settings file",
+                "Tooltip for the translation service api key, others settings section",
+            ));
+
+            for (resp, temp_id, text) in [
+                (&endpoint_resp, endpoint_id, &endpoint),
+                (&key_resp, key_id, &api_key),
+            ] {
+                if resp.lost_focus() {
+                    ui.data_mut(|d| d.remove::<String>(temp_id));
+                } else if resp.has_focus() {
+                    ui.data_mut(|d| d.insert_temp(temp_id, text.clone()));
+                }
+            }
+
+            if !endpoint_resp.lost_focus() && !key_resp.lost_focus() {
+                return;
+            }
+
+            let endpoint = Some(endpoint.trim().to_owned()).filter(|e| !e.is_empty());
+            let api_key = Some(api_key.trim().to_owned()).filter(|k| !k.is_empty());
+            let valid = endpoint
+                .as_deref()
+                .is_none_or(|e| Translator::new(e, None).is_some());
+            if valid
+                && (endpoint != self.settings.translation_endpoint
+                    || api_key != self.settings.translation_api_key)
+            {
+                self.settings.translation_endpoint = endpoint.clone();
+                self.settings.translation_api_key = api_key.clone();
+                action = Some(SettingsAction::SetTranslation(endpoint, api_key));
+            }
         });
 
         action
@@ -562,6 +1281,12 @@ impl<'a> SettingsView<'a> {
                     ))
                     .color(ui.visuals().gray_out(ui.visuals().text_color())),
                 );
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Anyone with this key can post as you. Never share it.",
+                    "Tooltip warning about sharing the secret key"
+                )));
             });
 
             let is_password_id = ui.id().with("is-password");
@@ -622,6 +1347,360 @@ impl<'a> SettingsView<'a> {
         });
     }
 
+    /// Save keys, relay and follow lists and settings to a file that can be
+    /// restored from the login screen
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn backup_section(&mut self, ui: &mut egui::Ui) {
+        let title = tr!(
+            self.note_context.i18n,
+            "Backup",
+            "Label for backup settings section"
+        );
+
+        settings_group(ui, title, |ui| {
+            let id = ui.id().with("backup");
+            let passphrase_id = id.with("passphrase");
+            let confirm_id = id.with("confirm");
+            let status_id = id.with("status");
+
+            let mut passphrase = ui
+                .data(|d| d.get_temp::<String>(passphrase_id))
+                .unwrap_or_default();
+            let mut confirm = ui
+                .data(|d| d.get_temp::<String>(confirm_id))
+                .unwrap_or_default();
+            let status = ui.data(|d| d.get_temp::<Arc<Mutex<BackupStatus>>>(status_id));
+            let saving = status
+                .as_ref()
+                .is_some_and(|s| matches!(*s.lock().unwrap(), BackupStatus::Saving));
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Passphrase:",
+                    "Label for the backup passphrase, Backup settings section"
+                )));
+
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Your secret keys are encrypted with this passphrase (NIP-49). You need it to restore the backup, and it can't be recovered",
+                    "Tooltip for the backup passphrase, Backup settings section"
+                )));
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut passphrase)
+                            .password(true)
+                            .desired_width(180.0),
+                    )
+                    .changed()
+                {
+                    ui.data_mut(|d| d.insert_temp(passphrase_id, passphrase.clone()));
+                }
+            });
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Confirm passphrase:",
+                    "Label for repeating the backup passphrase, Backup settings section"
+                )));
+
+                if ui
+                    .add(
+                        TextEdit::singleline(&mut confirm)
+                            .password(true)
+                            .desired_width(180.0),
+                    )
+                    .changed()
+                {
+                    ui.data_mut(|d| d.insert_temp(confirm_id, confirm.clone()));
+                }
+
+                let matches = passphrase == confirm;
+                let export = ui.add_enabled(
+                    !passphrase.is_empty() && matches && !saving,
+                    Button::new(richtext_small(tr!(
+                        self.note_context.i18n,
+                        "Export",
+                        "Button to save a backup file, Backup settings section"
+                    ))),
+                );
+
+                if !confirm.is_empty() && !matches {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        richtext_small(tr!(
+                            self.note_context.i18n,
+                            "Passphrases don't match",
+                            "Shown when the backup passphrase and its confirmation differ"
+                        )),
+                    );
+                }
+
+                if !export.clicked() {
+                    return;
+                }
+
+                let Some(path) = rfd::FileDialog::new()
+                    .set_file_name("notedeck-backup.json")
+                    .add_filter("notedeck backup", &["json"])
+                    .save_file()
+                else {
+                    return;
+                };
+
+                ui.data_mut(|d| {
+                    d.remove::<String>(passphrase_id);
+                    d.remove::<String>(confirm_id);
+                });
+
+                // encrypting the keys takes a moment on purpose, keep it
+                // off the UI thread
+                let contents = notedeck::BackupContents::new(
+                    self.note_context.accounts,
+                    self.note_context.ndb,
+                    self.settings.clone(),
+                );
+                let status = Arc::new(Mutex::new(BackupStatus::Saving));
+                ui.data_mut(|d| d.insert_temp(status_id, status.clone()));
+
+                let ctx = ui.ctx().clone();
+                std::thread::spawn(move || {
+                    let saved = contents
+                        .encrypt(&passphrase)
+                        .map_err(|e| e.to_string())
+                        .and_then(|backup| backup.to_json().map_err(|e| e.to_string()))
+                        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+
+                    *status.lock().unwrap() = match saved {
+                        Ok(()) => BackupStatus::Saved,
+                        Err(err) => {
+                            tracing::error!("could not save backup: {err}");
+                            BackupStatus::Failed
+                        }
+                    };
+                    ctx.request_repaint();
+                });
+            });
+
+            let Some(status) = status else {
+                return;
+            };
+
+            let status = match *status.lock().unwrap() {
+                BackupStatus::Saving => {
+                    ui.add(egui::Spinner::new());
+                    return;
+                }
+                BackupStatus::Saved => tr!(
+                    self.note_context.i18n,
+                    "Backup saved",
+                    "Shown after a backup file was saved"
+                ),
+                BackupStatus::Failed => tr!(
+                    self.note_context.i18n,
+                    "Could not save the backup",
+                    "Shown when a backup file could not be saved"
+                ),
+            };
+            ui.label(richtext_small(status));
+        });
+    }
+
+    /// Write the selected account's events over a date range to a JSON
+    /// lines file
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    fn export_section(&mut self, ui: &mut egui::Ui) {
+        let title = tr!(
+            self.note_context.i18n,
+            "Export",
+            "Label for the event export settings section"
+        );
+
+        settings_group(ui, title, |ui| {
+            let id = ui.id().with("export");
+            let options_id = id.with("options");
+            let status_id = id.with("status");
+
+            let mut export = ui
+                .data(|d| d.get_temp::<notedeck::EventExport>(options_id))
+                .unwrap_or_default();
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "From:",
+                    "Label for the first day to export, Export settings section"
+                )));
+                DatePicker::new(id.with("from"), &mut export.from, self.note_context.i18n).show(ui);
+
+                ui.label(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "To:",
+                    "Label for the last day to export, Export settings section"
+                )));
+                DatePicker::new(id.with("to"), &mut export.to, self.note_context.i18n).show(ui);
+            });
+
+            ui.checkbox(
+                &mut export.calendar_invites,
+                richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Include calendar events I was invited to",
+                    "Checkbox to also export calendar events that tag the account, Export settings section"
+                )),
+            );
+
+            ui.horizontal_wrapped(|ui| {
+                ui.add(InfoIcon::new(tr!(
+                    self.note_context.i18n,
+                    "Saves every event this account published in the range, one JSON event per line. Only events already on this device are exported",
+                    "Tooltip for the export button, Export settings section"
+                )));
+
+                let valid_range = export.from <= export.to;
+                let clicked = ui
+                    .add_enabled(
+                        valid_range,
+                        Button::new(richtext_small(tr!(
+                            self.note_context.i18n,
+                            "Export events",
+                            "Button to save an event export file, Export settings section"
+                        ))),
+                    )
+                    .clicked();
+
+                if !clicked {
+                    return;
+                }
+
+                let Some(path) = rfd::FileDialog::new()
+                    .set_file_name("notedeck-events.jsonl")
+                    .add_filter("JSON lines", &["jsonl"])
+                    .save_file()
+                else {
+                    return;
+                };
+
+                let author = *self.note_context.accounts.selected_account_pubkey();
+                let written = std::fs::File::create(&path).and_then(|file| {
+                    let mut out = std::io::BufWriter::new(file);
+                    export.write(self.note_context.ndb, &author, &mut out)
+                });
+
+                let status = match written {
+                    Ok(count) => tr_plural!(
+                        self.note_context.i18n,
+                        "Exported {count} event",
+                        "Exported {count} events",
+                        "Shown after events were exported to a file",
+                        count
+                    ),
+                    Err(err) => {
+                        tracing::error!("could not export events: {err}");
+                        tr!(
+                            self.note_context.i18n,
+                            "Could not export events",
+                            "Shown when the event export file could not be saved"
+                        )
+                    }
+                };
+                ui.data_mut(|d| d.insert_temp(status_id, status));
+            });
+
+            ui.data_mut(|d| d.insert_temp(options_id, export));
+
+            if let Some(status) = ui.data(|d| d.get_temp::<String>(status_id)) {
+                ui.label(richtext_small(status));
+            }
+        });
+    }
+
+    fn shortcuts_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
+        let mut action = None;
+        let recording_id = ui.id().with("recording-shortcut");
+        let recording = ui
+            .data(|d| d.get_temp::<Option<&'static str>>(recording_id))
+            .flatten();
+
+        let title = tr!(
+            self.note_context.i18n,
+            "Keyboard shortcuts",
+            "Label for keyboard shortcuts settings section"
+        );
+        settings_group(ui, title, |ui| {
+            let ctx = ui.ctx().clone();
+            for shortcut in shortcuts::registered(&ctx) {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(richtext_small((shortcut.description)(
+                        self.note_context.i18n,
+                    )));
+
+                    let is_recording = recording == Some(shortcut.id);
+                    let binding = shortcuts::binding(&ctx, &shortcut);
+                    let label = if is_recording {
+                        tr!(
+                            self.note_context.i18n,
+                            "Press keys…",
+                            "Shown while waiting for the new keys of a shortcut"
+                        )
+                    } else {
+                        ctx.format_shortcut(&binding)
+                    };
+
+                    if ui
+                        .selectable_label(is_recording, richtext_small(label))
+                        .clicked()
+                    {
+                        let recording = (!is_recording).then_some(shortcut.id);
+                        ui.data_mut(|d| d.insert_temp(recording_id, recording));
+                    }
+
+                    if binding != shortcut.default
+                        && ui
+                            .small_button(tr!(
+                                self.note_context.i18n,
+                                "Reset",
+                                "Button to put a keyboard shortcut back to its default keys"
+                            ))
+                            .clicked()
+                    {
+                        action = Some(SettingsAction::SetShortcut(shortcut.id, None));
+                    }
+
+                    let conflicts = shortcuts::conflicts(&ctx, &shortcut);
+                    if let Some(other) = conflicts.first() {
+                        let other = (other.description)(self.note_context.i18n);
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            richtext_small(tr!(
+                                self.note_context.i18n,
+                                "Also used by {other}",
+                                "Warning that two keyboard shortcuts use the same keys",
+                                other = other
+                            )),
+                        );
+                    }
+
+                    if is_recording {
+                        if let Some(new_binding) = record_shortcut(ui) {
+                            ui.data_mut(|d| d.remove::<Option<&'static str>>(recording_id));
+                            if let Some(new_binding) = new_binding {
+                                action = Some(SettingsAction::SetShortcut(
+                                    shortcut.id,
+                                    Some(new_binding),
+                                ));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        action
+    }
+
     fn manage_relays_section(&mut self, ui: &mut egui::Ui) -> Option<SettingsAction> {
         let mut action = None;
 
@@ -639,6 +1718,20 @@ impl<'a> SettingsView<'a> {
             action = Some(SettingsAction::OpenRelays);
         }
 
+        if ui
+            .add_sized(
+                [ui.available_width(), 30.0],
+                Button::new(richtext_small(tr!(
+                    self.note_context.i18n,
+                    "Muted users, words and hashtags",
+                    "Label for the mute list button, settings section",
+                ))),
+            )
+            .clicked()
+        {
+            action = Some(SettingsAction::OpenMuteList);
+        }
+
         action
     }
 
@@ -662,12 +1755,29 @@ impl<'a> SettingsView<'a> {
 
                     self.keys_section(ui);
 
+                    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+                    {
+                        ui.add_space(5.0);
+                        self.backup_section(ui);
+
+                        ui.add_space(5.0);
+                        self.export_section(ui);
+                    }
+
                     ui.add_space(5.0);
 
                     if let Some(new_action) = self.other_options_section(ui) {
                         action = Some(new_action);
                     }
 
+                    if !is_compiled_as_mobile() {
+                        ui.add_space(5.0);
+
+                        if let Some(new_action) = self.shortcuts_section(ui) {
+                            action = Some(new_action);
+                        }
+                    }
+
                     ui.add_space(10.0);
 
                     if let Some(new_action) = self.manage_relays_section(ui) {
@@ -682,22 +1792,27 @@ impl<'a> SettingsView<'a> {
     }
 }
 
-pub fn format_size(size_bytes: u64) -> String {
-    const KB: f64 = 1024.0;
-    const MB: f64 = KB * 1024.0;
-    const GB: f64 = MB * 1024.0;
-
-    let size = size_bytes as f64;
-
-    if size < KB {
-        format!("{size:.0} Bytes")
-    } else if size < MB {
-        format!("{:.1} KB", size / KB)
-    } else if size < GB {
-        format!("{:.1} MB", size / MB)
-    } else {
-        format!("{:.2} GB", size / GB)
-    }
+/// The next key pressed, with its modifiers. `Some(None)` when recording
+/// was cancelled with Escape.
+fn record_shortcut(ui: &egui::Ui) -> Option<Option<KeyboardShortcut>> {
+    ui.input_mut(|i| {
+        let (key, modifiers) = i.events.iter().find_map(|event| match event {
+            egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+                ..
+            } => Some((*key, *modifiers)),
+            _ => None,
+        })?;
+
+        i.consume_key(modifiers, key);
+        if key == egui::Key::Escape {
+            return Some(None);
+        }
+
+        Some(Some(KeyboardShortcut::new(modifiers, key)))
+    })
 }
 
 fn item_frame(ui: &egui::Ui) -> egui::Frame {
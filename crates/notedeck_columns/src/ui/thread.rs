@@ -181,7 +181,15 @@ fn show_notes(
         .ok()
         .is_some_and(|root_id| is_muted(&note.note, root_id.bytes()));
 
-        if muted {
+        // the selected note was asked for, so it always shows
+        let spam = cur_index != selected_note_index
+            && notedeck::pow::below_min_pow(
+                &note.note,
+                note_context.accounts.get_selected_account(),
+                note_context.min_pow,
+            );
+
+        if muted || spam {
             return 1;
         }
 
@@ -22,9 +22,13 @@ use notedeck::{
 };
 use notedeck_ui::{
     anim::{AnimationHelper, ICON_EXPANSION_MULTIPLE},
+    note::prefetch_note_media,
     NoteOptions, NoteView,
 };
 
+/// How many notes past the bottom of the view to prefetch media for
+const PREFETCH_AHEAD: usize = 3;
+
 pub struct TimelineView<'a, 'd> {
     timeline_id: &'a TimelineKind,
     timeline_cache: &'a mut TimelineCache,
@@ -338,7 +342,7 @@ pub fn tabs_ui(
         width: 2.0,
     };
 
-    let speed = 0.1f32;
+    let speed = notedeck::motion::anim_time(ui.ctx(), 0.1);
 
     // animate underline position
     let x = ui
@@ -404,6 +408,7 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
         let len = self.tab.units.len();
 
         let mute = self.note_context.accounts.mute();
+        let mut last_rendered: Option<usize> = None;
 
         self.tab
             .list
@@ -412,6 +417,7 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
                 // tracing::info!("rendering index: {index}");
                 ui.spacing_mut().item_spacing.y = 0.0;
                 ui.spacing_mut().item_spacing.x = 4.0;
+                last_rendered = Some(last_rendered.map_or(index, |last| last.max(index)));
 
                 let Some(entry) = self.tab.units.get(index) else {
                     return 0;
@@ -430,9 +436,33 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
                 1
             });
 
+        if let Some(last) = last_rendered {
+            self.prefetch_media(ui.ctx(), last + 1..(last + 1 + PREFETCH_AHEAD).min(len));
+        }
+
         action
     }
 
+    /// Get a head start on the media of notes that are about to scroll
+    /// into view
+    fn prefetch_media(&mut self, ctx: &egui::Context, range: std::ops::Range<usize>) {
+        for index in range {
+            let Some(entry) = self.tab.units.get(index) else {
+                break;
+            };
+
+            let Ok(note) = self
+                .note_context
+                .ndb
+                .get_note_by_key(self.txn, underlying_note_key(entry))
+            else {
+                continue;
+            };
+
+            prefetch_note_media(ctx, self.note_context, self.txn, &note, self.note_options);
+        }
+    }
+
     fn render_entry(
         &mut self,
         ui: &mut egui::Ui,
@@ -440,13 +470,7 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
         mute: &std::sync::Arc<Muted>,
     ) -> RenderEntryResponse {
         let underlying_note = {
-            let underlying_note_key = match entry {
-                NoteUnit::Single(note_ref) => note_ref.key,
-                NoteUnit::Composite(composite_unit) => match composite_unit {
-                    CompositeUnit::Reaction(reaction_unit) => reaction_unit.note_reacted_to.key,
-                    CompositeUnit::Repost(repost_unit) => repost_unit.note_reposted.key,
-                },
-            };
+            let underlying_note_key = underlying_note_key(entry);
 
             let Ok(note) = self
                 .note_context
@@ -468,7 +492,13 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
         )
         .is_ok_and(|root_id| mute.is_muted(&underlying_note, root_id.bytes()));
 
-        if muted {
+        if muted
+            || notedeck::pow::below_min_pow(
+                &underlying_note,
+                self.note_context.accounts.get_selected_account(),
+                self.note_context.min_pow,
+            )
+        {
             return RenderEntryResponse::Success(None);
         }
 
@@ -506,6 +536,17 @@ impl<'a, 'd> TimelineTabView<'a, 'd> {
     }
 }
 
+/// The note a timeline unit is about
+fn underlying_note_key(entry: &NoteUnit) -> nostrdb::NoteKey {
+    match entry {
+        NoteUnit::Single(note_ref) => note_ref.key,
+        NoteUnit::Composite(composite_unit) => match composite_unit {
+            CompositeUnit::Reaction(reaction_unit) => reaction_unit.note_reacted_to.key,
+            CompositeUnit::Repost(repost_unit) => repost_unit.note_reposted.key,
+        },
+    }
+}
+
 enum ReferencedNoteType {
     Tagged,
     Yours,
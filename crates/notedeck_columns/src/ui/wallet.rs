@@ -347,13 +347,18 @@ fn show_with_wallet(
     action
 }
 
-fn show_balance(ui: &mut egui::Ui, msats: u64) -> egui::Response {
+/// A wallet balance in sats, shortened like `12.5k sats`
+pub fn format_balance(msats: u64) -> String {
     let sats = human_format::Formatter::new()
         .with_decimals(2)
         .format(msats as f64 / 1000.0);
 
+    format!("{sats} sats")
+}
+
+fn show_balance(ui: &mut egui::Ui, msats: u64) -> egui::Response {
     ui.with_layout(Layout::top_down(egui::Align::Center), |ui| {
-        ui.label(egui::RichText::new(format!("{sats} sats")).size(48.0))
+        ui.label(egui::RichText::new(format_balance(msats)).size(48.0))
     })
     .inner
 }
@@ -5,6 +5,8 @@ use notedeck_ui::nip51_set::Nip51SetUiCache;
 
 use crate::deck_state::DeckState;
 use crate::login_manager::AcquireKeyState;
+use crate::ui::database::DatabaseViewState;
+use crate::ui::profile::edit::ProfileUploads;
 use crate::ui::search::SearchQueryState;
 use enostr::ProfileState;
 use notedeck_ui::media::MediaViewerState;
@@ -22,6 +24,9 @@ pub struct ViewState {
     pub searches: HashMap<egui::Id, SearchQueryState>,
     pub pubkey_to_profile_state: HashMap<Pubkey, ProfileState>,
 
+    /// Picture and banner uploads in the profile editor
+    pub profile_uploads: ProfileUploads,
+
     /// Keeps track of what urls we are actively viewing in the
     /// fullscreen media viewier, as well as any other state we want to
     /// keep track of
@@ -29,6 +34,12 @@ pub struct ViewState {
 
     /// Keep track of checkbox state of follow pack onboarding
     pub follow_packs: Nip51SetUiCache,
+
+    /// Database stats and the prune form
+    pub database: DatabaseViewState,
+
+    /// The account picked in the composer in all-accounts mode
+    pub post_as: Option<Pubkey>,
 }
 
 impl ViewState {
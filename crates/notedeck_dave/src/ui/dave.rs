@@ -223,6 +223,10 @@ impl<'a> DaveUi<'a> {
             clipboard: ctx.clipboard,
             i18n: ctx.i18n,
             global_wallet: ctx.global_wallet,
+            media_uploader: ctx.media_uploader,
+            publish_tracker: ctx.publish_tracker,
+            translations: ctx.translations,
+            min_pow: ctx.settings.min_pow(),
         };
 
         let txn = Transaction::new(note_context.ndb).unwrap();
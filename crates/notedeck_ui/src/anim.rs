@@ -1,4 +1,5 @@
 use egui::{vec2, Pos2, Rect, Response, Sense};
+use notedeck::motion;
 
 pub fn hover_expand(
     ui: &mut egui::Ui,
@@ -12,6 +13,7 @@ pub fn hover_expand(
     let (rect, response) =
         ui.allocate_exact_size(egui::vec2(default_size, default_size), egui::Sense::click());
 
+    let anim_speed = motion::anim_time(ui.ctx(), anim_speed);
     let val = ui
         .ctx()
         .animate_bool_with_time(id, response.hovered(), anim_speed);
@@ -52,9 +54,11 @@ impl AnimationHelper {
         let id = ui.id().with(animation_name);
         let (rect, response) = ui.allocate_exact_size(max_size, Sense::click());
 
-        let animation_progress =
-            ui.ctx()
-                .animate_bool_with_time(id, response.hovered(), ANIM_SPEED);
+        let animation_progress = ui.ctx().animate_bool_with_time(
+            id,
+            response.hovered(),
+            motion::anim_time(ui.ctx(), ANIM_SPEED),
+        );
 
         Self {
             rect,
@@ -85,9 +89,11 @@ impl AnimationHelper {
         let id = ui.id().with(animation_name);
         let response = ui.allocate_rect(animation_rect, Sense::click());
 
-        let animation_progress =
-            ui.ctx()
-                .animate_bool_with_time(id, response.hovered(), ANIM_SPEED);
+        let animation_progress = ui.ctx().animate_bool_with_time(
+            id,
+            response.hovered(),
+            motion::anim_time(ui.ctx(), ANIM_SPEED),
+        );
 
         Self {
             rect: animation_rect,
@@ -200,9 +206,11 @@ impl<'a> PulseAlpha<'a> {
             0.0
         };
 
-        let cur_val = self
-            .ctx
-            .animate_value_with_time(self.id, target, self.animation_speed);
+        let cur_val = self.ctx.animate_value_with_time(
+            self.id,
+            target,
+            motion::anim_time(self.ctx, self.animation_speed),
+        );
 
         if (target - cur_val).abs() < 0.5 {
             self.ctx
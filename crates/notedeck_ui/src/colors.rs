@@ -4,3 +4,10 @@ pub const ALMOST_WHITE: Color32 = Color32::from_rgb(0xFA, 0xFA, 0xFA);
 pub const MID_GRAY: Color32 = Color32::from_rgb(0xbd, 0xbd, 0xbd);
 pub const PINK: Color32 = Color32::from_rgb(0xE4, 0x5A, 0xC9);
 pub const TEAL: Color32 = Color32::from_rgb(0x77, 0xDC, 0xE1);
+
+/// A color to tell accounts apart by, the same for an account everywhere
+pub fn account_color(pubkey: &enostr::Pubkey) -> Color32 {
+    let bytes = pubkey.bytes();
+    let hue = u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32;
+    egui::ecolor::Hsva::new(hue, 0.6, 0.85, 1.0).into()
+}
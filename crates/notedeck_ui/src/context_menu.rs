@@ -72,6 +72,7 @@ pub fn context_button(ui: &mut egui::Ui, id: egui::Id, put_at: egui::Rect) -> eg
     let response = ui.interact(put_at, id, egui::Sense::click());
 
     let hovered = response.hovered();
+    let anim_speed = notedeck::motion::anim_time(ui.ctx(), anim_speed);
     let animation_progress = ui.ctx().animate_bool_with_time(id, hovered, anim_speed);
 
     if hovered {
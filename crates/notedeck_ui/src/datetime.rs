@@ -0,0 +1,453 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDate, NaiveTime, Timelike, Weekday};
+use egui::{vec2, Button, Key, KeyboardShortcut, Modifiers, PopupCloseBehavior, RichText, Stroke};
+use notedeck::{shortcuts, tr, Localization, NotedeckTextStyle, Shortcut};
+
+const DAY_CELL_SIZE: egui::Vec2 = vec2(30.0, 26.0);
+
+/// A button showing the selected date which opens a month grid popup.
+///
+/// While the popup is open the arrow keys move the selection by a day or a
+/// week, PageUp/PageDown move by a month and Enter/Escape close it.
+pub struct DatePicker<'a> {
+    id_salt: egui::Id,
+    date: &'a mut NaiveDate,
+    i18n: &'a mut Localization,
+    week_start: Weekday,
+}
+
+impl<'a> DatePicker<'a> {
+    pub fn new(
+        id_salt: impl std::hash::Hash,
+        date: &'a mut NaiveDate,
+        i18n: &'a mut Localization,
+    ) -> Self {
+        Self {
+            id_salt: egui::Id::new(id_salt),
+            date,
+            i18n,
+            week_start: Weekday::Mon,
+        }
+    }
+
+    /// The first column of the month grid
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        let id = ui.make_persistent_id(self.id_salt);
+        let popup_id = id.with("popup");
+        let month_id = id.with("shown_month");
+
+        let label = format!(
+            "{} {}, {}",
+            month_name(self.i18n, self.date.month()),
+            self.date.day(),
+            self.date.year()
+        );
+
+        let mut response = ui.add(Button::new(
+            RichText::new(label).text_style(NotedeckTextStyle::Body.text_style()),
+        ));
+
+        if response.clicked() {
+            ui.data_mut(|d| d.insert_temp(month_id, first_of_month(*self.date)));
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        }
+
+        let is_open = ui.memory(|m| m.is_popup_open(popup_id));
+        if !is_open {
+            return response;
+        }
+
+        let before = *self.date;
+        let mut shown = ui
+            .data(|d| d.get_temp::<NaiveDate>(month_id))
+            .unwrap_or_else(|| first_of_month(*self.date));
+
+        if let Some(moved) = handle_date_keys(ui, *self.date) {
+            *self.date = moved;
+            shown = first_of_month(moved);
+        }
+
+        if ui.input_mut(|i| {
+            i.consume_key(Modifiers::NONE, Key::Enter)
+                || i.consume_key(Modifiers::NONE, Key::Escape)
+        }) {
+            ui.memory_mut(|m| m.close_popup());
+        }
+
+        let DatePicker {
+            date,
+            i18n,
+            week_start,
+            ..
+        } = self;
+
+        egui::popup_below_widget(
+            ui,
+            popup_id,
+            &response,
+            PopupCloseBehavior::CloseOnClickOutside,
+            |ui| {
+                ui.set_min_width(DAY_CELL_SIZE.x * 7.0 + 24.0);
+                month_header(ui, i18n, &mut shown);
+                if let Some(picked) = month_grid(ui, i18n, id, shown, *date, week_start) {
+                    *date = picked;
+                    ui.memory_mut(|m| m.close_popup());
+                }
+            },
+        );
+
+        ui.data_mut(|d| d.insert_temp(month_id, shown));
+
+        if *date != before {
+            response.mark_changed();
+        }
+
+        response
+    }
+}
+
+impl egui::Widget for DatePicker<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        self.show(ui)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn date_picker_group(i18n: &mut Localization) -> String {
+    tr!(
+        i18n,
+        "Date picker",
+        "Keyboard shortcut group for the date picker"
+    )
+}
+
+const PREV_DAY: Shortcut = Shortcut::new(
+    "date.prev_day",
+    date_picker_group,
+    |i18n| tr!(i18n, "Previous day", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowLeft),
+);
+
+const NEXT_DAY: Shortcut = Shortcut::new(
+    "date.next_day",
+    date_picker_group,
+    |i18n| tr!(i18n, "Next day", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowRight),
+);
+
+const PREV_WEEK: Shortcut = Shortcut::new(
+    "date.prev_week",
+    date_picker_group,
+    |i18n| tr!(i18n, "Previous week", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowUp),
+);
+
+const NEXT_WEEK: Shortcut = Shortcut::new(
+    "date.next_week",
+    date_picker_group,
+    |i18n| tr!(i18n, "Next week", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowDown),
+);
+
+const PREV_MONTH: Shortcut = Shortcut::new(
+    "date.prev_month",
+    date_picker_group,
+    |i18n| tr!(i18n, "Previous month", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::PageUp),
+);
+
+const NEXT_MONTH: Shortcut = Shortcut::new(
+    "date.next_month",
+    date_picker_group,
+    |i18n| tr!(i18n, "Next month", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::PageDown),
+);
+
+/// The date picker's keyboard shortcuts
+pub const SHORTCUTS: [Shortcut; 6] = [
+    PREV_DAY, NEXT_DAY, PREV_WEEK, NEXT_WEEK, PREV_MONTH, NEXT_MONTH,
+];
+
+fn handle_date_keys(ui: &mut egui::Ui, date: NaiveDate) -> Option<NaiveDate> {
+    let ctx = ui.ctx();
+    if shortcuts::consume(ctx, &PREV_DAY) {
+        date.checked_sub_days(Days::new(1))
+    } else if shortcuts::consume(ctx, &NEXT_DAY) {
+        date.checked_add_days(Days::new(1))
+    } else if shortcuts::consume(ctx, &PREV_WEEK) {
+        date.checked_sub_days(Days::new(7))
+    } else if shortcuts::consume(ctx, &NEXT_WEEK) {
+        date.checked_add_days(Days::new(7))
+    } else if shortcuts::consume(ctx, &PREV_MONTH) {
+        date.checked_sub_months(Months::new(1))
+    } else if shortcuts::consume(ctx, &NEXT_MONTH) {
+        date.checked_add_months(Months::new(1))
+    } else {
+        None
+    }
+}
+
+fn month_header(ui: &mut egui::Ui, i18n: &mut Localization, shown: &mut NaiveDate) {
+    ui.horizontal(|ui| {
+        if ui.small_button("◀").clicked() {
+            if let Some(prev) = shown.checked_sub_months(Months::new(1)) {
+                *shown = prev;
+            }
+        }
+
+        ui.label(
+            RichText::new(format!(
+                "{} {}",
+                month_name(i18n, shown.month()),
+                shown.year()
+            ))
+            .text_style(NotedeckTextStyle::Body.text_style())
+            .strong(),
+        );
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.small_button("▶").clicked() {
+                if let Some(next) = shown.checked_add_months(Months::new(1)) {
+                    *shown = next;
+                }
+            }
+        });
+    });
+}
+
+/// Render a 6 week grid starting on `week_start`. Returns the clicked day.
+fn month_grid(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    id: egui::Id,
+    shown: NaiveDate,
+    selected: NaiveDate,
+    week_start: Weekday,
+) -> Option<NaiveDate> {
+    let today = Local::now().date_naive();
+    let offset = shown.weekday().days_since(week_start) as u64;
+    let grid_start = shown.checked_sub_days(Days::new(offset))?;
+
+    let visuals = ui.visuals().clone();
+    let mut picked = None;
+
+    egui::Grid::new(id.with("month_grid"))
+        .num_columns(7)
+        .spacing(vec2(2.0, 2.0))
+        .show(ui, |ui| {
+            let mut weekday = week_start;
+            for _ in 0..7 {
+                ui.label(
+                    RichText::new(weekday_short_name(i18n, weekday))
+                        .text_style(NotedeckTextStyle::Small.text_style())
+                        .color(visuals.weak_text_color()),
+                );
+                weekday = weekday.succ();
+            }
+            ui.end_row();
+
+            for (i, day) in grid_start.iter_days().take(42).enumerate() {
+                let is_selected = day == selected;
+                let in_month = day.month() == shown.month();
+
+                let mut text = RichText::new(day.day().to_string())
+                    .text_style(NotedeckTextStyle::Small.text_style());
+                if is_selected {
+                    text = text.color(visuals.selection.stroke.color);
+                } else if !in_month {
+                    text = text.color(visuals.weak_text_color());
+                }
+
+                let stroke = if day == today {
+                    Stroke::new(1.0, visuals.selection.bg_fill)
+                } else {
+                    Stroke::NONE
+                };
+
+                let fill = if is_selected {
+                    visuals.selection.bg_fill
+                } else {
+                    egui::Color32::TRANSPARENT
+                };
+
+                let resp = ui.add(
+                    Button::new(text)
+                        .fill(fill)
+                        .stroke(stroke)
+                        .corner_radius(4.0)
+                        .min_size(DAY_CELL_SIZE),
+                );
+
+                if resp.clicked() {
+                    picked = Some(day);
+                }
+
+                if i % 7 == 6 {
+                    ui.end_row();
+                }
+            }
+        });
+
+    picked
+}
+
+/// Hour and minute spinners. Drag, scroll or use the arrow keys while a
+/// field is focused to change the value.
+pub struct TimePicker<'a> {
+    id_salt: egui::Id,
+    time: &'a mut NaiveTime,
+    minute_step: u32,
+    use_24h: bool,
+}
+
+impl<'a> TimePicker<'a> {
+    pub fn new(id_salt: impl std::hash::Hash, time: &'a mut NaiveTime) -> Self {
+        Self {
+            id_salt: egui::Id::new(id_salt),
+            time,
+            minute_step: 5,
+            use_24h: true,
+        }
+    }
+
+    /// Granularity of the minute spinner, clamped to 1..=30
+    pub fn minute_step(mut self, step: u32) -> Self {
+        self.minute_step = step.clamp(1, 30);
+        self
+    }
+
+    /// Show a 12 hour clock with an AM/PM toggle instead of a 24 hour clock
+    pub fn use_24h(mut self, use_24h: bool) -> Self {
+        self.use_24h = use_24h;
+        self
+    }
+
+    pub fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        let before = *self.time;
+        let (is_pm, hour12) = self.time.hour12();
+        let mut hour = if self.use_24h {
+            self.time.hour()
+        } else {
+            hour12
+        };
+        let mut minute = self.time.minute();
+        let mut pm = is_pm;
+
+        let mut response = ui
+            .push_id(self.id_salt, |ui| {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 2.0;
+
+                    let hour_range = if self.use_24h { 0..=23 } else { 1..=12 };
+                    ui.add(
+                        egui::DragValue::new(&mut hour)
+                            .range(hour_range)
+                            .speed(0.1)
+                            .custom_formatter(|n, _| format!("{n:02}")),
+                    );
+
+                    ui.label(":");
+
+                    let minute_resp = ui.add(
+                        egui::DragValue::new(&mut minute)
+                            .range(0..=59)
+                            .speed(0.2 * self.minute_step as f64)
+                            .custom_formatter(|n, _| format!("{n:02}")),
+                    );
+                    if minute_resp.changed() {
+                        minute = snap_minute(minute, self.minute_step);
+                    }
+
+                    if !self.use_24h {
+                        ui.add_space(4.0);
+                        let label = if pm { "PM" } else { "AM" };
+                        if ui.small_button(label).clicked() {
+                            pm = !pm;
+                        }
+                    }
+                })
+                .response
+            })
+            .inner;
+
+        let hour24 = if self.use_24h {
+            hour
+        } else {
+            (hour % 12) + if pm { 12 } else { 0 }
+        };
+
+        if let Some(time) = NaiveTime::from_hms_opt(hour24, minute.min(59), 0) {
+            *self.time = time;
+        }
+
+        if *self.time != before {
+            response.mark_changed();
+        }
+
+        response
+    }
+}
+
+impl egui::Widget for TimePicker<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        self.show(ui)
+    }
+}
+
+/// The multiple of `step` closest to `minute` that still fits in an hour
+fn snap_minute(minute: u32, step: u32) -> u32 {
+    let step = step.max(1);
+    let snapped = (minute + step / 2) / step * step;
+    snapped.min(59 / step * step)
+}
+
+pub fn month_name(i18n: &mut Localization, month: u32) -> String {
+    match month {
+        1 => tr!(i18n, "January", "Month name"),
+        2 => tr!(i18n, "February", "Month name"),
+        3 => tr!(i18n, "March", "Month name"),
+        4 => tr!(i18n, "April", "Month name"),
+        5 => tr!(i18n, "May", "Month name"),
+        6 => tr!(i18n, "June", "Month name"),
+        7 => tr!(i18n, "July", "Month name"),
+        8 => tr!(i18n, "August", "Month name"),
+        9 => tr!(i18n, "September", "Month name"),
+        10 => tr!(i18n, "October", "Month name"),
+        11 => tr!(i18n, "November", "Month name"),
+        _ => tr!(i18n, "December", "Month name"),
+    }
+}
+
+pub fn weekday_short_name(i18n: &mut Localization, weekday: Weekday) -> String {
+    match weekday {
+        Weekday::Mon => tr!(i18n, "Mo", "Two letter abbreviation for Monday"),
+        Weekday::Tue => tr!(i18n, "Tu", "Two letter abbreviation for Tuesday"),
+        Weekday::Wed => tr!(i18n, "We", "Two letter abbreviation for Wednesday"),
+        Weekday::Thu => tr!(i18n, "Th", "Two letter abbreviation for Thursday"),
+        Weekday::Fri => tr!(i18n, "Fr", "Two letter abbreviation for Friday"),
+        Weekday::Sat => tr!(i18n, "Sa", "Two letter abbreviation for Saturday"),
+        Weekday::Sun => tr!(i18n, "Su", "Two letter abbreviation for Sunday"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minutes_snap_to_the_step() {
+        assert_eq!(snap_minute(7, 5), 5);
+        assert_eq!(snap_minute(8, 5), 10);
+        assert_eq!(snap_minute(58, 5), 55);
+        assert_eq!(snap_minute(53, 15), 45);
+        assert_eq!(snap_minute(23, 15), 30);
+        assert_eq!(snap_minute(59, 1), 59);
+    }
+}
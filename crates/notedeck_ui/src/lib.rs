@@ -3,6 +3,7 @@ pub mod app_images;
 pub mod colors;
 pub mod constants;
 pub mod context_menu;
+pub mod datetime;
 pub mod icons;
 pub mod images;
 pub mod media;
@@ -10,15 +11,21 @@ pub mod mention;
 pub mod nip51_set;
 pub mod note;
 pub mod profile;
+mod publish_receipt;
+mod relay_status;
 mod username;
 pub mod widgets;
 
 pub use anim::{AnimationHelper, PulseAlpha};
+pub use datetime::{DatePicker, TimePicker};
 pub use icons::{expanding_button, ICON_EXPANSION_MULTIPLE, ICON_WIDTH};
 pub use mention::Mention;
 pub use note::{NoteContents, NoteOptions, NoteView};
 pub use profile::{ProfilePic, ProfilePreview};
+pub use publish_receipt::PublishReceiptView;
+pub use relay_status::RelayStatusChip;
 pub use username::Username;
+pub use widgets::InfoIcon;
 
 use egui::{Label, Margin, Pos2, RichText};
 
@@ -94,6 +101,40 @@ pub fn clear_input_rect(ui: &mut egui::Ui) {
     ui.data_mut(|d| d.remove::<egui::Rect>(egui::Id::new(INPUT_RECT_KEY)))
 }
 
+const RTL_KEY: &str = "notedeck_rtl";
+
+/// Set the layout direction for the frame, from
+/// [`notedeck::Localization::is_rtl`]
+pub fn set_rtl(ctx: &egui::Context, rtl: bool) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(RTL_KEY), rtl))
+}
+
+/// Are we laying things out right-to-left?
+pub fn is_rtl(ui: &egui::Ui) -> bool {
+    ui.data(|d| d.get_temp(egui::Id::new(RTL_KEY)))
+        .unwrap_or(false)
+}
+
+/// A row that starts on the reading side: left for most languages, right
+/// for arabic, hebrew and friends
+pub fn row_layout(ui: &egui::Ui, valign: egui::Align) -> egui::Layout {
+    if is_rtl(ui) {
+        egui::Layout::right_to_left(valign)
+    } else {
+        egui::Layout::left_to_right(valign)
+    }
+}
+
+/// A column whose items hug the reading side
+pub fn column_layout(ui: &egui::Ui) -> egui::Layout {
+    let align = if is_rtl(ui) {
+        egui::Align::RIGHT
+    } else {
+        egui::Align::LEFT
+    };
+    egui::Layout::top_down(align)
+}
+
 /// Center the galley on the center pos, returning the position of the top left position of the galley,
 /// for the `painter.galley(..)`
 pub fn galley_centered_pos(galley: &std::sync::Arc<egui::Galley>, center: Pos2) -> Pos2 {
@@ -103,3 +144,22 @@ pub fn galley_centered_pos(galley: &std::sync::Arc<egui::Galley>, center: Pos2)
 
     top_left
 }
+
+/// Human readable size for a number of bytes
+pub fn format_size(size_bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+
+    let size = size_bytes as f64;
+
+    if size < KB {
+        format!("{size:.0} Bytes")
+    } else if size < MB {
+        format!("{:.1} KB", size / KB)
+    } else if size < GB {
+        format!("{:.1} MB", size / MB)
+    } else {
+        format!("{:.2} GB", size / GB)
+    }
+}
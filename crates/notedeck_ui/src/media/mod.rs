@@ -1,3 +1,6 @@
 mod viewer;
 
-pub use viewer::{MediaViewer, MediaViewerFlags, MediaViewerState};
+pub use viewer::{
+    MediaViewer, MediaViewerAction, MediaViewerFlags, MediaViewerResponse, MediaViewerState,
+    SHORTCUTS as MEDIA_VIEWER_SHORTCUTS,
+};
@@ -1,7 +1,16 @@
 use bitflags::bitflags;
-use egui::{emath::TSTransform, pos2, Color32, Rangef, Rect};
-use notedeck::media::{AnimationMode, MediaInfo, ViewMediaInfo};
-use notedeck::{ImageType, Images};
+use egui::{
+    emath::TSTransform, pos2, Color32, Key, KeyboardShortcut, Modifiers, Pos2, Rangef, Rect,
+    RichText,
+};
+use enostr::NoteId;
+use notedeck::media::{AnimationMode, MediaDownload, MediaInfo, ViewMediaInfo};
+use notedeck::{
+    shortcuts, supported_mime_hosted_at_url, tr, tr_plural, ImageType, Images, JobPool,
+    Localization, Shortcut,
+};
+
+use crate::format_size;
 
 bitflags! {
     #[repr(transparent)]
@@ -15,6 +24,9 @@ bitflags! {
 
         /// Are we open or closed?
         const Open = 1 << 2;
+
+        /// Show the info panel for the focused media
+        const Info = 1 << 3;
     }
 }
 
@@ -25,6 +37,12 @@ pub struct MediaViewerState {
     pub scene_rect: Option<Rect>,
     pub flags: MediaViewerFlags,
     pub anim_id: egui::Id,
+
+    /// Where each media item was placed in the scene on the last frame
+    tile_rects: Vec<Rect>,
+
+    /// Save all in progress, or finished until dismissed
+    download: Option<MediaDownload>,
 }
 
 impl Default for MediaViewerState {
@@ -34,6 +52,8 @@ impl Default for MediaViewerState {
             media_info: Default::default(),
             scene_rect: None,
             flags: MediaViewerFlags::Transition | MediaViewerFlags::Fullscreen,
+            tile_rects: Vec::new(),
+            download: None,
         }
     }
 }
@@ -51,7 +71,7 @@ impl MediaViewerState {
         ui.ctx().animate_bool_with_time_and_easing(
             self.anim_id,
             self.flags.contains(MediaViewerFlags::Open),
-            0.3,
+            notedeck::motion::anim_time(ui.ctx(), 0.3),
             egui::emath::easing::cubic_out,
         )
     }
@@ -66,16 +86,158 @@ impl MediaViewerState {
         // we are closing
         self.open_amount(ui) > 0.0
     }
+
+    /// Save every media item into `dir`, after [`MediaViewerAction::SaveAll`]
+    pub fn save_all(&mut self, ctx: &egui::Context, dir: std::path::PathBuf) {
+        if let Some(download) = &self.download {
+            download.cancel();
+        }
+
+        let urls = self
+            .media_info
+            .medias
+            .iter()
+            .map(|media| media.url.clone())
+            .collect();
+        self.download = Some(MediaDownload::start(ctx, urls, dir));
+    }
+
+    /// Move to the media at `index` and fit it to the screen
+    fn focus_media(&mut self, index: usize) {
+        if index >= self.media_info.medias.len() {
+            return;
+        }
+
+        self.media_info.clicked_index = index;
+        if let Some(rect) = self.tile_rects.get(index).filter(|r| r.is_positive()) {
+            self.scene_rect = Some(*rect);
+        }
+    }
+}
+
+/// Things the viewer can't do itself and asks the app to handle
+pub enum MediaViewerAction {
+    /// Open the note the media was posted in
+    OpenNote(NoteId),
+    /// Pick a folder and pass it to [`MediaViewerState::save_all`]
+    SaveAll,
+}
+
+pub struct MediaViewerResponse {
+    pub response: egui::Response,
+    pub action: Option<MediaViewerAction>,
 }
 
+enum ViewerKey {
+    Prev,
+    Next,
+    ZoomIn,
+    ZoomOut,
+    Reset,
+    ToggleInfo,
+}
+
+fn viewer_group(i18n: &mut Localization) -> String {
+    tr!(
+        i18n,
+        "Media viewer",
+        "Keyboard shortcut group for the media viewer"
+    )
+}
+
+const PREV_SHORTCUT: Shortcut = Shortcut::new(
+    "media.prev",
+    viewer_group,
+    |i18n| tr!(i18n, "Previous media", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowLeft),
+);
+
+const NEXT_SHORTCUT: Shortcut = Shortcut::new(
+    "media.next",
+    viewer_group,
+    |i18n| tr!(i18n, "Next media", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::ArrowRight),
+);
+
+const ZOOM_IN_SHORTCUT: Shortcut = Shortcut::new(
+    "media.zoom_in",
+    viewer_group,
+    |i18n| tr!(i18n, "Zoom in", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::Plus),
+);
+
+const ZOOM_OUT_SHORTCUT: Shortcut = Shortcut::new(
+    "media.zoom_out",
+    viewer_group,
+    |i18n| tr!(i18n, "Zoom out", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::Minus),
+);
+
+const RESET_SHORTCUT: Shortcut = Shortcut::new(
+    "media.reset",
+    viewer_group,
+    |i18n| tr!(i18n, "Reset zoom", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::Num0),
+);
+
+const INFO_SHORTCUT: Shortcut = Shortcut::new(
+    "media.info",
+    viewer_group,
+    |i18n| tr!(i18n, "Show media info", "Keyboard shortcut description"),
+    KeyboardShortcut::new(Modifiers::NONE, Key::I),
+);
+
+/// The media viewer's keyboard shortcuts
+pub const SHORTCUTS: [Shortcut; 6] = [
+    PREV_SHORTCUT,
+    NEXT_SHORTCUT,
+    ZOOM_IN_SHORTCUT,
+    ZOOM_OUT_SHORTCUT,
+    RESET_SHORTCUT,
+    INFO_SHORTCUT,
+];
+
+impl ViewerKey {
+    /// Consume the next viewer key, leaving anything else (eg. Escape) for
+    /// the caller to handle
+    fn consume(ui: &mut egui::Ui) -> Option<Self> {
+        let ctx = ui.ctx();
+        if shortcuts::consume(ctx, &PREV_SHORTCUT) {
+            Some(Self::Prev)
+        } else if shortcuts::consume(ctx, &NEXT_SHORTCUT) {
+            Some(Self::Next)
+        } else if shortcuts::consume(ctx, &ZOOM_IN_SHORTCUT)
+            // = is + without shift on most keyboards
+            || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Equals))
+        {
+            Some(Self::ZoomIn)
+        } else if shortcuts::consume(ctx, &ZOOM_OUT_SHORTCUT) {
+            Some(Self::ZoomOut)
+        } else if shortcuts::consume(ctx, &RESET_SHORTCUT) {
+            Some(Self::Reset)
+        } else if shortcuts::consume(ctx, &INFO_SHORTCUT) {
+            Some(Self::ToggleInfo)
+        } else {
+            None
+        }
+    }
+}
+
+/// How much a single +/- press zooms by
+const KEY_ZOOM_FACTOR: f32 = 1.25;
+
 /// A panning, scrolling, optionally fullscreen, and tiling media viewer
 pub struct MediaViewer<'a> {
     state: &'a mut MediaViewerState,
+    author_name: Option<&'a str>,
 }
 
 impl<'a> MediaViewer<'a> {
     pub fn new(state: &'a mut MediaViewerState) -> Self {
-        Self { state }
+        Self {
+            state,
+            author_name: None,
+        }
     }
 
     /// Is this
@@ -90,24 +252,43 @@ impl<'a> MediaViewer<'a> {
         self
     }
 
-    pub fn ui(&mut self, images: &mut Images, ui: &mut egui::Ui) -> egui::Response {
+    /// Name of the author of the media's note, shown in the info panel
+    pub fn author_name(mut self, name: Option<&'a str>) -> Self {
+        self.author_name = name;
+        self
+    }
+
+    pub fn ui(
+        &mut self,
+        images: &mut Images,
+        job_pool: &JobPool,
+        i18n: &mut Localization,
+        ui: &mut egui::Ui,
+    ) -> MediaViewerResponse {
         if self.state.flags.contains(MediaViewerFlags::Fullscreen) {
             egui::Window::new("Media Viewer")
                 .title_bar(false)
                 .fixed_size(ui.ctx().screen_rect().size())
                 .fixed_pos(ui.ctx().screen_rect().min)
                 .frame(egui::Frame::NONE)
-                .show(ui.ctx(), |ui| self.ui_content(images, ui))
+                .show(ui.ctx(), |ui| self.ui_content(images, job_pool, i18n, ui))
                 .unwrap() // SAFETY: we are always open
                 .inner
                 .unwrap()
         } else {
-            self.ui_content(images, ui)
+            self.ui_content(images, job_pool, i18n, ui)
         }
     }
 
-    fn ui_content(&mut self, images: &mut Images, ui: &mut egui::Ui) -> egui::Response {
+    fn ui_content(
+        &mut self,
+        images: &mut Images,
+        job_pool: &JobPool,
+        i18n: &mut Localization,
+        ui: &mut egui::Ui,
+    ) -> MediaViewerResponse {
         let avail_rect = ui.available_rect_before_wrap();
+        let zoom_range: egui::Rangef = (0.0..=10.0).into();
 
         let scene_rect = if let Some(scene_rect) = self.state.scene_rect {
             scene_rect
@@ -116,8 +297,6 @@ impl<'a> MediaViewer<'a> {
             avail_rect
         };
 
-        let zoom_range: egui::Rangef = (0.0..=10.0).into();
-
         let is_open = self.state.flags.contains(MediaViewerFlags::Open);
         let can_transition = self.state.flags.contains(MediaViewerFlags::Transition);
         let open_amount = self.state.open_amount(ui);
@@ -129,6 +308,12 @@ impl<'a> MediaViewer<'a> {
             open_amount > 0.0
         };
 
+        let scene_rect = if is_open && !transitioning {
+            self.handle_input(ui, &avail_rect, &zoom_range, scene_rect)
+        } else {
+            scene_rect
+        };
+
         let mut trans_rect = if transitioning {
             let clicked_img = &self.state.media_info.clicked_media();
             let src_pos = &clicked_img.original_position;
@@ -161,12 +346,376 @@ impl<'a> MediaViewer<'a> {
         */
 
         let resp = scene.show(ui, &mut trans_rect, |ui| {
-            Self::render_image_tiles(&self.state.media_info.medias, images, ui, open_amount);
+            Self::render_image_tiles(
+                &self.state.media_info.medias,
+                self.state.media_info.clicked_index,
+                images,
+                job_pool,
+                ui,
+                open_amount,
+            )
         });
 
+        let tiles = resp.inner;
+
+        if is_open && !transitioning {
+            if let Some((index, pos)) = tiles.double_clicked {
+                trans_rect =
+                    double_tap_zoom(&avail_rect, &zoom_range, trans_rect, &tiles, index, pos);
+                self.state.media_info.clicked_index = index;
+            }
+
+            if let Some(content) = tiles.content_rect() {
+                trans_rect = clamp_view_to_content(trans_rect, content);
+            }
+        }
+
+        self.state.tile_rects = tiles.rects;
         self.state.scene_rect = Some(trans_rect);
 
-        resp.response
+        let mut action = None;
+        if is_open && !transitioning {
+            self.info_button(ui, &avail_rect, i18n);
+
+            // there's no folder picker on mobile
+            let can_save_all =
+                self.state.media_info.medias.len() > 1 && !notedeck::ui::is_compiled_as_mobile();
+            if can_save_all && self.save_all_button(ui, &avail_rect, i18n) {
+                action = Some(MediaViewerAction::SaveAll);
+            }
+            self.download_panel(ui, &avail_rect, i18n);
+
+            if self.state.flags.contains(MediaViewerFlags::Info) {
+                action = self.info_panel(ui, &avail_rect, images, i18n);
+            }
+        }
+
+        MediaViewerResponse {
+            response: resp.response,
+            action,
+        }
+    }
+
+    /// Toggle for the info panel, in the top right corner
+    fn info_button(&mut self, ui: &mut egui::Ui, avail_rect: &Rect, i18n: &mut Localization) {
+        egui::Area::new(self.state.anim_id.with("info_button"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(avail_rect.right_top() + egui::vec2(-48.0, 16.0))
+            .show(ui.ctx(), |ui| {
+                let selected = self.state.flags.contains(MediaViewerFlags::Info);
+                let resp = ui
+                    .selectable_label(selected, RichText::new("ℹ").size(20.0))
+                    .on_hover_text(tr!(
+                        i18n,
+                        "Media info (I)",
+                        "Hover text for the button that shows details about media in the media viewer"
+                    ));
+
+                if resp.clicked() {
+                    self.state.flags.toggle(MediaViewerFlags::Info);
+                }
+            });
+    }
+
+    /// Save every media item of the note, left of the info button
+    fn save_all_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        avail_rect: &Rect,
+        i18n: &mut Localization,
+    ) -> bool {
+        let saving = self
+            .state
+            .download
+            .as_ref()
+            .is_some_and(|d| !d.progress().finished);
+
+        egui::Area::new(self.state.anim_id.with("save_all_button"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(avail_rect.right_top() + egui::vec2(-96.0, 16.0))
+            .show(ui.ctx(), |ui| {
+                ui.add_enabled(!saving, egui::Button::new(RichText::new("⬇").size(20.0)))
+                    .on_hover_text(tr!(
+                        i18n,
+                        "Save all",
+                        "Hover text for the button that saves every media item of a note in the media viewer"
+                    ))
+                    .clicked()
+            })
+            .inner
+    }
+
+    /// Progress of Save all, with cancel while saving and dismiss after
+    fn download_panel(&mut self, ui: &mut egui::Ui, avail_rect: &Rect, i18n: &mut Localization) {
+        let Some(download) = &self.state.download else {
+            return;
+        };
+
+        let total = download.total();
+        let progress = download.progress();
+        let done = progress.saved + progress.failed;
+        let mut dismiss = false;
+
+        egui::Area::new(self.state.anim_id.with("download_panel"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+            .constrain_to(*avail_rect)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(280.0);
+
+                    let status = if !progress.finished {
+                        tr!(
+                            i18n,
+                            "Saving {done} of {total}...",
+                            "Progress of saving every media item of a note",
+                            done = done + 1,
+                            total = total
+                        )
+                    } else if download.is_cancelled() {
+                        tr_plural!(
+                            i18n,
+                            "Cancelled, saved {count} file",
+                            "Cancelled, saved {count} files",
+                            "Shown when saving every media item of a note was cancelled",
+                            progress.saved,
+                        )
+                    } else if progress.failed > 0 {
+                        tr!(
+                            i18n,
+                            "Saved {saved} of {total}, {failed} failed",
+                            "Shown when saving media finished with some files failing",
+                            saved = progress.saved,
+                            total = total,
+                            failed = progress.failed
+                        )
+                    } else {
+                        tr_plural!(
+                            i18n,
+                            "Saved {count} file",
+                            "Saved {count} files",
+                            "Shown when every media item of a note was saved",
+                            progress.saved,
+                        )
+                    };
+
+                    ui.label(status);
+                    ui.add(
+                        egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                            .desired_height(6.0),
+                    );
+
+                    ui.horizontal(|ui| {
+                        if !progress.finished {
+                            if ui
+                                .button(tr!(
+                                    i18n,
+                                    "Cancel",
+                                    "Button to stop saving every media item of a note"
+                                ))
+                                .clicked()
+                            {
+                                download.cancel();
+                            }
+                        } else if ui
+                            .button(tr!(
+                                i18n,
+                                "Close",
+                                "Button to dismiss the media saving progress"
+                            ))
+                            .clicked()
+                        {
+                            dismiss = true;
+                        }
+                    });
+                });
+            });
+
+        if dismiss {
+            self.state.download = None;
+        }
+    }
+
+    /// Details about the focused media and where it came from
+    fn info_panel(
+        &mut self,
+        ui: &mut egui::Ui,
+        avail_rect: &Rect,
+        images: &mut Images,
+        i18n: &mut Localization,
+    ) -> Option<MediaViewerAction> {
+        let url = self.state.media_info.clicked_media().url.clone();
+        let details = images.media_details(&url);
+
+        // fall back to the size of the loaded texture, which can be
+        // smaller than the original for very large images
+        let dimensions = details.dimensions.or_else(|| {
+            images
+                .loaded_texture(ui, &url, AnimationMode::NoAnimation)
+                .map(|texture| {
+                    let [w, h] = texture.size();
+                    (w as u32, h as u32)
+                })
+        });
+
+        let unknown = tr!(
+            i18n,
+            "Unknown",
+            "Shown in the media info panel when a detail isn't known"
+        );
+        let source = self.state.media_info.source;
+        let mut action = None;
+
+        egui::Area::new(self.state.anim_id.with("info_panel"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(avail_rect.right_top() + egui::vec2(-336.0, 56.0))
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(300.0);
+
+                    egui::Grid::new("media_info_grid")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label(tr!(
+                                i18n,
+                                "URL",
+                                "Label for the media url in the media info panel"
+                            ));
+                            ui.add(egui::Label::new(url.as_str()).truncate())
+                                .on_hover_text(url.as_str());
+                            ui.end_row();
+
+                            ui.label(tr!(
+                                i18n,
+                                "Resolution",
+                                "Label for the media width and height in the media info panel"
+                            ));
+                            ui.label(
+                                dimensions
+                                    .map(|(w, h)| format!("{w} × {h}"))
+                                    .unwrap_or_else(|| unknown.clone()),
+                            );
+                            ui.end_row();
+
+                            ui.label(tr!(
+                                i18n,
+                                "Cached size",
+                                "Label for the size of our cached copy of the media in the media info panel"
+                            ));
+                            ui.label(
+                                details
+                                    .cached_size
+                                    .map(format_size)
+                                    .unwrap_or_else(|| unknown.clone()),
+                            );
+                            ui.end_row();
+
+                            ui.label(tr!(
+                                i18n,
+                                "Type",
+                                "Label for the media mime type in the media info panel"
+                            ));
+                            ui.label(details.mime_type.as_deref().unwrap_or(&unknown));
+                            ui.end_row();
+
+                            if source.is_some() {
+                                ui.label(tr!(
+                                    i18n,
+                                    "Posted by",
+                                    "Label for the author of the note the media is from in the media info panel"
+                                ));
+                                ui.label(self.author_name.unwrap_or(&unknown));
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(tr!(
+                                i18n,
+                                "Copy URL",
+                                "Button to copy the media url in the media info panel"
+                            ))
+                            .clicked()
+                        {
+                            ui.ctx().copy_text(url.clone());
+                        }
+
+                        if let Some(source) = source {
+                            if ui
+                                .button(tr!(
+                                    i18n,
+                                    "Open note",
+                                    "Button to open the note the media was posted in from the media info panel"
+                                ))
+                                .clicked()
+                            {
+                                action = Some(MediaViewerAction::OpenNote(source.note_id));
+                            }
+                        }
+                    });
+                });
+            });
+
+        action
+    }
+
+    /// Keyboard navigation and scroll zoom. Returns the updated scene rect.
+    fn handle_input(
+        &mut self,
+        ui: &mut egui::Ui,
+        avail_rect: &Rect,
+        zoom_range: &Rangef,
+        scene_rect: Rect,
+    ) -> Rect {
+        let current = self.state.media_info.clicked_index;
+
+        match ViewerKey::consume(ui) {
+            Some(ViewerKey::Prev) => self.state.focus_media(current.saturating_sub(1)),
+            Some(ViewerKey::Next) => self.state.focus_media(current + 1),
+            Some(ViewerKey::Reset) => self.state.focus_media(current),
+            Some(ViewerKey::ToggleInfo) => self.state.flags.toggle(MediaViewerFlags::Info),
+            Some(ViewerKey::ZoomIn) => {
+                self.state.scene_rect = Some(zoom_rect_around(
+                    scene_rect,
+                    scene_rect.center(),
+                    KEY_ZOOM_FACTOR,
+                ));
+            }
+            Some(ViewerKey::ZoomOut) => {
+                self.state.scene_rect = Some(zoom_rect_around(
+                    scene_rect,
+                    scene_rect.center(),
+                    1.0 / KEY_ZOOM_FACTOR,
+                ));
+            }
+            None => {}
+        }
+
+        let scene_rect = self.state.scene_rect.unwrap_or(scene_rect);
+
+        // a plain scroll zooms around the pointer instead of panning
+        let (scroll, pointer, modifiers) =
+            ui.input(|i| (i.smooth_scroll_delta.y, i.pointer.hover_pos(), i.modifiers));
+
+        let Some(pointer) = pointer.filter(|p| avail_rect.contains(*p)) else {
+            return scene_rect;
+        };
+
+        if scroll == 0.0 || !modifiers.is_none() {
+            return scene_rect;
+        }
+
+        ui.input_mut(|i| i.smooth_scroll_delta = egui::Vec2::ZERO);
+
+        let to_global = fit_to_rect_in_scene(avail_rect, &scene_rect, zoom_range);
+        let pointer_in_scene = to_global.inverse() * pointer;
+        let zoomed = zoom_rect_around(scene_rect, pointer_in_scene, (scroll * 0.005).exp());
+
+        self.state.scene_rect = Some(zoomed);
+        zoomed
     }
 
     /// The rect of the first image to be placed.
@@ -201,24 +750,44 @@ impl<'a> MediaViewer<'a> {
     /// TODO(jb55): Let's improve image tiling over time, spiraling outward. We
     /// should have a way to click "next" and have the scene smoothly transition and
     /// focus on the next image
+    /// Lay out the media side by side. The media being viewed is fetched
+    /// right away and the ones next to it are prefetched, the rest show
+    /// once they're loaded.
     fn render_image_tiles(
         infos: &[MediaInfo],
+        current: usize,
         images: &mut Images,
+        job_pool: &JobPool,
         ui: &mut egui::Ui,
         open_amount: f32,
-    ) {
-        for info in infos {
+    ) -> ImageTiles {
+        let mut tiles = ImageTiles {
+            rects: Vec::with_capacity(infos.len()),
+            double_clicked: None,
+        };
+
+        for (i, info) in infos.iter().enumerate() {
             let url = &info.url;
 
             // fetch image texture
 
             // we want to continually redraw things in the gallery
-            let Some(texture) = images.latest_texture(
-                ui,
-                url,
-                ImageType::Content(None),
-                AnimationMode::Continuous { fps: None }, // media viewer has continuous rendering
-            ) else {
+            let animation_mode = AnimationMode::Continuous { fps: None };
+            let texture = if i == current {
+                images.latest_texture(ui, url, ImageType::Content(None), animation_mode)
+            } else {
+                let texture = images.loaded_texture(ui, url, animation_mode);
+                if texture.is_none() && i.abs_diff(current) == 1 {
+                    if let Some(cache_type) = supported_mime_hosted_at_url(&mut images.urls, url) {
+                        images.prefetch(ui.ctx(), job_pool, url, cache_type);
+                    }
+                }
+                texture
+            };
+
+            let Some(texture) = texture else {
+                // keep indices in line with the media list
+                tiles.rects.push(Rect::NOTHING);
                 continue;
             };
 
@@ -238,15 +807,6 @@ impl<'a> MediaViewer<'a> {
                 img_rect.set_width(size.x);
                 let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
 
-                // image actions
-                //let response = ui.interact(render_rect, carousel_id.with("img"), Sense::click());
-
-                /*
-                if response.clicked() {
-                } else if background_response.clicked() {
-                }
-                */
-
                 // Paint image
                 ui.painter().image(
                     texture.id(),
@@ -255,12 +815,91 @@ impl<'a> MediaViewer<'a> {
                     Color32::from_white_alpha((open_amount * 255.0) as u8),
                 );
 
+                // clicks on the media itself don't close the viewer
+                let response = ui.interact(
+                    img_rect,
+                    ui.id().with(("media_tile", i)),
+                    egui::Sense::click(),
+                );
+                if response.double_clicked() {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        tiles.double_clicked = Some((i, pos));
+                    }
+                }
+
                 ui.advance_cursor_after_rect(img_rect);
+                tiles.rects.push(img_rect);
             }
         }
+
+        tiles
+    }
+}
+
+/// Where the media tiles were placed in the scene
+struct ImageTiles {
+    rects: Vec<Rect>,
+    /// Index and scene position of a double click/tap on a tile
+    double_clicked: Option<(usize, Pos2)>,
+}
+
+impl ImageTiles {
+    /// The bounds of everything that was placed
+    fn content_rect(&self) -> Option<Rect> {
+        self.rects
+            .iter()
+            .filter(|r| r.is_positive())
+            .copied()
+            .reduce(|a, b| a.union(b))
     }
 }
 
+/// How far a double tap zooms in
+const DOUBLE_TAP_ZOOM: f32 = 2.5;
+
+/// Double tap zooms into the tapped point, or back to fit if we are
+/// already zoomed in
+fn double_tap_zoom(
+    avail_rect: &Rect,
+    zoom_range: &Rangef,
+    view: Rect,
+    tiles: &ImageTiles,
+    index: usize,
+    pos: Pos2,
+) -> Rect {
+    let Some(tile) = tiles.rects.get(index).filter(|r| r.is_positive()) else {
+        return view;
+    };
+
+    let fit_scale = fit_to_rect_in_scene(avail_rect, tile, zoom_range).scaling;
+    let view_scale = fit_to_rect_in_scene(avail_rect, &view, zoom_range).scaling;
+
+    if view_scale > fit_scale * 1.05 {
+        *tile
+    } else {
+        zoom_rect_around(view, pos, DOUBLE_TAP_ZOOM)
+    }
+}
+
+/// Keep the center of the view over the content so it can't be panned or
+/// zoomed out of sight
+fn clamp_view_to_content(view: Rect, content: Rect) -> Rect {
+    let center = view.center();
+    let clamped = pos2(
+        center.x.clamp(content.min.x, content.max.x),
+        center.y.clamp(content.min.y, content.max.y),
+    );
+    view.translate(clamped - center)
+}
+
+/// Scale `rect` around `center`. Factors above 1 zoom in.
+fn zoom_rect_around(rect: Rect, center: Pos2, factor: f32) -> Rect {
+    Rect::from_min_max(
+        center + (rect.min - center) / factor,
+        center + (rect.max - center) / factor,
+    )
+}
+
 /// Helper: lerp a TSTransform (uniform scale + translation)
 fn lerp_ts(a: TSTransform, b: TSTransform, t: f32) -> TSTransform {
     let s = egui::lerp(a.scaling..=b.scaling, t);
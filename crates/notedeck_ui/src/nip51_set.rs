@@ -9,7 +9,7 @@ use notedeck::{
 };
 
 use crate::{
-    note::media::{render_media, ScaledTextureFlags},
+    note::media::{render_media, MediaTransition, ScaledTextureFlags},
     ProfilePic,
 };
 
@@ -183,6 +183,7 @@ fn render_pack(
             max_img_size,
             None,
             ScaledTextureFlags::RESPECT_MAX_DIMS,
+            MediaTransition::Blur(egui::Id::new(("nip51_set_image", &pack.identifier))),
         )
         .response
         .rect;
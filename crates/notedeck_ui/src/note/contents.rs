@@ -4,10 +4,15 @@ use crate::{
     secondary_label,
 };
 use egui::{Color32, Hyperlink, Label, RichText};
+use enostr::{NoteId, Pubkey};
 use nostrdb::{BlockType, Mention, Note, NoteKey, Transaction};
-use notedeck::Localization;
-use notedeck::{time_format, update_imeta_blurhashes, NoteCache, NoteContext, NotedeckTextStyle};
+use notedeck::media::MediaSource;
+use notedeck::{
+    note_content_warning, time_format, tr, update_imeta_blurhashes, NoteCache, NoteContext,
+    NotedeckTextStyle, TranslationView,
+};
 use notedeck::{JobsCache, RenderableMedia};
+use notedeck::{Localization, MediaAction};
 use tracing::warn;
 
 pub struct NoteContents<'a, 'd> {
@@ -129,6 +134,13 @@ fn render_note_contents(
 ) -> NoteResponse {
     let response = render_undecorated_note_contents(ui, note_context, txn, note, options, jobs);
 
+    if !options.contains(NoteOptions::IsPreview)
+        && note_context.translations.is_configured()
+        && !note.content().trim().is_empty()
+    {
+        translation_ui(ui, note_context, note);
+    }
+
     ui.horizontal_wrapped(|ui| {
         note_bottom_metadata_ui(
             ui,
@@ -142,6 +154,116 @@ fn render_note_contents(
     response
 }
 
+/// The translation of a note under its content, with a button to
+/// translate it or go back to only the original
+fn translation_ui(ui: &mut egui::Ui, note_context: &mut NoteContext, note: &Note) {
+    let note_id = NoteId::new(*note.id());
+    let link_color = ui.visuals().hyperlink_color;
+
+    let mut translate = false;
+    let mut revert = false;
+
+    match note_context.translations.view(&note_id) {
+        TranslationView::Original => {
+            translate = small_link(
+                ui,
+                link_color,
+                tr!(
+                    note_context.i18n,
+                    "Translate",
+                    "Button to translate the content of a note"
+                ),
+            );
+        }
+        TranslationView::Pending => {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new().size(12.0));
+                secondary_label(
+                    ui,
+                    tr!(
+                        note_context.i18n,
+                        "Translating…",
+                        "Shown while the content of a note is being translated"
+                    ),
+                );
+            });
+        }
+        TranslationView::Translated(text) => {
+            let text = text.to_owned();
+            egui::Frame::new()
+                .inner_margin(egui::Margin::symmetric(8, 4))
+                .stroke(ui.visuals().noninteractive().bg_stroke)
+                .corner_radius(4.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        Label::new(
+                            RichText::new(text)
+                                .text_style(NotedeckTextStyle::NoteBody.text_style()),
+                        )
+                        .wrap(),
+                    );
+                });
+            revert = small_link(
+                ui,
+                link_color,
+                tr!(
+                    note_context.i18n,
+                    "Show original",
+                    "Button to hide the translation of a note"
+                ),
+            );
+        }
+        TranslationView::Failed(err) => {
+            let err = err.to_owned();
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    RichText::new(tr!(
+                        note_context.i18n,
+                        "Couldn't translate this note",
+                        "Error shown when translating a note failed"
+                    ))
+                    .small(),
+                )
+                .on_hover_text(err);
+                translate = small_link(
+                    ui,
+                    link_color,
+                    tr!(
+                        note_context.i18n,
+                        "Retry",
+                        "Button to try translating a note again"
+                    ),
+                );
+            });
+        }
+    }
+
+    if translate {
+        let target = note_context
+            .i18n
+            .get_current_locale()
+            .language
+            .as_str()
+            .to_owned();
+        note_context
+            .translations
+            .show(ui.ctx(), note_id, note.content(), &target);
+    } else if revert {
+        note_context.translations.revert(&note_id);
+    }
+}
+
+fn small_link(ui: &mut egui::Ui, color: Color32, text: String) -> bool {
+    ui.add(
+        Label::new(RichText::new(text).small().color(color))
+            .sense(egui::Sense::click())
+            .selectable(false),
+    )
+    .on_hover_cursor(egui::CursorIcon::PointingHand)
+    .clicked()
+}
+
 /// Client name, full timestamp, etc
 fn note_bottom_metadata_ui(
     ui: &mut egui::Ui,
@@ -277,10 +399,15 @@ fn render_undecorated_note_contents<'a>(
                             update_imeta_blurhashes(note, &mut note_context.img_cache.metadata);
                         }
 
-                        let Some(media) = note_context.img_cache.get_renderable_media(url) else {
+                        let Some(mut media) = note_context.img_cache.get_renderable_media(url)
+                        else {
                             return false;
                         };
 
+                        if media.content_warning.is_none() {
+                            media.content_warning = note_content_warning(note);
+                        }
+
                         supported_medias.push(media);
                         true
                     };
@@ -382,6 +509,13 @@ fn render_undecorated_note_contents<'a>(
             note_context.i18n,
             options,
         );
+
+        if let Some(MediaAction::ViewMedias(info)) = &mut media_action {
+            info.source = Some(MediaSource {
+                note_id: NoteId::new(*note.id()),
+                author: Pubkey::new(*note.pubkey()),
+            });
+        }
         ui.add_space(2.0);
     }
 
@@ -392,6 +526,71 @@ fn render_undecorated_note_contents<'a>(
     NoteResponse::new(response.response).with_action(note_action)
 }
 
+/// Start fetching the media of a note before it scrolls into view. Only
+/// media that would be loaded automatically when shown is prefetched.
+pub fn prefetch_note_media(
+    ctx: &egui::Context,
+    note_context: &mut NoteContext,
+    txn: &Transaction,
+    note: &Note,
+    options: NoteOptions,
+) {
+    if options.intersects(NoteOptions::HideMedia | NoteOptions::DataSaver | NoteOptions::Textmode) {
+        return;
+    }
+
+    let trusted = options.contains(NoteOptions::TrustMedia) || {
+        let acc = note_context.accounts.get_selected_account();
+        note.pubkey() == acc.key.pubkey.bytes()
+            || matches!(acc.is_following(note.pubkey()), notedeck::IsFollowing::Yes)
+    };
+
+    if !trusted {
+        return;
+    }
+
+    let Some(note_key) = note.key() else {
+        return;
+    };
+
+    let Ok(blocks) = note_context.ndb.get_blocks_by_key(txn, note_key) else {
+        return;
+    };
+
+    let note_warning = note_content_warning(note);
+    let show_sensitive = options.contains(NoteOptions::ShowSensitiveMedia);
+
+    for block in blocks.iter(note) {
+        if !matches!(block.blocktype(), BlockType::Url) {
+            continue;
+        }
+
+        let url = block.as_str();
+        if !note_context.img_cache.metadata.contains_key(url) {
+            update_imeta_blurhashes(note, &mut note_context.img_cache.metadata);
+        }
+
+        let Some(media) = note_context.img_cache.get_renderable_media(url) else {
+            continue;
+        };
+
+        let sensitive = media.content_warning.is_some() || note_warning.is_some();
+        if sensitive && !show_sensitive {
+            continue;
+        }
+
+        if !note_context.img_cache.prefetch(
+            ctx,
+            note_context.job_pool,
+            &media.url,
+            media.media_type,
+        ) {
+            // we're at capacity, try again next frame
+            break;
+        }
+    }
+}
+
 fn rot13(input: &str) -> String {
     input
         .chars()
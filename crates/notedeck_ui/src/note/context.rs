@@ -1,6 +1,6 @@
 use egui::{Rect, Vec2};
 use nostrdb::NoteKey;
-use notedeck::{tr, BroadcastContext, Localization, NoteContextSelection};
+use notedeck::{tr, BroadcastContext, IsFollowing, Localization, NoteContextSelection};
 
 use crate::context_menu::{context_button, stationary_arbitrary_menu_button};
 
@@ -61,16 +61,42 @@ impl NoteContextButton {
     }
 
     #[profiling::function]
+    /// `following` is whether the selected account follows the author,
+    /// `None` when it can't follow them, eg. for its own notes
     pub fn menu(
         ui: &mut egui::Ui,
         i18n: &mut Localization,
         button_response: egui::Response,
+        following: Option<IsFollowing>,
     ) -> Option<NoteContextSelection> {
         let mut context_selection: Option<NoteContextSelection> = None;
 
         stationary_arbitrary_menu_button(ui, button_response, |ui| {
             ui.set_max_width(200.0);
 
+            match following {
+                Some(IsFollowing::No) => {
+                    if ui
+                        .button(tr!(i18n, "Follow", "Follow the author of this note"))
+                        .clicked()
+                    {
+                        context_selection = Some(NoteContextSelection::FollowAuthor);
+                        ui.close_menu();
+                    }
+                }
+                Some(IsFollowing::Yes) => {
+                    if ui
+                        .button(tr!(i18n, "Unfollow", "Unfollow the author of this note"))
+                        .clicked()
+                    {
+                        context_selection = Some(NoteContextSelection::UnfollowAuthor);
+                        ui.close_menu();
+                    }
+                }
+                // we can't publish a contact list we don't have
+                Some(IsFollowing::Unknown) | None => {}
+            }
+
             if ui
                 .button(tr!(
                     i18n,
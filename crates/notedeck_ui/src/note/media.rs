@@ -2,18 +2,18 @@ use std::path::Path;
 
 use bitflags::bitflags;
 use egui::{
-    vec2, Button, Color32, Context, CornerRadius, FontId, Image, InnerResponse, Response,
-    TextureHandle, Vec2,
+    vec2, Button, Color32, CornerRadius, FontId, Image, InnerResponse, Response, TextureHandle,
+    Vec2,
 };
 use notedeck::{
     compute_blurhash, fonts::get_font_size, show_one_error_message, tr, BlurhashParams,
     GifStateMap, Images, Job, JobId, JobParams, JobPool, JobState, JobsCache, Localization,
-    MediaAction, MediaCacheType, NotedeckTextStyle, ObfuscationType, PointDimensions,
-    RenderableMedia, TexturedImage, TexturesCache,
+    MediaAction, MediaCacheType, NotedeckTextStyle, ObfuscationType, PixelDimensions,
+    PointDimensions, RenderableMedia, TexturedImage, TexturesCache,
 };
 
 use crate::NoteOptions;
-use notedeck::media::gif::ensure_latest_texture;
+use notedeck::media::gif::{animation_mode_if_visible, ensure_latest_texture};
 use notedeck::media::images::{fetch_no_pfp_promise, ImageType};
 use notedeck::media::AnimationMode;
 use notedeck::media::{MediaInfo, ViewMediaInfo};
@@ -40,81 +40,302 @@ pub fn image_carousel(
     // let's make sure everything is within our area
 
     let size = {
-        let height = 360.0;
         let width = ui.available_width();
-        egui::vec2(width, height)
+        egui::vec2(width, note_options.media_height(width))
     };
 
     let mut action = None;
+    let paged = medias.len() > 1;
+
+    let mut carousel = CarouselState::load(ui.ctx(), carousel_id);
+    let mut scroll_area = egui::ScrollArea::horizontal()
+        .drag_to_scroll(false)
+        .id_salt(carousel_id);
+
+    if paged {
+        // keep the animation baseline at the current offset so a snap
+        // animates from wherever the user left the carousel
+        let anim_id = carousel_id.with("snap_anim");
+        let value = carousel.snap_to.unwrap_or(carousel.offset);
+        let time = notedeck::motion::anim_time(ui.ctx(), 0.2);
+        let offset = ui.ctx().animate_value_with_time(anim_id, value, time);
+        if carousel.snap_to.is_some() {
+            scroll_area = scroll_area.horizontal_scroll_offset(offset);
+        }
+    }
 
-    //let has_touch_screen = ui.ctx().input(|i| i.has_touch_screen());
-    ui.add_sized(size, |ui: &mut egui::Ui| {
-        egui::ScrollArea::horizontal()
-            .drag_to_scroll(false)
-            .id_salt(carousel_id)
-            .show(ui, |ui| {
-                let response = ui
-                    .horizontal(|ui| {
-                        let spacing = ui.spacing_mut();
-                        spacing.item_spacing.x = 8.0;
-
-                        let mut media_infos: Vec<MediaInfo> = Vec::with_capacity(medias.len());
-                        let mut media_action: Option<(usize, MediaUIAction)> = None;
-
-                        for (i, media) in medias.iter().enumerate() {
-                            let media_response = render_media(
-                                ui,
-                                img_cache,
-                                job_pool,
-                                jobs,
-                                media,
-                                note_options.contains(NoteOptions::TrustMedia),
-                                i18n,
-                                size,
-                                if note_options.contains(NoteOptions::NoAnimations) {
-                                    Some(AnimationMode::NoAnimation)
-                                } else {
-                                    None
-                                },
-                                if note_options.contains(NoteOptions::Wide) {
-                                    ScaledTextureFlags::SCALE_TO_WIDTH
-                                } else {
-                                    ScaledTextureFlags::empty()
-                                },
-                            );
-
-                            if let Some(action) = media_response.inner {
-                                media_action = Some((i, action))
-                            }
-
-                            let rect = media_response.response.rect;
-                            media_infos.push(MediaInfo {
-                                url: media.url.clone(),
-                                original_position: rect,
-                            })
+    let carousel_resp = ui.add_sized(size, |ui: &mut egui::Ui| {
+        let output = scroll_area.show(ui, |ui| {
+            let mut item_lefts: Vec<f32> = Vec::with_capacity(medias.len());
+            let response = ui
+                .horizontal(|ui| {
+                    let spacing = ui.spacing_mut();
+                    spacing.item_spacing.x = 8.0;
+
+                    let mut media_infos: Vec<MediaInfo> = Vec::with_capacity(medias.len());
+                    let mut media_action: Option<(usize, MediaUIAction)> = None;
+
+                    for (i, media) in medias.iter().enumerate() {
+                        let trusted = note_options.contains(NoteOptions::TrustMedia)
+                            && !note_options.contains(NoteOptions::DataSaver)
+                            && (media.content_warning.is_none()
+                                || note_options.contains(NoteOptions::ShowSensitiveMedia));
+
+                        let media_response = render_media(
+                            ui,
+                            img_cache,
+                            job_pool,
+                            jobs,
+                            media,
+                            trusted,
+                            i18n,
+                            size,
+                            if note_options
+                                .intersects(NoteOptions::NoAnimations | NoteOptions::DataSaver)
+                            {
+                                Some(AnimationMode::NoAnimation)
+                            } else {
+                                None
+                            },
+                            if note_options.intersects(NoteOptions::Wide | NoteOptions::LargeMedia)
+                            {
+                                ScaledTextureFlags::SCALE_TO_WIDTH
+                            } else {
+                                ScaledTextureFlags::empty()
+                            },
+                            if note_options.contains(NoteOptions::NoMediaTransitions) {
+                                MediaTransition::Skip
+                            } else {
+                                MediaTransition::Blur(carousel_id)
+                            },
+                        );
+
+                        if let Some(action) = media_response.inner {
+                            media_action = Some((i, action))
                         }
 
-                        if let Some((i, media_action)) = media_action {
-                            action = media_action.into_media_action(
-                                ui.ctx(),
-                                medias,
-                                media_infos,
-                                i,
-                                img_cache,
-                                ImageType::Content(Some((size.x as u32, size.y as u32))),
-                            );
-                        }
-                    })
-                    .response;
-                ui.add_space(8.0);
-                response
-            })
-            .inner
+                        let rect = media_response.response.rect;
+                        item_lefts.push(rect.min.x);
+                        media_infos.push(MediaInfo {
+                            url: media.url.clone(),
+                            original_position: rect,
+                        })
+                    }
+
+                    if let Some((i, media_action)) = media_action {
+                        action = media_action.into_media_action(
+                            ui.ctx(),
+                            medias,
+                            media_infos,
+                            i,
+                            img_cache,
+                            ImageType::Content(Some((size.x as u32, size.y as u32))),
+                        );
+                    }
+                })
+                .response;
+            ui.add_space(8.0);
+            (response, item_lefts)
+        });
+
+        let (response, item_lefts) = output.inner;
+
+        if paged {
+            // item positions relative to the start of the scroll content
+            let offset = output.state.offset.x;
+            let origin = output.inner_rect.min.x - offset;
+            let max_offset = (output.content_size.x - output.inner_rect.width()).max(0.0);
+            carousel.item_offsets = item_lefts
+                .iter()
+                .map(|left| (left - origin).clamp(0.0, max_offset))
+                .collect();
+            carousel.offset = offset;
+        }
+
+        response
     });
 
+    if paged {
+        carousel.update_snap(ui);
+
+        if !notedeck::ui::is_narrow(ui.ctx()) {
+            carousel_chevrons(ui, carousel_id, &carousel_resp, &mut carousel, medias.len());
+        }
+
+        carousel_dots(ui, carousel_id, &mut carousel, medias.len());
+        carousel.store(ui.ctx(), carousel_id);
+    }
+
     action
 }
 
+/// Paging state for an [`image_carousel`] with more than one item
+#[derive(Clone, Default)]
+struct CarouselState {
+    /// The item the carousel is resting on
+    page: usize,
+    /// Scroll offset we are animating towards
+    snap_to: Option<f32>,
+    /// Scroll offset as of the last frame
+    offset: f32,
+    /// Scroll offset of each item, clamped to the scrollable range
+    item_offsets: Vec<f32>,
+}
+
+impl CarouselState {
+    fn load(ctx: &egui::Context, id: egui::Id) -> Self {
+        ctx.data(|d| d.get_temp(id.with("carousel_state")))
+            .unwrap_or_default()
+    }
+
+    fn store(self, ctx: &egui::Context, id: egui::Id) {
+        ctx.data_mut(|d| d.insert_temp(id.with("carousel_state"), self));
+    }
+
+    fn go_to(&mut self, page: usize) {
+        let Some(target) = self.item_offsets.get(page) else {
+            return;
+        };
+        self.page = page;
+        self.snap_to = Some(*target);
+    }
+
+    fn nearest_page(&self) -> usize {
+        self.item_offsets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (*a - self.offset)
+                    .abs()
+                    .total_cmp(&(*b - self.offset).abs())
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// Once the user stops scrolling, settle on a single item. A swipe
+    /// moves at most one page from where the carousel was resting.
+    fn update_snap(&mut self, ui: &egui::Ui) {
+        if let Some(target) = self.snap_to {
+            if (self.offset - target).abs() < 0.5 {
+                self.snap_to = None;
+            } else {
+                ui.ctx().request_repaint();
+            }
+            return;
+        }
+
+        let Some(resting) = self.item_offsets.get(self.page).copied() else {
+            self.page = 0;
+            return;
+        };
+
+        if (self.offset - resting).abs() < 0.5 {
+            return;
+        }
+
+        let idle = ui.input(|i| !i.pointer.any_down() && i.time_since_last_scroll() > 0.15);
+        if !idle {
+            // wake up again once scrolling has settled
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(150));
+            return;
+        }
+
+        let nearest = self.nearest_page();
+        let page = nearest.clamp(self.page.saturating_sub(1), self.page + 1);
+        self.go_to(page);
+    }
+}
+
+fn carousel_chevrons(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    carousel_resp: &Response,
+    carousel: &mut CarouselState,
+    count: usize,
+) {
+    if !carousel_resp.contains_pointer() {
+        return;
+    }
+
+    let rect = carousel_resp.rect;
+    let radius = 14.0;
+    let margin = 8.0 + radius;
+
+    let chevrons = [
+        (
+            carousel.page > 0,
+            rect.left_center() + vec2(margin, 0.0),
+            "‹",
+            -1,
+        ),
+        (
+            carousel.page + 1 < count,
+            rect.right_center() - vec2(margin, 0.0),
+            "›",
+            1,
+        ),
+    ];
+
+    for (enabled, center, glyph, dir) in chevrons {
+        if !enabled {
+            continue;
+        }
+
+        let button_rect = egui::Rect::from_center_size(center, Vec2::splat(radius * 2.0));
+        let resp = ui
+            .interact(button_rect, id.with(("chevron", dir)), egui::Sense::click())
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+        let alpha = if resp.hovered() { 220 } else { 160 };
+        let painter = ui.painter();
+        painter.circle_filled(center, radius, Color32::from_black_alpha(alpha));
+        painter.text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            glyph,
+            FontId::proportional(20.0),
+            Color32::WHITE,
+        );
+
+        if resp.clicked() {
+            let page = if dir < 0 {
+                carousel.page.saturating_sub(1)
+            } else {
+                carousel.page + 1
+            };
+            carousel.go_to(page);
+        }
+    }
+}
+
+fn carousel_dots(ui: &mut egui::Ui, id: egui::Id, carousel: &mut CarouselState, count: usize) {
+    let radius = 3.0;
+    let spacing = 10.0;
+    let width = spacing * (count as f32 - 1.0) + radius * 2.0;
+    let (rect, _) = ui.allocate_exact_size(vec2(ui.available_width(), 14.0), egui::Sense::hover());
+    let start = egui::pos2(rect.center().x - width / 2.0 + radius, rect.center().y);
+
+    let active = ui.visuals().strong_text_color();
+    let inactive = ui.visuals().weak_text_color();
+
+    for i in 0..count {
+        let center = start + vec2(spacing * i as f32, 0.0);
+        let hit = egui::Rect::from_center_size(center, Vec2::splat(spacing));
+        let resp = ui
+            .interact(hit, id.with(("carousel_dot", i)), egui::Sense::click())
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+        let color = if i == carousel.page { active } else { inactive };
+        ui.painter().circle_filled(center, radius, color);
+
+        if resp.clicked() {
+            carousel.go_to(i);
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn render_media(
     ui: &mut egui::Ui,
@@ -127,22 +348,34 @@ pub fn render_media(
     size: Vec2,
     animation_mode: Option<AnimationMode>,
     scale_flags: ScaledTextureFlags,
+    transition: MediaTransition,
 ) -> InnerResponse<Option<MediaUIAction>> {
     let RenderableMedia {
         url,
         media_type,
         obfuscation_type: blur_type,
+        alt,
+        dimensions,
+        content_warning,
     } = media;
 
+    // media behind a content warning stays hidden until it's tapped, even
+    // when another note already loaded it. Once tapped it stays revealed,
+    // also when its texture was evicted and has to be loaded again.
+    let revealed = img_cache.is_revealed(url);
+    let warning_hidden = content_warning.is_some() && !revealed;
+
     let cache = match media_type {
         MediaCacheType::Image => &mut img_cache.static_imgs,
         MediaCacheType::Gif => &mut img_cache.gifs,
     };
+
     let media_state = get_content_media_render_state(
         ui,
         job_pool,
         jobs,
-        trusted_media,
+        trusted_media || revealed,
+        warning_hidden,
         size,
         &mut cache.textures_cache,
         url,
@@ -160,20 +393,131 @@ pub fn render_media(
         AnimationMode::Continuous { fps }
     });
 
-    render_media_internal(
+    let mut resp = render_media_internal(
         ui,
         &mut img_cache.gif_states,
         media_state,
         url,
         size,
+        dimensions.as_ref(),
+        content_warning.as_deref(),
         i18n,
         scale_flags,
         animation_mode,
-    )
+        transition,
+    );
+
+    if matches!(resp.inner, Some(MediaUIAction::Unblur)) {
+        img_cache.reveal(url);
+    }
+
+    if let Some(alt) = alt {
+        resp.response = show_alt_text(ui, i18n, url, alt, resp.response);
+    }
+
+    resp
+}
+
+/// Expose the media description as a hover tooltip and to screen readers,
+/// plus an ALT badge that toggles the full description over the media
+fn show_alt_text(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    url: &str,
+    alt: &str,
+    response: Response,
+) -> Response {
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Image, true, alt));
+    let response = response.on_hover_text(alt);
+    let rect = response.rect;
+
+    let badge_galley =
+        ui.painter()
+            .layout_no_wrap("ALT".to_owned(), FontId::proportional(11.0), Color32::WHITE);
+    let badge_size = badge_galley.size() + vec2(8.0, 4.0);
+    let badge_rect = egui::Rect::from_min_size(
+        rect.left_bottom() + vec2(6.0, -6.0 - badge_size.y),
+        badge_size,
+    );
+
+    if !rect.contains_rect(badge_rect) {
+        return response;
+    }
+
+    let open_id = egui::Id::new(("alt_text_open", url));
+    let badge_resp = ui
+        .interact(badge_rect, open_id.with("badge"), egui::Sense::click())
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+        .on_hover_text(tr!(
+            i18n,
+            "Show media description",
+            "Hover text for the ALT badge on media"
+        ));
+
+    let mut open = ui.data(|d| d.get_temp::<bool>(open_id).unwrap_or(false));
+    if badge_resp.clicked() {
+        open = !open;
+        ui.data_mut(|d| d.insert_temp(open_id, open));
+    }
+
+    let painter = ui.painter_at(rect);
+
+    if open {
+        let text_galley = painter.layout(
+            alt.to_owned(),
+            NotedeckTextStyle::Small.get_font_id(ui.ctx()),
+            Color32::WHITE,
+            rect.width() - 16.0,
+        );
+        painter.rect_filled(rect, CornerRadius::same(5), Color32::from_black_alpha(200));
+        painter.galley(
+            rect.left_top() + vec2(8.0, 8.0),
+            text_galley,
+            Color32::WHITE,
+        );
+    }
+
+    painter.rect_filled(
+        badge_rect,
+        CornerRadius::same(4),
+        Color32::from_black_alpha(180),
+    );
+    painter.galley(
+        badge_rect.min + vec2(4.0, 2.0),
+        badge_galley,
+        Color32::WHITE,
+    );
+
+    response
+}
+
+/// How loaded media replaces its placeholder
+#[derive(Debug, Clone, Copy)]
+pub enum MediaTransition {
+    /// Settle the shimmer and fade the blurhash out over the image. The
+    /// state is kept per id and url, so the same media in another note
+    /// transitions on its own. Use the id of the note or its carousel.
+    Blur(egui::Id),
+    /// Show the image right away
+    Skip,
+}
+
+impl MediaTransition {
+    /// Ids of the shimmer and of the transition state for `url`
+    fn ids(&self, url: &str) -> Option<(egui::Id, egui::Id)> {
+        let MediaTransition::Blur(scope) = self else {
+            return None;
+        };
+
+        let id = scope.with(("blur", url));
+        Some((id.with("shimmer"), id.with("transition")))
+    }
 }
 
 pub enum MediaUIAction {
     Unblur,
+    /// The error card was tapped
+    Retry,
     Error,
     DoneLoading,
     Clicked,
@@ -197,9 +541,10 @@ impl MediaUIAction {
             MediaUIAction::Clicked => Some(MediaAction::ViewMedias(ViewMediaInfo {
                 clicked_index: selected,
                 medias: responses,
+                source: None,
             })),
 
-            MediaUIAction::Unblur => {
+            MediaUIAction::Unblur | MediaUIAction::Retry => {
                 let url = &medias[selected].url;
                 let cache = img_cache.get_cache(medias[selected].media_type);
                 let cache_type = cache.cache_type;
@@ -244,6 +589,7 @@ pub fn get_content_media_render_state<'a>(
     job_pool: &'a mut JobPool,
     jobs: &'a mut JobsCache,
     media_trusted: bool,
+    warning_hidden: bool,
     size: Vec2,
     cache: &'a mut TexturesCache,
     url: &'a str,
@@ -251,7 +597,18 @@ pub fn get_content_media_render_state<'a>(
     cache_dir: &Path,
     obfuscation_type: &'a ObfuscationType,
 ) -> MediaRenderState<'a> {
-    let render_type = if media_trusted {
+    let retry_wait = cache.time_until_retry(url);
+
+    let render_type = if warning_hidden && !media_trusted {
+        return MediaRenderState::Obfuscated(get_obfuscated(
+            ui,
+            url,
+            obfuscation_type,
+            job_pool,
+            jobs,
+            size,
+        ));
+    } else if media_trusted {
         cache.handle_and_get_or_insert_loadable(url, || {
             notedeck::media::images::fetch_img(
                 cache_dir,
@@ -283,7 +640,13 @@ pub fn get_content_media_render_state<'a>(
             jobs,
             size,
         )),
-        notedeck::LoadableTextureState::Error(e) => MediaRenderState::Error(e),
+        notedeck::LoadableTextureState::Error(e) => {
+            // wake up for the next automatic retry
+            if let Some(wait) = retry_wait {
+                ui.ctx().request_repaint_after(wait);
+            }
+            MediaRenderState::Error(e)
+        }
         notedeck::LoadableTextureState::Loading { actual_image_tex } => {
             let obfuscation = get_obfuscated(ui, url, obfuscation_type, job_pool, jobs, size);
             MediaRenderState::Transitioning {
@@ -309,8 +672,12 @@ fn get_obfuscated<'a>(
         return ObfuscatedTexture::Default;
     };
 
+    let Some(blurhash) = &renderable_blur.blurhash else {
+        return ObfuscatedTexture::Default;
+    };
+
     let params = BlurhashParams {
-        blurhash: &renderable_blur.blurhash,
+        blurhash,
         url,
         ctx: ui.ctx(),
     };
@@ -370,10 +737,27 @@ fn render_media_internal(
     render_state: MediaRenderState,
     url: &str,
     size: egui::Vec2,
+    dimensions: Option<&PixelDimensions>,
+    content_warning: Option<&str>,
     i18n: &mut Localization,
     scale_flags: ScaledTextureFlags,
     animation_mode: AnimationMode,
+    transition: MediaTransition,
 ) -> egui::InnerResponse<Option<MediaUIAction>> {
+    let ids = transition.ids(url);
+    let shimmer_id = ids.map(|(shimmer, _)| shimmer);
+
+    if let Some((_, transition_id)) = ids {
+        // anything before the image arrives starts the transition over,
+        // like reloading after the texture was evicted
+        if matches!(
+            render_state,
+            MediaRenderState::Shimmering(_) | MediaRenderState::Obfuscated(_)
+        ) {
+            ui.data_mut(|d| d.remove::<BlurTransition>(transition_id));
+        }
+    }
+
     match render_state {
         MediaRenderState::ActualImage(image) => {
             let resp = render_success_media(
@@ -392,11 +776,12 @@ fn render_media_internal(
                 egui::InnerResponse::new(None, resp)
             }
         }
-        MediaRenderState::Transitioning { image, obfuscation } => match obfuscation {
-            ObfuscatedTexture::Blur(texture) => {
+        MediaRenderState::Transitioning { image, obfuscation } => match (obfuscation, ids) {
+            (ObfuscatedTexture::Blur(texture), Some((shimmer_id, transition_id))) => {
                 let resp = render_blur_transition(
                     ui,
-                    url,
+                    shimmer_id,
+                    transition_id,
                     size,
                     texture,
                     image.get_first_texture(),
@@ -408,35 +793,42 @@ fn render_media_internal(
                     egui::InnerResponse::new(None, resp.response)
                 }
             }
-            ObfuscatedTexture::Default => {
+            _ => {
                 let scaled = ScaledTexture::new(image.get_first_texture(), size, scale_flags);
                 let resp = ui.add(scaled.get_image());
                 egui::InnerResponse::new(Some(MediaUIAction::DoneLoading), resp)
             }
         },
         MediaRenderState::Error(e) => {
-            let response = ui.allocate_response(size, egui::Sense::hover());
             show_one_error_message(ui, &format!("Could not render media {url}: {e}"));
-            egui::InnerResponse::new(Some(MediaUIAction::Error), response)
+            let response = render_error_card(
+                ui,
+                i18n,
+                size,
+                dimensions,
+                scale_flags.contains(ScaledTextureFlags::SCALE_TO_WIDTH),
+            );
+            if response.clicked() {
+                egui::InnerResponse::new(Some(MediaUIAction::Retry), response)
+            } else {
+                egui::InnerResponse::new(Some(MediaUIAction::Error), response)
+            }
         }
         MediaRenderState::Shimmering(obfuscated_texture) => match obfuscated_texture {
             ObfuscatedTexture::Blur(texture_handle) => egui::InnerResponse::new(
                 None,
-                shimmer_blurhash(texture_handle, ui, url, size, scale_flags),
+                shimmer_blurhash(texture_handle, ui, shimmer_id, size, scale_flags),
+            ),
+            ObfuscatedTexture::Default => egui::InnerResponse::new(
+                None,
+                render_default_blur_bg(
+                    ui,
+                    size,
+                    dimensions,
+                    shimmer_id,
+                    scale_flags.contains(ScaledTextureFlags::SCALE_TO_WIDTH),
+                ),
             ),
-            ObfuscatedTexture::Default => {
-                let shimmer = true;
-                egui::InnerResponse::new(
-                    None,
-                    render_default_blur_bg(
-                        ui,
-                        size,
-                        url,
-                        shimmer,
-                        scale_flags.contains(ScaledTextureFlags::SCALE_TO_WIDTH),
-                    ),
-                )
-            }
         },
         MediaRenderState::Obfuscated(obfuscated_texture) => {
             let resp = match obfuscated_texture {
@@ -444,12 +836,14 @@ fn render_media_internal(
                     let scaled = ScaledTexture::new(texture_handle, size, scale_flags);
 
                     let resp = ui.add(scaled.get_image());
-                    render_blur_text(ui, i18n, url, resp.rect)
+                    render_blur_text(ui, i18n, url, content_warning, resp.rect)
                 }
                 ObfuscatedTexture::Default => render_default_blur(
                     ui,
                     i18n,
                     size,
+                    dimensions,
+                    content_warning,
                     url,
                     scale_flags.contains(ScaledTextureFlags::SCALE_TO_WIDTH),
                 ),
@@ -469,6 +863,7 @@ fn render_blur_text(
     ui: &mut egui::Ui,
     i18n: &mut Localization,
     url: &str,
+    content_warning: Option<&str>,
     render_rect: egui::Rect,
 ) -> egui::Response {
     let helper = AnimationHelper::new_from_rect(ui, ("show_media", url), render_rect);
@@ -482,13 +877,27 @@ fn render_blur_text(
         helper.scale_1d_pos(get_font_size(ui.ctx(), &text_style)),
         text_style.font_family(),
     );
-    let info_galley = painter.layout(
-        tr!(
+    let info_text = match content_warning {
+        Some("") => tr!(
+            i18n,
+            "Content warning",
+            "Text shown on blurred media marked as sensitive"
+        ),
+        Some(reason) => tr!(
+            i18n,
+            "Content warning: {reason}",
+            "Text shown on blurred media marked as sensitive, with the reason given by the author",
+            reason = reason
+        ),
+        None => tr!(
             i18n,
             "Media from someone you don't follow",
             "Text shown on blurred media from unfollowed users"
-        )
-        .to_owned(),
+        ),
+    };
+
+    let info_galley = painter.layout(
+        info_text,
         animation_fontid.clone(),
         ui.visuals().text_color(),
         render_rect.width() / 2.0,
@@ -551,39 +960,108 @@ fn render_blur_text(
     helper.take_animation_response()
 }
 
+/// Placeholder for media that failed to load, tapping it fetches it again
+fn render_error_card(
+    ui: &mut egui::Ui,
+    i18n: &mut Localization,
+    size: egui::Vec2,
+    dimensions: Option<&PixelDimensions>,
+    is_scaled: bool,
+) -> egui::Response {
+    let size = placeholder_size(size, dimensions, is_scaled);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let response = response.on_hover_cursor(egui::CursorIcon::PointingHand);
+
+    let visuals = ui.visuals();
+    let fill = if response.hovered() {
+        visuals.widgets.hovered.bg_fill
+    } else {
+        visuals.widgets.noninteractive.bg_fill
+    };
+    let text_color = visuals.weak_text_color();
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, CornerRadius::same(8), fill);
+
+    let font = NotedeckTextStyle::Small.get_font_id(ui.ctx());
+    painter.text(
+        rect.center() - vec2(0.0, font.size),
+        egui::Align2::CENTER_CENTER,
+        tr!(
+            i18n,
+            "Couldn't load media",
+            "Shown when media failed to load"
+        ),
+        font.clone(),
+        text_color,
+    );
+    painter.text(
+        rect.center() + vec2(0.0, font.size),
+        egui::Align2::CENTER_CENTER,
+        tr!(i18n, "Tap to retry", "Button text to retry loading media"),
+        font,
+        ui.visuals().hyperlink_color,
+    );
+
+    response
+}
+
 fn render_default_blur(
     ui: &mut egui::Ui,
     i18n: &mut Localization,
     size: egui::Vec2,
+    dimensions: Option<&PixelDimensions>,
+    content_warning: Option<&str>,
     url: &str,
     is_scaled: bool,
 ) -> egui::Response {
-    let shimmer = false;
-    let response = render_default_blur_bg(ui, size, url, shimmer, is_scaled);
-    render_blur_text(ui, i18n, url, response.rect)
+    let response = render_default_blur_bg(ui, size, dimensions, None, is_scaled);
+    render_blur_text(ui, i18n, url, content_warning, response.rect)
+}
+
+/// The size a placeholder should take up before the media is loaded. When
+/// we know the media dimensions from imeta we match what [`ScaledTexture`]
+/// will produce, so nothing shifts when the texture arrives.
+fn placeholder_size(
+    max_size: Vec2,
+    dimensions: Option<&PixelDimensions>,
+    scale_to_width: bool,
+) -> Vec2 {
+    let Some(dims) = dimensions.filter(|d| d.x > 0 && d.y > 0) else {
+        return if scale_to_width {
+            max_size
+        } else {
+            vec2(max_size.y, max_size.y)
+        };
+    };
+
+    let aspect = dims.x as f32 / dims.y as f32;
+
+    if scale_to_width {
+        vec2(max_size.x, max_size.x / aspect)
+    } else {
+        let height = (dims.y as f32).min(max_size.y);
+        vec2(height * aspect, height)
+    }
 }
 
 fn render_default_blur_bg(
     ui: &mut egui::Ui,
     size: egui::Vec2,
-    url: &str,
-    shimmer: bool,
+    dimensions: Option<&PixelDimensions>,
+    shimmer_id: Option<egui::Id>,
     is_scaled: bool,
 ) -> egui::Response {
-    let size = if is_scaled {
-        size
-    } else {
-        vec2(size.y, size.y)
-    };
+    let size = placeholder_size(size, dimensions, is_scaled);
 
     let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
 
     let painter = ui.painter_at(rect);
 
     let mut color = crate::colors::MID_GRAY;
-    if shimmer {
+    if let Some(shimmer_id) = shimmer_id {
         let [r, g, b, _a] = color.to_srgba_unmultiplied();
-        let cur_alpha = get_blur_current_alpha(ui, url);
+        let cur_alpha = get_blur_current_alpha(ui, shimmer_id);
         color = Color32::from_rgba_unmultiplied(r, g, b, cur_alpha)
     }
 
@@ -640,6 +1118,11 @@ fn render_success_media(
     scale_flags: ScaledTextureFlags,
     animation_mode: AnimationMode,
 ) -> Response {
+    let animation_mode = animation_mode_if_visible(
+        ui,
+        egui::Rect::from_min_size(ui.cursor().min, size),
+        animation_mode,
+    );
     let texture = ensure_latest_texture(ui, url, gifs, tex, animation_mode);
 
     let scaled = ScaledTexture::new(&texture, size, scale_flags);
@@ -658,17 +1141,19 @@ fn texture_to_image<'a>(tex: &TextureHandle, size: Vec2) -> egui::Image<'a> {
         .maintain_aspect_ratio(true)
 }
 
-static BLUR_SHIMMER_ID: fn(&str) -> egui::Id = |url| egui::Id::new(("blur_shimmer", url));
-
-fn get_blur_current_alpha(ui: &mut egui::Ui, url: &str) -> u8 {
-    let id = BLUR_SHIMMER_ID(url);
-
+fn get_blur_current_alpha(ui: &mut egui::Ui, shimmer_id: egui::Id) -> u8 {
     let (alpha_min, alpha_max) = if ui.visuals().dark_mode {
         (150, 255)
     } else {
         (220, 255)
     };
-    PulseAlpha::new(ui.ctx(), id, alpha_min, alpha_max)
+
+    // a shimmer that can't move would only flicker
+    if notedeck::motion::reduce_motion(ui.ctx()) {
+        return alpha_max;
+    }
+
+    PulseAlpha::new(ui.ctx(), shimmer_id, alpha_min, alpha_max)
         .with_speed(0.3)
         .start_max_alpha()
         .animate()
@@ -677,11 +1162,14 @@ fn get_blur_current_alpha(ui: &mut egui::Ui, url: &str) -> u8 {
 fn shimmer_blurhash(
     tex: &TextureHandle,
     ui: &mut egui::Ui,
-    url: &str,
+    shimmer_id: Option<egui::Id>,
     size: Vec2,
     scale_flags: ScaledTextureFlags,
 ) -> egui::Response {
-    let cur_alpha = get_blur_current_alpha(ui, url);
+    let cur_alpha = match shimmer_id {
+        Some(shimmer_id) => get_blur_current_alpha(ui, shimmer_id),
+        None => 255,
+    };
 
     let scaled = ScaledTexture::new(tex, size, scale_flags);
     let img = scaled.get_image();
@@ -704,7 +1192,8 @@ type FinishedTransition = bool;
 // return true if transition is finished
 fn render_blur_transition(
     ui: &mut egui::Ui,
-    url: &str,
+    shimmer_id: egui::Id,
+    transition_id: egui::Id,
     size: Vec2,
     blur_texture: &TextureHandle,
     image_texture: &TextureHandle,
@@ -713,13 +1202,43 @@ fn render_blur_transition(
     let scaled_texture = ScaledTexture::new(image_texture, size, scale_flags);
     let scaled_blur_img = ScaledTexture::new(blur_texture, size, scale_flags);
 
-    match get_blur_transition_state(ui.ctx(), url) {
+    let now = ui.input(|i| i.time);
+    let stored = ui
+        .data(|d| d.get_temp::<BlurTransition>(transition_id))
+        // a new texture for the same url starts over
+        .filter(|t| t.texture == image_texture.id());
+    let transition = match stored {
+        Some(transition) => transition,
+        None => {
+            let transition = BlurTransition {
+                texture: image_texture.id(),
+                from_alpha: get_blur_current_alpha(ui, shimmer_id),
+                started: now,
+                speed: notedeck::motion::anim_time(ui.ctx(), BLUR_TRANSITION_TIME),
+            };
+            ui.data_mut(|d| d.insert_temp(transition_id, transition));
+            transition
+        }
+    };
+
+    let state = transition.state(now);
+    if state != BlurTransitionState::Done {
+        ui.ctx().request_repaint();
+    }
+
+    match state {
         BlurTransitionState::StoppingShimmer { cur_alpha } => egui::InnerResponse::new(
             false,
             show_blurhash_with_alpha(ui, scaled_blur_img.get_image(), cur_alpha),
         ),
-        BlurTransitionState::FadingBlur => {
-            render_blur_fade(ui, url, scaled_blur_img.get_image(), &scaled_texture)
+        BlurTransitionState::FadingBlur { cur_alpha } => egui::InnerResponse::new(
+            false,
+            render_blur_fade(ui, scaled_blur_img.get_image(), &scaled_texture, cur_alpha),
+        ),
+        // stays done until the image is evicted, so a late DoneLoading
+        // doesn't replay it
+        BlurTransitionState::Done => {
+            egui::InnerResponse::new(true, ui.add(scaled_texture.get_image()))
         }
     }
 }
@@ -788,19 +1307,10 @@ impl<'a> ScaledTexture<'a> {
 
 fn render_blur_fade(
     ui: &mut egui::Ui,
-    url: &str,
     blur_img: Image,
     image_texture: &ScaledTexture,
-) -> egui::InnerResponse<FinishedTransition> {
-    let blur_fade_id = ui.id().with(("blur_fade", url));
-
-    let cur_alpha = {
-        PulseAlpha::new(ui.ctx(), blur_fade_id, 0, 255)
-            .start_max_alpha()
-            .with_speed(0.3)
-            .animate()
-    };
-
+    cur_alpha: u8,
+) -> egui::Response {
     let img = image_texture.get_image();
 
     let blur_img = blur_img.tint(fade_color(cur_alpha));
@@ -812,23 +1322,105 @@ fn render_blur_fade(
     img.paint_at(ui, rect);
     blur_img.paint_at(ui, rect);
 
-    egui::InnerResponse::new(cur_alpha == 0, resp)
+    resp
 }
 
-fn get_blur_transition_state(ctx: &Context, url: &str) -> BlurTransitionState {
-    let shimmer_id = BLUR_SHIMMER_ID(url);
+/// Seconds for each step of the blur transition
+const BLUR_TRANSITION_TIME: f32 = 0.3;
+
+/// A blurhash giving way to the loaded image: first the shimmer settles
+/// to full alpha, then the blurhash fades out over the image. Kept per
+/// note and url, see [`MediaTransition`].
+#[derive(Debug, Clone, Copy)]
+struct BlurTransition {
+    /// The image this is for. After an eviction the url gets a new
+    /// texture, which transitions again.
+    texture: egui::TextureId,
+    /// Shimmer alpha when the image arrived
+    from_alpha: u8,
+    /// Input time the image arrived
+    started: f64,
+    /// Seconds per step, 0 to skip them
+    speed: f32,
+}
 
-    let max_alpha = 255.0;
-    let cur_shimmer_alpha = ctx.animate_value_with_time(shimmer_id, max_alpha, 0.3);
-    if cur_shimmer_alpha == max_alpha {
-        BlurTransitionState::FadingBlur
-    } else {
-        let cur_alpha = (cur_shimmer_alpha).clamp(0.0, max_alpha) as u8;
-        BlurTransitionState::StoppingShimmer { cur_alpha }
+impl BlurTransition {
+    fn state(&self, now: f64) -> BlurTransitionState {
+        if self.speed <= 0.0 {
+            return BlurTransitionState::Done;
+        }
+
+        let elapsed = (now - self.started).max(0.0) as f32;
+
+        // the shimmer only has its remaining way up to go
+        let settle = self.speed * (255 - self.from_alpha) as f32 / 255.0;
+        if elapsed < settle {
+            let t = elapsed / settle;
+            let alpha = self.from_alpha as f32 + t * (255 - self.from_alpha) as f32;
+            return BlurTransitionState::StoppingShimmer {
+                cur_alpha: alpha as u8,
+            };
+        }
+
+        let t = (elapsed - settle) / self.speed;
+        if t >= 1.0 {
+            BlurTransitionState::Done
+        } else {
+            BlurTransitionState::FadingBlur {
+                cur_alpha: ((1.0 - t) * 255.0) as u8,
+            }
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum BlurTransitionState {
     StoppingShimmer { cur_alpha: u8 },
-    FadingBlur,
+    FadingBlur { cur_alpha: u8 },
+    Done,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(from_alpha: u8, speed: f32) -> BlurTransition {
+        BlurTransition {
+            texture: egui::TextureId::Managed(1),
+            from_alpha,
+            started: 10.0,
+            speed,
+        }
+    }
+
+    #[test]
+    fn blur_transition_settles_then_fades() {
+        let t = transition(0, 1.0);
+
+        // the shimmer settles to full alpha first
+        assert_eq!(
+            t.state(10.5),
+            BlurTransitionState::StoppingShimmer { cur_alpha: 127 }
+        );
+        // then the blurhash fades out over the image
+        assert_eq!(
+            t.state(11.5),
+            BlurTransitionState::FadingBlur { cur_alpha: 127 }
+        );
+        assert_eq!(t.state(12.0), BlurTransitionState::Done);
+        assert_eq!(t.state(20.0), BlurTransitionState::Done);
+
+        // a shimmer that was already up goes straight to fading
+        assert_eq!(
+            transition(255, 1.0).state(10.0),
+            BlurTransitionState::FadingBlur { cur_alpha: 255 }
+        );
+    }
+
+    #[test]
+    fn blur_transition_is_skipped_without_animations() {
+        let t = transition(0, 0.0);
+        assert_eq!(t.state(10.0), BlurTransitionState::Done);
+        assert_eq!(t.state(10.5), BlurTransitionState::Done);
+    }
 }
@@ -5,11 +5,14 @@ pub mod options;
 pub mod reply_description;
 
 use crate::{app_images, secondary_label};
-use crate::{widgets::x_button, ProfilePic, ProfilePreview, PulseAlpha, Username};
+use crate::{
+    widgets::x_button, ProfilePic, ProfilePreview, PublishReceiptView, PulseAlpha, Username,
+};
 
-pub use contents::{render_note_preview, NoteContents};
+pub use contents::{prefetch_note_media, render_note_preview, NoteContents};
 pub use context::NoteContextButton;
 use notedeck::get_current_wallet;
+use notedeck::media::AnimationMode;
 use notedeck::note::{reaction_sent_id, ZapTargetAmount};
 use notedeck::ui::is_narrow;
 use notedeck::Accounts;
@@ -18,6 +21,7 @@ use notedeck::Images;
 use notedeck::JobsCache;
 use notedeck::Localization;
 use notedeck::MediaAction;
+use notedeck::PublishReceipt;
 pub use options::NoteOptions;
 pub use reply_description::reply_desc;
 
@@ -223,7 +227,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
     fn textmode_ui(&mut self, ui: &mut egui::Ui) -> egui::Response {
         let txn = self.note.txn().expect("todo: implement non-db notes");
 
-        ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
+        ui.with_layout(crate::row_layout(ui, egui::Align::TOP), |ui| {
             let profile = self
                 .note_context
                 .ndb
@@ -280,6 +284,14 @@ impl<'a, 'd> NoteView<'a, 'd> {
         }
 
         let pfp_size = self.options().pfp_size();
+        let animation_mode = if self
+            .options()
+            .intersects(NoteOptions::NoAnimations | NoteOptions::DataSaver)
+        {
+            AnimationMode::NoAnimation
+        } else {
+            AnimationMode::Reactive
+        };
 
         match profile
             .as_ref()
@@ -295,6 +307,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
                 pfp_size,
                 note_key,
                 profile,
+                animation_mode,
             ),
 
             None => show_fallback_pfp(ui, self.note_context.img_cache, pfp_size),
@@ -378,12 +391,12 @@ impl<'a, 'd> NoteView<'a, 'd> {
         note_key: NoteKey,
         profile: &Result<ProfileRecord, nostrdb::Error>,
     ) -> egui::InnerResponse<NoteUiResponse> {
-        ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+        ui.with_layout(crate::column_layout(ui), |ui| {
             let mut note_action: Option<NoteAction> = None;
             let mut pfp_rect = None;
 
             if !self.flags.contains(NoteOptions::NotificationPreview) {
-                ui.horizontal(|ui| {
+                ui.with_layout(crate::row_layout(ui, egui::Align::Center), |ui| {
                     let pfp_resp = self.pfp(note_key, profile, ui);
                     pfp_rect = Some(pfp_resp.bounding_rect);
                     note_action = pfp_resp
@@ -463,6 +476,9 @@ impl<'a, 'd> NoteView<'a, 'd> {
                             self.note.pubkey(),
                             self.note_context.accounts.selected_account_pubkey(),
                             note_key,
+                            self.note_context
+                                .publish_tracker
+                                .receipt(&NoteId::new(*self.note.id())),
                             self.note_context.i18n,
                         )
                     })
@@ -485,7 +501,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
         profile: &Result<ProfileRecord, nostrdb::Error>,
     ) -> egui::InnerResponse<NoteUiResponse> {
         // main design
-        ui.with_layout(egui::Layout::left_to_right(egui::Align::TOP), |ui| {
+        ui.with_layout(crate::row_layout(ui, egui::Align::TOP), |ui| {
             let (mut note_action, pfp_rect) =
                 if self.flags.contains(NoteOptions::NotificationPreview) {
                     // do not render pfp
@@ -496,7 +512,7 @@ impl<'a, 'd> NoteView<'a, 'd> {
                     (pfp_resp.into_action(self.note.pubkey()), Some(pfp_rect))
                 };
 
-            ui.with_layout(egui::Layout::top_down(egui::Align::LEFT), |ui| {
+            ui.with_layout(crate::column_layout(ui), |ui| {
                 if !self.flags.contains(NoteOptions::NotificationPreview) {
                     NoteView::note_header(
                         ui,
@@ -552,6 +568,9 @@ impl<'a, 'd> NoteView<'a, 'd> {
                                 self.note.pubkey(),
                                 self.note_context.accounts.selected_account_pubkey(),
                                 note_key,
+                                self.note_context
+                                    .publish_tracker
+                                    .receipt(&NoteId::new(*self.note.id())),
                                 self.note_context.i18n,
                             )
                         })
@@ -599,8 +618,14 @@ impl<'a, 'd> NoteView<'a, 'd> {
                 Rect::from_min_size(min, egui::vec2(size, size))
             };
 
+            let acc = self.note_context.accounts.get_selected_account();
+            let following = (acc.key.secret_key.is_some()
+                && acc.key.pubkey.bytes() != self.note.pubkey())
+            .then(|| acc.is_following(self.note.pubkey()));
+
             let resp = ui.add(NoteContextButton::new(note_key).place_at(context_pos));
-            if let Some(action) = NoteContextButton::menu(ui, self.note_context.i18n, resp.clone())
+            if let Some(action) =
+                NoteContextButton::menu(ui, self.note_context.i18n, resp.clone(), following)
             {
                 note_action = Some(NoteAction::Context(ContextSelection { note_key, action }));
             }
@@ -687,6 +712,7 @@ fn show_actual_pfp(
     pfp_size: i8,
     note_key: NoteKey,
     profile: &Result<nostrdb::ProfileRecord<'_>, nostrdb::Error>,
+    animation_mode: AnimationMode,
 ) -> PfpResponse {
     let anim_speed = 0.05;
     let profile_key = profile.as_ref().unwrap().record().note_key();
@@ -702,7 +728,9 @@ fn show_actual_pfp(
 
     let resp = resp.on_hover_cursor(egui::CursorIcon::PointingHand);
 
-    let mut pfp = ProfilePic::new(images, pic).size(size);
+    let mut pfp = ProfilePic::new(images, pic)
+        .size(size)
+        .animation_mode(animation_mode);
     let pfp_resp = ui.put(rect, &mut pfp);
     let action = pfp.action;
 
@@ -852,6 +880,7 @@ fn render_note_actionbar(
     note_pubkey: &[u8; 32],
     current_user_pubkey: &Pubkey,
     note_key: NoteKey,
+    receipt: Option<&PublishReceipt>,
     i18n: &mut Localization,
 ) -> Option<NoteAction> {
     let mut action = None;
@@ -890,6 +919,11 @@ fn render_note_actionbar(
 
     action = zap_actionbar_button(ui, note_id, note_pubkey, zapper, i18n).or(action);
 
+    // notes we just published show which relays took them
+    if let Some(receipt) = receipt {
+        ui.add(PublishReceiptView::new(receipt, i18n));
+    }
+
     action
 }
 
@@ -1025,9 +1059,13 @@ fn zap_button<'a>(
             }
             AnyZapState::Pending => {
                 let alpha_min = if ui.visuals().dark_mode { 50 } else { 180 };
-                let cur_alpha = PulseAlpha::new(&ctx, id, alpha_min, 255)
-                    .with_speed(0.35)
-                    .animate();
+                let cur_alpha = if notedeck::motion::reduce_motion(&ctx) {
+                    255
+                } else {
+                    PulseAlpha::new(&ctx, id, alpha_min, 255)
+                        .with_speed(0.35)
+                        .animate()
+                };
 
                 let cur_color = egui::Color32::from_rgba_unmultiplied(0xFF, 0xB7, 0x57, cur_alpha);
                 img = img.tint(cur_color);
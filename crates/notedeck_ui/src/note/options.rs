@@ -47,6 +47,24 @@ bitflags! {
 
         /// There is enough trust to show media in this note
         const TrustMedia = 1 << 20;
+
+        /// Don't fetch media until it is tapped and don't animate gifs,
+        /// regardless of trust
+        const DataSaver = 1 << 21;
+
+        /// The account opted to show media behind content warnings without
+        /// an extra tap
+        const ShowSensitiveMedia = 1 << 22;
+
+        /// Show media as soon as it loads instead of fading in from its
+        /// blurhash
+        const NoMediaTransitions = 1 << 23;
+
+        /// Media in notes is 180px tall instead of 360px
+        const CompactMedia = 1 << 24;
+
+        /// Media in notes fills the column's width
+        const LargeMedia = 1 << 25;
     }
 }
 
@@ -75,4 +93,15 @@ impl NoteOptions {
             ProfilePic::default_size()
         }
     }
+
+    /// How tall media in a note is, given the width it has
+    pub fn media_height(&self, available_width: f32) -> f32 {
+        if self.contains(NoteOptions::CompactMedia) {
+            180.0
+        } else if self.contains(NoteOptions::LargeMedia) {
+            available_width
+        } else {
+            360.0
+        }
+    }
 }
@@ -14,9 +14,16 @@ pub struct ProfilePic<'cache, 'url> {
     sense: Sense,
     border: Option<Stroke>,
     animation_mode: AnimationMode,
+    /// Animate gifs, or only show their first frame until hovered. By
+    /// default only pictures of at least [`ANIMATED_PFP_SIZE`] animate.
+    animate: Option<bool>,
     pub action: Option<MediaAction>,
 }
 
+/// Smaller profile pictures show the first frame of a gif, since there
+/// can be many of them on screen at once
+pub const ANIMATED_PFP_SIZE: f32 = 48.0;
+
 impl egui::Widget for &mut ProfilePic<'_, '_> {
     #[profiling::function]
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
@@ -28,6 +35,7 @@ impl egui::Widget for &mut ProfilePic<'_, '_> {
             self.border,
             self.sense,
             self.animation_mode,
+            self.animate.unwrap_or(self.size >= ANIMATED_PFP_SIZE),
         );
 
         self.action = inner.inner;
@@ -47,6 +55,7 @@ impl<'cache, 'url> ProfilePic<'cache, 'url> {
             url,
             size,
             animation_mode: AnimationMode::Reactive,
+            animate: None,
             border: None,
             action: None,
         }
@@ -62,6 +71,12 @@ impl<'cache, 'url> ProfilePic<'cache, 'url> {
         self
     }
 
+    /// Always animate gifs, or only on hover, regardless of size
+    pub fn animate(mut self, animate: bool) -> Self {
+        self.animate = Some(animate);
+        self
+    }
+
     pub fn border_stroke(ui: &egui::Ui) -> Stroke {
         Stroke::new(4.0, ui.visuals().panel_fill)
     }
@@ -127,6 +142,7 @@ fn render_pfp(
     border: Option<Stroke>,
     sense: Sense,
     animation_mode: AnimationMode,
+    animate: bool,
 ) -> InnerResponse<Option<MediaAction>> {
     // We will want to downsample these so it's not blurry on hi res displays
     let img_size = 128u32;
@@ -159,10 +175,16 @@ fn render_pfp(
             )
         }
         notedeck::TextureState::Loaded(textured_image) => {
-            let texture_handle =
-                ensure_latest_texture(ui, url, cur_state.gifs, textured_image, animation_mode);
+            let (rect, response) = ui.allocate_at_least(vec2(ui_size, ui_size), sense);
+
+            let texture_handle = if animate || response.hovered() {
+                ensure_latest_texture(ui, url, cur_state.gifs, textured_image, animation_mode)
+            } else {
+                textured_image.get_first_texture().clone()
+            };
 
-            egui::InnerResponse::new(None, pfp_image(ui, &texture_handle, ui_size, border, sense))
+            pfp_image(ui, rect, &texture_handle, ui_size, border);
+            egui::InnerResponse::new(None, response)
         }
     }
 }
@@ -170,18 +192,15 @@ fn render_pfp(
 #[profiling::function]
 fn pfp_image(
     ui: &mut egui::Ui,
+    rect: egui::Rect,
     img: &TextureHandle,
     size: f32,
     border: Option<Stroke>,
-    sense: Sense,
-) -> egui::Response {
-    let (rect, response) = ui.allocate_at_least(vec2(size, size), sense);
+) {
     if let Some(stroke) = border {
         draw_bg_border(ui, rect.center(), size, stroke);
     }
     ui.put(rect, egui::Image::new(img).max_width(size));
-
-    response
 }
 
 fn paint_circle(
@@ -0,0 +1,100 @@
+use egui::RichText;
+use notedeck::{tr, Localization, PublishReceipt, RelayReceipt};
+
+use crate::colors;
+
+/// How many relays took a note we published. Hovering lists every relay
+/// with its answer.
+pub struct PublishReceiptView<'a> {
+    receipt: &'a PublishReceipt,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> PublishReceiptView<'a> {
+    pub fn new(receipt: &'a PublishReceipt, i18n: &'a mut Localization) -> Self {
+        Self { receipt, i18n }
+    }
+}
+
+impl egui::Widget for PublishReceiptView<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let receipt = self.receipt;
+        if receipt.is_queued() {
+            let text = tr!(
+                self.i18n,
+                "Waiting for a connection to send",
+                "Shown under a note that was published while offline"
+            );
+            return ui.add(
+                egui::Label::new(
+                    RichText::new(text)
+                        .size(10.0)
+                        .color(ui.visuals().warn_fg_color),
+                )
+                .selectable(false),
+            );
+        }
+
+        let pending = receipt.is_pending();
+        if pending {
+            // the OKs come in over the next frames
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(500));
+        }
+
+        let color = if receipt.accepted() == 0 && !pending {
+            ui.visuals().error_fg_color
+        } else {
+            ui.visuals().weak_text_color()
+        };
+
+        let summary = tr!(
+            self.i18n,
+            "Sent to {accepted} of {total} relays",
+            "Summary of how many relays accepted a published note",
+            accepted = receipt.accepted(),
+            total = receipt.total()
+        );
+
+        let resp = ui
+            .horizontal(|ui| {
+                if pending {
+                    ui.add(egui::Spinner::new().size(10.0));
+                }
+                ui.add(
+                    egui::Label::new(RichText::new(summary).size(10.0).color(color))
+                        .selectable(false),
+                );
+            })
+            .response;
+
+        let accepted_label = tr!(self.i18n, "accepted", "A relay stored a published note");
+        let waiting_label = tr!(self.i18n, "waiting", "Still waiting on a relay's answer");
+        let no_answer_label = tr!(
+            self.i18n,
+            "no answer",
+            "A relay didn't answer about a published note"
+        );
+
+        resp.on_hover_ui(|ui| {
+            for (relay, answer) in receipt.relays() {
+                let (text, color) = match answer {
+                    RelayReceipt::Accepted => (accepted_label.clone(), colors::TEAL),
+                    RelayReceipt::Pending if pending => {
+                        (waiting_label.clone(), ui.visuals().weak_text_color())
+                    }
+                    RelayReceipt::Pending => (no_answer_label.clone(), ui.visuals().warn_fg_color),
+                    RelayReceipt::Rejected(reason) if reason.is_empty() => {
+                        (String::from("✗"), ui.visuals().error_fg_color)
+                    }
+                    RelayReceipt::Rejected(reason) => (reason.clone(), ui.visuals().error_fg_color),
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label(relay);
+                    ui.colored_label(color, text);
+                });
+            }
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use egui::{Rgba, RichText};
+use enostr::{PoolSummary, RelayStatus};
+use notedeck::{tr, Localization};
+
+use crate::app_images;
+
+/// A small pill with how many relays are connected, so it's clear why
+/// nothing is arriving. Hovering explains what's going on.
+pub struct RelayStatusChip<'a> {
+    summary: PoolSummary,
+    i18n: &'a mut Localization,
+}
+
+impl<'a> RelayStatusChip<'a> {
+    pub fn new(summary: PoolSummary, i18n: &'a mut Localization) -> Self {
+        Self { summary, i18n }
+    }
+}
+
+impl egui::Widget for RelayStatusChip<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let summary = self.summary;
+        let status = summary.status();
+
+        let fg_color = match status {
+            RelayStatus::Connected if summary.reconnecting() == 0 => ui.visuals().selection.bg_fill,
+            RelayStatus::Connected | RelayStatus::Connecting => ui.visuals().warn_fg_color,
+            RelayStatus::Disconnected => ui.visuals().error_fg_color,
+        };
+        let bg_color = egui::lerp(Rgba::from(fg_color)..=Rgba::BLACK, 0.8).into();
+
+        let icon = match status {
+            RelayStatus::Connected => app_images::connected_image(),
+            RelayStatus::Connecting => app_images::connecting_image(),
+            RelayStatus::Disconnected => app_images::disconnected_image(),
+        };
+
+        let resp = egui::Frame::new()
+            .corner_radius(egui::CornerRadius::same(100))
+            .fill(bg_color)
+            .inner_margin(egui::Margin::symmetric(8, 2))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(icon.max_height(10.0));
+                    ui.add(
+                        egui::Label::new(
+                            RichText::new(format!("{}/{}", summary.connected, summary.total))
+                                .size(10.0)
+                                .color(fg_color),
+                        )
+                        .selectable(false),
+                    );
+                });
+            })
+            .response;
+
+        let hover = if summary.total == 0 {
+            tr!(
+                self.i18n,
+                "No relays configured",
+                "Hover text of the relay status chip when there are no relays"
+            )
+        } else if summary.reconnecting() == 0 {
+            tr!(
+                self.i18n,
+                "Connected to all {total} relays",
+                "Hover text of the relay status chip when every relay is connected",
+                total = summary.total
+            )
+        } else {
+            tr!(
+                self.i18n,
+                "Connected to {connected} of {total} relays, reconnecting to {reconnecting}",
+                "Hover text of the relay status chip while some relays are down",
+                connected = summary.connected,
+                total = summary.total,
+                reconnecting = summary.reconnecting()
+            )
+        };
+
+        resp.on_hover_text(hover)
+    }
+}
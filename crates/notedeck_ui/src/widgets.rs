@@ -77,3 +77,61 @@ pub fn styled_button_toggleable(
         resp
     }
 }
+
+/// A small circled "i" that shows `tooltip` when hovered
+pub struct InfoIcon {
+    tooltip: String,
+    size: f32,
+    color: Option<egui::Color32>,
+}
+
+impl InfoIcon {
+    pub fn new(tooltip: impl Into<String>) -> Self {
+        Self {
+            tooltip: tooltip.into(),
+            size: 14.0,
+            color: None,
+        }
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Defaults to the weak text color of the current theme
+    pub fn color(mut self, color: egui::Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl egui::Widget for InfoIcon {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(self.size, self.size), egui::Sense::hover());
+
+        let color = self.color.unwrap_or_else(|| {
+            if response.hovered() {
+                ui.visuals().text_color()
+            } else {
+                ui.visuals().weak_text_color()
+            }
+        });
+
+        let painter = ui.painter_at(rect.expand(1.0));
+        let radius = self.size / 2.0 - 1.0;
+        painter.circle_stroke(rect.center(), radius, Stroke::new(1.0, color));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "i",
+            egui::FontId::proportional(self.size * 0.7),
+            color,
+        );
+
+        response
+            .on_hover_cursor(egui::CursorIcon::Help)
+            .on_hover_text(self.tooltip)
+    }
+}